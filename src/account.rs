@@ -0,0 +1,196 @@
+//! Account module: named credentials, each a token and the API endpoint it belongs to, so one
+//! can switch between e.g. a GitHub Enterprise Server account for work and a github.com one.
+//!
+//! Accounts are kept next to the config file in `accounts.json`, readable only by the user.
+//! The account used by a run is the one given with `--account`, or else the one chosen with
+//! `account use`; without either, `GITHUB_TOKEN` and `--api-url` are used as before. An
+//! account's token is only sent to its own endpoint.
+
+use crate::config;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// The credentials of one account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Account {
+    /// The GraphQL endpoint the token belongs to.
+    pub api_url: String,
+    /// The personal access token.
+    pub token: String,
+}
+
+impl Account {
+    /// The token to send to the GraphQL endpoint `api_url`, if the account belongs to it.
+    pub fn token_for(&self, api_url: &str) -> Option<&str> {
+        (self.api_url.trim_end_matches('/') == api_url.trim_end_matches('/'))
+            .then_some(self.token.as_str())
+    }
+}
+
+/// The contents of the accounts file.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountList {
+    /// The account chosen with `account use`, if any.
+    #[serde(default)]
+    pub current: Option<String>,
+    /// The accounts by name.
+    #[serde(default)]
+    pub accounts: BTreeMap<String, Account>,
+}
+
+/// A file of stored accounts.
+pub struct Accounts {
+    path: PathBuf,
+}
+
+impl Accounts {
+    /// Accounts stored at `path`.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Accounts stored next to the default config file, if there is a config directory.
+    pub fn open() -> Option<Self> {
+        config::default_path().and_then(|path| {
+            path.parent()
+                .map(|dir| Self::new(dir.join("accounts.json")))
+        })
+    }
+
+    /// The stored accounts; none when the file is missing.
+    pub fn load(&self) -> Result<AccountList> {
+        if !self.path.exists() {
+            return Ok(AccountList::default());
+        }
+        let json = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read accounts from {:?}", self.path))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse accounts {:?}", self.path))
+    }
+
+    /// Adds the account `name`, replacing a stored one of the same name.
+    pub fn add(&self, name: &str, account: Account) -> Result<()> {
+        if name.trim().is_empty() {
+            bail!("Account name cannot be empty");
+        }
+        let mut list = self.load()?;
+        list.accounts.insert(name.to_string(), account);
+        self.store(&list)
+    }
+
+    /// Makes `name` the account runs use when `--account` isn't given.
+    pub fn set_current(&self, name: &str) -> Result<()> {
+        let mut list = self.load()?;
+        if !list.accounts.contains_key(name) {
+            bail!("{}", unknown_account(name, &list));
+        }
+        list.current = Some(name.to_string());
+        self.store(&list)
+    }
+
+    /// The account named `name`, or the current account when `name` is `None`, with its name.
+    /// Fails when a named account isn't stored.
+    pub fn selected(&self, name: Option<&str>) -> Result<Option<(String, Account)>> {
+        let list = self.load()?;
+        let Some(name) = name.or(list.current.as_deref()) else {
+            return Ok(None);
+        };
+        match list.accounts.get(name) {
+            Some(account) => Ok(Some((name.to_string(), account.clone()))),
+            None => bail!("{}", unknown_account(name, &list)),
+        }
+    }
+
+    /// Writes `list`, readable only by the user since it holds tokens.
+    fn store(&self, list: &AccountList) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create config directory {:?}", dir))?;
+        }
+        let json = serde_json::to_string_pretty(list).context("Failed to serialize accounts")?;
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(json.as_bytes()))
+            .with_context(|| format!("Failed to write accounts {:?}", self.path))
+    }
+}
+
+/// The error message for an account `name` that isn't in `list`.
+fn unknown_account(name: &str, list: &AccountList) -> String {
+    let known: Vec<&str> = list.accounts.keys().map(String::as_str).collect();
+    format!(
+        "Unknown account: {}. Stored accounts: {}",
+        name,
+        if known.is_empty() {
+            "none (add one with `account add`)".to_string()
+        } else {
+            known.join(", ")
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_add_use_and_select_accounts() {
+        let dir = env::temp_dir().join(format!("github-activity-accounts-{}", std::process::id()));
+        let accounts = Accounts::new(dir.join("accounts.json"));
+        assert_eq!(accounts.selected(None).unwrap(), None);
+
+        let work = Account {
+            api_url: "https://github.example.com/api/graphql".into(),
+            token: "ghp_work".into(),
+        };
+        let personal = Account {
+            api_url: "https://api.github.com/graphql".into(),
+            token: "ghp_personal".into(),
+        };
+        accounts.add("work", work.clone()).unwrap();
+        accounts.add("personal", personal.clone()).unwrap();
+        assert_eq!(
+            personal.token_for("https://api.github.com/graphql/"),
+            Some("ghp_personal")
+        );
+        assert_eq!(
+            personal.token_for("https://github.example.com/api/graphql"),
+            None
+        );
+        assert_eq!(accounts.selected(None).unwrap(), None);
+        assert!(accounts.set_current("home").is_err());
+
+        accounts.set_current("work").unwrap();
+        assert_eq!(
+            accounts.selected(None).unwrap(),
+            Some(("work".to_string(), work))
+        );
+        assert_eq!(
+            accounts.selected(Some("personal")).unwrap(),
+            Some(("personal".to_string(), personal))
+        );
+        let err = accounts.selected(Some("home")).unwrap_err();
+        assert!(err.to_string().contains("personal, work"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.join("accounts.json"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
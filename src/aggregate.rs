@@ -0,0 +1,392 @@
+//! Aggregate module: team-level distributions of activity, such as the median number of pull
+//! requests per member or review latency percentiles, that never attribute a number to a
+//! person, for organizations whose works councils don't allow per-person metrics.
+//!
+//! Metrics are pluggable: a `Metric` turns one member's activity into samples, and only the
+//! distribution of the samples pooled over the whole team is reported.
+
+use crate::github::reviewed_pull_requests::ReviewedPullRequestsSearchNodesOnPullRequest as ReviewedPullRequest;
+use crate::github::user_activity::UserActivityUserContributionsCollection as ContributionsCollection;
+use crate::locale::NumberLocale;
+use crate::report::Report;
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// The fewest members a team may have; with fewer, a distribution would reveal individuals.
+pub const MIN_MEMBERS: usize = 3;
+
+/// One member's activity, fed to the metrics; never reported on its own.
+#[derive(Debug, Clone)]
+pub struct MemberActivity {
+    /// The member's report.
+    pub report: Report,
+    /// Hours from the creation of each pull request the member reviewed to their first review.
+    pub review_latencies: Vec<f64>,
+}
+
+/// What the samples of a metric measure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    /// A number of contributions.
+    Count,
+    /// A duration in hours.
+    Hours,
+}
+
+/// A team metric: the samples one member contributes to its distribution.
+pub trait Metric {
+    /// Name of the metric, e.g. `Pull requests per member`.
+    fn name(&self) -> &str;
+
+    /// What the samples measure.
+    fn unit(&self) -> Unit;
+
+    /// The samples of `member`, pooled with those of the rest of the team.
+    fn samples(&self, member: &MemberActivity) -> Vec<f64>;
+}
+
+/// A count per member, one sample each.
+pub struct PerMember {
+    /// Name of the metric.
+    pub name: &'static str,
+    /// The member's count.
+    pub count: fn(&ContributionsCollection) -> i64,
+}
+
+impl Metric for PerMember {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn unit(&self) -> Unit {
+        Unit::Count
+    }
+
+    fn samples(&self, member: &MemberActivity) -> Vec<f64> {
+        let user = member.report.activity.user.as_ref();
+        vec![user.map_or(0, |user| (self.count)(&user.contributions_collection)) as f64]
+    }
+}
+
+/// Hours from opening to merging each merged pull request.
+pub struct MergeTime;
+
+impl Metric for MergeTime {
+    fn name(&self) -> &str {
+        "Time to merge"
+    }
+
+    fn unit(&self) -> Unit {
+        Unit::Hours
+    }
+
+    fn samples(&self, member: &MemberActivity) -> Vec<f64> {
+        let Some(user) = &member.report.activity.user else {
+            return Vec::new();
+        };
+        let prs = &user.contributions_collection.pull_request_contributions;
+        prs.nodes
+            .iter()
+            .flatten()
+            .filter_map(|node| {
+                let pr = &node.pull_request;
+                hours_between(&pr.created_at, pr.merged_at.as_deref()?)
+            })
+            .collect()
+    }
+}
+
+/// Hours from the creation of each reviewed pull request to the member's first review.
+pub struct ReviewLatency;
+
+impl Metric for ReviewLatency {
+    fn name(&self) -> &str {
+        "Review latency"
+    }
+
+    fn unit(&self) -> Unit {
+        Unit::Hours
+    }
+
+    fn samples(&self, member: &MemberActivity) -> Vec<f64> {
+        member.review_latencies.clone()
+    }
+}
+
+/// The metrics aggregated when none are chosen, in order.
+pub fn default_metrics() -> Vec<Box<dyn Metric>> {
+    vec![
+        Box::new(PerMember {
+            name: "Commits per member",
+            count: |cc| cc.total_commit_contributions,
+        }),
+        Box::new(PerMember {
+            name: "Pull requests per member",
+            count: |cc| cc.total_pull_request_contributions,
+        }),
+        Box::new(PerMember {
+            name: "Reviews per member",
+            count: |cc| cc.total_pull_request_review_contributions,
+        }),
+        Box::new(PerMember {
+            name: "Issues per member",
+            count: |cc| cc.total_issue_contributions,
+        }),
+        Box::new(MergeTime),
+        Box::new(ReviewLatency),
+    ]
+}
+
+/// Hours between two RFC 3339 timestamps.
+fn hours_between(start: &str, end: &str) -> Option<f64> {
+    let start = DateTime::parse_from_rfc3339(start).ok()?;
+    let end = DateTime::parse_from_rfc3339(end).ok()?;
+    Some((end - start).num_seconds() as f64 / 3600.0)
+}
+
+/// Hours from the creation of each pull request in `prs` to its first review, for reviews
+/// submitted between `start` and `end`.
+pub fn review_latencies(
+    prs: &[ReviewedPullRequest],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<f64> {
+    prs.iter()
+        .filter_map(|pr| {
+            let reviews = pr.reviews.as_ref()?.nodes.as_ref()?;
+            let submitted_at = reviews.iter().flatten().next()?.submitted_at.as_deref()?;
+            let submitted = DateTime::parse_from_rfc3339(submitted_at).ok()?;
+            if submitted < start || submitted > end {
+                return None;
+            }
+            hours_between(&pr.created_at, submitted_at)
+        })
+        .collect()
+}
+
+/// Percentiles of a metric's samples.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Distribution {
+    /// How many samples there are.
+    pub samples: usize,
+    /// The 25th percentile.
+    pub p25: f64,
+    /// The median.
+    pub median: f64,
+    /// The 75th percentile.
+    pub p75: f64,
+    /// The 90th percentile.
+    pub p90: f64,
+}
+
+impl Distribution {
+    /// The distribution of `samples`, interpolating between the closest ranks; `None` when
+    /// there are none.
+    pub fn of(mut samples: Vec<f64>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            let rank = p * (samples.len() - 1) as f64;
+            let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+            samples[lower] + (samples[upper] - samples[lower]) * (rank - lower as f64)
+        };
+        Some(Self {
+            samples: samples.len(),
+            p25: percentile(0.25),
+            median: percentile(0.5),
+            p75: percentile(0.75),
+            p90: percentile(0.9),
+        })
+    }
+}
+
+/// The distribution of one metric over the team.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricSummary {
+    /// Name of the metric.
+    pub name: String,
+    /// What the samples measure.
+    pub unit: Unit,
+    /// The distribution, if any member contributed samples.
+    pub distribution: Option<Distribution>,
+}
+
+/// Anonymized analytics of a team: distributions only, with no per-person numbers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TeamAnalytics {
+    /// Start of the time range.
+    pub start_date: DateTime<Utc>,
+    /// End of the time range.
+    pub end_date: DateTime<Utc>,
+    /// How many members the team has.
+    pub members: usize,
+    /// The distribution of each metric, in order.
+    pub metrics: Vec<MetricSummary>,
+}
+
+impl TeamAnalytics {
+    /// Aggregates `metrics` over `members`, which must be at least `MIN_MEMBERS`.
+    pub fn aggregate(members: &[MemberActivity], metrics: &[Box<dyn Metric>]) -> Result<Self> {
+        if members.len() < MIN_MEMBERS {
+            bail!(
+                "Anonymized aggregation needs at least {} members, but the team has {}",
+                MIN_MEMBERS,
+                members.len()
+            );
+        }
+        let report = &members[0].report;
+        Ok(Self {
+            start_date: report.start_date,
+            end_date: report.end_date,
+            members: members.len(),
+            metrics: metrics
+                .iter()
+                .map(|metric| MetricSummary {
+                    name: metric.name().to_string(),
+                    unit: metric.unit(),
+                    distribution: Distribution::of(
+                        members.iter().flat_map(|m| metric.samples(m)).collect(),
+                    ),
+                })
+                .collect(),
+        })
+    }
+
+    /// Renders the analytics as a Markdown table of percentiles per metric.
+    pub fn to_markdown(&self, title: &str, locale: NumberLocale) -> String {
+        let mut output = format!(
+            "# {}\n\n_{} to {} · {} members · no per-person numbers_\n\n",
+            title,
+            self.start_date.format("%Y-%m-%d"),
+            self.end_date.format("%Y-%m-%d"),
+            locale.format(self.members as i64)
+        );
+        output.push_str("| Metric | Samples | P25 | Median | P75 | P90 |\n");
+        output.push_str("|--------|---------|-----|--------|-----|-----|\n");
+        for metric in &self.metrics {
+            let name = match metric.unit {
+                Unit::Count => metric.name.clone(),
+                Unit::Hours => format!("{} (hours)", metric.name),
+            };
+            let Some(d) = &metric.distribution else {
+                output.push_str(&format!("| {} | 0 | – | – | – | – |\n", name));
+                continue;
+            };
+            output.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                name,
+                locale.format(d.samples as i64),
+                locale.format_decimal(d.p25),
+                locale.format_decimal(d.median),
+                locale.format_decimal(d.p75),
+                locale.format_decimal(d.p90)
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn member(
+        username: &str,
+        prs: i64,
+        merged: &[(&str, &str)],
+        review_latencies: Vec<f64>,
+    ) -> MemberActivity {
+        let nodes: Vec<_> = merged
+            .iter()
+            .enumerate()
+            .map(|(i, (created, merged))| {
+                json!({ "pullRequest": {
+                    "number": i, "title": "PR", "url": format!("https://github.com/o/r/pull/{}", i),
+                    "createdAt": created, "state": "MERGED", "merged": true, "mergedAt": merged,
+                    "mergeCommit": null, "closedAt": merged, "additions": 1, "deletions": 1,
+                    "changedFiles": 1
+                } })
+            })
+            .collect();
+        let page = |nodes: Vec<serde_json::Value>| json!({ "totalCount": nodes.len(), "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes });
+        let activity = serde_json::from_value(json!({ "user": { "contributionsCollection": {
+            "totalCommitContributions": 0,
+            "totalIssueContributions": 0,
+            "totalPullRequestContributions": prs,
+            "totalPullRequestReviewContributions": 0,
+            "contributionCalendar": { "totalContributions": 0, "weeks": [] },
+            "commitContributionsByRepository": [],
+            "issueContributions": page(vec![]),
+            "pullRequestContributions": page(nodes),
+            "pullRequestReviewContributions": page(vec![])
+        } }, "rateLimit": null }))
+        .unwrap();
+        let start = DateTime::parse_from_rfc3339("2025-03-01T00:00:00Z").unwrap();
+        let end = DateTime::parse_from_rfc3339("2025-03-31T00:00:00Z").unwrap();
+        MemberActivity {
+            report: Report::new(username.into(), start.into(), end.into(), activity),
+            review_latencies,
+        }
+    }
+
+    #[test]
+    fn test_distribution() {
+        assert_eq!(Distribution::of(Vec::new()), None);
+        let d = Distribution::of(vec![4.0, 1.0, 3.0, 2.0, 5.0]).unwrap();
+        assert_eq!((d.samples, d.p25, d.median, d.p75), (5, 2.0, 3.0, 4.0));
+        assert!((d.p90 - 4.6).abs() < 1e-9);
+        let d = Distribution::of(vec![1.0, 2.0]).unwrap();
+        assert_eq!(d.median, 1.5);
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let members = vec![
+            member(
+                "alice",
+                2,
+                &[("2025-03-02T00:00:00Z", "2025-03-02T12:00:00Z")],
+                vec![1.0],
+            ),
+            member("bob", 4, &[], vec![3.0, 5.0]),
+            member(
+                "carol",
+                9,
+                &[("2025-03-03T00:00:00Z", "2025-03-04T00:00:00Z")],
+                vec![],
+            ),
+        ];
+        let analytics = TeamAnalytics::aggregate(&members, &default_metrics()).unwrap();
+        assert_eq!(analytics.members, 3);
+        let median = |name: &str| {
+            let metric = analytics.metrics.iter().find(|m| m.name == name).unwrap();
+            metric.distribution.as_ref().map(|d| d.median)
+        };
+        assert_eq!(median("Pull requests per member"), Some(4.0));
+        assert_eq!(median("Time to merge"), Some(18.0));
+        assert_eq!(median("Review latency"), Some(3.0));
+
+        let markdown = analytics.to_markdown("Team Analytics", NumberLocale::default());
+        assert!(markdown.starts_with(
+            "# Team Analytics\n\n_2025-03-01 to 2025-03-31 · 3 members · no per-person numbers_\n"
+        ));
+        assert!(markdown.contains("| Pull requests per member | 3 | 3.0 | 4.0 | 6.5 | 8.0 |\n"));
+        assert!(markdown.contains("| Review latency (hours) | 3 | 2.0 | 3.0 | 4.0 | 4.6 |\n"));
+        for username in ["alice", "bob", "carol"] {
+            assert!(!markdown.contains(username));
+            assert!(
+                !serde_json::to_string(&analytics)
+                    .unwrap()
+                    .contains(username)
+            );
+        }
+
+        let err = TeamAnalytics::aggregate(&members[..2], &default_metrics()).unwrap_err();
+        assert!(err.to_string().contains("at least 3 members"));
+    }
+}
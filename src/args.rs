@@ -1,21 +1,55 @@
+use crate::account::Accounts;
+use crate::cache;
+use crate::config::Config;
+use crate::deliver::{DeliveryTarget, IssueTarget, SlackPayload};
+use crate::history::RecentUsernames;
+use crate::watch;
+use anyhow::Context;
 use chrono::{DateTime, Duration, Utc};
-use clap::Parser;
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
+use clap_complete::{ArgValueCandidates, CompletionCandidate};
+use github_activity_rs::calendar::CalendarGranularity;
+use github_activity_rs::filter::SortKey;
+use github_activity_rs::format::{GroupBy, MarkdownSection};
+use github_activity_rs::github;
+use github_activity_rs::locale::NumberLocale;
+use github_activity_rs::narrative::Tone;
+use github_activity_rs::review::ReportMode;
+use github_activity_rs::statistics::ForecastPeriod;
 use regex::Regex;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::io::{BufRead, IsTerminal, Write};
 use std::str::FromStr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Command-line arguments for the GitHub activity tool.
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, subcommand_negates_reqs = true)]
 pub struct Args {
-    /// GitHub username (allowed: letters, digits, hyphens; max 39 characters)
-    #[arg(short, long)]
-    pub username: GitHubUsername,
+    /// Optional subcommand; without one, activity is fetched from GitHub
+    #[command(subcommand)]
+    pub command: Option<Command>,
 
-    /// Time period (e.g., 1d, 7d, 30d, 2w, 1m, 3m)
+    /// GitHub username (allowed: letters, digits, hyphens; max 39 characters); repeat it or
+    /// pass a comma-separated list to report on several users
+    #[arg(
+        short,
+        long = "username",
+        value_name = "USERNAME",
+        required = true,
+        value_delimiter = ',',
+        add = ArgValueCandidates::new(recent_username_candidates)
+    )]
+    pub usernames: Vec<GitHubUsername>,
+
+    /// Time period (e.g., 1d, 7d, 30d, 2w, 1m, 3m), or the name of a period defined under
+    /// [periods] in the config (e.g., sprint-42)
     /// Mutually exclusive with --from and --to
-    #[arg(short, long, value_parser = parse_period, conflicts_with_all = ["from", "to"])]
-    pub period: Option<Duration>,
+    #[arg(short, long, value_parser = parse_period_arg, conflicts_with_all = ["from", "to"])]
+    pub period: Option<Period>,
 
     /// Start date in ISO 8601 format (e.g., 2024-01-01 or 2024-01-01T00:00:00Z)
     /// Required if --to is specified
@@ -27,6 +61,11 @@ pub struct Args {
     #[arg(long, requires = "from", value_parser = parse_datetime)]
     pub to: Option<DateTime<Utc>>,
 
+    /// Only fetch contributions made since the previous --since-last-run run for the user and
+    /// merge them into that run's report; the first run fetches the whole range
+    #[arg(long)]
+    pub since_last_run: bool,
+
     /// Optional repository filter in the format "owner/repo"
     #[arg(long)]
     pub repo: Option<String>,
@@ -35,32 +74,685 @@ pub struct Args {
     #[arg(long)]
     pub org: Option<String>,
 
-    /// Output format: plain, markdown, or json
-    #[arg(short, long, default_value = "json", value_parser = parse_output_format)]
+    /// Scope contributions to this organization on GitHub's side, so totals and the
+    /// contribution calendar count only its repositories too (unlike --org, which filters
+    /// the fetched lists)
+    #[arg(long, value_name = "ORG")]
+    pub org_scope: Option<String>,
+
+    /// Only report issues and pull requests (and reviews of pull requests) that carry this
+    /// label; may be repeated to keep those with any of the labels
+    #[arg(long = "label", value_name = "NAME")]
+    pub labels: Vec<String>,
+
+    /// Only report pull request reviews in this state (approved, changes-requested, commented,
+    /// or dismissed); may be repeated to keep reviews in any of the states
+    #[arg(long = "review-state", value_name = "STATE", value_parser = parse_review_state)]
+    pub review_states: Vec<ReviewState>,
+
+    /// Also fetch the bodies of pull request reviews, shown in plain-text reports
+    #[arg(long)]
+    pub review_bodies: bool,
+
+    /// Only report pull requests and reviews that change files matching this glob
+    /// (e.g. packages/frontend/**); may be repeated. Fetches the changed files of each pull request
+    #[arg(long = "path", value_name = "GLOB")]
+    pub paths: Vec<String>,
+
+    /// Only report pull requests and reviews that change files owned (per this CODEOWNERS
+    /// file) by --code-owner
+    #[arg(long, value_name = "PATH", group = "codeowners_source")]
+    pub codeowners: Option<PathBuf>,
+
+    /// Like --codeowners, using the CODEOWNERS file of the --repo repository
+    #[arg(long, requires = "repo", group = "codeowners_source")]
+    pub codeowners_from_repo: bool,
+
+    /// CODEOWNERS owner whose paths are in scope, e.g. @org/team; may be repeated.
+    /// Defaults to the user (@username)
+    #[arg(
+        long = "code-owner",
+        value_name = "OWNER",
+        requires = "codeowners_source"
+    )]
+    pub code_owners: Vec<String>,
+
+    /// Note contributions made in the user's own forks, and the repository each was forked
+    /// from, in plain, color, markdown, and html reports. Looks up each repository the user
+    /// owns
+    #[arg(long)]
+    pub mark_forks: bool,
+
+    /// Leave out contributions made in the user's own forks, which would otherwise count
+    /// alongside the upstream work. Looks up each repository the user owns
+    #[arg(long, conflicts_with = "mark_forks")]
+    pub exclude_forks: bool,
+
+    /// Print, before the report, how many repositories, issues, pull requests, and reviews
+    /// each filter (--repo, --org, --label, --review-state, --exclude-forks, --path,
+    /// --code-owner) removed
+    #[arg(long)]
+    pub explain_filters: bool,
+
+    /// Fetch issue timeline events and show a per-issue history in plain and markdown reports;
+    /// slower and costs more API calls
+    #[arg(long)]
+    pub include_timeline: bool,
+
+    /// Report mode: standard, or review for a performance review / brag document summary of
+    /// a quarter or year (highlights, totals against the previous period of the same length,
+    /// and a per-month breakdown) in Markdown, or JSON for .json outputs
+    #[arg(long, default_value = "standard", value_name = "MODE")]
+    pub mode: ReportMode,
+
+    /// Triage report mode: emphasize issues labeled, closed as duplicate or not planned, and
+    /// first responses given in the --repo repository (requires --include-timeline)
+    #[arg(long, requires_all = ["repo", "include_timeline"])]
+    pub triage: bool,
+
+    /// Flag merged pull requests that were later reverted; costs one search per repository
+    #[arg(long)]
+    pub detect_reverts: bool,
+
+    /// Report commits GitHub didn't attribute to you because their author email isn't a
+    /// verified email of your account; costs one commits API call per 100 commits of each
+    /// repository, and the token needs the user:email scope
+    #[arg(long)]
+    pub check_commit_emails: bool,
+
+    /// Annotate merged pull requests with the first release that shipped them; costs one
+    /// compare API call per checked release
+    #[arg(long)]
+    pub include_releases: bool,
+
+    /// Show, per repository, the share of pull requests by others merged in the range that
+    /// you reviewed; costs two searches per repository
+    #[arg(long)]
+    pub review_coverage: bool,
+
+    /// Add a Statistics section: longest and current streak, busiest day, averages per day
+    /// and week, and the ratios of contribution types
+    #[arg(long)]
+    pub stats: bool,
+
+    /// With --stats, project the totals to the end of the week, month, quarter, or year the
+    /// report ends in at the report's run rate; start the report with the period (e.g.
+    /// --from on the quarter's first day) for an "on pace for" figure
+    #[arg(long, value_name = "PERIOD", requires = "stats")]
+    pub forecast: Option<ForecastPeriod>,
+
+    /// GraphQL endpoint of the GitHub API; for GitHub Enterprise Server use
+    /// https://HOST/api/graphql. REST calls go to the matching https://HOST/api/v3
+    #[arg(
+        long,
+        global = true,
+        env = "GITHUB_GRAPHQL_URL",
+        default_value = github::DEFAULT_API_URL
+    )]
+    pub api_url: String,
+
+    /// Send GraphQL queries as GET requests with a persisted-query hash, for caching gateways
+    /// in front of the API; falls back to POST when the endpoint doesn't support them
+    #[arg(long, global = true)]
+    pub persisted_queries: bool,
+
+    /// Where the activity cache, last runs, and backfill progress are kept: a SQLite database
+    /// path or, in builds with the postgres feature, a postgres:// URL shared by several
+    /// instances; defaults to a SQLite database in the state directory
+    #[arg(long, global = true, env = "GITHUB_ACTIVITY_STORE", value_name = "URL")]
+    pub store: Option<String>,
+
+    /// Always fetch from the GitHub API, ignoring and not updating cached activity
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// How many seconds fetched activity is reused from the cache
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        default_value_t = cache::DEFAULT_TTL_SECS
+    )]
+    pub cache_ttl: i64,
+
+    /// Issue contributions requested per page (1-100)
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = github::PageSizes::default().issues,
+        value_parser = clap::value_parser!(i64).range(1..=100)
+    )]
+    pub issues_page_size: i64,
+
+    /// Pull request contributions requested per page (1-100)
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = github::PageSizes::default().prs,
+        value_parser = clap::value_parser!(i64).range(1..=100)
+    )]
+    pub prs_page_size: i64,
+
+    /// Pull request review contributions requested per page (1-100)
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = github::PageSizes::default().reviews,
+        value_parser = clap::value_parser!(i64).range(1..=100)
+    )]
+    pub reviews_page_size: i64,
+
+    /// Fail as soon as the GitHub API rate limit runs out instead of waiting for it to reset,
+    /// and don't retry requests that hit a rate limit
+    #[arg(long, global = true)]
+    pub no_wait: bool,
+
+    /// Print a JSON line to stderr for every page fetched (user, connection, page, fetched,
+    /// total, done), for wrapper UIs and CI logs that show progress
+    #[arg(long, global = true)]
+    pub progress_json: bool,
+
+    /// Don't check once a day whether a newer release is available
+    #[arg(long, global = true)]
+    pub no_update_check: bool,
+
+    /// Output format: plain, color (plain text with ANSI colors), markdown, json, jsonl (one
+    /// JSON object per contribution, streamed as pages arrive), html, csv, slack (Block Kit
+    /// JSON), or ics (iCalendar). Defaults to color when printing to a terminal and json
+    /// otherwise
+    #[arg(short, long, global = true, default_value = "json", value_parser = parse_output_format)]
     pub format: OutputFormat,
 
-    /// Path to the output file, if not specified, the output will be printed to the console
-    #[arg(short, long)]
-    pub output: Option<PathBuf>,
+    /// Write json output without indentation and line breaks
+    #[arg(long, global = true)]
+    pub compact_json: bool,
+
+    /// Write only the daily contribution calendar, as date,count rows in csv or as json or
+    /// jsonl, instead of the whole report
+    #[arg(long, global = true, conflicts_with = "template")]
+    pub calendar_only: bool,
+
+    /// Render every output through this Handlebars template instead of --format; the
+    /// template receives the report as serialized to JSON
+    #[arg(long, global = true, value_name = "PATH")]
+    pub template: Option<PathBuf>,
+
+    /// Render one section of Markdown reports through a Handlebars snippet instead of the
+    /// built-in layout, as SECTION=PATH (e.g. pull-requests=prs.hbs); may be repeated, or set
+    /// as a list in the config. The snippet receives the report like --template does.
+    /// Sections: summary, statistics, unattributed-commits, categories, review-coverage,
+    /// triage, calendar, repositories, issues, pull-requests, reviews
+    #[arg(
+        long = "section-template",
+        global = true,
+        value_name = "SECTION=PATH",
+        value_parser = parse_section_template
+    )]
+    pub section_templates: Vec<(MarkdownSection, PathBuf)>,
+
+    /// Move URLs in Markdown tables into reference-style footnotes at the end of the report
+    #[arg(long, global = true)]
+    pub footnote_links: bool,
+
+    /// Diff-friendly Markdown for reports committed to git: no column padding, the period as
+    /// dates without the time of the run, repositories by name, and contributions newest
+    /// first unless --sort is given
+    #[arg(long, global = true)]
+    pub stable_markdown: bool,
+
+    /// Locale used to group digits of totals in plain, markdown, html, and slack output
+    /// (e.g. en for 12,345, de for 12.345, fr, or none); JSON and CSV keep raw numbers
+    #[arg(long, global = true, default_value = "en")]
+    pub locale: NumberLocale,
+
+    /// In HTML reports, draw the contribution mix (commits vs issues vs pull requests vs
+    /// reviews) as a pie chart
+    #[arg(long, global = true)]
+    pub mix_chart: bool,
+
+    /// Contribution calendar detail in plain, color, markdown, and html output: daily,
+    /// weekly, monthly, or off
+    #[arg(long, global = true, default_value = "daily")]
+    pub calendar: CalendarGranularity,
+
+    /// In plain and markdown output, list every day of the daily calendar on its own line
+    /// instead of drawing it as a heatmap
+    #[arg(long, global = true)]
+    pub calendar_detail: bool,
+
+    /// How plain and markdown reports phrase their sections: bullet lists every contribution,
+    /// narrative summarizes each section in a few sentences ready to paste into a status email
+    #[arg(long, global = true, default_value = "bullet")]
+    pub tone: Tone,
+
+    /// In markdown and html output, put the issue, pull request, and review tables under a
+    /// heading per repository (repo) or per category of the config's category rules (category),
+    /// or replace them with per-week (week) or per-month (month) counts, instead of one table
+    /// each for all repositories (none)
+    #[arg(long, global = true, value_name = "GROUPING", default_value = "none")]
+    pub group_by: GroupBy,
+
+    /// List at most N issues, pull requests, and reviews each; the totals still count all of
+    /// them
+    #[arg(long, global = true, value_name = "N")]
+    pub top: Option<usize>,
+
+    /// List a random sample of N issues, pull requests, and reviews each, for skimming huge
+    /// reports; the totals and --stats still count all of them
+    #[arg(long, global = true, value_name = "N")]
+    pub sample: Option<usize>,
+
+    /// Seed of --sample, to list the same sample again; a random seed is logged when it isn't
+    /// given
+    #[arg(long, global = true, value_name = "SEED", requires = "sample")]
+    pub sample_seed: Option<u64>,
+
+    /// Order the issue, pull request, and review tables by creation (created, newest first),
+    /// repository (repo), state, or title instead of the order GitHub returned them in
+    #[arg(long, global = true, value_name = "KEY")]
+    pub sort: Option<SortKey>,
+
+    /// Don't emit ANSI colors in color output; also disabled by setting NO_COLOR
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Path to the output file, if not specified, the output will be printed to the console.
+    /// May be repeated to write several files from a single fetch; the format of each file
+    /// is inferred from its extension, falling back to --format.
+    #[arg(short, long, global = true)]
+    pub output: Vec<PathBuf>,
+
+    /// Write one report per team of the config's [teams] into this directory, named after the
+    /// team, plus an index.md linking them; without --username, every team member is fetched
+    #[arg(long, value_name = "DIR", conflicts_with = "output")]
+    pub output_dir: Option<PathBuf>,
+
+    /// Also write the repository, issue, pull request, and review tables as Parquet files
+    /// into this directory, with a username column, for DuckDB or Spark; needs a build with
+    /// the parquet feature
+    #[arg(long, global = true, value_name = "DIR", hide = !cfg!(feature = "parquet"))]
+    pub parquet_dir: Option<PathBuf>,
+
+    /// Encrypt every output to this age recipient (age1...), so reports naming private
+    /// repositories can be stored or uploaded safely. May be repeated; stdout output is
+    /// ASCII-armored
+    #[arg(long, global = true, value_name = "RECIPIENT")]
+    pub encrypt_to: Vec<age::x25519::Recipient>,
+
+    /// After writing the reports, also post each one to the chat service whose webhook is
+    /// given by --webhook-url: slack, or discord (the plain text report), split between
+    /// sections into numbered messages when it's too long for one; or publish the Markdown
+    /// reports as a secret gist with gist, or as issues (see --target) with issue, printing
+    /// their URLs; or add them to the GitHub Actions job summary with step-summary, the default
+    /// when GITHUB_STEP_SUMMARY is set
+    #[arg(long, global = true, value_name = "TARGET")]
+    pub deliver: Option<DeliveryTarget>,
+
+    /// Incoming webhook URL that --deliver slack and --deliver discord post reports to
+    #[arg(
+        long,
+        global = true,
+        value_name = "URL",
+        env = "GITHUB_ACTIVITY_WEBHOOK_URL",
+        required_if_eq_any = [("deliver", "slack"), ("deliver", "discord")]
+    )]
+    pub webhook_url: Option<String>,
+
+    /// Gist that --deliver gist updates instead of creating a new one each run
+    #[arg(long, global = true, value_name = "ID", requires = "deliver")]
+    pub gist_id: Option<String>,
+
+    /// Repository --deliver issue opens an issue with each report in, as owner/repo; with
+    /// owner/repo#123, the reports are added as comments to that tracking issue instead
+    #[arg(
+        long = "target",
+        global = true,
+        value_name = "OWNER/REPO[#ISSUE]",
+        required_if_eq("deliver", "issue")
+    )]
+    pub issue_target: Option<IssueTarget>,
+
+    /// How --deliver slack posts each report: blocks (Block Kit, as --format slack renders
+    /// it) or text (the plain text report)
+    #[arg(long, global = true, value_name = "PAYLOAD", default_value = "blocks")]
+    pub deliver_as: SlackPayload,
+
+    /// Print the exact messages and requests --deliver would send, and the job summary it
+    /// would write, instead of delivering anything
+    #[arg(long, global = true)]
+    pub dry_run_delivery: bool,
+
+    /// Write a JSON manifest of the run (inputs, API request count, warnings, and the
+    /// checksum of every file written) to this path
+    #[arg(long, global = true, value_name = "PATH")]
+    pub manifest: Option<PathBuf>,
+
+    /// When another run with the same arguments is in progress, wait for it to finish instead
+    /// of exiting with code 75
+    #[arg(long, global = true)]
+    pub wait_for_lock: bool,
+
+    /// Read default options from this TOML file instead of
+    /// ~/.config/github-activity/config.toml
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Apply the options of this profile of the config file on top of its defaults
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Use the token and API endpoint of this stored account (see the account subcommand)
+    /// instead of GITHUB_TOKEN, overriding the account chosen with `account use`
+    #[arg(long, global = true, value_name = "NAME")]
+    pub account: Option<String>,
+}
+
+/// Subcommands of the GitHub activity tool.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Re-render a saved JSON report into another format without calling the GitHub API
+    Render {
+        /// Paths to reports previously saved with --format json, or - to read one from stdin;
+        /// several are rendered together like the reports of several users
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        /// age identity file (as written by age-keygen) to decrypt a report saved with
+        /// --encrypt-to
+        #[arg(long, value_name = "PATH")]
+        identity: Option<PathBuf>,
+    },
+    /// Check a saved JSON report against the report schema
+    Validate {
+        /// Path to a report previously saved with --format json
+        input: PathBuf,
+    },
+    /// Generate every report described in a TOML file of report specs
+    Batch {
+        /// Path to the specs file; each [[report]] entry defines username, period or
+        /// from/to, optional repo/org filters, format, and output path
+        specs: PathBuf,
+    },
+    /// Combine the activity of a team into one Markdown newsletter described by a TOML team
+    /// spec
+    Newsletter {
+        /// Path to the team spec; defines the team (org/team-slug) or members, period or
+        /// from/to, an optional org filter, the sections and their order, and the output path
+        spec: PathBuf,
+    },
+    /// Aggregate the activity of a team into anonymized distributions (medians and
+    /// percentiles) that attribute no number to any member
+    AggregateAnon {
+        /// Path to a team spec as used by newsletter; only its team or members, period or
+        /// from/to, org filter, and output path apply
+        spec: PathBuf,
+    },
+    /// Fetch a long time range one window at a time, remembering which windows succeeded so
+    /// an interrupted backfill can be resumed
+    Backfill {
+        /// Retry only the windows that failed or were not reached in the previous backfill of
+        /// each user, instead of starting over
+        #[arg(long)]
+        resume: bool,
+        /// Length of each fetched window (e.g., 7d, 2w, 1m)
+        #[arg(long, default_value = "30d", value_parser = parse_window)]
+        window: Duration,
+    },
+    /// Compare the contribution calendar of the requested range with an earlier period: both
+    /// side by side with a difference overlay, in the terminal or as SVG (--output *.svg)
+    Compare {
+        /// Start of the earlier period in ISO 8601 format; without it, the earlier period is the
+        /// one of the same length right before the requested range
+        #[arg(long, requires = "previous_to", value_parser = parse_datetime)]
+        previous_from: Option<DateTime<Utc>>,
+        /// End of the earlier period in ISO 8601 format
+        #[arg(long, requires = "previous_from", value_parser = parse_datetime)]
+        previous_to: Option<DateTime<Utc>>,
+    },
+    /// Summarize a year of the user's contributions: the total, longest streak, top
+    /// repositories, busiest month, and first and last contribution, in Markdown or HTML
+    /// (--format html or --output *.html)
+    YearInReview {
+        /// The year to review; defaults to the current year up to today
+        #[arg(long)]
+        year: Option<i32>,
+    },
+    /// Manage the cache of fetched activity
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Maintain the GitHub GraphQL schema the queries are checked against
+    Schema {
+        #[command(subcommand)]
+        action: SchemaAction,
+    },
+    /// Keep running and regenerate the report on a schedule, writing the outputs and
+    /// delivering it through --deliver each time
+    Watch {
+        /// Interval between reports, e.g. 30min, 6h, 1d, or 1w; the first report is made
+        /// right away
+        #[arg(
+            long,
+            value_name = "INTERVAL",
+            value_parser = watch::parse_interval,
+            required_unless_present = "cron",
+            conflicts_with = "cron"
+        )]
+        every: Option<Duration>,
+        /// Cron expression (minute hour day-of-month month day-of-week, in UTC) of the times
+        /// to make reports at, e.g. "0 8 * * Mon"
+        #[arg(long, value_name = "EXPR", value_parser = watch::parse_cron)]
+        cron: Option<Box<cron::Schedule>>,
+    },
+    /// Store the credentials of several accounts (e.g. GitHub Enterprise Server for work and
+    /// github.com) and choose which one runs use
+    Account {
+        #[command(subcommand)]
+        action: AccountAction,
+    },
+}
+
+/// Actions of the `account` subcommand.
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum AccountAction {
+    /// Store an account, reading its token from stdin (prompting on a terminal); the endpoint
+    /// is --api-url. Replaces a stored account of the same name
+    Add {
+        /// Name to select the account by
+        name: String,
+    },
+    /// List the stored accounts and their endpoints, marking the current one
+    List,
+    /// Make runs use this account when --account isn't given
+    Use {
+        /// Name of a stored account
+        name: String,
+    },
+}
+
+/// Actions of the `schema` subcommand.
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum SchemaAction {
+    /// Download the current GitHub GraphQL schema and check the bundled queries against it,
+    /// reporting the fields they select that no longer exist
+    Update {
+        /// Where to save the schema; point it at src/github/schema.graphql to refresh the one
+        /// the client is generated from
+        #[arg(long, value_name = "PATH", default_value = "src/github/schema.graphql")]
+        output: PathBuf,
+        /// URL of the schema to download, in GraphQL SDL
+        #[arg(long, default_value = github_activity_rs::github::drift::SCHEMA_URL)]
+        url: String,
+    },
+}
+
+/// Actions of the `cache` subcommand.
+#[derive(Subcommand, Debug, Clone, PartialEq)]
+pub enum CacheAction {
+    /// Delete all cached activity
+    Clear,
 }
 
 impl Args {
+    /// Parse the process arguments. On an interactive terminal a missing `--username` is not
+    /// an error, so it can be prompted for; scripts keep the strict behavior.
+    pub fn parse_for_terminal() -> Self {
+        let mut command = Self::command();
+        if is_interactive() {
+            command = command.mut_arg("usernames", |arg| arg.required(false));
+        }
+        if std::io::stdout().is_terminal() {
+            command = command.mut_arg("format", |arg| arg.default_value("color"));
+        }
+        let command = with_config(command, std::env::args_os()).unwrap_or_else(|err| {
+            Self::command()
+                .error(ErrorKind::InvalidValue, format!("{:#}", err))
+                .exit()
+        });
+        let matches = command.get_matches();
+        Self::from_matches(&matches).unwrap_or_else(|err| err.exit())
+    }
+
+    /// Builds the arguments from parsed matches. A time range given on the command line
+    /// replaces one of the other kind (period vs. from/to) set in the config, and the
+    /// terminal's color default doesn't carry over to output files.
+    fn from_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        let mut args = Self::from_arg_matches(matches)?;
+        let from_config = |id| matches.value_source(id) == Some(ValueSource::DefaultValue);
+        if args.period.is_some() && args.from.is_some() {
+            if from_config("period") {
+                args.period = None;
+            } else if from_config("from") {
+                (args.from, args.to) = (None, None);
+            }
+        }
+        if args.format == OutputFormat::Color
+            && from_config("format")
+            && (!args.output.is_empty() || args.output_dir.is_some())
+        {
+            args.format = OutputFormat::Json;
+        }
+        Ok(args)
+    }
+
+    /// Whether color output should contain ANSI colors: not with --no-color or when NO_COLOR
+    /// is set to a non-empty value.
+    pub fn color(&self) -> bool {
+        !self.no_color && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+    }
+
+    /// The page sizes requested for each contribution connection.
+    pub fn page_sizes(&self) -> github::PageSizes {
+        github::PageSizes {
+            issues: self.issues_page_size,
+            prs: self.prs_page_size,
+            reviews: self.reviews_page_size,
+        }
+    }
+
     /// Get the date range for the query
     pub fn get_date_range(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
-        match (self.period, self.from, self.to) {
-            (Some(period), None, None) => {
-                let end = Utc::now();
-                let start = end - period;
-                Ok((start, end))
-            }
-            (None, Some(from), Some(to)) => {
-                if from >= to {
-                    return Err("Start date must be before end date".to_string());
-                }
-                Ok((from, to))
+        match self.period {
+            Some(Period::Named(from, to)) => resolve_date_range(None, Some(from), Some(to)),
+            _ => resolve_date_range(self.rolling_period(), self.from, self.to),
+        }
+    }
+
+    /// The --period duration when the range ends now; `None` for fixed ranges, including
+    /// named periods.
+    pub fn rolling_period(&self) -> Option<Duration> {
+        match self.period {
+            Some(Period::Last(duration)) => Some(duration),
+            _ => None,
+        }
+    }
+}
+
+/// Applies the config file and profile selected in `argv` to `command` as default values.
+fn with_config(
+    mut command: clap::Command,
+    argv: impl IntoIterator<Item = OsString>,
+) -> anyhow::Result<clap::Command> {
+    let selection = command
+        .clone()
+        .ignore_errors(true)
+        .try_get_matches_from(argv)
+        .context("Failed to parse arguments")?;
+    let config =
+        Config::load_or_default(selection.get_one::<PathBuf>("config").map(PathBuf::as_path))?;
+    let profile = selection.get_one::<String>("profile").map(String::as_str);
+    let mut options = config.options(profile)?;
+    let periods = config.periods()?;
+    if !periods.is_empty() {
+        command = command.mut_arg("period", |arg| {
+            arg.value_parser(move |s: &str| match periods.get(s) {
+                Some(period) => Ok(*period),
+                None => parse_period_arg(s).map_err(|err| {
+                    let names: Vec<&str> = periods.keys().map(String::as_str).collect();
+                    format!("{}, or a period from the config: {}", err, names.join(", "))
+                }),
+            })
+        });
+    }
+    // The selected account's endpoint replaces the config's, while --api-url still wins; the
+    // account subcommand itself takes the endpoint of the account it adds from --api-url.
+    if selection.subcommand_name() != Some("account") {
+        let name = selection.get_one::<String>("account").cloned().or_else(|| {
+            options
+                .get("account")
+                .and_then(|values| values.first().cloned())
+        });
+        if let Some(accounts) = Accounts::open()
+            && let Some((_, account)) = accounts.selected(name.as_deref())?
+        {
+            options.insert("api-url".to_string(), vec![account.api_url]);
+        }
+    }
+    // With --output-dir, the users default to the members of the config's teams.
+    if selection.get_one::<PathBuf>("output_dir").is_some() || options.contains_key("output-dir") {
+        command = command.mut_arg("usernames", |arg| arg.required(false));
+    }
+    apply_options(command, options)
+}
+
+/// Makes each value of `options`, keyed by long option name, the default of that option.
+fn apply_options(
+    mut command: clap::Command,
+    options: BTreeMap<String, Vec<String>>,
+) -> anyhow::Result<clap::Command> {
+    for (long, values) in options {
+        let id = command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(long.as_str()))
+            .map(|arg| arg.get_id().clone())
+            .with_context(|| format!("Unknown option in config: {}", long))?;
+        command = command.mut_arg(id, |arg| arg.required(false).default_values(values));
+    }
+    Ok(command)
+}
+
+/// Resolves either a period (ending now) or an explicit from/to pair into a date range.
+pub fn resolve_date_range(
+    period: Option<Duration>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+    match (period, from, to) {
+        (Some(period), None, None) => {
+            let end = Utc::now();
+            let start = end - period;
+            Ok((start, end))
+        }
+        (None, Some(from), Some(to)) => {
+            if from >= to {
+                return Err("Start date must be before end date".to_string());
             }
-            _ => Err("Either specify --period or both --from and --to".to_string()),
+            Ok((from, to))
         }
+        _ => Err("Either specify --period or both --from and --to".to_string()),
     }
 }
 
@@ -95,8 +787,67 @@ impl std::fmt::Display for GitHubUsername {
     }
 }
 
+/// Whether both stdin and stderr are terminals, i.e. a person can answer prompts.
+pub fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// Completion candidates for `--username`: the recently used usernames, most recent first.
+fn recent_username_candidates() -> Vec<CompletionCandidate> {
+    RecentUsernames::open()
+        .map(|history| history.load())
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .map(|(i, username)| CompletionCandidate::new(username).display_order(Some(i)))
+        .collect()
+}
+
+/// Prompts for a GitHub username until a valid one is entered, using the same validation as
+/// `--username`. The `recent` usernames are listed first and can be picked by number.
+pub fn prompt_username(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    recent: &[String],
+) -> anyhow::Result<GitHubUsername> {
+    if !recent.is_empty() {
+        writeln!(output, "Recent usernames:")?;
+        for (i, username) in recent.iter().enumerate() {
+            writeln!(output, "  {}) {}", i + 1, username)?;
+        }
+    }
+    loop {
+        if recent.is_empty() {
+            write!(output, "GitHub username: ")?;
+        } else {
+            write!(output, "GitHub username or number: ")?;
+        }
+        output.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            anyhow::bail!("--username is required");
+        }
+        let mut line = line.trim();
+        if let Ok(number) = line.parse::<usize>()
+            && !recent.is_empty()
+        {
+            match number.checked_sub(1).and_then(|i| recent.get(i)) {
+                Some(username) => line = username,
+                None => {
+                    writeln!(output, "Pick a number from 1 to {}", recent.len())?;
+                    continue;
+                }
+            }
+        }
+        match line.parse::<GitHubUsername>() {
+            Ok(username) => return Ok(username),
+            Err(problem) => writeln!(output, "{}", problem)?,
+        }
+    }
+}
+
 /// Parses a time period string into a `chrono::Duration`.
-fn parse_period(arg: &str) -> Result<Duration, String> {
+pub fn parse_period(arg: &str) -> Result<Duration, String> {
     let (amount, unit) = arg.split_at(
         arg.find(|c: char| !c.is_ascii_digit())
             .ok_or("Invalid period format. Use e.g., 1d, 7d, 30d, 2w, 1m")?,
@@ -115,8 +866,32 @@ fn parse_period(arg: &str) -> Result<Duration, String> {
     }
 }
 
+/// Parses the `backfill --window` length like [`parse_period`], rejecting empty windows, which
+/// would never advance through the range.
+fn parse_window(arg: &str) -> Result<Duration, String> {
+    let window = parse_period(arg)?;
+    if window < Duration::days(1) {
+        return Err("The window must be at least a day".into());
+    }
+    Ok(window)
+}
+
+/// A time period given with --period.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Period {
+    /// A duration ending now.
+    Last(Duration),
+    /// A fixed range defined under [periods] in the config.
+    Named(DateTime<Utc>, DateTime<Utc>),
+}
+
+/// Parses a --period duration; named periods are only known once the config is read.
+fn parse_period_arg(arg: &str) -> Result<Period, String> {
+    parse_period(arg).map(Period::Last)
+}
+
 /// Parses a datetime string in ISO 8601 format
-fn parse_datetime(s: &str) -> Result<DateTime<Utc>, String> {
+pub fn parse_datetime(s: &str) -> Result<DateTime<Utc>, String> {
     // Try parsing with different formats
     if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
         return Ok(dt.with_timezone(&Utc));
@@ -136,11 +911,17 @@ fn parse_datetime(s: &str) -> Result<DateTime<Utc>, String> {
 }
 
 /// Supported output formats.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum OutputFormat {
     Plain,
+    Color,
     Markdown,
     Json,
+    Jsonl,
+    Html,
+    Csv,
+    Slack,
+    Ics,
 }
 
 impl FromStr for OutputFormat {
@@ -148,16 +929,106 @@ impl FromStr for OutputFormat {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "plain" => Ok(OutputFormat::Plain),
+            "color" | "colour" => Ok(OutputFormat::Color),
             "markdown" | "md" => Ok(OutputFormat::Markdown),
             "json" => Ok(OutputFormat::Json),
+            "jsonl" | "ndjson" => Ok(OutputFormat::Jsonl),
+            "html" => Ok(OutputFormat::Html),
+            "csv" => Ok(OutputFormat::Csv),
+            "slack" => Ok(OutputFormat::Slack),
+            "ics" | "ical" | "icalendar" => Ok(OutputFormat::Ics),
+            _ => Err(format!(
+                "Invalid output format: {}. Use plain, color, markdown, json, jsonl, html, csv, slack, or ics",
+                s
+            )),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Infers the output format from a file extension, if it is a recognized one. The
+    /// `.age` extension of encrypted outputs is skipped, e.g. `report.md.age` is markdown.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension().and_then(|s| s.to_str())?;
+        if ext.eq_ignore_ascii_case("age") {
+            return Self::from_path(Path::new(path.file_stem()?));
+        }
+        match ext.to_lowercase().as_str() {
+            "md" | "markdown" => Some(OutputFormat::Markdown),
+            "txt" => Some(OutputFormat::Plain),
+            "json" => Some(OutputFormat::Json),
+            "jsonl" | "ndjson" => Some(OutputFormat::Jsonl),
+            "html" | "htm" => Some(OutputFormat::Html),
+            "csv" => Some(OutputFormat::Csv),
+            "ics" | "ical" => Some(OutputFormat::Ics),
+            _ => None,
+        }
+    }
+
+    /// The file extension of the format, e.g. `md` for markdown.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Plain | OutputFormat::Color => "txt",
+            OutputFormat::Markdown => "md",
+            OutputFormat::Json | OutputFormat::Slack => "json",
+            OutputFormat::Jsonl => "jsonl",
+            OutputFormat::Html => "html",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Ics => "ics",
+        }
+    }
+}
+
+/// States of a submitted pull request review.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReviewState {
+    Approved,
+    ChangesRequested,
+    Commented,
+    Dismissed,
+}
+
+impl FromStr for ReviewState {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "approved" => Ok(ReviewState::Approved),
+            "changes-requested" => Ok(ReviewState::ChangesRequested),
+            "commented" => Ok(ReviewState::Commented),
+            "dismissed" => Ok(ReviewState::Dismissed),
             _ => Err(format!(
-                "Invalid output format: {}. Use plain, markdown, or json",
+                "Invalid review state: {}. Use approved, changes-requested, commented, or dismissed",
                 s
             )),
         }
     }
 }
 
+impl ReviewState {
+    /// The state as GitHub's GraphQL API names it.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewState::Approved => "APPROVED",
+            ReviewState::ChangesRequested => "CHANGES_REQUESTED",
+            ReviewState::Commented => "COMMENTED",
+            ReviewState::Dismissed => "DISMISSED",
+        }
+    }
+}
+
+/// A helper to use the FromStr implementation.
+fn parse_review_state(s: &str) -> Result<ReviewState, String> {
+    s.parse()
+}
+
+/// Parses a `SECTION=PATH` section template.
+fn parse_section_template(s: &str) -> Result<(MarkdownSection, PathBuf), String> {
+    let (section, path) = s
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid section template: {}. Use SECTION=PATH", s))?;
+    Ok((section.trim().parse()?, PathBuf::from(path)))
+}
+
 /// A helper to use the FromStr implementation.
 fn parse_output_format(s: &str) -> Result<OutputFormat, String> {
     s.parse()
@@ -234,6 +1105,16 @@ mod tests {
         assert!(period.is_err());
     }
 
+    #[test]
+    fn test_parse_window_rejects_empty_windows() {
+        assert_eq!(parse_window("2w").unwrap().num_days(), 14);
+        assert!(parse_window("0d").is_err());
+        assert!(parse_window("0m").is_err());
+        assert!(
+            Args::try_parse_from(["github-activity-rs", "backfill", "--window", "0d"]).is_err()
+        );
+    }
+
     #[test]
     fn test_parse_datetime_rfc3339() {
         let dt_str = "2024-01-01T12:34:56Z";
@@ -263,16 +1144,80 @@ mod tests {
     #[test]
     fn test_get_date_range_period() {
         // When period is provided, from/to should be computed relative to now.
-        let period = Some(chrono::Duration::days(7));
+        let period = Some(Period::Last(chrono::Duration::days(7)));
         let args = Args {
-            username: "dummy".parse().unwrap(),
+            command: None,
+            usernames: vec!["dummy".parse().unwrap()],
             period,
             from: None,
             to: None,
+            since_last_run: false,
             repo: None,
             org: None,
+            org_scope: None,
+            labels: vec![],
+            review_states: vec![],
+            review_bodies: false,
+            paths: vec![],
+            codeowners: None,
+            codeowners_from_repo: false,
+            code_owners: vec![],
+            mark_forks: false,
+            exclude_forks: false,
+            explain_filters: false,
+            include_timeline: false,
+            mode: ReportMode::Standard,
+            triage: false,
+            detect_reverts: false,
+            check_commit_emails: false,
+            include_releases: false,
+            review_coverage: false,
+            stats: false,
+            forecast: None,
+            api_url: github::DEFAULT_API_URL.to_string(),
+            persisted_queries: false,
+            store: None,
+            no_cache: false,
+            cache_ttl: cache::DEFAULT_TTL_SECS,
+            issues_page_size: 10,
+            prs_page_size: 10,
+            reviews_page_size: 10,
+            no_wait: false,
+            progress_json: false,
+            no_update_check: false,
             format: OutputFormat::Json,
-            output: None,
+            compact_json: false,
+            calendar_only: false,
+            no_color: false,
+            calendar_detail: false,
+            tone: Tone::Bullet,
+            group_by: GroupBy::None,
+            top: None,
+            sample: None,
+            sample_seed: None,
+            sort: None,
+            template: None,
+            section_templates: vec![],
+            footnote_links: false,
+            stable_markdown: false,
+            locale: NumberLocale::default(),
+            mix_chart: false,
+            calendar: CalendarGranularity::Daily,
+            output: vec![],
+            output_dir: None,
+            parquet_dir: None,
+            encrypt_to: Vec::new(),
+            deliver: None,
+            webhook_url: None,
+            gist_id: None,
+            issue_target: None,
+            deliver_as: SlackPayload::Blocks,
+            dry_run_delivery: false,
+            manifest: None,
+            wait_for_lock: false,
+            config: None,
+            profile: None,
+            account: None,
         };
         let range = args.get_date_range();
         assert!(range.is_ok());
@@ -286,14 +1231,78 @@ mod tests {
         let from = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
         let to = Utc.with_ymd_and_hms(2024, 1, 10, 0, 0, 0).unwrap();
         let args = Args {
-            username: "dummy".parse().unwrap(),
+            command: None,
+            usernames: vec!["dummy".parse().unwrap()],
             period: None,
             from: Some(from),
             to: Some(to),
+            since_last_run: false,
             repo: None,
             org: None,
+            org_scope: None,
+            labels: vec![],
+            review_states: vec![],
+            review_bodies: false,
+            paths: vec![],
+            codeowners: None,
+            codeowners_from_repo: false,
+            code_owners: vec![],
+            mark_forks: false,
+            exclude_forks: false,
+            explain_filters: false,
+            include_timeline: false,
+            mode: ReportMode::Standard,
+            triage: false,
+            detect_reverts: false,
+            check_commit_emails: false,
+            include_releases: false,
+            review_coverage: false,
+            stats: false,
+            forecast: None,
+            api_url: github::DEFAULT_API_URL.to_string(),
+            persisted_queries: false,
+            store: None,
+            no_cache: false,
+            cache_ttl: cache::DEFAULT_TTL_SECS,
+            issues_page_size: 10,
+            prs_page_size: 10,
+            reviews_page_size: 10,
+            no_wait: false,
+            progress_json: false,
+            no_update_check: false,
             format: OutputFormat::Json,
-            output: None,
+            compact_json: false,
+            calendar_only: false,
+            no_color: false,
+            calendar_detail: false,
+            tone: Tone::Bullet,
+            group_by: GroupBy::None,
+            top: None,
+            sample: None,
+            sample_seed: None,
+            sort: None,
+            template: None,
+            section_templates: vec![],
+            footnote_links: false,
+            stable_markdown: false,
+            locale: NumberLocale::default(),
+            mix_chart: false,
+            calendar: CalendarGranularity::Daily,
+            output: vec![],
+            output_dir: None,
+            parquet_dir: None,
+            encrypt_to: Vec::new(),
+            deliver: None,
+            webhook_url: None,
+            gist_id: None,
+            issue_target: None,
+            deliver_as: SlackPayload::Blocks,
+            dry_run_delivery: false,
+            manifest: None,
+            wait_for_lock: false,
+            config: None,
+            profile: None,
+            account: None,
         };
         let range = args.get_date_range();
         assert!(range.is_ok());
@@ -308,14 +1317,78 @@ mod tests {
         let from = Utc.with_ymd_and_hms(2024, 1, 10, 0, 0, 0).unwrap();
         let to = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
         let args = Args {
-            username: "dummy".parse().unwrap(),
+            command: None,
+            usernames: vec!["dummy".parse().unwrap()],
             period: None,
             from: Some(from),
             to: Some(to),
+            since_last_run: false,
             repo: None,
             org: None,
+            org_scope: None,
+            labels: vec![],
+            review_states: vec![],
+            review_bodies: false,
+            paths: vec![],
+            codeowners: None,
+            codeowners_from_repo: false,
+            code_owners: vec![],
+            mark_forks: false,
+            exclude_forks: false,
+            explain_filters: false,
+            include_timeline: false,
+            mode: ReportMode::Standard,
+            triage: false,
+            detect_reverts: false,
+            check_commit_emails: false,
+            include_releases: false,
+            review_coverage: false,
+            stats: false,
+            forecast: None,
+            api_url: github::DEFAULT_API_URL.to_string(),
+            persisted_queries: false,
+            store: None,
+            no_cache: false,
+            cache_ttl: cache::DEFAULT_TTL_SECS,
+            issues_page_size: 10,
+            prs_page_size: 10,
+            reviews_page_size: 10,
+            no_wait: false,
+            progress_json: false,
+            no_update_check: false,
             format: OutputFormat::Json,
-            output: None,
+            compact_json: false,
+            calendar_only: false,
+            no_color: false,
+            calendar_detail: false,
+            tone: Tone::Bullet,
+            group_by: GroupBy::None,
+            top: None,
+            sample: None,
+            sample_seed: None,
+            sort: None,
+            template: None,
+            section_templates: vec![],
+            footnote_links: false,
+            stable_markdown: false,
+            locale: NumberLocale::default(),
+            mix_chart: false,
+            calendar: CalendarGranularity::Daily,
+            output: vec![],
+            output_dir: None,
+            parquet_dir: None,
+            encrypt_to: Vec::new(),
+            deliver: None,
+            webhook_url: None,
+            gist_id: None,
+            issue_target: None,
+            deliver_as: SlackPayload::Blocks,
+            dry_run_delivery: false,
+            manifest: None,
+            wait_for_lock: false,
+            config: None,
+            profile: None,
+            account: None,
         };
         let range = args.get_date_range();
         assert!(range.is_err());
@@ -336,4 +1409,466 @@ mod tests {
         let invalid: Result<OutputFormat, _> = "invalid".parse();
         assert!(invalid.is_err());
     }
+
+    #[test]
+    fn test_review_state_from_str() {
+        let args = Args::try_parse_from([
+            "github-activity-rs",
+            "--username",
+            "octocat",
+            "--review-state",
+            "approved",
+            "--review-state",
+            "CHANGES_REQUESTED",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.review_states,
+            [ReviewState::Approved, ReviewState::ChangesRequested]
+        );
+        assert_eq!(args.review_states[1].as_str(), "CHANGES_REQUESTED");
+        assert!("pending".parse::<ReviewState>().is_err());
+    }
+
+    #[test]
+    fn test_username_required_without_subcommand() {
+        let result = Args::try_parse_from(["github-activity-rs", "--period", "7d"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_render_subcommand() {
+        let args = Args::try_parse_from([
+            "github-activity-rs",
+            "render",
+            "report.json",
+            "--format",
+            "markdown",
+        ])
+        .unwrap();
+        assert!(args.usernames.is_empty());
+        assert_eq!(args.format, OutputFormat::Markdown);
+        match args.command {
+            Some(Command::Render { inputs, identity }) => {
+                assert_eq!(inputs, [PathBuf::from("report.json")]);
+                assert_eq!(identity, None);
+            }
+            other => panic!("Expected render subcommand, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_render_subcommand_takes_several_inputs() {
+        let args =
+            Args::try_parse_from(["github-activity-rs", "render", "alice.json", "-"]).unwrap();
+        match args.command {
+            Some(Command::Render { inputs, .. }) => {
+                assert_eq!(inputs, [PathBuf::from("alice.json"), PathBuf::from("-")]);
+            }
+            other => panic!("Expected render subcommand, got {:?}", other),
+        }
+        assert!(Args::try_parse_from(["github-activity-rs", "render"]).is_err());
+    }
+
+    #[test]
+    fn test_triage_requires_repo_and_timeline() {
+        let base = [
+            "github-activity-rs",
+            "--username",
+            "dummy",
+            "--period",
+            "7d",
+        ];
+        let without_repo =
+            Args::try_parse_from(base.iter().chain(&["--triage", "--include-timeline"]));
+        assert!(without_repo.is_err());
+        let without_timeline =
+            Args::try_parse_from(base.iter().chain(&["--triage", "--repo", "owner/repo"]));
+        assert!(without_timeline.is_err());
+        let args = Args::try_parse_from(base.iter().chain(&[
+            "--triage",
+            "--include-timeline",
+            "--repo",
+            "owner/repo",
+        ]))
+        .unwrap();
+        assert!(args.triage);
+    }
+
+    #[test]
+    fn test_codeowners_sources() {
+        let base = [
+            "github-activity-rs",
+            "--username",
+            "octocat",
+            "--period",
+            "7d",
+        ];
+        let both = Args::try_parse_from(base.iter().chain(&[
+            "--codeowners",
+            "CODEOWNERS",
+            "--codeowners-from-repo",
+            "--repo",
+            "owner/repo",
+        ]));
+        assert!(both.is_err());
+        let owner_without_source =
+            Args::try_parse_from(base.iter().chain(&["--code-owner", "@org/team"]));
+        assert!(owner_without_source.is_err());
+        let args = Args::try_parse_from(base.iter().chain(&[
+            "--codeowners",
+            "CODEOWNERS",
+            "--code-owner",
+            "@org/team",
+        ]))
+        .unwrap();
+        assert_eq!(args.codeowners, Some(PathBuf::from("CODEOWNERS")));
+        assert_eq!(args.code_owners, ["@org/team"]);
+    }
+
+    #[test]
+    fn test_output_format_from_path() {
+        assert_eq!(
+            OutputFormat::from_path(Path::new("report.MD")),
+            Some(OutputFormat::Markdown)
+        );
+        assert_eq!(
+            OutputFormat::from_path(Path::new("out/report.json")),
+            Some(OutputFormat::Json)
+        );
+        assert_eq!(
+            OutputFormat::from_path(Path::new("report.txt")),
+            Some(OutputFormat::Plain)
+        );
+        assert_eq!(
+            OutputFormat::from_path(Path::new("report.html")),
+            Some(OutputFormat::Html)
+        );
+        assert_eq!(
+            OutputFormat::from_path(Path::new("report.csv")),
+            Some(OutputFormat::Csv)
+        );
+        assert_eq!(
+            OutputFormat::from_path(Path::new("activity.ics")),
+            Some(OutputFormat::Ics)
+        );
+        assert_eq!(
+            OutputFormat::from_path(Path::new("export.jsonl")),
+            Some(OutputFormat::Jsonl)
+        );
+        assert_eq!(
+            OutputFormat::from_path(Path::new("out/report.md.age")),
+            Some(OutputFormat::Markdown)
+        );
+        assert_eq!(OutputFormat::from_path(Path::new("report.age")), None);
+        assert_eq!(OutputFormat::from_path(Path::new("report.pdf")), None);
+        assert_eq!(OutputFormat::from_path(Path::new("report")), None);
+    }
+
+    #[test]
+    fn test_deliver_requires_webhook_url() {
+        let base = ["github-activity-rs", "-u", "octocat", "--period", "7d"];
+        let without_url = Args::try_parse_from(base.iter().chain(&["--deliver", "slack"]));
+        assert!(without_url.is_err());
+        let args = Args::try_parse_from(base.iter().chain(&[
+            "--deliver",
+            "slack",
+            "--webhook-url",
+            "https://hooks.slack.com/services/T/B/X",
+            "--deliver-as",
+            "text",
+        ]))
+        .unwrap();
+        assert_eq!(args.deliver, Some(DeliveryTarget::Slack));
+        assert_eq!(args.deliver_as, SlackPayload::Text);
+
+        let args = Args::try_parse_from(base.iter().chain(&["--deliver", "gist"])).unwrap();
+        assert_eq!(args.deliver, Some(DeliveryTarget::Gist));
+        assert!(Args::try_parse_from(base.iter().chain(&["--deliver", "issue"])).is_err());
+        let args = Args::try_parse_from(base.iter().chain(&[
+            "--deliver",
+            "issue",
+            "--target",
+            "octo/reports#7",
+        ]))
+        .unwrap();
+        assert_eq!(args.issue_target.unwrap().number, Some(7));
+    }
+
+    #[test]
+    fn test_watch_schedule() {
+        let base = [
+            "github-activity-rs",
+            "-u",
+            "octocat",
+            "--period",
+            "1d",
+            "watch",
+        ];
+        let args = Args::try_parse_from(base.iter().chain(&["--every", "24h"])).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Watch { every: Some(every), cron: None }) if every == Duration::hours(24)
+        ));
+        let args = Args::try_parse_from(base.iter().chain(&["--cron", "0 8 * * Mon"])).unwrap();
+        assert!(matches!(
+            args.command,
+            Some(Command::Watch {
+                every: None,
+                cron: Some(_)
+            })
+        ));
+        assert!(Args::try_parse_from(base).is_err());
+        assert!(
+            Args::try_parse_from(base.iter().chain(&["--every", "1d", "--cron", "0 8 * * *"]))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_watch_delivery() {
+        let base = [
+            "github-activity-rs",
+            "-u",
+            "octocat",
+            "watch",
+            "--every",
+            "24h",
+            "--deliver",
+        ];
+        let args = Args::try_parse_from(base.iter().chain(&[
+            "slack",
+            "--webhook-url",
+            "https://hooks.slack.com/services/T/B/X",
+            "--deliver-as",
+            "text",
+        ]))
+        .unwrap();
+        assert!(matches!(args.deliver, Some(DeliveryTarget::Slack)));
+        assert_eq!(
+            args.webhook_url.as_deref(),
+            Some("https://hooks.slack.com/services/T/B/X")
+        );
+        assert!(matches!(args.deliver_as, SlackPayload::Text));
+        let args = Args::try_parse_from(base.iter().chain(&["issue", "--target", "octo/reports"]))
+            .unwrap();
+        assert_eq!(args.issue_target.unwrap().repo, "octo/reports");
+        let args =
+            Args::try_parse_from(base.iter().chain(&["gist", "--gist-id", "abc123"])).unwrap();
+        assert_eq!(args.gist_id.as_deref(), Some("abc123"));
+        assert!(Args::try_parse_from(base.iter().chain(&["issue"])).is_err());
+    }
+
+    #[test]
+    fn test_section_template() {
+        let args = Args::try_parse_from([
+            "github-activity-rs",
+            "-u",
+            "octocat",
+            "--section-template",
+            "pull-requests=templates/prs.hbs",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.section_templates,
+            [(
+                MarkdownSection::PullRequests,
+                PathBuf::from("templates/prs.hbs")
+            )]
+        );
+        assert!(parse_section_template("pull-requests").is_err());
+        assert!(parse_section_template("footer=footer.hbs").is_err());
+    }
+
+    #[test]
+    fn test_output_repeated() {
+        let args = Args::try_parse_from([
+            "github-activity-rs",
+            "--username",
+            "dummy",
+            "--period",
+            "7d",
+            "--output",
+            "report.md",
+            "--output",
+            "report.json",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.output,
+            vec![PathBuf::from("report.md"), PathBuf::from("report.json")]
+        );
+    }
+
+    #[test]
+    fn test_username_repeated_or_comma_separated() {
+        let args = Args::try_parse_from([
+            "github-activity-rs",
+            "--username",
+            "alice,bob",
+            "-u",
+            "carol",
+            "--period",
+            "7d",
+        ])
+        .unwrap();
+        let usernames: Vec<String> = args.usernames.iter().map(ToString::to_string).collect();
+        assert_eq!(usernames, ["alice", "bob", "carol"]);
+
+        let invalid = Args::try_parse_from([
+            "github-activity-rs",
+            "--username",
+            "alice,bad_name",
+            "--period",
+            "7d",
+        ]);
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_prompt_username_retries_until_valid() {
+        let mut output = Vec::new();
+        let username = prompt_username(
+            std::io::Cursor::new("bad_name\n\noctocat\n"),
+            &mut output,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(username.0, "octocat");
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("GitHub username: ").count(), 3);
+        assert!(output.contains("Username contains invalid characters"));
+        assert!(output.contains("Username cannot be empty"));
+    }
+
+    #[test]
+    fn test_prompt_username_picks_recent_by_number() {
+        let recent = ["octocat".to_string(), "hubot".to_string()];
+        let mut output = Vec::new();
+        let username =
+            prompt_username(std::io::Cursor::new("3\n2\n"), &mut output, &recent).unwrap();
+        assert_eq!(username.0, "hubot");
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("Recent usernames:\n  1) octocat\n  2) hubot\n"));
+        assert!(output.contains("Pick a number from 1 to 2"));
+        assert_eq!(output.matches("GitHub username or number: ").count(), 2);
+
+        let username =
+            prompt_username(std::io::Cursor::new("monalisa\n"), Vec::new(), &recent).unwrap();
+        assert_eq!(username.0, "monalisa");
+    }
+
+    #[test]
+    fn test_prompt_username_fails_at_end_of_input() {
+        assert!(prompt_username(std::io::Cursor::new(""), std::io::sink(), &[]).is_err());
+    }
+
+    #[test]
+    fn test_named_period_from_config() {
+        let path = std::env::temp_dir().join(format!(
+            "github-activity-periods-{}.toml",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            "[periods]\nsprint-42 = { from = \"2024-05-06\", to = \"2024-05-17\" }\n",
+        )
+        .unwrap();
+        let parse = |period: &str| {
+            let argv = [
+                "github-activity-rs",
+                "-u",
+                "octocat",
+                "--config",
+                path.to_str().unwrap(),
+                "--period",
+                period,
+            ]
+            .map(OsString::from);
+            let command = with_config(Args::command(), argv.clone()).unwrap();
+            command
+                .try_get_matches_from(argv)
+                .and_then(|matches| Args::from_matches(&matches))
+        };
+
+        let args = parse("sprint-42").unwrap();
+        assert_eq!(
+            args.get_date_range().unwrap(),
+            (
+                parse_datetime("2024-05-06").unwrap(),
+                parse_datetime("2024-05-17").unwrap()
+            )
+        );
+        assert_eq!(args.rolling_period(), None);
+        assert_eq!(
+            parse("7d").unwrap().rolling_period(),
+            Some(Duration::days(7))
+        );
+        let err = parse("sprint-43").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("a period from the config: sprint-42")
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_config_options_are_defaults() {
+        let options = BTreeMap::from([
+            ("username".to_string(), vec!["octocat".to_string()]),
+            ("period".to_string(), vec!["7d".to_string()]),
+            ("format".to_string(), vec!["markdown".to_string()]),
+            ("no-wait".to_string(), vec!["true".to_string()]),
+        ]);
+        let command = apply_options(Args::command(), options).unwrap();
+        let parse = |argv: &[&str]| {
+            let matches = command.clone().try_get_matches_from(argv).unwrap();
+            Args::from_matches(&matches).unwrap()
+        };
+
+        let args = parse(&["github-activity-rs", "--format", "json"]);
+        assert_eq!(args.usernames[0].0, "octocat");
+        assert_eq!(args.period, Some(Period::Last(Duration::days(7))));
+        assert_eq!(args.format, OutputFormat::Json);
+        assert!(args.no_wait);
+
+        let args = parse(&[
+            "github-activity-rs",
+            "--from",
+            "2025-03-01",
+            "--to",
+            "2025-03-08",
+        ]);
+        assert_eq!(args.period, None);
+        assert!(args.from.is_some());
+        assert_eq!(args.format, OutputFormat::Markdown);
+
+        let unknown = BTreeMap::from([("colour".to_string(), vec!["always".to_string()])]);
+        assert!(apply_options(Args::command(), unknown).is_err());
+    }
+
+    #[test]
+    fn test_terminal_color_default_skips_output_files() {
+        let command = Args::command().mut_arg("format", |arg| arg.default_value("color"));
+        let parse = |argv: &[&str]| {
+            let matches = command.clone().try_get_matches_from(argv).unwrap();
+            Args::from_matches(&matches).unwrap()
+        };
+
+        let args = parse(&["github-activity-rs", "-u", "octocat"]);
+        assert_eq!(args.format, OutputFormat::Color);
+        let args = parse(&["github-activity-rs", "-u", "octocat", "-o", "out/report"]);
+        assert_eq!(args.format, OutputFormat::Json);
+        let args = parse(&[
+            "github-activity-rs",
+            "-u",
+            "octocat",
+            "-f",
+            "color",
+            "-o",
+            "r",
+        ]);
+        assert_eq!(args.format, OutputFormat::Color);
+    }
 }
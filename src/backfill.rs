@@ -0,0 +1,204 @@
+//! Backfill module: remembers the progress of each user's `backfill` run, fetched one window
+//! at a time, so `backfill --resume` only retries the windows that failed.
+//!
+//! Progress is kept in the `backfill` namespace of the store, keyed by API endpoint and
+//! username, with the activity of every finished window, and saved after each window so an
+//! interrupted run loses at most the window in flight.
+
+use crate::storage::Storage;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use github_activity_rs::github::user_activity;
+use github_activity_rs::merge::merge_activity;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Where a window of a backfill stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowStatus {
+    /// Not fetched yet.
+    Pending,
+    /// Fetched; its activity is stored.
+    Done,
+    /// Fetching failed; retried by `--resume`.
+    Failed,
+}
+
+/// One window of a backfill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Window {
+    /// Start of the window.
+    pub start: DateTime<Utc>,
+    /// End of the window.
+    pub end: DateTime<Utc>,
+    /// Where the window stands.
+    pub status: WindowStatus,
+    /// Why the last attempt failed.
+    pub error: Option<String>,
+    /// The fetched, unfiltered activity, once done.
+    pub activity: Option<user_activity::ResponseData>,
+}
+
+/// The progress of one user's backfill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backfill {
+    /// The user being backfilled.
+    pub username: String,
+    /// The windows of the backfilled range, in order.
+    pub windows: Vec<Window>,
+}
+
+impl Backfill {
+    /// A backfill of `start..end` for `username`, split into consecutive windows of at most
+    /// `window`, each ending a second before the next begins.
+    pub fn plan(
+        username: String,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        window: Duration,
+    ) -> Self {
+        let mut windows = Vec::new();
+        let mut window_start = start;
+        loop {
+            let window_end = window_start + window;
+            let (last, end) = if window_end >= end {
+                (true, end)
+            } else {
+                (false, window_end - Duration::seconds(1))
+            };
+            windows.push(Window {
+                start: window_start,
+                end,
+                status: WindowStatus::Pending,
+                error: None,
+                activity: None,
+            });
+            if last {
+                return Self { username, windows };
+            }
+            window_start = window_end;
+        }
+    }
+
+    /// How many windows failed.
+    pub fn failed(&self) -> usize {
+        self.windows
+            .iter()
+            .filter(|w| w.status == WindowStatus::Failed)
+            .count()
+    }
+
+    /// The activity of the whole range, once every window is done.
+    pub fn merged_activity(&self) -> Option<user_activity::ResponseData> {
+        let mut windows = self.windows.iter();
+        let first = windows.next()?.activity.clone()?;
+        windows.try_fold(first, |merged, window| {
+            Some(merge_activity(merged, window.activity.clone()?))
+        })
+    }
+}
+
+/// Namespace of backfill progress in the store.
+const NAMESPACE: &str = "backfill";
+
+/// Backfill progress in a store.
+pub struct BackfillStore {
+    storage: Storage,
+}
+
+impl BackfillStore {
+    /// Backfills stored in `storage`.
+    pub fn new(storage: Storage) -> Self {
+        Self { storage }
+    }
+
+    /// The key of the backfill of `username` against `api_url`, fetched with `scope`: what else,
+    /// like an organization scope, sets the fetched activity apart.
+    pub fn key(api_url: &str, username: &str, scope: &str) -> String {
+        let digest = Sha256::digest(format!("{}\n{}{}", api_url, username.to_lowercase(), scope));
+        format!("{:x}", digest)
+    }
+
+    /// The backfill stored under `key`, if there is one.
+    pub fn load(&self, key: &str) -> Result<Option<Backfill>> {
+        let Some(json) = self.storage.get(NAMESPACE, key)? else {
+            return Ok(None);
+        };
+        serde_json::from_str(&json)
+            .map(Some)
+            .with_context(|| format!("Failed to parse backfill {}", key))
+    }
+
+    /// Stores `backfill` under `key`, replacing its previous progress.
+    pub fn store(&self, key: &str, backfill: &Backfill) -> Result<()> {
+        let json = serde_json::to_string(backfill).context("Failed to serialize backfill")?;
+        self.storage.put(NAMESPACE, key, &json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_plan_windows() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+        let backfill = Backfill::plan("octocat".into(), start, end, Duration::days(30));
+        let ranges: Vec<_> = backfill.windows.iter().map(|w| (w.start, w.end)).collect();
+        assert_eq!(
+            ranges,
+            [
+                (
+                    start,
+                    Utc.with_ymd_and_hms(2025, 1, 30, 23, 59, 59).unwrap()
+                ),
+                (Utc.with_ymd_and_hms(2025, 1, 31, 0, 0, 0).unwrap(), end),
+            ]
+        );
+        assert!(
+            backfill
+                .windows
+                .iter()
+                .all(|w| w.status == WindowStatus::Pending)
+        );
+        assert!(backfill.merged_activity().is_none());
+    }
+
+    #[test]
+    fn test_store_and_resume() {
+        let store = BackfillStore::new(std::sync::Arc::new(
+            crate::storage::SqliteBackend::in_memory().unwrap(),
+        ));
+        let key = BackfillStore::key("https://api.github.com/graphql", "octocat", "");
+        assert!(store.load(&key).unwrap().is_none());
+
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 1, 3, 0, 0, 0).unwrap();
+        let mut backfill = Backfill::plan("octocat".into(), start, end, Duration::days(1));
+        backfill.windows[0].status = WindowStatus::Done;
+        backfill.windows[0].activity = Some(Default::default());
+        backfill.windows[1].status = WindowStatus::Failed;
+        backfill.windows[1].error = Some("rate limited".into());
+        store.store(&key, &backfill).unwrap();
+
+        let mut resumed = store.load(&key).unwrap().unwrap();
+        assert_eq!(resumed.failed(), 1);
+        assert_eq!(resumed.windows[1].error.as_deref(), Some("rate limited"));
+        assert!(resumed.merged_activity().is_none());
+
+        resumed.windows[1].status = WindowStatus::Done;
+        resumed.windows[1].activity = Some(Default::default());
+        assert_eq!(resumed.failed(), 0);
+        assert!(resumed.merged_activity().is_some());
+
+        let scoped = BackfillStore::key(
+            "https://api.github.com/graphql",
+            "octocat",
+            " with review bodies",
+        );
+        assert!(store.load(&scoped).unwrap().is_none());
+    }
+}
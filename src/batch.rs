@@ -0,0 +1,189 @@
+//! Batch module: parses a TOML file of report specs for the `batch` subcommand.
+//!
+//! Each `[[report]]` entry describes one report:
+//!
+//! ```toml
+//! [[report]]
+//! username = "octocat"
+//! period = "7d"
+//! format = "markdown"
+//! output = "reports/octocat.md"
+//! ```
+
+use crate::args::{GitHubUsername, OutputFormat, parse_datetime, parse_period, resolve_date_range};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A file of report specs.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchSpecs {
+    /// The reports to generate, in order.
+    #[serde(rename = "report", default)]
+    pub reports: Vec<ReportSpec>,
+}
+
+/// A single report entry as written in the specs file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReportSpec {
+    /// GitHub username.
+    pub username: String,
+    /// Time period (e.g., 7d, 2w, 1m); mutually exclusive with `from`/`to`.
+    pub period: Option<String>,
+    /// Start date in ISO 8601 format.
+    pub from: Option<String>,
+    /// End date in ISO 8601 format.
+    pub to: Option<String>,
+    /// Optional repository filter in the format "owner/repo".
+    pub repo: Option<String>,
+    /// Optional organization filter.
+    pub org: Option<String>,
+    /// Output format; inferred from the output extension when omitted.
+    pub format: Option<String>,
+    /// Path the report is written to.
+    pub output: PathBuf,
+}
+
+/// A validated report spec, ready to be fetched.
+#[derive(Debug)]
+pub struct BatchReport {
+    /// GitHub username.
+    pub username: GitHubUsername,
+    /// Length of the reported time range, when given as a period.
+    pub period: Option<Duration>,
+    /// Start of the reported time range.
+    pub start_date: DateTime<Utc>,
+    /// End of the reported time range.
+    pub end_date: DateTime<Utc>,
+    /// Optional repository filter.
+    pub repo: Option<String>,
+    /// Optional organization filter.
+    pub org: Option<String>,
+    /// Explicit output format, if one was given.
+    pub format: Option<OutputFormat>,
+    /// Path the report is written to.
+    pub output: PathBuf,
+}
+
+impl ReportSpec {
+    /// Validates the spec using the same rules as the command-line arguments.
+    pub fn resolve(&self) -> Result<BatchReport, String> {
+        let username = self.username.parse::<GitHubUsername>()?;
+        let period = self.period.as_deref().map(parse_period).transpose()?;
+        let from = self.from.as_deref().map(parse_datetime).transpose()?;
+        let to = self.to.as_deref().map(parse_datetime).transpose()?;
+        let (start_date, end_date) = resolve_date_range(period, from, to)
+            .map_err(|_| "Either specify period or both from and to".to_string())?;
+        let format = self.format.as_deref().map(str::parse).transpose()?;
+
+        Ok(BatchReport {
+            username,
+            period,
+            start_date,
+            end_date,
+            repo: self.repo.clone(),
+            org: self.org.clone(),
+            format,
+            output: self.output.clone(),
+        })
+    }
+}
+
+/// Reads and validates a specs file, failing on the first invalid entry.
+pub fn load_specs(path: &Path) -> Result<Vec<BatchReport>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read batch specs from {:?}", path))?;
+    parse_specs(&contents).with_context(|| format!("Invalid batch specs in {:?}", path))
+}
+
+/// Parses and validates the contents of a specs file.
+fn parse_specs(contents: &str) -> Result<Vec<BatchReport>> {
+    let specs: BatchSpecs = toml::from_str(contents).context("Failed to parse TOML")?;
+    specs
+        .reports
+        .iter()
+        .enumerate()
+        .map(|(i, spec)| {
+            spec.resolve()
+                .map_err(|e| anyhow::anyhow!("report #{} ({}): {}", i + 1, spec.username, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_specs_valid() {
+        let reports = parse_specs(
+            r#"
+            [[report]]
+            username = "octocat"
+            period = "7d"
+            format = "markdown"
+            output = "octocat.md"
+
+            [[report]]
+            username = "hubot"
+            from = "2024-01-01"
+            to = "2024-02-01"
+            org = "github"
+            output = "hubot.json"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].username.0, "octocat");
+        assert_eq!((reports[0].end_date - reports[0].start_date).num_days(), 7);
+        assert_eq!(reports[0].format, Some(OutputFormat::Markdown));
+        assert_eq!(reports[1].org.as_deref(), Some("github"));
+        assert_eq!(reports[1].format, None);
+        assert_eq!(reports[1].output, PathBuf::from("hubot.json"));
+    }
+
+    #[test]
+    fn test_parse_specs_requires_date_range() {
+        let err = parse_specs(
+            r#"
+            [[report]]
+            username = "octocat"
+            output = "octocat.md"
+            "#,
+        )
+        .unwrap_err();
+        assert!(format!("{:#}", err).contains("report #1 (octocat)"));
+    }
+
+    #[test]
+    fn test_parse_specs_rejects_invalid_username() {
+        let result = parse_specs(
+            r#"
+            [[report]]
+            username = "-invalid-"
+            period = "7d"
+            output = "out.md"
+            "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_specs_rejects_unknown_fields() {
+        let result = parse_specs(
+            r#"
+            [[report]]
+            username = "octocat"
+            period = "7d"
+            output = "out.md"
+            colour = "blue"
+            "#,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! Cache module: keeps fetched activity in the store for a while, so re-running a report (e.g. to
+//! try another format) doesn't hit the GitHub API again.
+//!
+//! Entries live in the `activity` namespace of the store and are keyed by API endpoint,
+//! username, and requested time range. Filters are applied after loading, so filtered and
+//! unfiltered runs share entries.
+
+use crate::storage::Storage;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use github_activity_rs::github::user_activity;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::path::PathBuf;
+
+/// How long cached activity stays fresh unless `--cache-ttl` says otherwise.
+pub const DEFAULT_TTL_SECS: i64 = 3600;
+
+/// This tool's directory in the user's cache directory, following the XDG base directory
+/// spec.
+pub fn cache_dir() -> Option<PathBuf> {
+    let cache_dir = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(cache_dir.join("github-activity-rs"))
+}
+
+/// Identifies a requested time range. A relative period is keyed by its length rather than by
+/// its resolved dates, which move with every run.
+pub fn range_key(period: Option<Duration>, start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    match period {
+        Some(period) => format!("last {}s", period.num_seconds()),
+        None => format!("{}..{}", start.to_rfc3339(), end.to_rfc3339()),
+    }
+}
+
+/// Activity as fetched from the API, with the time range it covers.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedActivity {
+    /// When the activity was fetched.
+    pub fetched_at: DateTime<Utc>,
+    /// Start of the fetched time range.
+    pub start_date: DateTime<Utc>,
+    /// End of the fetched time range.
+    pub end_date: DateTime<Utc>,
+    /// The merged, unfiltered activity.
+    pub activity: user_activity::ResponseData,
+}
+
+/// Namespace of cached activity entries in the store.
+const NAMESPACE: &str = "activity";
+
+/// Cached activity entries in a store.
+pub struct ActivityCache {
+    storage: Storage,
+    ttl: Duration,
+}
+
+impl ActivityCache {
+    /// A cache in `storage` whose entries stay fresh for `ttl`.
+    pub fn new(storage: Storage, ttl: Duration) -> Self {
+        Self { storage, ttl }
+    }
+
+    /// The key of the entry for a fetch.
+    pub fn key(api_url: &str, username: &str, range: &str) -> String {
+        let digest = Sha256::digest(format!(
+            "{}\n{}\n{}",
+            api_url,
+            username.to_lowercase(),
+            range
+        ));
+        format!("{:x}", digest)
+    }
+
+    /// The entry stored under `key`, if it exists and is younger than the TTL at `now`.
+    pub fn load(&self, key: &str, now: DateTime<Utc>) -> Option<CachedActivity> {
+        let json = match self.storage.get(NAMESPACE, key) {
+            Ok(json) => json?,
+            Err(err) => {
+                debug!("Ignoring cache entry {}: {:#}", key, err);
+                return None;
+            }
+        };
+        let entry: CachedActivity = match serde_json::from_str(&json) {
+            Ok(entry) => entry,
+            Err(err) => {
+                debug!("Ignoring unreadable cache entry {}: {}", key, err);
+                return None;
+            }
+        };
+        if now - entry.fetched_at >= self.ttl {
+            debug!("Cache entry {} expired", key);
+            return None;
+        }
+        Some(entry)
+    }
+
+    /// Stores `entry` under `key`, replacing any previous entry.
+    pub fn store(&self, key: &str, entry: &CachedActivity) -> Result<()> {
+        let json = serde_json::to_string(entry).context("Failed to serialize cache entry")?;
+        self.storage.put(NAMESPACE, key, &json)
+    }
+
+    /// Deletes every entry, returning how many were removed.
+    pub fn clear(&self) -> Result<usize> {
+        self.storage.clear(NAMESPACE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_store_load_and_clear() {
+        let storage = std::sync::Arc::new(crate::storage::SqliteBackend::in_memory().unwrap());
+        let cache = ActivityCache::new(storage, Duration::hours(1));
+        let fetched_at = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let key = ActivityCache::key("https://api.github.com/graphql", "octocat", "last 604800s");
+        cache
+            .store(
+                &key,
+                &CachedActivity {
+                    fetched_at,
+                    start_date: fetched_at - Duration::days(7),
+                    end_date: fetched_at,
+                    activity: Default::default(),
+                },
+            )
+            .unwrap();
+
+        let fresh = cache.load(&key, fetched_at + Duration::minutes(59));
+        assert_eq!(fresh.unwrap().end_date, fetched_at);
+        assert!(cache.load(&key, fetched_at + Duration::hours(1)).is_none());
+        assert!(cache.load("missing", fetched_at).is_none());
+
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert!(cache.load(&key, fetched_at).is_none());
+    }
+
+    #[test]
+    fn test_key_depends_on_endpoint_user_and_range() {
+        let key = ActivityCache::key("https://api.github.com/graphql", "octocat", "last 604800s");
+        assert_eq!(
+            key,
+            ActivityCache::key("https://api.github.com/graphql", "OctoCat", "last 604800s")
+        );
+        assert_ne!(
+            key,
+            ActivityCache::key("https://api.github.com/graphql", "hubot", "last 604800s")
+        );
+        assert_ne!(
+            key,
+            ActivityCache::key(
+                "https://ghe.example.com/api/graphql",
+                "octocat",
+                "last 604800s"
+            )
+        );
+        assert_ne!(
+            key,
+            ActivityCache::key("https://api.github.com/graphql", "octocat", "last 86400s")
+        );
+    }
+}
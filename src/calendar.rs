@@ -0,0 +1,358 @@
+//! Calendar module: aggregates the daily contribution calendar into weeks or months, so long
+//! reports summarize it instead of listing every day.
+
+use crate::github::user_activity::UserActivityUserContributionsCollection as ContributionsCollection;
+use crate::github::user_activity::UserActivityUserContributionsCollectionContributionCalendar as ContributionCalendar;
+use crate::report::Report;
+use chrono::{Datelike, Days, NaiveDate};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+/// How the contribution calendar is shown in reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CalendarGranularity {
+    /// One entry per day.
+    #[default]
+    Daily,
+    /// One entry per calendar week (GitHub weeks start on Sunday).
+    Weekly,
+    /// One entry per month.
+    Monthly,
+    /// No calendar section at all.
+    Off,
+}
+
+impl FromStr for CalendarGranularity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" => Ok(Self::Daily),
+            "weekly" => Ok(Self::Weekly),
+            "monthly" => Ok(Self::Monthly),
+            "off" => Ok(Self::Off),
+            _ => Err(format!(
+                "Invalid calendar granularity: {}. Use daily, weekly, monthly, or off",
+                s
+            )),
+        }
+    }
+}
+
+/// Contributions within one week or month of the calendar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarPeriod {
+    /// Human-readable period, e.g. `Week of 2025-03-02` or `2025-03`.
+    pub label: String,
+    /// Total contributions in the period.
+    pub contribution_count: i64,
+}
+
+/// Sums the calendar per week or per month, in calendar order; daily and off granularities
+/// yield no periods.
+pub fn calendar_periods(
+    calendar: &ContributionCalendar,
+    granularity: CalendarGranularity,
+) -> Vec<CalendarPeriod> {
+    let mut periods: Vec<CalendarPeriod> = Vec::new();
+    for week in &calendar.weeks {
+        for day in &week.contribution_days {
+            let label = match granularity {
+                CalendarGranularity::Weekly => {
+                    // Label the week by its first day in the calendar.
+                    let first = week
+                        .contribution_days
+                        .first()
+                        .map_or(&day.date, |d| &d.date);
+                    format!("Week of {}", first)
+                }
+                CalendarGranularity::Monthly => day.date.chars().take(7).collect(),
+                CalendarGranularity::Daily | CalendarGranularity::Off => return Vec::new(),
+            };
+            match periods.last_mut() {
+                Some(period) if period.label == label => {
+                    period.contribution_count += day.contribution_count
+                }
+                _ => periods.push(CalendarPeriod {
+                    label,
+                    contribution_count: day.contribution_count,
+                }),
+            }
+        }
+    }
+    periods
+}
+
+/// One day of the contribution calendar, as exported with `--calendar-only`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CalendarDay {
+    /// Whose calendar the day is from, when the days of several reports are exported together.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// The day, `YYYY-MM-DD`.
+    pub date: String,
+    /// Contributions on the day.
+    pub count: i64,
+}
+
+/// Every day of the reports' contribution calendars, in calendar order and report by report;
+/// days are labeled with their username when there are several reports.
+pub fn calendar_days(reports: &[Report]) -> Vec<CalendarDay> {
+    let labeled = reports.len() > 1;
+    let mut days = Vec::new();
+    for report in reports {
+        let Some(user) = &report.activity.user else {
+            continue;
+        };
+        let calendar = &user.contributions_collection.contribution_calendar;
+        for day in calendar
+            .weeks
+            .iter()
+            .flat_map(|week| &week.contribution_days)
+        {
+            days.push(CalendarDay {
+                username: labeled.then(|| report.username.clone()),
+                date: day.date.chars().take(10).collect(),
+                count: day.contribution_count,
+            });
+        }
+    }
+    days
+}
+
+/// Contributions and issue, pull request, and review counts within one week or month.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Rollup {
+    /// Human-readable period, labeled like the calendar periods.
+    pub label: String,
+    /// Total contributions in the period, from the calendar.
+    pub contribution_count: i64,
+    /// Issues opened in the period.
+    pub issues: usize,
+    /// Pull requests opened in the period.
+    pub pull_requests: usize,
+    /// Reviews submitted in the period.
+    pub reviews: usize,
+}
+
+/// The label of the week or month of `date` (`YYYY-MM-DD...`) when the calendar doesn't
+/// cover it: weeks are labeled by their Sunday.
+fn period_label(date: &str, granularity: CalendarGranularity) -> Option<String> {
+    match granularity {
+        CalendarGranularity::Weekly => {
+            let day = NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()?;
+            let sunday =
+                day.checked_sub_days(Days::new(day.weekday().num_days_from_sunday().into()))?;
+            Some(format!("Week of {}", sunday))
+        }
+        CalendarGranularity::Monthly => date.get(..7).map(str::to_string),
+        CalendarGranularity::Daily | CalendarGranularity::Off => None,
+    }
+}
+
+/// Sums the calendar of `cc` per week or per month like `calendar_periods`, and counts the
+/// issues and pull requests opened and reviews submitted in each period, in period order;
+/// daily and off granularities yield no rollups.
+pub fn rollups(cc: &ContributionsCollection, granularity: CalendarGranularity) -> Vec<Rollup> {
+    // Both week and month labels sort in calendar order.
+    let mut rollups: BTreeMap<String, Rollup> =
+        calendar_periods(&cc.contribution_calendar, granularity)
+            .into_iter()
+            .map(|period| {
+                let rollup = Rollup {
+                    label: period.label.clone(),
+                    contribution_count: period.contribution_count,
+                    ..Default::default()
+                };
+                (period.label, rollup)
+            })
+            .collect();
+    if rollups.is_empty() {
+        return Vec::new();
+    }
+
+    // Contributions fall in the calendar period of their day, so both agree on labels.
+    let mut labels: HashMap<&str, String> = HashMap::new();
+    for week in &cc.contribution_calendar.weeks {
+        for day in &week.contribution_days {
+            let label = match granularity {
+                CalendarGranularity::Weekly => week
+                    .contribution_days
+                    .first()
+                    .map(|first| format!("Week of {}", first.date)),
+                _ => period_label(&day.date, granularity),
+            };
+            if let (Some(date), Some(label)) = (day.date.get(..10), label) {
+                labels.insert(date, label);
+            }
+        }
+    }
+    let label_of = |date: &str| {
+        date.get(..10)
+            .and_then(|day| labels.get(day).cloned())
+            .or_else(|| period_label(date, granularity))
+            .unwrap_or_default()
+    };
+    fn entry(rollups: &mut BTreeMap<String, Rollup>, label: String) -> &mut Rollup {
+        rollups.entry(label.clone()).or_insert_with(|| Rollup {
+            label,
+            ..Default::default()
+        })
+    }
+    for node in cc.issue_contributions.nodes.iter().flatten() {
+        entry(&mut rollups, label_of(&node.issue.created_at)).issues += 1;
+    }
+    for node in cc.pull_request_contributions.nodes.iter().flatten() {
+        entry(&mut rollups, label_of(&node.pull_request.created_at)).pull_requests += 1;
+    }
+    for node in cc.pull_request_review_contributions.nodes.iter().flatten() {
+        entry(&mut rollups, label_of(&node.occurred_at)).reviews += 1;
+    }
+    rollups.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn calendar() -> ContributionCalendar {
+        let day = |date: &str, count: i64| json!({ "date": date, "contributionCount": count, "weekday": 0 });
+        serde_json::from_value(json!({
+            "totalContributions": 10,
+            "weeks": [
+                { "contributionDays": [day("2025-02-23", 1), day("2025-02-28", 2)] },
+                { "contributionDays": [day("2025-03-01", 3)] },
+                { "contributionDays": [day("2025-03-02", 4)] }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_calendar_periods() {
+        let counts = |granularity| {
+            calendar_periods(&calendar(), granularity)
+                .into_iter()
+                .map(|p| (p.label, p.contribution_count))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            counts(CalendarGranularity::Weekly),
+            [
+                ("Week of 2025-02-23".to_string(), 3),
+                ("Week of 2025-03-01".to_string(), 3),
+                ("Week of 2025-03-02".to_string(), 4)
+            ]
+        );
+        assert_eq!(
+            counts(CalendarGranularity::Monthly),
+            [("2025-02".to_string(), 3), ("2025-03".to_string(), 7)]
+        );
+        assert!(counts(CalendarGranularity::Daily).is_empty());
+    }
+
+    #[test]
+    fn test_rollups() {
+        let page = |nodes: serde_json::Value| json!({ "totalCount": 1, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes });
+        let cc: ContributionsCollection = serde_json::from_value(json!({
+            "totalCommitContributions": 10,
+            "totalIssueContributions": 1,
+            "totalPullRequestContributions": 1,
+            "totalPullRequestReviewContributions": 1,
+            "contributionCalendar": serde_json::to_value(calendar()).unwrap(),
+            "commitContributionsByRepository": [],
+            "issueContributions": page(json!([{ "issue": {
+                "id": "I_1", "number": 1, "title": "Issue", "url": "https://github.com/o/r/issues/1",
+                "createdAt": "2025-02-28T10:00:00Z", "state": "OPEN", "closedAt": null
+            } }])),
+            "pullRequestContributions": page(json!([{ "pullRequest": {
+                "number": 2, "title": "PR", "url": "https://github.com/o/r/pull/2",
+                "createdAt": "2025-03-02T10:00:00Z", "state": "OPEN", "merged": false,
+                "mergedAt": null, "mergeCommit": null, "closedAt": null,
+                "additions": 1, "deletions": 1, "changedFiles": 1
+            } }])),
+            "pullRequestReviewContributions": page(json!([{
+                "pullRequestReview": { "pullRequest": {
+                    "number": 3, "title": "Reviewed", "url": "https://github.com/o/r/pull/3"
+                }, "state": "APPROVED" },
+                "occurredAt": "2025-03-20T10:00:00Z"
+            }]))
+        }))
+        .unwrap();
+        let rows = |granularity| {
+            rollups(&cc, granularity)
+                .into_iter()
+                .map(|r| {
+                    (
+                        r.label,
+                        r.contribution_count,
+                        r.issues,
+                        r.pull_requests,
+                        r.reviews,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            rows(CalendarGranularity::Weekly),
+            [
+                ("Week of 2025-02-23".to_string(), 3, 1, 0, 0),
+                ("Week of 2025-03-01".to_string(), 3, 0, 0, 0),
+                ("Week of 2025-03-02".to_string(), 4, 0, 1, 0),
+                // Outside the calendar, so labeled by its Sunday.
+                ("Week of 2025-03-16".to_string(), 0, 0, 0, 1),
+            ]
+        );
+        assert_eq!(
+            rows(CalendarGranularity::Monthly),
+            [
+                ("2025-02".to_string(), 3, 1, 0, 0),
+                ("2025-03".to_string(), 7, 0, 1, 1)
+            ]
+        );
+        assert!(rows(CalendarGranularity::Off).is_empty());
+    }
+
+    #[test]
+    fn test_calendar_days() {
+        let report = |username: &str| {
+            let page = json!({ "totalCount": 0, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": [] });
+            let activity = serde_json::from_value(json!({ "user": { "contributionsCollection": {
+                "totalCommitContributions": 0,
+                "totalIssueContributions": 0,
+                "totalPullRequestContributions": 0,
+                "totalPullRequestReviewContributions": 0,
+                "contributionCalendar": serde_json::to_value(calendar()).unwrap(),
+                "commitContributionsByRepository": [],
+                "issueContributions": page,
+                "pullRequestContributions": page,
+                "pullRequestReviewContributions": page
+            } }, "rateLimit": null }))
+            .unwrap();
+            let now = chrono::Utc::now();
+            Report::new(username.into(), now, now, activity)
+        };
+        let days = calendar_days(&[report("octocat")]);
+        assert_eq!(days.len(), 4);
+        assert_eq!(
+            serde_json::to_value(&days[..2]).unwrap(),
+            json!([
+                { "date": "2025-02-23", "count": 1 },
+                { "date": "2025-02-28", "count": 2 }
+            ])
+        );
+
+        let days = calendar_days(&[report("octocat"), report("hubot")]);
+        assert_eq!(days.len(), 8);
+        assert_eq!(days[4].username.as_deref(), Some("hubot"));
+        assert_eq!(days[4].date, "2025-02-23");
+    }
+
+    #[test]
+    fn test_parse_granularity() {
+        assert_eq!("Weekly".parse(), Ok(CalendarGranularity::Weekly));
+        assert!("yearly".parse::<CalendarGranularity>().is_err());
+    }
+}
@@ -0,0 +1,276 @@
+//! Category module: tags issues, pull requests, and reviews with custom categories (e.g.
+//! feature, bugfix, ops, mentoring) by rules matching their repository, labels, or title, so
+//! reports can be broken down the way sprint reports are.
+
+use crate::filter::label_names;
+use crate::github::user_activity;
+use crate::github::user_activity::UserActivityUserContributionsCollection as ContributionsCollection;
+use crate::revert::repository_of;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Categories of contributions, keyed by issue or pull request URL; a review shares the
+/// category of its pull request.
+pub type Categories = BTreeMap<String, String>;
+
+/// The category of contributions no rule matched.
+pub const UNCATEGORIZED: &str = "uncategorized";
+
+/// A rule tagging the contributions it matches with a category.
+#[derive(Debug, Clone)]
+pub struct CategoryRule {
+    /// The category, e.g. `bugfix`.
+    pub name: String,
+    /// Repositories as `owner/name`, or `owner/*` for all of an owner's repositories.
+    pub repos: Vec<String>,
+    /// Labels, compared case-insensitively.
+    pub labels: Vec<String>,
+    /// Patterns searched for in the title.
+    pub titles: Vec<Regex>,
+}
+
+impl CategoryRule {
+    /// A rule for category `name`, with `titles` compiled as regular expressions.
+    pub fn new(name: &str, repos: &[String], labels: &[String], titles: &[String]) -> Result<Self> {
+        let titles = titles
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).with_context(|| {
+                    format!("Invalid title pattern {:?} of category {}", pattern, name)
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            name: name.to_string(),
+            repos: repos.to_vec(),
+            labels: labels.to_vec(),
+            titles,
+        })
+    }
+
+    /// Whether a contribution with `url`, `labels`, and `title` matches any of the rule's
+    /// repositories, labels, or title patterns.
+    fn matches(&self, url: &str, labels: &[&str], title: &str) -> bool {
+        let repository = repository_of(url).unwrap_or_default();
+        self.repos.iter().any(|repo| match repo.strip_suffix("/*") {
+            Some(owner) => repository
+                .split_once('/')
+                .is_some_and(|(o, _)| o.eq_ignore_ascii_case(owner)),
+            None => repository.eq_ignore_ascii_case(repo),
+        }) || self
+            .labels
+            .iter()
+            .any(|label| labels.iter().any(|l| l.eq_ignore_ascii_case(label)))
+            || self.titles.iter().any(|pattern| pattern.is_match(title))
+    }
+}
+
+/// The category of a contribution with `url`, `labels`, and `title`: the first rule that
+/// matches, in order.
+fn category_of<'a>(
+    rules: &'a [CategoryRule],
+    url: &str,
+    labels: &[&str],
+    title: &str,
+) -> Option<&'a str> {
+    rules
+        .iter()
+        .find(|rule| rule.matches(url, labels, title))
+        .map(|rule| rule.name.as_str())
+}
+
+/// The categories of the issues, pull requests, and reviewed pull requests in `activity`
+/// that a rule matches.
+pub fn categorize(activity: &user_activity::ResponseData, rules: &[CategoryRule]) -> Categories {
+    let mut categories = Categories::new();
+    let Some(user) = &activity.user else {
+        return categories;
+    };
+    let cc = &user.contributions_collection;
+    let contributions = cc
+        .issue_contributions
+        .nodes
+        .iter()
+        .flatten()
+        .map(|node| (&node.issue.url, &node.issue.labels, &node.issue.title))
+        .chain(
+            cc.pull_request_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(|node| {
+                    let pr = &node.pull_request;
+                    (&pr.url, &pr.labels, &pr.title)
+                }),
+        )
+        .chain(
+            cc.pull_request_review_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(|node| {
+                    let pr = &node.pull_request_review.pull_request;
+                    (&pr.url, &pr.labels, &pr.title)
+                }),
+        );
+    for (url, labels, title) in contributions {
+        if let Some(category) = category_of(rules, url, &label_names(labels), title) {
+            categories.insert(url.clone(), category.to_string());
+        }
+    }
+    categories
+}
+
+/// Issue, pull request, and review counts of one category.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CategoryCounts {
+    /// The category.
+    pub name: String,
+    /// Issues opened.
+    pub issues: usize,
+    /// Pull requests opened.
+    pub pull_requests: usize,
+    /// Reviews submitted.
+    pub reviews: usize,
+}
+
+/// The contributions in `cc` per category, in name order with uncategorized ones last; empty
+/// when nothing was categorized.
+pub fn breakdown(cc: &ContributionsCollection, categories: &Categories) -> Vec<CategoryCounts> {
+    if categories.is_empty() {
+        return Vec::new();
+    }
+    fn entry<'a>(
+        counts: &'a mut BTreeMap<String, CategoryCounts>,
+        categories: &Categories,
+        url: &str,
+    ) -> &'a mut CategoryCounts {
+        let name = categories.get(url).map_or(UNCATEGORIZED, String::as_str);
+        counts
+            .entry(name.to_string())
+            .or_insert_with(|| CategoryCounts {
+                name: name.to_string(),
+                ..Default::default()
+            })
+    }
+    let mut counts = BTreeMap::new();
+    for node in cc.issue_contributions.nodes.iter().flatten() {
+        entry(&mut counts, categories, &node.issue.url).issues += 1;
+    }
+    for node in cc.pull_request_contributions.nodes.iter().flatten() {
+        entry(&mut counts, categories, &node.pull_request.url).pull_requests += 1;
+    }
+    for node in cc.pull_request_review_contributions.nodes.iter().flatten() {
+        let url = &node.pull_request_review.pull_request.url;
+        entry(&mut counts, categories, url).reviews += 1;
+    }
+    let uncategorized = counts.remove(UNCATEGORIZED);
+    counts.into_values().chain(uncategorized).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn activity() -> user_activity::ResponseData {
+        let labels = |names: &[&str]| {
+            let nodes: Vec<_> = names.iter().map(|name| json!({ "name": name })).collect();
+            json!({ "nodes": nodes })
+        };
+        let issue = |number: i64, repo: &str, title: &str, label: &[&str]| {
+            json!({ "issue": {
+                "id": format!("I_{}", number), "number": number, "title": title, "url": format!("https://github.com/{}/issues/{}", repo, number),
+                "createdAt": "2025-03-02T00:00:00Z", "state": "OPEN", "closedAt": null, "labels": labels(label)
+            } })
+        };
+        let pr = |number: i64, repo: &str, title: &str, label: &[&str]| {
+            json!({ "pullRequest": {
+                "number": number, "title": title, "url": format!("https://github.com/{}/pull/{}", repo, number),
+                "createdAt": "2025-03-02T00:00:00Z", "state": "OPEN", "merged": false, "mergedAt": null,
+                "mergeCommit": null, "closedAt": null, "additions": 1, "deletions": 1, "changedFiles": 1, "labels": labels(label)
+            } })
+        };
+        let page = |nodes: Vec<serde_json::Value>| json!({ "totalCount": nodes.len(), "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes });
+        serde_json::from_value(json!({ "user": { "contributionsCollection": {
+            "totalCommitContributions": 0,
+            "totalIssueContributions": 2,
+            "totalPullRequestContributions": 2,
+            "totalPullRequestReviewContributions": 1,
+            "contributionCalendar": { "totalContributions": 0, "weeks": [] },
+            "commitContributionsByRepository": [],
+            "issueContributions": page(vec![
+                issue(1, "octo/api", "Crash on start", &["Bug"]),
+                issue(2, "octo/docs", "Typo", &[])
+            ]),
+            "pullRequestContributions": page(vec![
+                pr(3, "octo/api", "fix: crash on start", &[]),
+                pr(4, "infra/deploy", "Bump runners", &[])
+            ]),
+            "pullRequestReviewContributions": page(vec![json!({
+                "pullRequestReview": { "pullRequest": {
+                    "number": 5, "title": "Add search", "url": "https://github.com/octo/api/pull/5",
+                    "labels": labels(&["feature"])
+                }, "state": "APPROVED" },
+                "occurredAt": "2025-03-03T00:00:00Z"
+            })])
+        } }, "rateLimit": null }))
+        .unwrap()
+    }
+
+    fn rules() -> Vec<CategoryRule> {
+        let strings = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        vec![
+            CategoryRule::new("bugfix", &[], &strings(&["bug"]), &strings(&["^fix"])).unwrap(),
+            CategoryRule::new("ops", &strings(&["infra/*"]), &[], &[]).unwrap(),
+            CategoryRule::new("feature", &[], &strings(&["feature"]), &[]).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_categorize() {
+        let categories = categorize(&activity(), &rules());
+        let category = |url: &str| categories.get(url).map(String::as_str);
+        assert_eq!(
+            category("https://github.com/octo/api/issues/1"),
+            Some("bugfix")
+        );
+        assert_eq!(
+            category("https://github.com/octo/api/pull/3"),
+            Some("bugfix")
+        );
+        assert_eq!(
+            category("https://github.com/infra/deploy/pull/4"),
+            Some("ops")
+        );
+        assert_eq!(
+            category("https://github.com/octo/api/pull/5"),
+            Some("feature")
+        );
+        assert_eq!(category("https://github.com/octo/docs/issues/2"), None);
+
+        let err = CategoryRule::new("broken", &[], &[], &["(".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("of category broken"));
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let activity = activity();
+        let cc = &activity.user.as_ref().unwrap().contributions_collection;
+        assert!(breakdown(cc, &Categories::new()).is_empty());
+        let counts: Vec<_> = breakdown(cc, &categorize(&activity, &rules()))
+            .into_iter()
+            .map(|c| (c.name, c.issues, c.pull_requests, c.reviews))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ("bugfix".to_string(), 1, 1, 0),
+                ("feature".to_string(), 0, 0, 1),
+                ("ops".to_string(), 0, 1, 0),
+                ("uncategorized".to_string(), 1, 0, 0),
+            ]
+        );
+    }
+}
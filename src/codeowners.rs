@@ -0,0 +1,125 @@
+//! CODEOWNERS module: parses a CODEOWNERS file so activity can be scoped to the paths a user
+//! or team owns.
+//!
+//! Patterns follow GitHub's rules: they are gitignore-style, and the last matching line
+//! decides a path's owners.
+
+use anyhow::{Context, Result};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+/// Locations GitHub looks for a CODEOWNERS file in, in order of precedence.
+pub const CODEOWNERS_PATHS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// A parsed CODEOWNERS file.
+pub struct CodeOwners {
+    rules: Vec<(GlobSet, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// Parses the contents of a CODEOWNERS file.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.split_once('#').map_or(line, |(rule, _)| rule).trim();
+            let mut tokens = line.split_whitespace();
+            let Some(pattern) = tokens.next() else {
+                continue;
+            };
+            let owners = tokens.map(str::to_lowercase).collect();
+            let globs = pattern_globs(pattern)
+                .with_context(|| format!("Invalid CODEOWNERS pattern on line {}", i + 1))?;
+            rules.push((globs, owners));
+        }
+        Ok(Self { rules })
+    }
+
+    /// The owners of `path`, as listed by the last matching rule.
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(globs, _)| globs.is_match(path))
+            .map(|(_, owners)| owners.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Whether `path` is owned by any of `owners` (e.g. `@octocat` or `@org/team`).
+    pub fn is_owned_by(&self, path: &str, owners: &[String]) -> bool {
+        self.owners_of(path)
+            .iter()
+            .any(|owner| owners.iter().any(|o| o.eq_ignore_ascii_case(owner)))
+    }
+}
+
+/// Translates a gitignore-style CODEOWNERS pattern into globs matching repository paths.
+fn pattern_globs(pattern: &str) -> Result<GlobSet> {
+    let directory_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_matches('/');
+    // A pattern with a slash anywhere but at the end is relative to the repository root;
+    // otherwise it matches at any depth.
+    let anchored = pattern.starts_with('/') || trimmed.contains('/');
+    let base = if anchored || trimmed.starts_with("**") {
+        trimmed.to_string()
+    } else {
+        format!("**/{}", trimmed)
+    };
+
+    let mut candidates = vec![format!("{}/**", base)];
+    if !directory_only {
+        candidates.push(base);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for candidate in candidates {
+        builder.add(
+            GlobBuilder::new(&candidate)
+                .literal_separator(true)
+                .build()?,
+        );
+    }
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEOWNERS: &str = "
+# Default owners
+*                       @org/core
+*.md                    @docs-writer
+/packages/frontend/     @octocat @org/web
+apps/**/config.yml      @ops  # deploy configs
+/packages/frontend/legacy/
+";
+
+    #[test]
+    fn test_owners_of_uses_last_matching_rule() {
+        let owners = CodeOwners::parse(CODEOWNERS).unwrap();
+        assert_eq!(owners.owners_of("src/main.rs"), ["@org/core"]);
+        assert_eq!(
+            owners.owners_of("packages/frontend/README.md"),
+            ["@octocat", "@org/web"]
+        );
+        assert_eq!(owners.owners_of("docs/guide.md"), ["@docs-writer"]);
+        assert_eq!(owners.owners_of("apps/api/prod/config.yml"), ["@ops"]);
+        // A rule without owners un-assigns the path.
+        assert!(
+            owners
+                .owners_of("packages/frontend/legacy/old.js")
+                .is_empty()
+        );
+        // Anchored patterns only match at the root.
+        assert_eq!(
+            owners.owners_of("vendor/packages/frontend/x.js"),
+            ["@org/core"]
+        );
+    }
+
+    #[test]
+    fn test_is_owned_by() {
+        let owners = CodeOwners::parse(CODEOWNERS).unwrap();
+        assert!(owners.is_owned_by("packages/frontend/app.ts", &["@OctoCat".to_string()]));
+        assert!(!owners.is_owned_by("src/main.rs", &["@octocat".to_string()]));
+    }
+}
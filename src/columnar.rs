@@ -0,0 +1,367 @@
+//! Columnar module: the contribution nodes of reports as Arrow tables, written as Parquet
+//! files for analytics pipelines like DuckDB or Spark. Only built with the `parquet` feature.
+//!
+//! The tables mirror the CSV export's, with a leading `username` column so the reports of
+//! several users share one set of files, and typed columns: counts are integers, `merged` is a
+//! boolean, and dates are UTC timestamps.
+
+use crate::report::Report;
+use crate::revert::repository_of;
+use anyhow::{Context, Result};
+use arrow_array::{
+    ArrayRef, BooleanArray, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray,
+};
+use chrono::DateTime;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::sync::Arc;
+
+/// A table of the Parquet export.
+pub struct ParquetTable {
+    /// Table name, used as the file stem.
+    pub name: &'static str,
+    /// The table's rows.
+    pub batch: RecordBatch,
+}
+
+impl ParquetTable {
+    /// Encodes the table as a Snappy-compressed Parquet file.
+    pub fn to_parquet(&self) -> Result<Vec<u8>> {
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        let mut buffer = Vec::new();
+        let mut writer =
+            ArrowWriter::try_new(&mut buffer, self.batch.schema(), Some(properties))
+                .with_context(|| format!("Failed to start the {} Parquet table", self.name))?;
+        writer
+            .write(&self.batch)
+            .with_context(|| format!("Failed to write the {} Parquet table", self.name))?;
+        writer
+            .close()
+            .with_context(|| format!("Failed to finish the {} Parquet table", self.name))?;
+        Ok(buffer)
+    }
+}
+
+/// The repository, issue, pull request, and review tables of `reports`.
+pub fn tables(reports: &[Report]) -> Result<Vec<ParquetTable>> {
+    let collections: Vec<_> = reports
+        .iter()
+        .filter_map(|report| {
+            let user = report.activity.user.as_ref()?;
+            Some((report.username.as_str(), &user.contributions_collection))
+        })
+        .collect();
+    let repositories: Vec<_> = collections
+        .iter()
+        .flat_map(|(username, cc)| {
+            cc.commit_contributions_by_repository
+                .iter()
+                .map(move |contribution| (*username, contribution))
+        })
+        .collect();
+    let issues: Vec<_> = collections
+        .iter()
+        .flat_map(|(username, cc)| {
+            cc.issue_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(move |node| (*username, &node.issue))
+        })
+        .collect();
+    let pull_requests: Vec<_> = collections
+        .iter()
+        .flat_map(|(username, cc)| {
+            cc.pull_request_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(move |node| (*username, &node.pull_request))
+        })
+        .collect();
+    let reviews: Vec<_> = collections
+        .iter()
+        .flat_map(|(username, cc)| {
+            cc.pull_request_review_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(move |node| (*username, node))
+        })
+        .collect();
+    let repository = |url: &str| repository_of(url).unwrap_or_default();
+
+    let tables = [
+        (
+            "repositories",
+            vec![
+                ("username", text(repositories.iter().map(|r| r.0)), false),
+                (
+                    "repository",
+                    text(repositories.iter().map(|r| &r.1.repository.name_with_owner)),
+                    false,
+                ),
+                (
+                    "commits",
+                    integer(repositories.iter().map(|r| r.1.contributions.total_count)),
+                    false,
+                ),
+            ],
+        ),
+        (
+            "issues",
+            vec![
+                ("username", text(issues.iter().map(|i| i.0)), false),
+                (
+                    "repository",
+                    text(issues.iter().map(|i| repository(&i.1.url))),
+                    false,
+                ),
+                ("number", integer(issues.iter().map(|i| i.1.number)), false),
+                ("title", text(issues.iter().map(|i| &i.1.title)), false),
+                ("url", text(issues.iter().map(|i| &i.1.url)), false),
+                (
+                    "created_at",
+                    timestamp(issues.iter().map(|i| Some(&i.1.created_at))),
+                    true,
+                ),
+                ("state", text(issues.iter().map(|i| &i.1.state)), false),
+                (
+                    "closed_at",
+                    timestamp(issues.iter().map(|i| i.1.closed_at.as_ref())),
+                    true,
+                ),
+            ],
+        ),
+        (
+            "pull_requests",
+            vec![
+                ("username", text(pull_requests.iter().map(|p| p.0)), false),
+                (
+                    "repository",
+                    text(pull_requests.iter().map(|p| repository(&p.1.url))),
+                    false,
+                ),
+                (
+                    "number",
+                    integer(pull_requests.iter().map(|p| p.1.number)),
+                    false,
+                ),
+                (
+                    "title",
+                    text(pull_requests.iter().map(|p| &p.1.title)),
+                    false,
+                ),
+                ("url", text(pull_requests.iter().map(|p| &p.1.url)), false),
+                (
+                    "created_at",
+                    timestamp(pull_requests.iter().map(|p| Some(&p.1.created_at))),
+                    true,
+                ),
+                (
+                    "state",
+                    text(pull_requests.iter().map(|p| &p.1.state)),
+                    false,
+                ),
+                (
+                    "merged",
+                    boolean(pull_requests.iter().map(|p| p.1.merged)),
+                    false,
+                ),
+                (
+                    "merged_at",
+                    timestamp(pull_requests.iter().map(|p| p.1.merged_at.as_ref())),
+                    true,
+                ),
+                (
+                    "closed_at",
+                    timestamp(pull_requests.iter().map(|p| p.1.closed_at.as_ref())),
+                    true,
+                ),
+                (
+                    "additions",
+                    integer(pull_requests.iter().map(|p| p.1.additions)),
+                    false,
+                ),
+                (
+                    "deletions",
+                    integer(pull_requests.iter().map(|p| p.1.deletions)),
+                    false,
+                ),
+                (
+                    "changed_files",
+                    integer(pull_requests.iter().map(|p| p.1.changed_files)),
+                    false,
+                ),
+            ],
+        ),
+        (
+            "reviews",
+            vec![
+                ("username", text(reviews.iter().map(|r| r.0)), false),
+                (
+                    "repository",
+                    text(
+                        reviews
+                            .iter()
+                            .map(|r| repository(&r.1.pull_request_review.pull_request.url)),
+                    ),
+                    false,
+                ),
+                (
+                    "number",
+                    integer(
+                        reviews
+                            .iter()
+                            .map(|r| r.1.pull_request_review.pull_request.number),
+                    ),
+                    false,
+                ),
+                (
+                    "title",
+                    text(
+                        reviews
+                            .iter()
+                            .map(|r| &r.1.pull_request_review.pull_request.title),
+                    ),
+                    false,
+                ),
+                (
+                    "url",
+                    text(
+                        reviews
+                            .iter()
+                            .map(|r| &r.1.pull_request_review.pull_request.url),
+                    ),
+                    false,
+                ),
+                (
+                    "occurred_at",
+                    timestamp(reviews.iter().map(|r| Some(&r.1.occurred_at))),
+                    true,
+                ),
+                (
+                    "state",
+                    text(reviews.iter().map(|r| &r.1.pull_request_review.state)),
+                    false,
+                ),
+            ],
+        ),
+    ];
+    tables
+        .into_iter()
+        .map(|(name, columns)| {
+            let batch = RecordBatch::try_from_iter_with_nullable(columns)
+                .with_context(|| format!("Failed to build the {} table", name))?;
+            Ok(ParquetTable { name, batch })
+        })
+        .collect()
+}
+
+/// A column of strings.
+fn text<S: AsRef<str>>(values: impl Iterator<Item = S>) -> ArrayRef {
+    Arc::new(StringArray::from_iter_values(values))
+}
+
+/// A column of integers.
+fn integer(values: impl Iterator<Item = i64>) -> ArrayRef {
+    Arc::new(Int64Array::from_iter_values(values))
+}
+
+/// A column of booleans.
+fn boolean(values: impl Iterator<Item = bool>) -> ArrayRef {
+    Arc::new(values.map(Some).collect::<BooleanArray>())
+}
+
+/// A column of UTC timestamps parsed from GitHub's RFC 3339 dates; missing or unparsable
+/// dates are null.
+fn timestamp<'a>(values: impl Iterator<Item = Option<&'a String>>) -> ArrayRef {
+    let micros = values.map(|value| {
+        value
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .map(|time| time.timestamp_micros())
+    });
+    Arc::new(
+        micros
+            .collect::<TimestampMicrosecondArray>()
+            .with_timezone("UTC"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+    use chrono::{TimeZone, Utc};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use serde_json::json;
+
+    #[test]
+    fn test_tables_to_parquet() {
+        let page = |nodes: serde_json::Value| json!({ "totalCount": 1, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes });
+        let activity = serde_json::from_value(json!({ "user": { "contributionsCollection": {
+            "totalCommitContributions": 3,
+            "totalIssueContributions": 0,
+            "totalPullRequestContributions": 1,
+            "totalPullRequestReviewContributions": 0,
+            "contributionCalendar": { "totalContributions": 4, "weeks": [] },
+            "commitContributionsByRepository": [{
+                "repository": { "nameWithOwner": "o/b", "updatedAt": "2025-03-02T00:00:00Z" },
+                "contributions": { "totalCount": 3 }
+            }],
+            "issueContributions": page(json!([])),
+            "pullRequestContributions": page(json!([{ "pullRequest": {
+                "number": 2, "title": "PR", "url": "https://github.com/o/b/pull/2",
+                "createdAt": "2025-03-02T10:00:00Z", "state": "MERGED", "merged": true,
+                "mergedAt": "2025-03-03T10:00:00Z", "mergeCommit": null, "closedAt": null,
+                "additions": 5, "deletions": 1, "changedFiles": 2
+            } }])),
+            "pullRequestReviewContributions": page(json!([]))
+        } } }))
+        .unwrap();
+        let (start, end) = (
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 8, 0, 0, 0).unwrap(),
+        );
+        let report = Report::new("octocat".into(), start, end, activity);
+
+        let tables = tables(&[report.clone(), report]).unwrap();
+        let names: Vec<_> = tables.iter().map(|table| table.name).collect();
+        assert_eq!(
+            names,
+            ["repositories", "issues", "pull_requests", "reviews"]
+        );
+        let pull_requests = &tables[2].batch;
+        assert_eq!(pull_requests.num_rows(), 2);
+        let merged_at = pull_requests
+            .column_by_name("merged_at")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .unwrap();
+        assert_eq!(
+            merged_at.value(0),
+            Utc.with_ymd_and_hms(2025, 3, 3, 10, 0, 0)
+                .unwrap()
+                .timestamp_micros()
+        );
+        assert!(
+            pull_requests
+                .column_by_name("closed_at")
+                .unwrap()
+                .is_null(0)
+        );
+
+        let path = std::env::temp_dir().join(format!(
+            "github-activity-rs-test-{}-pull_requests.parquet",
+            std::process::id()
+        ));
+        std::fs::write(&path, tables[2].to_parquet().unwrap()).unwrap();
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,417 @@
+//! Compare module: lays out the contribution calendars of two periods, e.g. this quarter and
+//! the last, side by side with a difference overlay, in the terminal or as an SVG image.
+//!
+//! The periods are aligned week by week from their starts, and both calendars are shaded
+//! against the busiest day of either, so equal shades mean equal counts.
+
+use crate::format::{
+    HEATMAP_COLORS, ascii_cell, calendar_level, escape_html, heatmap_grid, padded_weekdays,
+};
+use crate::github::user_activity::UserActivityUserContributionsCollectionContributionCalendar as ContributionCalendar;
+use crate::locale::NumberLocale;
+use crate::report::Report;
+
+/// Fills of the SVG calendar shading levels (0-4), after GitHub's greens; more contributions
+/// in the difference overlay use them too.
+const SVG_GREENS: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+/// Fills of the difference overlay shading levels (0-4) for fewer contributions.
+const SVG_REDS: [&str; 5] = ["#ebedf0", "#ffc1c0", "#ff8182", "#e5534b", "#b62324"];
+
+/// Side of an SVG calendar day, and the distance between neighbouring days.
+const SVG_CELL: usize = 11;
+const SVG_PITCH: usize = 14;
+/// Width of the SVG weekday labels, gap between panels, and height of each text line.
+const SVG_LABEL_WIDTH: usize = 32;
+const SVG_PANEL_GAP: usize = 24;
+const SVG_LINE_HEIGHT: usize = 18;
+
+/// The contribution calendars of the same user over two periods.
+#[derive(Debug, Clone, Copy)]
+pub struct HeatmapComparison<'a> {
+    /// The report of the earlier period.
+    pub previous: &'a Report,
+    /// The report of the later period.
+    pub current: &'a Report,
+    /// How totals are grouped, e.g. `12,345`.
+    pub locale: NumberLocale,
+}
+
+/// Both calendars as heatmap grids of the same number of weeks.
+struct Grids {
+    previous: [Vec<Option<i64>>; 7],
+    current: [Vec<Option<i64>>; 7],
+    /// Contributions on the busiest day of either period.
+    max: i64,
+    /// The largest change of a day between the periods.
+    max_change: i64,
+}
+
+impl Grids {
+    fn new(previous: &ContributionCalendar, current: &ContributionCalendar) -> Self {
+        let (mut previous, mut current) = (heatmap_grid(previous), heatmap_grid(current));
+        let weeks = previous[0].len().max(current[0].len());
+        for row in previous.iter_mut().chain(current.iter_mut()) {
+            row.resize(weeks, None);
+        }
+        let max = previous
+            .iter()
+            .chain(&current)
+            .flatten()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or(0);
+        let mut grids = Self {
+            previous,
+            current,
+            max,
+            max_change: 0,
+        };
+        grids.max_change = (0..7)
+            .flat_map(|row| (0..weeks).map(move |column| (row, column)))
+            .filter_map(|(row, column)| grids.change(row, column))
+            .map(i64::abs)
+            .max()
+            .unwrap_or(0);
+        grids
+    }
+
+    fn weeks(&self) -> usize {
+        self.previous[0].len()
+    }
+
+    /// How many more contributions the current period has than the previous one on the day at
+    /// `row` and `column`; `None` when neither period covers it.
+    fn change(&self, row: usize, column: usize) -> Option<i64> {
+        match (self.previous[row][column], self.current[row][column]) {
+            (None, None) => None,
+            (previous, current) => Some(current.unwrap_or(0) - previous.unwrap_or(0)),
+        }
+    }
+}
+
+impl HeatmapComparison<'_> {
+    fn calendars(&self) -> Option<(&ContributionCalendar, &ContributionCalendar)> {
+        Some((calendar(self.previous)?, calendar(self.current)?))
+    }
+
+    fn period(report: &Report) -> String {
+        format!(
+            "{} to {}",
+            report.start_date.format("%Y-%m-%d"),
+            report.end_date.format("%Y-%m-%d")
+        )
+    }
+
+    /// One line comparing the totals of both periods, e.g.
+    /// `Contributions: 120 -> 150 (+30, +25%); busier on 12 days, quieter on 4`.
+    pub fn summary(&self) -> String {
+        let Some((previous, current)) = self.calendars() else {
+            return "No user data available.".to_string();
+        };
+        let grids = Grids::new(previous, current);
+        let changes: Vec<i64> = (0..7)
+            .flat_map(|row| (0..grids.weeks()).map(move |column| (row, column)))
+            .filter_map(|(row, column)| grids.change(row, column))
+            .collect();
+        let (before, after) = (previous.total_contributions, current.total_contributions);
+        let percent = if before > 0 {
+            format!(", {:+}%", (after - before) * 100 / before)
+        } else {
+            String::new()
+        };
+        format!(
+            "Contributions: {} -> {} ({}{}{}); busier on {} days, quieter on {}",
+            self.locale.format(before),
+            self.locale.format(after),
+            if after >= before { "+" } else { "-" },
+            self.locale.format((after - before).abs()),
+            percent,
+            self.locale
+                .format(changes.iter().filter(|&&c| c > 0).count() as i64),
+            self.locale
+                .format(changes.iter().filter(|&&c| c < 0).count() as i64)
+        )
+    }
+
+    /// The previous calendar, the current one, and their difference as three heatmaps side by
+    /// side, with ANSI colors when `color` is set and characters otherwise: the difference
+    /// marks busier days `+`, quieter days `-`, and unchanged ones `.`.
+    pub fn terminal(&self, color: bool) -> String {
+        let Some((previous, current)) = self.calendars() else {
+            return "No user data available.\n".to_string();
+        };
+        let grids = Grids::new(previous, current);
+        let paint = |code: String, text: &str| {
+            if color {
+                format!("\x1b[{}m{}\x1b[0m", code, text)
+            } else {
+                text.to_string()
+            }
+        };
+        let heat = |level: i64| {
+            if color {
+                paint(
+                    format!("38;5;{}", HEATMAP_COLORS[level.clamp(0, 4) as usize]),
+                    "■",
+                )
+            } else {
+                ascii_cell(level)
+            }
+        };
+        let change = |change: i64| {
+            let (code, symbol) = match change {
+                c if c > 0 => ("32", "+"),
+                c if c < 0 => ("31", "-"),
+                _ => ("38;5;238", "."),
+            };
+            paint(code.into(), if color { "■" } else { symbol })
+        };
+
+        // Each panel is at least as wide as its heading.
+        let width = (grids.weeks() * 2)
+            .saturating_sub(1)
+            .max("Difference".len());
+        let pad = |cells: Vec<String>| {
+            let visible = (cells.len() * 2).saturating_sub(1);
+            format!("{}{}", cells.join(" "), " ".repeat(width - visible))
+        };
+        let weekdays = padded_weekdays(self.locale);
+        // Headings and the legend start after the weekday labels.
+        let indent = " ".repeat(weekdays[0].chars().count() + 1);
+        let mut output = format!(
+            "Previous: {}\nCurrent:  {}\n\n{}{:<width$}   {:<width$}   Difference\n",
+            Self::period(self.previous),
+            Self::period(self.current),
+            indent,
+            "Previous",
+            "Current",
+        );
+        for (row, weekday) in weekdays.iter().enumerate() {
+            let cells = |grid: &[Vec<Option<i64>>; 7]| {
+                grid[row]
+                    .iter()
+                    .map(|count| match count {
+                        Some(count) => heat(calendar_level(*count, grids.max)),
+                        None => " ".to_string(),
+                    })
+                    .collect()
+            };
+            let changes = (0..grids.weeks())
+                .map(|column| grids.change(row, column).map_or(" ".to_string(), change))
+                .collect();
+            let line = format!(
+                "{} {}   {}   {}",
+                weekday,
+                pad(cells(&grids.previous)),
+                pad(cells(&grids.current)),
+                pad(changes)
+            );
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+        let legend: Vec<String> = (0..=4).map(heat).collect();
+        output.push_str(&format!(
+            "{}Less {} More   Difference: {} busier {} quieter {} same\n\n{}\n",
+            indent,
+            legend.join(" "),
+            change(1),
+            change(-1),
+            change(0),
+            self.summary()
+        ));
+        output
+    }
+
+    /// The previous calendar, the current one, and their difference as three panels of an
+    /// SVG image; the difference shades busier days green and quieter days red, darker for
+    /// larger changes. Every day has a tooltip with its counts.
+    pub fn svg(&self) -> String {
+        let Some((previous, current)) = self.calendars() else {
+            return svg_document(
+                300,
+                SVG_LINE_HEIGHT * 2,
+                "<text x=\"0\" y=\"14\">No user data available.</text>\n",
+            );
+        };
+        let grids = Grids::new(previous, current);
+        let panel_width = (grids.weeks() * SVG_PITCH).max(SVG_PITCH * 10);
+        let panel_x = |panel: usize| SVG_LABEL_WIDTH + panel * (panel_width + SVG_PANEL_GAP);
+        let top = SVG_LINE_HEIGHT * 2;
+
+        let mut body = format!(
+            "<text x=\"0\" y=\"14\" font-weight=\"bold\">{}</text>\n",
+            escape_html(&self.current.username)
+        );
+        let headings = [
+            format!("Previous ({})", Self::period(self.previous)),
+            format!("Current ({})", Self::period(self.current)),
+            "Difference".to_string(),
+        ];
+        for (panel, heading) in headings.iter().enumerate() {
+            body.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\">{}</text>\n",
+                panel_x(panel),
+                top - 6,
+                heading
+            ));
+        }
+        for (row, weekday) in self.locale.weekday_abbreviations().iter().enumerate() {
+            let y = top + row * SVG_PITCH;
+            body.push_str(&format!(
+                "<text x=\"0\" y=\"{}\">{}</text>\n",
+                y + SVG_CELL - 1,
+                escape_html(weekday)
+            ));
+            for column in 0..grids.weeks() {
+                let (before, after) = (grids.previous[row][column], grids.current[row][column]);
+                let x = column * SVG_PITCH;
+                for (panel, count) in [before, after].into_iter().enumerate() {
+                    if let Some(count) = count {
+                        body.push_str(&svg_cell(
+                            panel_x(panel) + x,
+                            y,
+                            SVG_GREENS[calendar_level(count, grids.max) as usize],
+                            &self.locale.format(count),
+                        ));
+                    }
+                }
+                if let Some(change) = grids.change(row, column) {
+                    let level = calendar_level(change.abs(), grids.max_change) as usize;
+                    let fill = if change < 0 {
+                        SVG_REDS[level]
+                    } else {
+                        SVG_GREENS[level]
+                    };
+                    body.push_str(&svg_cell(
+                        panel_x(2) + x,
+                        y,
+                        fill,
+                        &format!(
+                            "{} -> {} ({:+})",
+                            self.locale.format(before.unwrap_or(0)),
+                            self.locale.format(after.unwrap_or(0)),
+                            change
+                        ),
+                    ));
+                }
+            }
+        }
+        let bottom = top + 7 * SVG_PITCH + SVG_LINE_HEIGHT;
+        body.push_str(&format!(
+            "<text x=\"0\" y=\"{}\">{}</text>\n",
+            bottom,
+            escape_html(&self.summary())
+        ));
+        svg_document(panel_x(3) - SVG_PANEL_GAP, bottom + 6, &body)
+    }
+}
+
+/// The contribution calendar of `report`, if it has user data.
+fn calendar(report: &Report) -> Option<&ContributionCalendar> {
+    report
+        .activity
+        .user
+        .as_ref()
+        .map(|user| &user.contributions_collection.contribution_calendar)
+}
+
+/// One calendar day of an SVG panel, with `title` as its tooltip.
+fn svg_cell(x: usize, y: usize, fill: &str, title: &str) -> String {
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"2\" fill=\"{}\"><title>{}</title></rect>\n",
+        x, y, SVG_CELL, SVG_CELL, fill, title
+    )
+}
+
+/// A standalone SVG image of `width` by `height` pixels around `body`.
+fn svg_document(width: usize, height: usize, body: &str) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"10\">\n{body}</svg>\n",
+        w = width,
+        h = height,
+        body = body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    /// A report whose calendar has the given weeks of (weekday, count) days.
+    fn report(start: u32, weeks: &[&[(i64, i64)]]) -> Report {
+        let total: i64 = weeks.iter().flat_map(|w| w.iter()).map(|(_, c)| c).sum();
+        let weeks: Vec<_> = weeks
+            .iter()
+            .map(|days| {
+                let days: Vec<_> = days
+                    .iter()
+                    .map(|&(weekday, count)| json!({ "date": "2025-03-02", "contributionCount": count, "weekday": weekday }))
+                    .collect();
+                json!({ "contributionDays": days })
+            })
+            .collect();
+        let empty = json!({ "totalCount": 0, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": [] });
+        let activity = json!({ "user": { "contributionsCollection": {
+            "totalCommitContributions": total,
+            "totalIssueContributions": 0,
+            "totalPullRequestContributions": 0,
+            "totalPullRequestReviewContributions": 0,
+            "contributionCalendar": { "totalContributions": total, "weeks": weeks },
+            "commitContributionsByRepository": [],
+            "issueContributions": empty,
+            "pullRequestContributions": empty,
+            "pullRequestReviewContributions": empty
+        } } });
+        Report::new(
+            "octocat".into(),
+            Utc.with_ymd_and_hms(2025, start, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, start, 15, 0, 0, 0).unwrap(),
+            serde_json::from_value(activity).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_terminal() {
+        let previous = report(1, &[&[(0, 4), (1, 0)], &[(0, 2), (1, 1)]]);
+        let current = report(2, &[&[(0, 4), (1, 2)], &[(0, 1)]]);
+        let comparison = HeatmapComparison {
+            previous: &previous,
+            current: &current,
+            locale: NumberLocale::default(),
+        };
+        let output = comparison.terminal(false);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Previous: 2025-01-01 to 2025-01-15");
+        assert_eq!(lines[3], "    Previous     Current      Difference");
+        assert_eq!(lines[4], "Sun # +          # -          . -");
+        assert_eq!(lines[5], "Mon . -          +            + -");
+        assert_eq!(lines[6], "Tue");
+        assert!(output.ends_with(
+            "Difference: + busier - quieter . same\n\nContributions: 7 -> 7 (+0, +0%); busier on 1 days, quieter on 2\n"
+        ));
+        assert!(comparison.terminal(true).contains("\x1b[31m■\x1b[0m"));
+    }
+
+    #[test]
+    fn test_svg() {
+        let previous = report(1, &[&[(0, 1)]]);
+        let current = report(2, &[&[(0, 3), (6, 2)]]);
+        let svg = HeatmapComparison {
+            previous: &previous,
+            current: &current,
+            locale: NumberLocale::default(),
+        }
+        .svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(">Previous (2025-01-01 to 2025-01-15)</text>"));
+        assert_eq!(svg.matches("<rect").count(), 5);
+        assert!(svg.contains("fill=\"#216e39\"><title>1 -> 3 (+2)</title>"));
+        assert!(svg.contains("<title>0 -> 2 (+2)</title>"));
+        assert!(svg.contains("Contributions: 1 -&gt; 5 (+4, +400%)"));
+    }
+}
@@ -0,0 +1,382 @@
+//! Config module: default command-line options read from a TOML file, optionally overridden
+//! by a named profile chosen with `--profile`.
+//!
+//! Keys are the long option names, e.g.:
+//!
+//! ```toml
+//! [defaults]
+//! username = "octocat"
+//! format = "markdown"
+//!
+//! [profiles.work]
+//! api-url = "https://github.example.com/api/graphql"
+//! org = "example"
+//! ```
+//!
+//! Options given on the command line always win over the file.
+//!
+//! Category rules tag contributions for the category breakdown; the first rule that matches a
+//! repository (`owner/name` or `owner/*`), a label, or a title pattern wins:
+//!
+//! ```toml
+//! [[categories]]
+//! name = "bugfix"
+//! labels = ["bug"]
+//! titles = ["^fix"]
+//! ```
+//!
+//! Named periods can be selected with `--period`, e.g. `--period sprint-42`:
+//!
+//! ```toml
+//! [periods]
+//! sprint-42 = { from = "2024-05-06", to = "2024-05-17" }
+//! ```
+//!
+//! Teams map a name to the usernames of its members; with `--output-dir`, reports are written
+//! one file per team:
+//!
+//! ```toml
+//! [teams]
+//! platform = ["octocat", "hubot"]
+//! ```
+//!
+//! Hooks run a shell command when a run finishes; see the hook module for their environment:
+//!
+//! ```toml
+//! [hooks]
+//! on_success = "./upload.sh \"$GITHUB_ACTIVITY_REPORT\""
+//! ```
+
+use crate::args::{Args, GitHubUsername, Period, parse_datetime};
+use crate::config_check;
+use crate::hook::Hooks;
+use anyhow::{Context, Result, bail};
+use clap::CommandFactory;
+use github_activity_rs::category::CategoryRule;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Options that select the configuration and so can't be set by it.
+pub const RESERVED_KEYS: [&str; 2] = ["config", "profile"];
+
+/// Where the configuration is read from when `--config` isn't given, following the XDG base
+/// directory spec.
+pub fn default_path() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("github-activity").join("config.toml"))
+}
+
+/// The contents of a configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Options applied to every run.
+    #[serde(default)]
+    pub defaults: toml::Table,
+    /// Named sets of options, applied on top of the defaults when selected.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, toml::Table>,
+    /// Rules tagging contributions with categories, in order of precedence.
+    #[serde(default)]
+    pub categories: Vec<CategorySpec>,
+    /// Team names mapped to the usernames of their members.
+    #[serde(default)]
+    pub teams: BTreeMap<String, Vec<String>>,
+    /// Fixed time ranges selectable by name with `--period`.
+    #[serde(default)]
+    pub periods: BTreeMap<String, PeriodSpec>,
+    /// Commands run when a run finishes.
+    #[serde(default)]
+    pub hooks: Hooks,
+}
+
+/// A named period as written in the configuration.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeriodSpec {
+    /// Start date in ISO 8601 format.
+    pub from: String,
+    /// End date in ISO 8601 format.
+    pub to: String,
+}
+
+/// A category rule as written in the configuration.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CategorySpec {
+    /// The category, e.g. `feature`.
+    pub name: String,
+    /// Repositories as `owner/name`, or `owner/*`.
+    #[serde(default)]
+    pub repos: Vec<String>,
+    /// Labels, compared case-insensitively.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Regular expressions searched for in titles.
+    #[serde(default)]
+    pub titles: Vec<String>,
+}
+
+impl Config {
+    /// Reads the configuration at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config from {:?}", path))?;
+        Self::parse(&contents).with_context(|| format!("Invalid config {:?}", path))
+    }
+
+    /// Parses a configuration, reporting every problem the validation pass finds with its
+    /// line and column.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let problems = config_check::check(contents, &Args::command());
+        if !problems.is_empty() {
+            let lines: Vec<String> = problems
+                .iter()
+                .map(|problem| format!("  {}", problem.display(contents)))
+                .collect();
+            bail!(
+                "{} problem{} found:\n{}",
+                problems.len(),
+                if problems.len() == 1 { "" } else { "s" },
+                lines.join("\n")
+            );
+        }
+        toml::from_str(contents).map_err(|err| {
+            let (line, column) = err.span().map_or((1, 1), |span| {
+                config_check::line_column(contents, span.start)
+            });
+            anyhow::anyhow!("line {}, column {}: {}", line, column, err.message().trim())
+        })
+    }
+
+    /// Reads the configuration at `path`, or at the default location when `path` is `None`; a
+    /// missing file at the default location is an empty configuration.
+    pub fn load_or_default(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => Self::load(path),
+            None => match default_path() {
+                Some(path) if path.exists() => Self::load(&path),
+                _ => Ok(Self::default()),
+            },
+        }
+    }
+
+    /// The category rules, with their title patterns compiled.
+    pub fn category_rules(&self) -> Result<Vec<CategoryRule>> {
+        self.categories
+            .iter()
+            .map(|spec| CategoryRule::new(&spec.name, &spec.repos, &spec.labels, &spec.titles))
+            .collect()
+    }
+
+    /// The teams, with their members' usernames validated. Team names become file names, so
+    /// they can't contain path separators.
+    pub fn teams(&self) -> Result<BTreeMap<String, Vec<GitHubUsername>>> {
+        self.teams
+            .iter()
+            .map(|(name, members)| {
+                if name.is_empty() || name.contains(std::path::is_separator) || name == "index" {
+                    bail!("Invalid team name {:?} in the config", name);
+                }
+                let members = members
+                    .iter()
+                    .map(|member| member.parse())
+                    .collect::<Result<_, String>>()
+                    .map_err(|err| anyhow::anyhow!("Invalid member of team {}: {}", name, err))?;
+                Ok((name.clone(), members))
+            })
+            .collect()
+    }
+
+    /// The named periods, with their dates parsed.
+    pub fn periods(&self) -> Result<BTreeMap<String, Period>> {
+        self.periods
+            .iter()
+            .map(|(name, spec)| {
+                let date = |s: &str| {
+                    parse_datetime(s)
+                        .map_err(|err| anyhow::anyhow!("Invalid date of period {}: {}", name, err))
+                };
+                let (from, to) = (date(&spec.from)?, date(&spec.to)?);
+                if from >= to {
+                    bail!("Period {} must start before it ends", name);
+                }
+                Ok((name.clone(), Period::Named(from, to)))
+            })
+            .collect()
+    }
+
+    /// The option values to apply for `profile`, keyed by long option name: the defaults,
+    /// overridden key by key by the profile.
+    pub fn options(&self, profile: Option<&str>) -> Result<BTreeMap<String, Vec<String>>> {
+        let mut table = self.defaults.clone();
+        if let Some(name) = profile {
+            let Some(profile) = self.profiles.get(name) else {
+                let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                bail!(
+                    "Unknown profile: {}. Profiles in the config: {}",
+                    name,
+                    if known.is_empty() {
+                        "none".to_string()
+                    } else {
+                        known.join(", ")
+                    }
+                );
+            };
+            table.extend(profile.clone());
+        }
+
+        table
+            .into_iter()
+            .map(|(key, value)| {
+                if RESERVED_KEYS.contains(&key.as_str()) {
+                    bail!("{} can't be set in the config", key);
+                }
+                let values = option_values(&key, &value)?;
+                Ok((key, values))
+            })
+            .collect()
+    }
+}
+
+/// The command-line values of a config value: scalars are one value, arrays one per element.
+fn option_values(key: &str, value: &toml::Value) -> Result<Vec<String>> {
+    match value {
+        toml::Value::Array(items) => items.iter().map(|item| scalar(key, item)).collect(),
+        value => Ok(vec![scalar(key, value)?]),
+    }
+}
+
+fn scalar(key: &str, value: &toml::Value) -> Result<String> {
+    match value {
+        toml::Value::String(s) => Ok(s.clone()),
+        toml::Value::Integer(i) => Ok(i.to_string()),
+        toml::Value::Boolean(b) => Ok(b.to_string()),
+        toml::Value::Datetime(dt) => Ok(dt.to_string()),
+        _ => bail!(
+            "Config value of {} must be a string, number, boolean, or array of them",
+            key
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [defaults]
+        username = "octocat"
+        format = "markdown"
+
+        [profiles.work]
+        username = ["alice", "bob"]
+        api-url = "https://github.example.com/api/graphql"
+        no-wait = true
+    "#;
+
+    #[test]
+    fn test_profile_overrides_defaults() {
+        let config: Config = toml::from_str(CONFIG).unwrap();
+        let options = config.options(Some("work")).unwrap();
+        assert_eq!(options["username"], ["alice", "bob"]);
+        assert_eq!(options["format"], ["markdown"]);
+        assert_eq!(options["no-wait"], ["true"]);
+
+        assert_eq!(config.options(None).unwrap()["username"], ["octocat"]);
+        let err = config.options(Some("home")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown profile: home. Profiles in the config: work"
+        );
+    }
+
+    #[test]
+    fn test_reserved_and_unsupported_values() {
+        let config: Config = toml::from_str("[defaults]\nprofile = \"work\"").unwrap();
+        assert!(config.options(None).is_err());
+        let config: Config = toml::from_str("[defaults]\norg = { name = \"x\" }").unwrap();
+        assert!(config.options(None).is_err());
+        assert!(toml::from_str::<Config>("[default]\norg = \"x\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_reports_located_problems() {
+        let err = Config::parse("[defaults]\nfromat = \"json\"\nno-wait = 1\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "2 problems found:\n  line 2, column 1: unknown option `fromat` in [defaults]; did you mean `format`?\n  line 3, column 11: `no-wait` is a flag and takes true or false"
+        );
+        let err = Config::parse("[[categories]]\nname = \"x\"\nrepo = \"y\"\n").unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("line 3, column 1: unknown field `repo`")
+        );
+        assert!(Config::parse(CONFIG).is_ok());
+    }
+
+    #[test]
+    fn test_category_rules() {
+        let config: Config = toml::from_str(
+            r#"
+            [[categories]]
+            name = "ops"
+            repos = ["infra/*"]
+
+            [[categories]]
+            name = "bugfix"
+            labels = ["bug"]
+            titles = ["^fix"]
+        "#,
+        )
+        .unwrap();
+        let rules = config.category_rules().unwrap();
+        let names: Vec<&str> = rules.iter().map(|rule| rule.name.as_str()).collect();
+        assert_eq!(names, ["ops", "bugfix"]);
+        assert_eq!(rules[1].titles[0].as_str(), "^fix");
+
+        let config: Config =
+            toml::from_str("[[categories]]\nname = \"x\"\ntitles = [\"(\"]").unwrap();
+        assert!(config.category_rules().is_err());
+        assert!(toml::from_str::<Config>("[[categories]]\nname = \"x\"\nrepo = \"y\"").is_err());
+    }
+
+    #[test]
+    fn test_teams() {
+        let config: Config =
+            toml::from_str("[teams]\nplatform = [\"octocat\", \"hubot\"]\nweb = []").unwrap();
+        let teams = config.teams().unwrap();
+        let members: Vec<String> = teams["platform"].iter().map(ToString::to_string).collect();
+        assert_eq!(members, ["octocat", "hubot"]);
+        assert!(teams["web"].is_empty());
+
+        let config: Config = toml::from_str("[teams]\nplatform = [\"-bad\"]").unwrap();
+        assert!(config.teams().is_err());
+        let config: Config = toml::from_str("[teams]\n\"a/b\" = [\"octocat\"]").unwrap();
+        assert!(config.teams().is_err());
+    }
+
+    #[test]
+    fn test_periods() {
+        let config: Config = toml::from_str(
+            "[periods]\nsprint-42 = { from = \"2024-05-06\", to = \"2024-05-17T12:00:00Z\" }",
+        )
+        .unwrap();
+        let Period::Named(from, to) = config.periods().unwrap()["sprint-42"] else {
+            panic!("expected a named period");
+        };
+        assert_eq!(from.to_rfc3339(), "2024-05-06T00:00:00+00:00");
+        assert_eq!(to.to_rfc3339(), "2024-05-17T12:00:00+00:00");
+
+        let config: Config =
+            toml::from_str("[periods]\nq = { from = \"2024-05-17\", to = \"2024-05-06\" }")
+                .unwrap();
+        assert!(config.periods().is_err());
+        assert!(toml::from_str::<Config>("[periods]\nq = { from = \"2024-05-06\" }").is_err());
+    }
+}
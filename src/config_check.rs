@@ -0,0 +1,410 @@
+//! Config check module: a validation pass over the configuration file before it's applied,
+//! reporting every problem with its line and column instead of the first error serde hits.
+//!
+//! Option keys are checked against the command line: unknown keys get the closest option as
+//! a suggestion, values must fit the option (a flag takes `true` or `false`, other options
+//! what their parser accepts, and an array only where the option may be repeated), and
+//! options that conflict on the command line, like `period` and `from`, can't both be set,
+//! since clap doesn't check conflicts between default values.
+
+use crate::config::RESERVED_KEYS;
+use clap::error::{ContextKind, ErrorKind};
+use clap::{Arg, ArgAction};
+use std::collections::BTreeMap;
+use std::ops::Range;
+use toml::Spanned;
+
+/// The sections of a configuration file.
+const SECTIONS: [&str; 6] = [
+    "defaults",
+    "profiles",
+    "categories",
+    "teams",
+    "periods",
+    "hooks",
+];
+
+/// Option keys and their values, with where they are in the file.
+type OptionTable = BTreeMap<Spanned<String>, Spanned<toml::Value>>;
+
+/// The option tables of a configuration file.
+#[derive(Debug, Default, serde::Deserialize)]
+struct OptionTables {
+    #[serde(default)]
+    defaults: OptionTable,
+    #[serde(default)]
+    profiles: BTreeMap<Spanned<String>, OptionTable>,
+}
+
+/// A problem found in the configuration, at a byte range of its contents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+impl Problem {
+    fn new(span: Range<usize>, message: String) -> Self {
+        Self { span, message }
+    }
+
+    /// The problem prefixed with its 1-based line and column in `contents`, e.g.
+    /// `line 3, column 1: unknown option ...`.
+    pub fn display(&self, contents: &str) -> String {
+        let (line, column) = line_column(contents, self.span.start);
+        format!("line {}, column {}: {}", line, column, self.message)
+    }
+}
+
+/// The 1-based line and column of byte `offset` in `contents`.
+pub fn line_column(contents: &str, offset: usize) -> (usize, usize) {
+    let before = &contents[..offset.min(contents.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit_once('\n')
+        .map_or(before, |(_, rest)| rest)
+        .chars()
+        .count()
+        + 1;
+    (line, column)
+}
+
+/// Checks the configuration `contents` against the options of `command`.
+///
+/// Syntax errors and sections whose structure doesn't match are left to deserialization,
+/// which reports them with their location too.
+pub fn check(contents: &str, command: &clap::Command) -> Vec<Problem> {
+    let Ok(sections) = toml::from_str::<BTreeMap<Spanned<String>, toml::Value>>(contents) else {
+        return Vec::new();
+    };
+    let mut problems: Vec<Problem> = sections
+        .keys()
+        .filter(|key| !SECTIONS.contains(&key.get_ref().as_str()))
+        .map(|key| {
+            Problem::new(
+                key.span(),
+                format!(
+                    "unknown section `{}`{}",
+                    key.get_ref(),
+                    suggestion(key.get_ref(), SECTIONS)
+                ),
+            )
+        })
+        .collect();
+
+    let Ok(tables) = toml::from_str::<OptionTables>(contents) else {
+        return problems;
+    };
+    let options: Vec<&Arg> = command
+        .get_arguments()
+        .filter(|arg| arg.get_long().is_some())
+        .collect();
+    let long_names: Vec<&str> = options.iter().filter_map(|arg| arg.get_long()).collect();
+    let option = |key: &str| {
+        options
+            .iter()
+            .copied()
+            .find(|arg| arg.get_long() == Some(key))
+    };
+
+    let mut tables_to_check = vec![("[defaults]".to_string(), &tables.defaults)];
+    tables_to_check.extend(
+        tables
+            .profiles
+            .iter()
+            .map(|(name, table)| (format!("[profiles.{}]", name.get_ref()), table)),
+    );
+    for (section, table) in &tables_to_check {
+        for (key, value) in table.iter() {
+            let name = key.get_ref().as_str();
+            if RESERVED_KEYS.contains(&name) {
+                problems.push(Problem::new(
+                    key.span(),
+                    format!("`{}` selects the config and can't be set in it", name),
+                ));
+                continue;
+            }
+            match option(name) {
+                Some(arg) => problems.extend(check_value(command, name, arg, value)),
+                None => problems.push(Problem::new(
+                    key.span(),
+                    format!(
+                        "unknown option `{}` in {}{}",
+                        name,
+                        section,
+                        suggestion(name, long_names.iter().copied())
+                    ),
+                )),
+            }
+        }
+    }
+
+    // A profile is applied on top of the defaults, so its keys conflict with theirs too.
+    problems.extend(conflicts(command, &options, &tables.defaults, None));
+    for (name, profile) in &tables.profiles {
+        problems.extend(conflicts(
+            command,
+            &options,
+            profile,
+            Some((name.get_ref(), &tables.defaults)),
+        ));
+    }
+    problems.sort_by_key(|problem| problem.span.start);
+    problems
+}
+
+/// Checks that `value` fits the option `arg`.
+fn check_value(
+    command: &clap::Command,
+    name: &str,
+    arg: &Arg,
+    value: &Spanned<toml::Value>,
+) -> Option<Problem> {
+    let repeatable = matches!(arg.get_action(), ArgAction::Append);
+    let values: Vec<&toml::Value> = match value.get_ref() {
+        toml::Value::Array(items) if repeatable => items.iter().collect(),
+        toml::Value::Array(_) => {
+            return Some(Problem::new(
+                value.span(),
+                format!("`{}` takes a single value, not an array", name),
+            ));
+        }
+        value => vec![value],
+    };
+    let flag = matches!(arg.get_action(), ArgAction::SetTrue | ArgAction::SetFalse);
+    for item in values {
+        let text = match item {
+            toml::Value::String(s) => s.clone(),
+            toml::Value::Integer(i) => i.to_string(),
+            toml::Value::Boolean(b) => b.to_string(),
+            toml::Value::Datetime(dt) => dt.to_string(),
+            _ => {
+                return Some(Problem::new(
+                    value.span(),
+                    if repeatable {
+                        format!(
+                            "`{}` must be a string, number, boolean, or array of them",
+                            name
+                        )
+                    } else {
+                        format!("`{}` must be a string, number, or boolean", name)
+                    },
+                ));
+            }
+        };
+        if flag && !matches!(item, toml::Value::Boolean(_)) {
+            return Some(Problem::new(
+                value.span(),
+                format!("`{}` is a flag and takes true or false", name),
+            ));
+        }
+        if let Some(error) = (!flag).then(|| parse_error(command, name, &text)).flatten() {
+            return Some(Problem::new(
+                value.span(),
+                format!("invalid value `{}` for `{}`: {}", text, name, error),
+            ));
+        }
+    }
+    None
+}
+
+/// Why the option's own value parser rejects `value`, if it does.
+fn parse_error(command: &clap::Command, name: &str, value: &str) -> Option<String> {
+    let option = format!("--{}={}", name, value);
+    let err = command
+        .clone()
+        .try_get_matches_from([command.get_name(), option.as_str()])
+        .err()
+        .filter(|err| {
+            matches!(
+                err.kind(),
+                ErrorKind::InvalidValue | ErrorKind::ValueValidation
+            )
+        })?;
+    let reason = match std::error::Error::source(&err) {
+        Some(source) => source.to_string(),
+        None => err.get(ContextKind::ValidValue).map_or_else(
+            || err.kind().to_string(),
+            |valid| format!("expected {}", valid),
+        ),
+    };
+    Some(reason)
+}
+
+/// Pairs of keys in `table` whose options conflict on the command line, reported at the
+/// later key; for a profile, also its keys that conflict with the `defaults` it's applied on.
+fn conflicts(
+    command: &clap::Command,
+    options: &[&Arg],
+    table: &OptionTable,
+    profile: Option<(&str, &OptionTable)>,
+) -> Vec<Problem> {
+    let arg = |key: &Spanned<String>| {
+        options
+            .iter()
+            .copied()
+            .find(|arg| arg.get_long() == Some(key.get_ref().as_str()))
+    };
+    let conflicts_with = |a: &Arg, b: &Arg| {
+        command
+            .get_arg_conflicts_with(a)
+            .iter()
+            .any(|other| other.get_id() == b.get_id())
+    };
+    let conflicting = |a: &Arg, b: &Arg| conflicts_with(a, b) || conflicts_with(b, a);
+    let mut problems = Vec::new();
+    let keys: Vec<(&Spanned<String>, &Arg)> = table
+        .keys()
+        .filter_map(|key| arg(key).map(|arg| (key, arg)))
+        .collect();
+    for (i, (key, a)) in keys.iter().enumerate() {
+        for (other, b) in &keys[i + 1..] {
+            if conflicting(a, b) {
+                let (first, second) = if key.span().start < other.span().start {
+                    (key, other)
+                } else {
+                    (other, key)
+                };
+                problems.push(Problem::new(
+                    second.span(),
+                    format!(
+                        "`{}` conflicts with `{}`; set only one of them",
+                        second.get_ref(),
+                        first.get_ref()
+                    ),
+                ));
+            }
+        }
+        if let Some((name, defaults)) = profile {
+            for default in defaults.keys() {
+                // The profile's own value replaces a default of the same key.
+                if table.contains_key(default) {
+                    continue;
+                }
+                if let Some(b) = arg(default)
+                    && conflicting(a, b)
+                {
+                    problems.push(Problem::new(
+                        key.span(),
+                        format!(
+                            "`{}` in profile {} conflicts with `{}` in [defaults]; set `{}` in the profiles that need it instead",
+                            key.get_ref(),
+                            name,
+                            default.get_ref(),
+                            default.get_ref()
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    problems
+}
+
+/// ` ; did you mean `x`?` for the candidate closest to `name`, or nothing when none is close.
+fn suggestion<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= 2.max(name.chars().count() / 3))
+        .min()
+        .map(|(_, candidate)| format!("; did you mean `{}`?", candidate))
+        .unwrap_or_default()
+}
+
+/// The Levenshtein distance between `a` and `b`, case-insensitively.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::Args;
+    use clap::CommandFactory;
+
+    fn problems(contents: &str) -> Vec<String> {
+        check(contents, &Args::command())
+            .iter()
+            .map(|problem| problem.display(contents))
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_config_has_no_problems() {
+        let contents = r#"
+[defaults]
+username = ["octocat", "hubot"]
+format = "markdown"
+period = "7d"
+no-wait = true
+
+[profiles.q1]
+format = "html"
+"#;
+        assert_eq!(problems(contents), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_unknown_keys_are_located_with_suggestions() {
+        let contents =
+            "[default]\nusername = \"octocat\"\n\n[profiles.work]\nusrname = \"alice\"\n";
+        assert_eq!(
+            problems(contents),
+            [
+                "line 1, column 2: unknown section `default`; did you mean `defaults`?",
+                "line 5, column 1: unknown option `usrname` in [profiles.work]; did you mean `username`?",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_values_must_fit_the_option() {
+        let contents = "[defaults]\nformat = \"markdwn\"\nno-wait = \"yes\"\nperiod = [\"7d\", \"30d\"]\norg = 1.5\nprofile = \"work\"\n";
+        let problems = problems(contents);
+        assert_eq!(problems.len(), 5, "{:?}", problems);
+        assert!(
+            problems[0].starts_with("line 2, column 10: invalid value `markdwn` for `format`: ")
+        );
+        assert_eq!(
+            problems[1],
+            "line 3, column 11: `no-wait` is a flag and takes true or false"
+        );
+        assert_eq!(
+            problems[2],
+            "line 4, column 10: `period` takes a single value, not an array"
+        );
+        assert_eq!(
+            problems[3],
+            "line 5, column 7: `org` must be a string, number, or boolean"
+        );
+        assert_eq!(
+            problems[4],
+            "line 6, column 1: `profile` selects the config and can't be set in it"
+        );
+    }
+
+    #[test]
+    fn test_conflicting_options() {
+        let contents = "[defaults]\nperiod = \"7d\"\nfrom = \"2025-01-01\"\n\n[profiles.q1]\nto = \"2025-03-31\"\n\n[profiles.h1]\nperiod = \"180d\"\n";
+        assert_eq!(
+            problems(contents),
+            [
+                "line 3, column 1: `from` conflicts with `period`; set only one of them",
+                "line 6, column 1: `to` in profile q1 conflicts with `period` in [defaults]; set `period` in the profiles that need it instead",
+                "line 9, column 1: `period` in profile h1 conflicts with `from` in [defaults]; set `from` in the profiles that need it instead",
+            ]
+        );
+    }
+}
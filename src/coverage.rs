@@ -0,0 +1,136 @@
+//! Coverage module: per repository, the share of pull requests merged in the reported range
+//! that the user reviewed, so teams can see who reviews where and balance review assignments.
+//!
+//! Only pull requests by others count, since nobody reviews their own. Both counts come from
+//! the search API, two searches per repository.
+
+use crate::github::GithubClient;
+use crate::github::user_activity::UserActivityUserContributionsCollection as ContributionsCollection;
+use crate::revert::repository_of;
+use anyhow::{Context, Result};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// How many of a repository's merged pull requests the user reviewed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepositoryCoverage {
+    /// Repository in the format owner/name.
+    pub repository: String,
+    /// Pull requests by others merged in the range.
+    pub merged: i64,
+    /// Those of them the user reviewed.
+    pub reviewed: i64,
+}
+
+impl RepositoryCoverage {
+    /// The reviewed share of merged pull requests in whole percent; `None` when none were
+    /// merged.
+    pub fn percent(&self) -> Option<i64> {
+        (self.merged > 0)
+            .then(|| (self.reviewed as f64 * 100.0 / self.merged as f64).round() as i64)
+    }
+}
+
+/// The repositories the user committed to, opened pull requests in, or reviewed in, in name
+/// order.
+pub fn coverage_repositories(cc: &ContributionsCollection) -> BTreeSet<String> {
+    let mut repositories: BTreeSet<String> = cc
+        .commit_contributions_by_repository
+        .iter()
+        .map(|contribution| contribution.repository.name_with_owner.clone())
+        .collect();
+    for node in cc.pull_request_contributions.nodes.iter().flatten() {
+        repositories.extend(repository_of(&node.pull_request.url));
+    }
+    for node in cc.pull_request_review_contributions.nodes.iter().flatten() {
+        repositories.extend(repository_of(&node.pull_request_review.pull_request.url));
+    }
+    repositories
+}
+
+/// Counts, in each repository of `cc`, the pull requests by others merged in the client's
+/// range and those the client's user reviewed. Repositories without merged pull requests are
+/// left out.
+pub async fn review_coverage(
+    client: &GithubClient,
+    cc: &ContributionsCollection,
+) -> Result<Vec<RepositoryCoverage>> {
+    let username = client.username();
+    let merged = format!(
+        "is:pr is:merged merged:{}..{} -author:{}",
+        client.start_date().format("%Y-%m-%dT%H:%M:%SZ"),
+        client.end_date().format("%Y-%m-%dT%H:%M:%SZ"),
+        username
+    );
+    let mut coverage = Vec::new();
+    for repository in coverage_repositories(cc) {
+        info!("Counting merged pull requests of {}", repository);
+        let query = format!("repo:{} {}", repository, merged);
+        let merged = client
+            .count_search_results(&query)
+            .await
+            .with_context(|| format!("Failed to count merged pull requests of {}", repository))?;
+        if merged == 0 {
+            continue;
+        }
+        let reviewed = client
+            .count_search_results(&format!("{} reviewed-by:{}", query, username))
+            .await
+            .with_context(|| format!("Failed to count reviewed pull requests of {}", repository))?;
+        coverage.push(RepositoryCoverage {
+            repository,
+            merged,
+            reviewed,
+        });
+    }
+    Ok(coverage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_coverage_repositories_and_percent() {
+        let page = |nodes: serde_json::Value| json!({ "totalCount": 1, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes });
+        let cc: ContributionsCollection = serde_json::from_value(json!({
+            "totalCommitContributions": 3,
+            "totalIssueContributions": 0,
+            "totalPullRequestContributions": 1,
+            "totalPullRequestReviewContributions": 1,
+            "contributionCalendar": { "totalContributions": 5, "weeks": [] },
+            "commitContributionsByRepository": [{
+                "repository": { "nameWithOwner": "o/b", "updatedAt": "2025-03-02T00:00:00Z" },
+                "contributions": { "totalCount": 3 }
+            }],
+            "issueContributions": page(json!([])),
+            "pullRequestContributions": page(json!([{ "pullRequest": {
+                "number": 2, "title": "PR", "url": "https://github.com/o/b/pull/2",
+                "createdAt": "2025-03-02T10:00:00Z", "state": "OPEN", "merged": false,
+                "mergedAt": null, "mergeCommit": null, "closedAt": null,
+                "additions": 1, "deletions": 1, "changedFiles": 1
+            } }])),
+            "pullRequestReviewContributions": page(json!([{
+                "pullRequestReview": { "pullRequest": {
+                    "number": 3, "title": "Reviewed", "url": "https://github.com/o/a/pull/3"
+                }, "state": "APPROVED" },
+                "occurredAt": "2025-03-20T10:00:00Z"
+            }]))
+        }))
+        .unwrap();
+        assert_eq!(
+            coverage_repositories(&cc).into_iter().collect::<Vec<_>>(),
+            ["o/a", "o/b"]
+        );
+
+        let coverage = |merged, reviewed| RepositoryCoverage {
+            repository: "o/a".into(),
+            merged,
+            reviewed,
+        };
+        assert_eq!(coverage(3, 2).percent(), Some(67));
+        assert_eq!(coverage(0, 0).percent(), None);
+    }
+}
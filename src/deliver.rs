@@ -0,0 +1,671 @@
+//! Delivery module: posts finished reports to a chat service's incoming webhook (Slack or
+//! Discord), publishes them on GitHub as a gist or an issue, or adds them to the summary of a
+//! GitHub Actions job, so a scheduled run can share its report without a separate upload step.
+
+use anyhow::{Context, Result};
+use github_activity_rs::Report;
+use log::{info, warn};
+use reqwest::StatusCode;
+use reqwest::header::RETRY_AFTER;
+use serde_json::{Value, json};
+use std::env;
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How many times a rate-limited post is attempted before giving up.
+const MAX_ATTEMPTS: usize = 4;
+
+/// How long to wait after a 429 response without a `Retry-After` header.
+const DEFAULT_RETRY_AFTER_SECS: f64 = 1.0;
+
+/// Longest `Retry-After` wait honored, so a misbehaving endpoint can't stall the run.
+const MAX_RETRY_AFTER_SECS: f64 = 60.0;
+
+/// File GitHub Actions renders as the job summary, appended to by each step.
+pub const STEP_SUMMARY_VAR: &str = "GITHUB_STEP_SUMMARY";
+
+/// File GitHub Actions reads a step's outputs from, as `name=value` lines.
+pub const STEP_OUTPUT_VAR: &str = "GITHUB_OUTPUT";
+
+/// Most characters Discord accepts in a message's content.
+const DISCORD_MAX_CHARS: usize = 2000;
+
+/// The code fence around each Discord and Slack text message, keeping the plain text report
+/// aligned.
+const CODE_FENCE: (&str, &str) = ("```\n", "\n```");
+
+/// Most characters of message text Slack shows; longer text is truncated.
+const SLACK_MAX_CHARS: usize = 40_000;
+
+/// Most blocks Slack accepts in a message.
+const SLACK_MAX_BLOCKS: usize = 50;
+
+/// Most characters Slack accepts in the text of a section block.
+const SLACK_MAX_SECTION_CHARS: usize = 3000;
+
+/// Most characters GitHub accepts in the body of an issue or comment.
+const ISSUE_MAX_CHARS: usize = 65_536;
+
+/// Room kept in every message for the `(2/3)` label numbering the parts of a split report.
+const PART_LABEL_CHARS: usize = 16;
+
+/// Where reports are delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryTarget {
+    /// A Slack incoming webhook.
+    Slack,
+    /// A Discord webhook.
+    Discord,
+    /// A secret GitHub gist holding the Markdown reports.
+    Gist,
+    /// A GitHub issue per Markdown report, or a comment on a tracking issue.
+    Issue,
+    /// The summary of the running GitHub Actions job.
+    StepSummary,
+}
+
+impl DeliveryTarget {
+    /// The target when `--deliver` isn't given: the job summary when running in GitHub
+    /// Actions, which sets `GITHUB_STEP_SUMMARY`.
+    pub fn detect() -> Option<Self> {
+        env::var_os(STEP_SUMMARY_VAR)
+            .is_some_and(|path| !path.is_empty())
+            .then_some(Self::StepSummary)
+    }
+}
+
+impl FromStr for DeliveryTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "slack" => Ok(Self::Slack),
+            "discord" => Ok(Self::Discord),
+            "gist" => Ok(Self::Gist),
+            "issue" => Ok(Self::Issue),
+            "step-summary" | "step_summary" => Ok(Self::StepSummary),
+            _ => Err(format!(
+                "Invalid delivery target: {}. Use slack, discord, gist, issue, or step-summary",
+                s
+            )),
+        }
+    }
+}
+
+/// The repository `--deliver issue` opens issues in, and the tracking issue to comment on
+/// instead, written `owner/repo` or `owner/repo#123`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueTarget {
+    /// The repository, as `owner/repo`.
+    pub repo: String,
+    /// The tracking issue reports are commented on, if any.
+    pub number: Option<u64>,
+}
+
+impl FromStr for IssueTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (repo, number) = match s.split_once('#') {
+            Some((repo, number)) => {
+                let number = number
+                    .parse()
+                    .map_err(|_| format!("Invalid issue number in {}", s))?;
+                (repo, Some(number))
+            }
+            None => (s, None),
+        };
+        match repo.split_once('/') {
+            Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => {
+                Ok(Self {
+                    repo: repo.to_string(),
+                    number,
+                })
+            }
+            _ => Err(format!(
+                "Invalid issue target: {}. Use owner/repo or owner/repo#123",
+                s
+            )),
+        }
+    }
+}
+
+/// How a report is posted to Slack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SlackPayload {
+    /// Block Kit blocks, as rendered by `--format slack`.
+    #[default]
+    Blocks,
+    /// The plain text report as the message text.
+    Text,
+}
+
+impl FromStr for SlackPayload {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "blocks" => Ok(Self::Blocks),
+            "text" => Ok(Self::Text),
+            _ => Err(format!("Invalid Slack payload: {}. Use blocks or text", s)),
+        }
+    }
+}
+
+/// The Slack messages posting a plain text report: as few as fit it within the text Slack
+/// shows, split between sections, each in a code block. Slack treats `&`, `<`, and `>` as
+/// control characters, so they are escaped.
+pub fn slack_text_messages(text: &str) -> Vec<Value> {
+    let escaped = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    fenced_messages(&escaped, SLACK_MAX_CHARS, "text")
+}
+
+/// The Discord messages posting a plain text report: as few as fit it within Discord's
+/// 2000-character limit, split between sections, each in a code block.
+pub fn discord_messages(text: &str) -> Vec<Value> {
+    fenced_messages(text, DISCORD_MAX_CHARS, "content")
+}
+
+/// Messages of at most `max_chars` characters in their `field`, holding the plain text report
+/// in code blocks, numbered when there are several.
+fn fenced_messages(text: &str, max_chars: usize, field: &str) -> Vec<Value> {
+    let limit = max_chars - CODE_FENCE.0.len() - CODE_FENCE.1.len() - PART_LABEL_CHARS;
+    let parts = split_sections(&plain_sections(text), limit);
+    let count = parts.len();
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, part)| {
+            let label = match part_label(index, count) {
+                Some(label) => format!("{}\n", label),
+                None => String::new(),
+            };
+            json!({ field: format!("{}{}{}{}", label, CODE_FENCE.0, part, CODE_FENCE.1) })
+        })
+        .collect()
+}
+
+/// The bodies of the issue or comments posting a Markdown report: as few as fit it within
+/// GitHub's limit, split between sections, each numbered when there are several.
+pub fn issue_bodies(markdown: &str) -> Vec<String> {
+    let parts = split_sections(
+        &markdown_sections(markdown),
+        ISSUE_MAX_CHARS - PART_LABEL_CHARS,
+    );
+    let count = parts.len();
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(index, part)| match part_label(index, count) {
+            Some(label) => format!("{}\n\n{}", label, part),
+            None => part,
+        })
+        .collect()
+}
+
+/// The Slack messages posting the Block Kit `message` rendered by `--format slack`: as few as
+/// hold its blocks within Slack's limits, split between blocks, each numbered when there are
+/// several. A section whose text is too long for one block is split between lines into
+/// several sections.
+pub fn slack_block_messages(mut message: Value) -> Vec<Value> {
+    let blocks = match message["blocks"].take() {
+        Value::Array(blocks) => blocks,
+        _ => Vec::new(),
+    };
+    let blocks: Vec<Value> = blocks.into_iter().flat_map(split_section_block).collect();
+    // One block of every message is kept for its label.
+    let chunks: Vec<&[Value]> = blocks.chunks(SLACK_MAX_BLOCKS - 1).collect();
+    let count = chunks.len();
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            let mut blocks = chunk.to_vec();
+            if let Some(label) = part_label(index, count) {
+                blocks.insert(
+                    0,
+                    json!({ "type": "context", "elements": [{ "type": "mrkdwn", "text": label }] }),
+                );
+            }
+            json!({ "blocks": blocks })
+        })
+        .collect()
+}
+
+/// `block` as it fits Slack: a section block whose text is too long split between lines into
+/// several, and any other block as it is.
+fn split_section_block(block: Value) -> Vec<Value> {
+    let text = block["text"]["text"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    if block["type"] != "section" || text.chars().count() <= SLACK_MAX_SECTION_CHARS {
+        return vec![block];
+    }
+    pack(
+        cut_lines(&text, SLACK_MAX_SECTION_CHARS),
+        "\n",
+        SLACK_MAX_SECTION_CHARS,
+    )
+    .into_iter()
+    .map(|part| {
+        let mut section = block.clone();
+        section["text"]["text"] = Value::String(part);
+        section
+    })
+    .collect()
+}
+
+/// The sections of a plain text report: its runs of lines between blank lines.
+fn plain_sections(text: &str) -> Vec<&str> {
+    text.trim_end().split("\n\n").collect()
+}
+
+/// The sections of a Markdown report, each starting at a heading outside a code block, with
+/// the blank lines between them left out.
+fn markdown_sections(text: &str) -> Vec<&str> {
+    let mut sections = Vec::new();
+    let (mut start, mut offset, mut fenced) = (0, 0, false);
+    for line in text.split_inclusive('\n') {
+        if line.starts_with("```") {
+            fenced = !fenced;
+        } else if line.starts_with('#') && !fenced && offset > start {
+            sections.push(text[start..offset].trim_end_matches('\n'));
+            start = offset;
+        }
+        offset += line.len();
+    }
+    sections.push(text[start..].trim_end_matches('\n'));
+    sections
+}
+
+/// Packs `sections` in order into as few parts of at most `limit` characters as they fit in,
+/// separated by blank lines. A section too long for a part of its own is split between
+/// lines, and a line too long for one is cut.
+fn split_sections(sections: &[&str], limit: usize) -> Vec<String> {
+    let mut parts: Vec<String> = Vec::new();
+    for section in sections {
+        let pieces = if section.chars().count() <= limit {
+            vec![section.to_string()]
+        } else {
+            pack(cut_lines(section, limit), "\n", limit)
+        };
+        for (i, piece) in pieces.into_iter().enumerate() {
+            match parts.last_mut() {
+                Some(part)
+                    if i == 0 && part.chars().count() + 2 + piece.chars().count() <= limit =>
+                {
+                    part.push_str("\n\n");
+                    part.push_str(&piece);
+                }
+                _ => parts.push(piece),
+            }
+        }
+    }
+    if parts.is_empty() {
+        parts.push(String::new());
+    }
+    parts
+}
+
+/// The `(2/3)` label of the part at `index` of `count` parts, if there is more than one.
+fn part_label(index: usize, count: usize) -> Option<String> {
+    (count > 1).then(|| format!("({}/{})", index + 1, count))
+}
+
+/// The lines of `text`, with each line longer than `limit` characters cut into pieces.
+fn cut_lines(text: &str, limit: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    for line in text.lines() {
+        let mut chars = line.chars().peekable();
+        if chars.peek().is_none() {
+            pieces.push(String::new());
+        }
+        while chars.peek().is_some() {
+            pieces.push(chars.by_ref().take(limit).collect());
+        }
+    }
+    pieces
+}
+
+/// Joins `pieces` of at most `limit` characters with `separator` into as few parts of at most
+/// `limit` characters as they fit in.
+fn pack(pieces: impl IntoIterator<Item = String>, separator: &str, limit: usize) -> Vec<String> {
+    let mut parts = Vec::new();
+    let (mut part, mut chars, mut joined) = (String::new(), 0, 0);
+    for piece in pieces {
+        let piece_chars = piece.chars().count();
+        if joined > 0 && chars + separator.len() + piece_chars > limit {
+            parts.push(std::mem::take(&mut part));
+            (chars, joined) = (0, 0);
+        }
+        if joined > 0 {
+            part.push_str(separator);
+            chars += separator.len();
+        }
+        part.push_str(&piece);
+        chars += piece_chars;
+        joined += 1;
+    }
+    if joined > 0 || parts.is_empty() {
+        parts.push(part);
+    }
+    parts
+}
+
+/// The GitHub Actions step outputs of a run, as `name=value` lines: the contribution totals
+/// of all `reports` together.
+pub fn step_outputs(reports: &[Report]) -> String {
+    let (mut commits, mut issues, mut prs, mut reviews) = (0, 0, 0, 0);
+    for user in reports.iter().filter_map(|r| r.activity.user.as_ref()) {
+        let cc = &user.contributions_collection;
+        commits += cc.total_commit_contributions;
+        issues += cc.total_issue_contributions;
+        prs += cc.total_pull_request_contributions;
+        reviews += cc.total_pull_request_review_contributions;
+    }
+    let mut outputs = String::new();
+    for (name, value) in [
+        ("contributions", commits + issues + prs + reviews),
+        ("commits", commits),
+        ("issues", issues),
+        ("pull_requests", prs),
+        ("reviews", reviews),
+    ] {
+        let _ = writeln!(outputs, "{}={}", name, value);
+    }
+    outputs
+}
+
+/// Posts `payload` as JSON to the webhook at `url`. A 429 response is retried after the
+/// delay its `Retry-After` header asks for, up to `MAX_ATTEMPTS` attempts in all.
+pub async fn post_webhook(client: &reqwest::Client, url: &str, payload: &Value) -> Result<()> {
+    for attempt in 1..=MAX_ATTEMPTS {
+        let response = client
+            .post(url)
+            .json(payload)
+            .send()
+            .await
+            .context("Failed to send report to webhook")?;
+        if response.status() != StatusCode::TOO_MANY_REQUESTS || attempt == MAX_ATTEMPTS {
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                anyhow::bail!("Webhook rejected the report ({}): {}", status, body.trim());
+            }
+            info!("Report delivered to webhook");
+            return Ok(());
+        }
+        let wait = retry_after(response.headers().get(RETRY_AFTER));
+        warn!(
+            "Webhook rate limited the report; retrying in {:.1}s (attempt {}/{})",
+            wait.as_secs_f64(),
+            attempt + 1,
+            MAX_ATTEMPTS
+        );
+        tokio::time::sleep(wait).await;
+    }
+    unreachable!("the last attempt always returns")
+}
+
+/// The wait a `Retry-After` header (in seconds, which Discord sends with a fraction) asks for,
+/// capped at `MAX_RETRY_AFTER_SECS`.
+fn retry_after(header: Option<&reqwest::header::HeaderValue>) -> Duration {
+    let secs = header
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+    Duration::from_secs_f64(secs.min(MAX_RETRY_AFTER_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_post_webhook_retries_when_rate_limited() {
+        let server = MockServer::start().await;
+        let payload = json!({ "text": "report" });
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(2)
+            .with_priority(1)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(body_json(&payload))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/hook", server.uri());
+        post_webhook(&reqwest::Client::new(), &url, &payload)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_webhook_fails_on_error_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("no_service"))
+            .mount(&server)
+            .await;
+
+        let err = post_webhook(&reqwest::Client::new(), &server.uri(), &json!({}))
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("no_service"));
+    }
+
+    #[test]
+    fn test_slack_text_messages_escape_control_characters() {
+        assert_eq!(
+            slack_text_messages("a <b> & c"),
+            [json!({ "text": "```\na &lt;b&gt; &amp; c\n```" })]
+        );
+        assert_eq!("Slack".parse(), Ok(DeliveryTarget::Slack));
+        assert_eq!("discord".parse(), Ok(DeliveryTarget::Discord));
+        assert_eq!("gist".parse(), Ok(DeliveryTarget::Gist));
+        assert!("teams".parse::<DeliveryTarget>().is_err());
+    }
+
+    #[test]
+    fn test_issue_target() {
+        assert_eq!(
+            "octo/reports".parse(),
+            Ok(IssueTarget {
+                repo: "octo/reports".into(),
+                number: None
+            })
+        );
+        assert_eq!(
+            "octo/reports#12".parse(),
+            Ok(IssueTarget {
+                repo: "octo/reports".into(),
+                number: Some(12)
+            })
+        );
+        assert!("octo".parse::<IssueTarget>().is_err());
+        assert!("octo/reports#x".parse::<IssueTarget>().is_err());
+        assert!("octo/reports/extra".parse::<IssueTarget>().is_err());
+        assert_eq!("issue".parse(), Ok(DeliveryTarget::Issue));
+    }
+
+    #[test]
+    fn test_discord_messages_fit_the_limit() {
+        let content = |message: &Value| message["content"].as_str().unwrap().to_string();
+        let short = discord_messages("Report\n\nTotals: 3\n");
+        assert_eq!(short.len(), 1);
+        assert_eq!(content(&short[0]), "```\nReport\n\nTotals: 3\n```");
+
+        let line = "x".repeat(900);
+        let long = format!("{}\n{}\n{}\n{}", line, line, line, "y".repeat(4500));
+        let messages = discord_messages(&long);
+        assert!(
+            messages
+                .iter()
+                .all(|m| content(m).chars().count() <= DISCORD_MAX_CHARS)
+        );
+        // Whole lines stay together; only the overlong one is cut.
+        assert_eq!(
+            content(&messages[0]),
+            format!("(1/{})\n```\n{}\n{}\n```", messages.len(), line, line)
+        );
+        let rejoined: String = messages
+            .iter()
+            .map(|m| {
+                let content = content(m);
+                let (_, fenced) = content.split_once('\n').unwrap();
+                fenced.replace("```\n", "").replace("\n```", "")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(rejoined.replace('\n', ""), long.replace('\n', ""));
+    }
+
+    #[test]
+    fn test_split_sections_keeps_sections_whole() {
+        let text = format!(
+            "Report\n\n{}\n\n{}\n\nTotals: 3",
+            "a".repeat(30),
+            "b\n".repeat(25).trim_end()
+        );
+        let parts = split_sections(&plain_sections(&text), 40);
+        assert_eq!(
+            parts,
+            [
+                format!("Report\n\n{}", "a".repeat(30)),
+                // A section too long for a part is split between lines.
+                "b\n".repeat(20).trim_end().to_string(),
+                format!("{}\n\nTotals: 3", "b\n".repeat(5).trim_end()),
+            ]
+        );
+        assert!(parts.iter().all(|part| part.chars().count() <= 40));
+
+        let markdown = "# Report\n\nTotals\n\n## Pull Requests\n\n- one\n\n```\n# not a heading\n```\n## Issues\n- two\n";
+        assert_eq!(
+            markdown_sections(markdown),
+            [
+                "# Report\n\nTotals",
+                "## Pull Requests\n\n- one\n\n```\n# not a heading\n```",
+                "## Issues\n- two",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_issue_bodies_split_oversized_reports() {
+        assert_eq!(
+            issue_bodies("# Report\n\nTotals: 3\n"),
+            ["# Report\n\nTotals: 3"]
+        );
+
+        let list = "- [#1 Fix the parser](https://github.com/o/r/pull/1)\n".repeat(700);
+        let report = format!(
+            "# Report\n\nTotals: 3\n\n## Pull Requests\n\n{}\n## Issues\n\n{}\n## Reviews\n\n- none\n",
+            list, list
+        );
+        assert!(report.chars().count() > ISSUE_MAX_CHARS);
+        let bodies = issue_bodies(&report);
+        assert_eq!(bodies.len(), 2);
+        assert!(
+            bodies
+                .iter()
+                .all(|body| body.chars().count() <= ISSUE_MAX_CHARS)
+        );
+        assert!(bodies[0].starts_with("(1/2)\n\n# Report\n\nTotals: 3\n\n## Pull Requests"));
+        assert!(bodies[1].starts_with("(2/2)\n\n## Issues"));
+        assert!(bodies[1].ends_with("## Reviews\n\n- none"));
+    }
+
+    #[test]
+    fn test_slack_messages_are_split_and_numbered() {
+        let section = "x".repeat(30_000);
+        let messages = slack_text_messages(&format!("Report\n\n{}\n\n{}", section, section));
+        let texts: Vec<&str> = messages
+            .iter()
+            .map(|m| m["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                format!("(1/2)\n```\nReport\n\n{}\n```", section),
+                format!("(2/2)\n```\n{}\n```", section),
+            ]
+        );
+
+        let item = format!("{}\n", "y".repeat(100));
+        let mut blocks =
+            vec![json!({ "type": "header", "text": { "type": "plain_text", "text": "Report" } })];
+        blocks.push(
+            json!({ "type": "section", "text": { "type": "mrkdwn", "text": item.repeat(40) } }),
+        );
+        blocks.extend((0..60).map(|_| json!({ "type": "divider" })));
+        let messages = slack_block_messages(json!({ "blocks": blocks }));
+        assert_eq!(messages.len(), 2);
+        let first = messages[0]["blocks"].as_array().unwrap();
+        assert_eq!(first.len(), SLACK_MAX_BLOCKS);
+        assert_eq!(first[0]["elements"][0]["text"], "(1/2)");
+        assert_eq!(first[1]["type"], "header");
+        // The 4040-character section became two sections.
+        let sections: Vec<usize> = first
+            .iter()
+            .filter(|block| block["type"] == "section")
+            .map(|block| block["text"]["text"].as_str().unwrap().chars().count())
+            .collect();
+        assert_eq!(sections, [2928, 1110]);
+        assert_eq!(messages[1]["blocks"][0]["elements"][0]["text"], "(2/2)");
+        assert_eq!(messages[1]["blocks"].as_array().unwrap().len(), 1 + 63 - 49);
+    }
+
+    #[test]
+    fn test_step_outputs_sum_the_reports() {
+        let report = |commits: i64, reviews: i64| {
+            let activity = serde_json::from_value(json!({
+                "user": {
+                    "contributionsCollection": {
+                        "totalCommitContributions": commits,
+                        "totalIssueContributions": 1,
+                        "totalPullRequestContributions": 2,
+                        "totalPullRequestReviewContributions": reviews,
+                        "contributionCalendar": { "totalContributions": 0, "weeks": [] },
+                        "commitContributionsByRepository": [],
+                        "issueContributions": { "totalCount": 0, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": [] },
+                        "pullRequestContributions": { "totalCount": 0, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": [] },
+                        "pullRequestReviewContributions": { "totalCount": 0, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": [] }
+                    }
+                },
+                "rateLimit": null
+            }))
+            .unwrap();
+            let now = chrono::Utc::now();
+            Report::new("octocat".into(), now, now, activity)
+        };
+        assert_eq!(
+            step_outputs(&[report(5, 3), report(1, 0)]),
+            "contributions=15\ncommits=6\nissues=2\npull_requests=4\nreviews=3\n"
+        );
+        assert_eq!("step-summary".parse(), Ok(DeliveryTarget::StepSummary));
+    }
+
+    #[test]
+    fn test_retry_after_accepts_fractions() {
+        let header = reqwest::header::HeaderValue::from_static("0.25");
+        assert_eq!(retry_after(Some(&header)), Duration::from_millis(250));
+        let header = reqwest::header::HeaderValue::from_static("3600");
+        assert_eq!(retry_after(Some(&header)), Duration::from_secs(60));
+        assert_eq!(retry_after(None), Duration::from_secs(1));
+    }
+}
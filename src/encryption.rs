@@ -0,0 +1,133 @@
+//! Encryption module: encrypts written reports to [age](https://age-encryption.org) recipients
+//! for `--encrypt-to`, so reports naming private repositories can be stored or uploaded
+//! safely, and decrypts them again for `render --identity`.
+
+use age::armor::{ArmoredReader, ArmoredWriter, Format};
+use age::stream::StreamWriter;
+use age::x25519::Recipient;
+use age::{Decryptor, Encryptor, Identity, IdentityFile};
+use anyhow::{Context, Result, bail};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// How binary age files start.
+const BINARY_MAGIC: &[u8] = b"age-encryption.org/";
+
+/// How ASCII-armored age files start.
+const ARMOR_MAGIC: &[u8] = b"-----BEGIN AGE ENCRYPTED FILE-----";
+
+/// A writer that encrypts everything written through it.
+pub struct EncryptWriter<W: Write> {
+    inner: StreamWriter<ArmoredWriter<W>>,
+}
+
+impl<W: Write> EncryptWriter<W> {
+    /// Encrypts to `recipients` into `output`, ASCII-armored when `armor` is set.
+    pub fn new(output: W, recipients: &[Recipient], armor: bool) -> Result<Self> {
+        let encryptor =
+            Encryptor::with_recipients(recipients.iter().map(|r| r as &dyn age::Recipient))
+                .context("Failed to set up encryption")?;
+        let format = if armor {
+            Format::AsciiArmor
+        } else {
+            Format::Binary
+        };
+        let inner = ArmoredWriter::wrap_output(output, format)
+            .and_then(|armored| encryptor.wrap_output(armored))
+            .context("Failed to start encrypted output")?;
+        Ok(Self { inner })
+    }
+
+    /// Writes the end of the encrypted file and returns the underlying writer.
+    pub fn finish(self) -> io::Result<W> {
+        self.inner.finish()?.finish()
+    }
+}
+
+impl<W: Write> Write for EncryptWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Encrypts `plaintext` to `recipients`, ASCII-armored when `armor` is set.
+pub fn encrypt(plaintext: &[u8], recipients: &[Recipient], armor: bool) -> Result<Vec<u8>> {
+    let mut writer = EncryptWriter::new(Vec::new(), recipients, armor)?;
+    writer
+        .write_all(plaintext)
+        .and_then(|()| writer.finish())
+        .context("Failed to encrypt report")
+}
+
+/// Whether `contents` are an age file, binary or ASCII-armored.
+pub fn is_encrypted(contents: &[u8]) -> bool {
+    contents.starts_with(BINARY_MAGIC) || contents.starts_with(ARMOR_MAGIC)
+}
+
+/// Decrypts the age file `ciphertext` with the identities in the file at `identity_path`, as
+/// written by `age-keygen`.
+pub fn decrypt(ciphertext: &[u8], identity_path: &Path) -> Result<Vec<u8>> {
+    let identities = IdentityFile::from_file(identity_path.to_string_lossy().into_owned())
+        .with_context(|| format!("Failed to read identity file {:?}", identity_path))?
+        .into_identities()
+        .with_context(|| format!("Failed to parse identity file {:?}", identity_path))?;
+    if identities.is_empty() {
+        bail!("Identity file {:?} contains no identities", identity_path);
+    }
+
+    let decryptor = Decryptor::new_buffered(ArmoredReader::new(ciphertext))
+        .context("Report is not a valid age file")?;
+    let mut reader = decryptor
+        .decrypt(
+            identities
+                .iter()
+                .map(|identity| identity.as_ref() as &dyn Identity),
+        )
+        .context("Failed to decrypt report; is it encrypted to this identity?")?;
+    let mut plaintext = Vec::new();
+    reader
+        .read_to_end(&mut plaintext)
+        .context("Failed to decrypt report")?;
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use age::secrecy::ExposeSecret;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        let identity = age::x25519::Identity::generate();
+        let path = env::temp_dir().join(format!("github-activity-identity-{}", std::process::id()));
+        fs::write(&path, identity.to_string().expose_secret()).unwrap();
+
+        for armor in [false, true] {
+            let ciphertext = encrypt(
+                b"{\"username\":\"octocat\"}",
+                &[identity.to_public()],
+                armor,
+            )
+            .unwrap();
+            assert!(is_encrypted(&ciphertext));
+            assert_eq!(ciphertext.starts_with(ARMOR_MAGIC), armor);
+            assert_eq!(
+                decrypt(&ciphertext, &path).unwrap(),
+                b"{\"username\":\"octocat\"}"
+            );
+        }
+        assert!(!is_encrypted(b"{\"username\":\"octocat\"}"));
+
+        let other = age::x25519::Identity::generate();
+        let ciphertext = encrypt(b"{}", &[other.to_public()], false).unwrap();
+        assert!(decrypt(&ciphertext, &path).is_err());
+
+        fs::remove_file(path).unwrap();
+    }
+}
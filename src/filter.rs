@@ -1,4 +1,13 @@
+//! Filter module: narrows fetched activity to a repository, an organization, a set of labels,
+//! or a set of pull requests, orders and truncates its tables, and records what each filter
+//! removed.
+
 use crate::github::user_activity;
+use crate::revert::repository_of;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 
 /// Filters the activity data based on repository and organization filters.
 ///
@@ -37,6 +46,394 @@ pub fn filter_activity(
     activity
 }
 
+/// The names of fetched labels, in the order GitHub returned them.
+pub fn label_names(labels: &Option<user_activity::LabelNames>) -> Vec<&str> {
+    labels
+        .iter()
+        .flat_map(|labels| labels.nodes.iter().flatten().flatten())
+        .map(|label| label.name.as_str())
+        .collect()
+}
+
+/// Retains only the issue, pull request, and pull request review contributions carrying at
+/// least one of `labels`, compared case-insensitively as GitHub does.
+///
+/// The contribution totals are updated to the retained counts so the summary matches the
+/// filtered tables.
+pub fn retain_labeled(
+    mut activity: user_activity::ResponseData,
+    labels: &[String],
+) -> user_activity::ResponseData {
+    let wanted = |names: Vec<&str>| {
+        names
+            .iter()
+            .any(|name| labels.iter().any(|label| label.eq_ignore_ascii_case(name)))
+    };
+    if let Some(user) = activity.user.as_mut() {
+        let cc = &mut user.contributions_collection;
+        if let Some(nodes) = cc.issue_contributions.nodes.as_mut() {
+            nodes.retain(|node| wanted(label_names(&node.issue.labels)));
+            cc.issue_contributions.total_count = nodes.len() as i64;
+            cc.total_issue_contributions = nodes.len() as i64;
+        }
+        if let Some(nodes) = cc.pull_request_contributions.nodes.as_mut() {
+            nodes.retain(|node| wanted(label_names(&node.pull_request.labels)));
+            cc.pull_request_contributions.total_count = nodes.len() as i64;
+            cc.total_pull_request_contributions = nodes.len() as i64;
+        }
+        if let Some(nodes) = cc.pull_request_review_contributions.nodes.as_mut() {
+            nodes.retain(|node| wanted(label_names(&node.pull_request_review.pull_request.labels)));
+            cc.pull_request_review_contributions.total_count = nodes.len() as i64;
+            cc.total_pull_request_review_contributions = nodes.len() as i64;
+        }
+    }
+    activity
+}
+
+/// Retains only the pull request review contributions in one of `states`, as GitHub's GraphQL
+/// API names them (e.g. `APPROVED`), compared case-insensitively.
+///
+/// The review totals are updated to the retained count so the summary matches the filtered
+/// table.
+pub fn retain_review_states(
+    mut activity: user_activity::ResponseData,
+    states: &[&str],
+) -> user_activity::ResponseData {
+    if let Some(user) = activity.user.as_mut() {
+        let cc = &mut user.contributions_collection;
+        if let Some(nodes) = cc.pull_request_review_contributions.nodes.as_mut() {
+            nodes.retain(|node| {
+                states
+                    .iter()
+                    .any(|state| state.eq_ignore_ascii_case(&node.pull_request_review.state))
+            });
+            cc.pull_request_review_contributions.total_count = nodes.len() as i64;
+            cc.total_pull_request_review_contributions = nodes.len() as i64;
+        }
+    }
+    activity
+}
+
+/// Retains only the pull request and pull request review contributions whose pull request URL
+/// is in `keep`.
+///
+/// The contribution totals are updated to the retained counts so the summary matches the
+/// scoped tables.
+pub fn retain_pull_requests(
+    mut activity: user_activity::ResponseData,
+    keep: &HashSet<String>,
+) -> user_activity::ResponseData {
+    if let Some(user) = activity.user.as_mut() {
+        let cc = &mut user.contributions_collection;
+        if let Some(nodes) = cc.pull_request_contributions.nodes.as_mut() {
+            nodes.retain(|node| keep.contains(&node.pull_request.url));
+            cc.pull_request_contributions.total_count = nodes.len() as i64;
+            cc.total_pull_request_contributions = nodes.len() as i64;
+        }
+        if let Some(nodes) = cc.pull_request_review_contributions.nodes.as_mut() {
+            nodes.retain(|node| keep.contains(&node.pull_request_review.pull_request.url));
+            cc.pull_request_review_contributions.total_count = nodes.len() as i64;
+            cc.total_pull_request_review_contributions = nodes.len() as i64;
+        }
+    }
+    activity
+}
+
+/// How many items of each kind fetched activity holds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ItemCounts {
+    /// Repositories with commit contributions.
+    pub repositories: usize,
+    /// Issue contributions.
+    pub issues: usize,
+    /// Pull request contributions.
+    pub pull_requests: usize,
+    /// Pull request review contributions.
+    pub reviews: usize,
+}
+
+impl ItemCounts {
+    /// Counts the items listed in `activity`.
+    pub fn of(activity: &user_activity::ResponseData) -> Self {
+        let Some(user) = &activity.user else {
+            return Self::default();
+        };
+        let cc = &user.contributions_collection;
+        Self {
+            repositories: cc.commit_contributions_by_repository.len(),
+            issues: cc.issue_contributions.nodes.as_ref().map_or(0, Vec::len),
+            pull_requests: cc
+                .pull_request_contributions
+                .nodes
+                .as_ref()
+                .map_or(0, Vec::len),
+            reviews: cc
+                .pull_request_review_contributions
+                .nodes
+                .as_ref()
+                .map_or(0, Vec::len),
+        }
+    }
+
+    /// The items in `self` that are no longer in `after`.
+    fn removed(self, after: Self) -> Self {
+        Self {
+            repositories: self.repositories.saturating_sub(after.repositories),
+            issues: self.issues.saturating_sub(after.issues),
+            pull_requests: self.pull_requests.saturating_sub(after.pull_requests),
+            reviews: self.reviews.saturating_sub(after.reviews),
+        }
+    }
+
+    /// The non-zero counts, e.g. `["2 issues", "1 review"]`.
+    fn describe(self, all: bool) -> Vec<String> {
+        let counted = |count: usize, one: &str, many: &str| {
+            format!("{} {}", count, if count == 1 { one } else { many })
+        };
+        [
+            (
+                self.repositories,
+                "repository with commits",
+                "repositories with commits",
+            ),
+            (self.issues, "issue", "issues"),
+            (self.pull_requests, "pull request", "pull requests"),
+            (self.reviews, "review", "reviews"),
+        ]
+        .into_iter()
+        .filter(|(count, _, _)| all || *count > 0)
+        .map(|(count, one, many)| counted(count, one, many))
+        .collect()
+    }
+}
+
+/// How many items each filter removed from a user's activity, in the order the filters ran;
+/// shown by `--explain-filters` to debug unexpectedly empty reports.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilterLog {
+    /// What was fetched, before any filter ran.
+    pub fetched: ItemCounts,
+    /// Each filter, described as given on the command line, with the items it removed.
+    pub steps: Vec<(String, ItemCounts)>,
+    /// What is left after the last filter.
+    pub remaining: ItemCounts,
+}
+
+impl FilterLog {
+    /// Starts a log for the unfiltered `activity`.
+    pub fn new(activity: &user_activity::ResponseData) -> Self {
+        let fetched = ItemCounts::of(activity);
+        Self {
+            fetched,
+            steps: Vec::new(),
+            remaining: fetched,
+        }
+    }
+
+    /// Runs `filter` on `activity`, recording what it removed under `name`.
+    pub fn apply(
+        &mut self,
+        name: impl Into<String>,
+        activity: user_activity::ResponseData,
+        filter: impl FnOnce(user_activity::ResponseData) -> user_activity::ResponseData,
+    ) -> user_activity::ResponseData {
+        let filtered = filter(activity);
+        self.record(name, &filtered);
+        filtered
+    }
+
+    /// Records a filter, run outside the log, that left `activity`.
+    pub fn record(&mut self, name: impl Into<String>, activity: &user_activity::ResponseData) {
+        let after = ItemCounts::of(activity);
+        self.steps
+            .push((name.into(), self.remaining.removed(after)));
+        self.remaining = after;
+    }
+}
+
+impl fmt::Display for FilterLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Fetched: {}", self.fetched.describe(true).join(", "))?;
+        if self.steps.is_empty() {
+            writeln!(f, "No filters applied")?;
+        }
+        for (name, removed) in &self.steps {
+            let removed = removed.describe(false);
+            if removed.is_empty() {
+                writeln!(f, "{}: removed nothing", name)?;
+            } else {
+                writeln!(f, "{}: removed {}", name, removed.join(", "))?;
+            }
+        }
+        write!(f, "Remaining: {}", self.remaining.describe(true).join(", "))
+    }
+}
+
+/// How the issue, pull request, and review tables are ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Newest first, by creation (or, for reviews, submission).
+    Created,
+    /// By repository name, newest first within a repository.
+    Repository,
+    /// By state as GitHub names it (e.g. `CLOSED`, `MERGED`, `OPEN`), newest first within a
+    /// state.
+    State,
+    /// By title, case-insensitively; reviews by the title of their pull request.
+    Title,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "created" => Ok(Self::Created),
+            "repo" | "repository" => Ok(Self::Repository),
+            "state" => Ok(Self::State),
+            "title" => Ok(Self::Title),
+            _ => Err(format!(
+                "Invalid sort key: {}. Use created, repo, state, or title",
+                s
+            )),
+        }
+    }
+}
+
+/// The fields of a contribution that tables can be ordered by.
+struct SortFields<'a> {
+    url: &'a str,
+    state: &'a str,
+    title: &'a str,
+    created_at: &'a str,
+}
+
+impl SortKey {
+    fn compare(self, a: &SortFields, b: &SortFields) -> Ordering {
+        // RFC 3339 timestamps in UTC order like strings.
+        let newest_first = b.created_at.cmp(a.created_at);
+        match self {
+            Self::Created => newest_first,
+            Self::Repository => repository_of(a.url)
+                .cmp(&repository_of(b.url))
+                .then(newest_first),
+            Self::State => a.state.cmp(b.state).then(newest_first),
+            Self::Title => a
+                .title
+                .to_lowercase()
+                .cmp(&b.title.to_lowercase())
+                .then(newest_first),
+        }
+    }
+}
+
+/// Orders `nodes` by `sort`, keeping API order among equals, and keeps the first `top`.
+fn sort_and_truncate<T>(
+    nodes: &mut Option<Vec<T>>,
+    sort: Option<SortKey>,
+    top: Option<usize>,
+    fields: impl Fn(&T) -> SortFields,
+) {
+    let Some(nodes) = nodes else {
+        return;
+    };
+    if let Some(sort) = sort {
+        nodes.sort_by(|a, b| sort.compare(&fields(a), &fields(b)));
+    }
+    if let Some(top) = top {
+        nodes.truncate(top);
+    }
+}
+
+/// Orders the issue, pull request, and pull request review contributions by `sort` and keeps
+/// at most `top` of each.
+///
+/// Unlike the filters, this leaves the contribution totals alone: the summary still counts
+/// every contribution while the tables list the first ones.
+pub fn sort_and_limit(
+    mut activity: user_activity::ResponseData,
+    sort: Option<SortKey>,
+    top: Option<usize>,
+) -> user_activity::ResponseData {
+    if let Some(user) = activity.user.as_mut() {
+        let cc = &mut user.contributions_collection;
+        sort_and_truncate(&mut cc.issue_contributions.nodes, sort, top, |node| {
+            SortFields {
+                url: &node.issue.url,
+                state: &node.issue.state,
+                title: &node.issue.title,
+                created_at: &node.issue.created_at,
+            }
+        });
+        sort_and_truncate(
+            &mut cc.pull_request_contributions.nodes,
+            sort,
+            top,
+            |node| SortFields {
+                url: &node.pull_request.url,
+                state: &node.pull_request.state,
+                title: &node.pull_request.title,
+                created_at: &node.pull_request.created_at,
+            },
+        );
+        sort_and_truncate(
+            &mut cc.pull_request_review_contributions.nodes,
+            sort,
+            top,
+            |node| SortFields {
+                url: &node.pull_request_review.pull_request.url,
+                state: &node.pull_request_review.state,
+                title: &node.pull_request_review.pull_request.title,
+                created_at: &node.occurred_at,
+            },
+        );
+    }
+    activity
+}
+
+/// Keeps a random sample of at most `size` issue, pull request, and pull request review
+/// contributions each, in their original order; the same `seed` picks the same sample.
+///
+/// Like `sort_and_limit`, this leaves the contribution totals alone, so the summary still
+/// counts every contribution while the tables list the sample.
+pub fn sample(
+    mut activity: user_activity::ResponseData,
+    size: usize,
+    seed: u64,
+) -> user_activity::ResponseData {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    if let Some(user) = activity.user.as_mut() {
+        let cc = &mut user.contributions_collection;
+        sample_nodes(&mut cc.issue_contributions.nodes, size, &mut rng);
+        sample_nodes(&mut cc.pull_request_contributions.nodes, size, &mut rng);
+        sample_nodes(
+            &mut cc.pull_request_review_contributions.nodes,
+            size,
+            &mut rng,
+        );
+    }
+    activity
+}
+
+/// Keeps `size` randomly chosen `nodes`, in their original order.
+fn sample_nodes<T>(nodes: &mut Option<Vec<T>>, size: usize, rng: &mut fastrand::Rng) {
+    let Some(nodes) = nodes.as_mut().filter(|nodes| nodes.len() > size) else {
+        return;
+    };
+    // A partial Fisher-Yates shuffle of the indices picks the sample.
+    let mut indices: Vec<usize> = (0..nodes.len()).collect();
+    for i in 0..size {
+        let j = rng.usize(i..indices.len());
+        indices.swap(i, j);
+    }
+    let mut keep = vec![false; nodes.len()];
+    for &i in &indices[..size] {
+        keep[i] = true;
+    }
+    let mut keep = keep.into_iter();
+    nodes.retain(|_| keep.next().unwrap_or(false));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,6 +509,7 @@ mod tests {
             user: Some(user_activity::UserActivityUser {
                 contributions_collection,
             }),
+            rate_limit: None,
         }
     }
 
@@ -188,4 +586,179 @@ mod tests {
             .commit_contributions_by_repository;
         assert_eq!(repos.len(), 0);
     }
+
+    #[test]
+    fn test_retain_pull_requests() {
+        let mut data = dummy_response_data_for_filtering();
+        let cc = &mut data.user.as_mut().unwrap().contributions_collection;
+        cc.pull_request_contributions.nodes = Some(
+            serde_json::from_value(serde_json::json!([
+                { "pullRequest": { "number": 1, "title": "Frontend", "url": "http://example.com/pull/1", "createdAt": "2025-03-01T00:00:00Z", "state": "MERGED", "merged": true, "mergedAt": null, "closedAt": null, "additions": 1, "deletions": 0, "changedFiles": 1 } },
+                { "pullRequest": { "number": 2, "title": "Backend", "url": "http://example.com/pull/2", "createdAt": "2025-03-01T00:00:00Z", "state": "OPEN", "merged": false, "mergedAt": null, "closedAt": null, "additions": 1, "deletions": 0, "changedFiles": 1 } }
+            ]))
+            .unwrap(),
+        );
+        cc.total_pull_request_contributions = 2;
+
+        let keep = HashSet::from(["http://example.com/pull/1".to_string()]);
+        let filtered = retain_pull_requests(data, &keep);
+
+        let cc = filtered.user.unwrap().contributions_collection;
+        let nodes = cc.pull_request_contributions.nodes.unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].pull_request.number, 1);
+        assert_eq!(cc.total_pull_request_contributions, 1);
+    }
+
+    #[test]
+    fn test_retain_labeled() {
+        let mut data = dummy_response_data_for_filtering();
+        let cc = &mut data.user.as_mut().unwrap().contributions_collection;
+        cc.issue_contributions.nodes = Some(
+            serde_json::from_value(serde_json::json!([
+                { "issue": { "id": "I_1", "number": 1, "title": "Crash", "url": "http://example.com/issues/1", "createdAt": "2025-03-01T00:00:00Z", "state": "OPEN", "closedAt": null, "labels": { "nodes": [{ "name": "Bug" }] } } },
+                { "issue": { "id": "I_2", "number": 2, "title": "Idea", "url": "http://example.com/issues/2", "createdAt": "2025-03-01T00:00:00Z", "state": "OPEN", "closedAt": null, "labels": { "nodes": [{ "name": "enhancement" }] } } }
+            ]))
+            .unwrap(),
+        );
+        cc.pull_request_contributions.nodes = Some(
+            serde_json::from_value(serde_json::json!([
+                { "pullRequest": { "number": 3, "title": "Unlabeled", "url": "http://example.com/pull/3", "createdAt": "2025-03-01T00:00:00Z", "state": "OPEN", "merged": false, "mergedAt": null, "closedAt": null, "additions": 1, "deletions": 0, "changedFiles": 1 } }
+            ]))
+            .unwrap(),
+        );
+        cc.total_issue_contributions = 2;
+        cc.total_pull_request_contributions = 1;
+
+        let filtered = retain_labeled(data, &["bug".to_string()]);
+
+        let cc = filtered.user.unwrap().contributions_collection;
+        let issues = cc.issue_contributions.nodes.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(label_names(&issues[0].issue.labels), ["Bug"]);
+        assert_eq!(cc.total_issue_contributions, 1);
+        assert!(cc.pull_request_contributions.nodes.unwrap().is_empty());
+        assert_eq!(cc.total_pull_request_contributions, 0);
+    }
+
+    #[test]
+    fn test_sort_and_limit() {
+        let mut data = dummy_response_data_for_filtering();
+        let cc = &mut data.user.as_mut().unwrap().contributions_collection;
+        cc.pull_request_contributions.nodes = Some(
+            serde_json::from_value(serde_json::json!([
+                { "pullRequest": { "number": 1, "title": "beta", "url": "https://github.com/o/web/pull/1", "createdAt": "2025-03-01T00:00:00Z", "state": "OPEN", "merged": false, "mergedAt": null, "closedAt": null, "additions": 1, "deletions": 0, "changedFiles": 1 } },
+                { "pullRequest": { "number": 2, "title": "Alpha", "url": "https://github.com/o/api/pull/2", "createdAt": "2025-03-03T00:00:00Z", "state": "MERGED", "merged": true, "mergedAt": null, "closedAt": null, "additions": 1, "deletions": 0, "changedFiles": 1 } },
+                { "pullRequest": { "number": 3, "title": "gamma", "url": "https://github.com/o/web/pull/3", "createdAt": "2025-03-02T00:00:00Z", "state": "MERGED", "merged": true, "mergedAt": null, "closedAt": null, "additions": 1, "deletions": 0, "changedFiles": 1 } }
+            ]))
+            .unwrap(),
+        );
+        cc.total_pull_request_contributions = 3;
+
+        let numbers = |sort: Option<SortKey>, top: Option<usize>| {
+            let cc = sort_and_limit(data.clone(), sort, top)
+                .user
+                .unwrap()
+                .contributions_collection;
+            assert_eq!(cc.total_pull_request_contributions, 3);
+            cc.pull_request_contributions
+                .nodes
+                .unwrap()
+                .iter()
+                .map(|node| node.pull_request.number)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(None, None), [1, 2, 3]);
+        assert_eq!(numbers(Some(SortKey::Created), None), [2, 3, 1]);
+        assert_eq!(numbers(Some(SortKey::Repository), None), [2, 3, 1]);
+        assert_eq!(numbers(Some(SortKey::State), None), [2, 3, 1]);
+        assert_eq!(numbers(Some(SortKey::Title), None), [2, 1, 3]);
+        assert_eq!(numbers(None, Some(2)), [1, 2]);
+        assert_eq!(numbers(Some(SortKey::Title), Some(1)), [2]);
+
+        assert_eq!("repo".parse(), Ok(SortKey::Repository));
+        assert!("size".parse::<SortKey>().is_err());
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut data = dummy_response_data_for_filtering();
+        let cc = &mut data.user.as_mut().unwrap().contributions_collection;
+        let pull_requests: Vec<_> = (1..=20)
+            .map(|number| serde_json::json!({ "pullRequest": { "number": number, "title": "PR", "url": format!("https://github.com/o/web/pull/{}", number), "createdAt": "2025-03-01T00:00:00Z", "state": "OPEN", "merged": false, "mergedAt": null, "closedAt": null, "additions": 1, "deletions": 0, "changedFiles": 1 } }))
+            .collect();
+        cc.pull_request_contributions.nodes =
+            Some(serde_json::from_value(serde_json::Value::Array(pull_requests)).unwrap());
+        cc.total_pull_request_contributions = 20;
+
+        let numbers = |size: usize, seed: u64| {
+            let cc = sample(data.clone(), size, seed)
+                .user
+                .unwrap()
+                .contributions_collection;
+            assert_eq!(cc.total_pull_request_contributions, 20);
+            cc.pull_request_contributions
+                .nodes
+                .unwrap()
+                .iter()
+                .map(|node| node.pull_request.number)
+                .collect::<Vec<_>>()
+        };
+        let picked = numbers(5, 7);
+        assert_eq!(picked.len(), 5);
+        assert!(picked.is_sorted());
+        assert_eq!(numbers(5, 7), picked);
+        assert_ne!(numbers(5, 8), picked);
+        assert_eq!(numbers(50, 7), (1..=20).collect::<Vec<_>>());
+        assert!(numbers(0, 7).is_empty());
+    }
+
+    #[test]
+    fn test_retain_review_states() {
+        let mut data = dummy_response_data_for_filtering();
+        let cc = &mut data.user.as_mut().unwrap().contributions_collection;
+        cc.pull_request_review_contributions.nodes = Some(
+            serde_json::from_value(serde_json::json!([
+                { "pullRequestReview": { "pullRequest": { "number": 1, "title": "Approved", "url": "http://example.com/pull/1" }, "state": "APPROVED" }, "occurredAt": "2025-03-01T00:00:00Z" },
+                { "pullRequestReview": { "pullRequest": { "number": 2, "title": "Commented", "url": "http://example.com/pull/2" }, "state": "COMMENTED" }, "occurredAt": "2025-03-01T00:00:00Z" }
+            ]))
+            .unwrap(),
+        );
+        cc.total_pull_request_review_contributions = 2;
+
+        let filtered = retain_review_states(data, &["approved"]);
+
+        let cc = filtered.user.unwrap().contributions_collection;
+        let reviews = cc.pull_request_review_contributions.nodes.unwrap();
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].pull_request_review.pull_request.number, 1);
+        assert_eq!(cc.total_pull_request_review_contributions, 1);
+    }
+
+    #[test]
+    fn test_filter_log() {
+        let data = dummy_response_data_for_filtering();
+        let mut log = FilterLog::new(&data);
+        let data = log.apply("--org org1", data, |a| {
+            filter_activity(a, &None, &Some("org1".to_string()))
+        });
+        let data = log.apply("--label bug", data, |a| {
+            retain_labeled(a, &["bug".to_string()])
+        });
+        log.apply("--repo org2/repo2", data, |a| {
+            filter_activity(a, &Some("org2/repo2".to_string()), &None)
+        });
+
+        assert_eq!(log.fetched.repositories, 3);
+        assert_eq!(log.steps[0].1.repositories, 1);
+        assert_eq!(log.remaining, ItemCounts::default());
+        assert_eq!(
+            log.to_string(),
+            "Fetched: 3 repositories with commits, 0 issues, 0 pull requests, 0 reviews\n\
+             --org org1: removed 1 repository with commits\n\
+             --label bug: removed nothing\n\
+             --repo org2/repo2: removed 2 repositories with commits\n\
+             Remaining: 0 repositories with commits, 0 issues, 0 pull requests, 0 reviews"
+        );
+    }
 }
@@ -0,0 +1,180 @@
+//! Fork module: tells contributions to the user's own forks apart from upstream work.
+//!
+//! Commits pushed to a personal fork (syncing it, or work that later lands upstream through a
+//! pull request) and pull requests opened within it show up as contributions alongside the
+//! upstream ones, inflating the numbers. Only repositories owned by the user are looked up,
+//! since forks kept by an organization usually carry real work.
+
+use crate::github::user_activity;
+use crate::revert::repository_of;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The user's forks among the reported repositories, keyed by `owner/name`, with the
+/// repository each was forked from; `None` when the parent isn't visible to the token.
+pub type Forks = BTreeMap<String, Option<String>>;
+
+/// The repositories of `activity` owned by `username`: those with commits, and those of
+/// issues, pull requests, and reviewed pull requests.
+pub fn own_repositories(
+    activity: &user_activity::ResponseData,
+    username: &str,
+) -> BTreeSet<String> {
+    let Some(user) = &activity.user else {
+        return BTreeSet::new();
+    };
+    let cc = &user.contributions_collection;
+    let commits = cc
+        .commit_contributions_by_repository
+        .iter()
+        .map(|contribution| contribution.repository.name_with_owner.clone());
+    let issues = cc
+        .issue_contributions
+        .nodes
+        .iter()
+        .flatten()
+        .filter_map(|node| repository_of(&node.issue.url));
+    let pull_requests = cc
+        .pull_request_contributions
+        .nodes
+        .iter()
+        .flatten()
+        .filter_map(|node| repository_of(&node.pull_request.url));
+    let reviews = cc
+        .pull_request_review_contributions
+        .nodes
+        .iter()
+        .flatten()
+        .filter_map(|node| repository_of(&node.pull_request_review.pull_request.url));
+    commits
+        .chain(issues)
+        .chain(pull_requests)
+        .chain(reviews)
+        .filter(|repo| {
+            repo.split_once('/')
+                .is_some_and(|(owner, _)| owner.eq_ignore_ascii_case(username))
+        })
+        .collect()
+}
+
+/// How a fork is noted next to its repository, e.g. `fork of rust-lang/rust`.
+pub fn fork_note(forks: &Forks, repo: &str) -> Option<String> {
+    forks.get(repo).map(|parent| match parent {
+        Some(parent) => format!("fork of {}", parent),
+        None => "fork".to_string(),
+    })
+}
+
+/// Removes the commit, issue, pull request, and pull request review contributions made in
+/// `forks`.
+///
+/// The contribution totals are reduced by what was removed so the summary matches the
+/// filtered tables; the contribution calendar can't be split by repository and is kept.
+pub fn exclude_forks(
+    mut activity: user_activity::ResponseData,
+    forks: &Forks,
+) -> user_activity::ResponseData {
+    let upstream = |url: &str| repository_of(url).is_none_or(|repo| !forks.contains_key(&repo));
+    if let Some(user) = activity.user.as_mut() {
+        let cc = &mut user.contributions_collection;
+        let fork_commits: i64 = cc
+            .commit_contributions_by_repository
+            .iter()
+            .filter(|contribution| forks.contains_key(&contribution.repository.name_with_owner))
+            .map(|contribution| contribution.contributions.total_count)
+            .sum();
+        cc.commit_contributions_by_repository
+            .retain(|contribution| !forks.contains_key(&contribution.repository.name_with_owner));
+        cc.total_commit_contributions = (cc.total_commit_contributions - fork_commits).max(0);
+        if let Some(nodes) = cc.issue_contributions.nodes.as_mut() {
+            let before = nodes.len() as i64;
+            nodes.retain(|node| upstream(&node.issue.url));
+            let removed = before - nodes.len() as i64;
+            cc.issue_contributions.total_count -= removed;
+            cc.total_issue_contributions -= removed;
+        }
+        if let Some(nodes) = cc.pull_request_contributions.nodes.as_mut() {
+            let before = nodes.len() as i64;
+            nodes.retain(|node| upstream(&node.pull_request.url));
+            let removed = before - nodes.len() as i64;
+            cc.pull_request_contributions.total_count -= removed;
+            cc.total_pull_request_contributions -= removed;
+        }
+        if let Some(nodes) = cc.pull_request_review_contributions.nodes.as_mut() {
+            let before = nodes.len() as i64;
+            nodes.retain(|node| upstream(&node.pull_request_review.pull_request.url));
+            let removed = before - nodes.len() as i64;
+            cc.pull_request_review_contributions.total_count -= removed;
+            cc.total_pull_request_review_contributions -= removed;
+        }
+    }
+    activity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn activity() -> user_activity::ResponseData {
+        let page = |nodes: serde_json::Value| json!({ "totalCount": 2, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes });
+        let pr = |number: i64, repo: &str| {
+            json!({ "pullRequest": {
+                "number": number, "title": "PR", "url": format!("https://github.com/{}/pull/{}", repo, number),
+                "createdAt": "2025-03-02T00:00:00Z", "state": "OPEN", "merged": false,
+                "mergedAt": null, "closedAt": null, "additions": 1, "deletions": 0, "changedFiles": 1
+            } })
+        };
+        let repository = |name: &str, commits: i64| {
+            json!({
+                "repository": { "nameWithOwner": name, "updatedAt": "2025-03-02T00:00:00Z" },
+                "contributions": { "totalCount": commits }
+            })
+        };
+        serde_json::from_value(json!({ "user": { "contributionsCollection": {
+            "totalCommitContributions": 7,
+            "totalIssueContributions": 0,
+            "totalPullRequestContributions": 2,
+            "totalPullRequestReviewContributions": 0,
+            "contributionCalendar": { "totalContributions": 9, "weeks": [] },
+            "commitContributionsByRepository": [
+                repository("octocat/rust", 5),
+                repository("rust-lang/rust", 2)
+            ],
+            "issueContributions": page(json!([])),
+            "pullRequestContributions": page(json!([pr(1, "octocat/rust"), pr(2, "rust-lang/rust")])),
+            "pullRequestReviewContributions": page(json!([]))
+        } } }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_own_repositories() {
+        let repos: Vec<String> = own_repositories(&activity(), "OctoCat")
+            .into_iter()
+            .collect();
+        assert_eq!(repos, ["octocat/rust"]);
+    }
+
+    #[test]
+    fn test_exclude_forks() {
+        let forks = Forks::from([(
+            "octocat/rust".to_string(),
+            Some("rust-lang/rust".to_string()),
+        )]);
+        assert_eq!(
+            fork_note(&forks, "octocat/rust").as_deref(),
+            Some("fork of rust-lang/rust")
+        );
+        assert_eq!(fork_note(&forks, "rust-lang/rust"), None);
+
+        let activity = exclude_forks(activity(), &forks);
+        let cc = &activity.user.unwrap().contributions_collection;
+        assert_eq!(cc.total_commit_contributions, 2);
+        assert_eq!(cc.commit_contributions_by_repository.len(), 1);
+        assert_eq!(cc.total_pull_request_contributions, 1);
+        assert_eq!(cc.pull_request_contributions.total_count, 1);
+        let nodes = cc.pull_request_contributions.nodes.as_ref().unwrap();
+        assert_eq!(nodes[0].pull_request.number, 2);
+        assert_eq!(cc.contribution_calendar.total_contributions, 9);
+    }
+}
@@ -1,32 +1,469 @@
 #![warn(missing_docs)]
 //! Formatting module: defines a trait to format GitHub activity data into various output styles.
 
-use crate::github::user_activity;
-use chrono::{DateTime as ChronoDateTime, Utc};
+use crate::calendar::{CalendarDay, CalendarGranularity, Rollup, calendar_periods, rollups};
+use crate::category::{Categories, CategoryCounts, UNCATEGORIZED, breakdown};
+use crate::coverage::RepositoryCoverage;
+use crate::filter::label_names;
+use crate::fork::fork_note;
+use crate::github::user_activity::UserActivityUserContributionsCollection as ContributionsCollection;
+use crate::github::user_activity::UserActivityUserContributionsCollectionContributionCalendar as ContributionCalendar;
+use crate::identity::{self, UnattributedEmail};
+use crate::locale::NumberLocale;
+use crate::narrative::{Tone, paragraphs};
+use crate::report::Report;
+use crate::revert::repository_of;
+use crate::sparkline::Sparklines;
+use crate::statistics::{Forecast, Statistics};
+use crate::text;
+use crate::timeline::history_line;
+use crate::triage::{TriageAction, TriageSummary};
+use anyhow::{Context, Result};
+use handlebars::Handlebars;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
 
 /// A trait for formatting GitHub activity data.
 pub trait FormatData {
-    /// Formats the activity data given the time range and username.
-    fn format(
-        &self,
-        activity: &user_activity::ResponseData,
-        start_date: ChronoDateTime<Utc>,
-        end_date: ChronoDateTime<Utc>,
-        username: &str,
-    ) -> String;
+    /// Formats the report's activity data along with its time range and username.
+    fn format(&self, report: &Report) -> String;
+}
+
+/// The triage action lists paired with their headings, in display order.
+fn triage_sections(triage: &TriageSummary) -> [(&'static str, &Vec<TriageAction>); 4] {
+    [
+        ("Issues Labeled", &triage.labeled),
+        ("Closed as Duplicate", &triage.closed_as_duplicate),
+        ("Closed as Not Planned", &triage.closed_as_not_planned),
+        ("First Responses", &triage.first_responses),
+    ]
+}
+
+/// Each contribution type's share of the total in whole percent, summing to 100, in the order
+/// commits, issues, pull requests, reviews; `None` when there were no contributions.
+pub fn contribution_mix(cc: &ContributionsCollection) -> Option<[(&'static str, i64); 4]> {
+    let counts = [
+        ("commits", cc.total_commit_contributions),
+        ("issues", cc.total_issue_contributions),
+        ("pull requests", cc.total_pull_request_contributions),
+        ("reviews", cc.total_pull_request_review_contributions),
+    ];
+    let total: i64 = counts.iter().map(|(_, count)| count).sum();
+    if total <= 0 {
+        return None;
+    }
+    // Largest remainder rounding, so the shares always add up to exactly 100%.
+    let mut mix = counts.map(|(label, count)| (label, count * 100 / total));
+    let mut by_remainder: Vec<usize> = (0..counts.len()).collect();
+    by_remainder.sort_by_key(|&i| std::cmp::Reverse(counts[i].1 * 100 % total));
+    let missing = 100 - mix.iter().map(|(_, percent)| percent).sum::<i64>();
+    for &i in by_remainder.iter().take(missing as usize) {
+        mix[i].1 += 1;
+    }
+    Some(mix)
+}
+
+/// Lines added and deleted across the report's pull requests, as `(additions, deletions)`.
+pub fn lines_changed(cc: &ContributionsCollection) -> (i64, i64) {
+    cc.pull_request_contributions.nodes.iter().flatten().fold(
+        (0, 0),
+        |(additions, deletions), node| {
+            (
+                additions + node.pull_request.additions,
+                deletions + node.pull_request.deletions,
+            )
+        },
+    )
+}
+
+/// Lines added and deleted, e.g. `+1,204 -310`.
+fn diff_stat(additions: i64, deletions: i64, locale: NumberLocale) -> String {
+    format!(
+        "+{} -{}",
+        locale.format(additions),
+        locale.format(deletions)
+    )
+}
+
+/// The size of a pull request, e.g. `+120 -15 in 4 files`.
+fn pr_size(additions: i64, deletions: i64, changed_files: i64, locale: NumberLocale) -> String {
+    format!(
+        "{} in {} {}",
+        diff_stat(additions, deletions, locale),
+        locale.format(changed_files),
+        if changed_files == 1 { "file" } else { "files" }
+    )
+}
+
+/// A pull request review state as shown in reports, e.g. `changes requested` for
+/// `CHANGES_REQUESTED`; empty for reports from before review states were fetched.
+fn review_state(state: &str) -> String {
+    state.to_lowercase().replace('_', " ")
+}
+
+/// The body of a pull request review, when it was fetched (`--review-bodies`) and isn't empty.
+fn review_body(body: &Option<String>) -> Option<&str> {
+    body.as_deref()
+        .map(str::trim)
+        .filter(|body| !body.is_empty())
+}
+
+/// The repository of a commit contribution as listed in reports, with a note when it's one of
+/// the user's forks, e.g. `octocat/rust (fork of rust-lang/rust)`.
+fn repository_name(report: &Report, repo: &str) -> String {
+    match fork_note(&report.forks, repo) {
+        Some(note) => format!("{} ({})", repo, note),
+        None => repo.to_string(),
+    }
+}
+
+/// The fork note of the repository of an issue or pull request `url`, if it's one of the
+/// user's forks.
+fn url_fork_note(report: &Report, url: &str) -> Option<String> {
+    repository_of(url).and_then(|repo| fork_note(&report.forks, &repo))
+}
+
+/// The contribution mix as one line, e.g. `62% commits, 21% issues, 11% pull requests, 6% reviews`.
+fn mix_line(mix: &[(&str, i64)]) -> String {
+    mix.iter()
+        .map(|(label, percent)| format!("{}% {}", percent, label))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The `--stats` statistics as labeled values, in display order.
+fn statistics_rows(statistics: &Statistics, locale: NumberLocale) -> Vec<(&'static str, String)> {
+    let days = |n: i64| {
+        format!(
+            "{} {}",
+            locale.format(n),
+            if n == 1 { "day" } else { "days" }
+        )
+    };
+    let mut rows = vec![
+        ("Longest Streak", days(statistics.longest_streak)),
+        ("Current Streak", days(statistics.current_streak)),
+        (
+            "Busiest Day",
+            statistics
+                .busiest_day
+                .as_ref()
+                .map_or("none".to_string(), |day| {
+                    format!(
+                        "{} ({} contributions)",
+                        day.date,
+                        locale.format(day.contribution_count)
+                    )
+                }),
+        ),
+        (
+            "Active Days",
+            format!(
+                "{} of {}",
+                locale.format(statistics.active_days),
+                locale.format(statistics.days)
+            ),
+        ),
+        (
+            "Average per Day",
+            locale.format_decimal(statistics.average_per_day),
+        ),
+        (
+            "Average per Week",
+            locale.format_decimal(statistics.average_per_week),
+        ),
+    ];
+    if !statistics.mix.is_empty() {
+        let mix: Vec<(&str, i64)> = statistics
+            .mix
+            .iter()
+            .map(|share| (share.kind.as_str(), share.percent))
+            .collect();
+        rows.push(("Contribution Mix", mix_line(&mix)));
+    }
+    if let Some(rate) = statistics.merge_rate {
+        rows.push(("Merge Rate", format!("{}%", rate)));
+    }
+    if let Some(ratio) = statistics.reviews_per_pull_request {
+        rows.push(("Reviews per Pull Request", locale.format_decimal(ratio)));
+    }
+    if let Some(forecast) = &statistics.forecast {
+        rows.push(("Forecast", forecast_line(forecast, locale)));
+    }
+    rows
+}
+
+/// A forecast as one line, e.g. `on pace for ~22 pull requests and ~140 commits this quarter
+/// (through 2025-06-30)`.
+fn forecast_line(forecast: &Forecast, locale: NumberLocale) -> String {
+    let totals: Vec<String> = [
+        (forecast.pull_requests, "pull request", "pull requests"),
+        (forecast.commits, "commit", "commits"),
+        (forecast.issues, "issue", "issues"),
+        (forecast.reviews, "review", "reviews"),
+    ]
+    .iter()
+    .filter(|(n, _, _)| *n > 0)
+    .map(|&(n, one, many)| format!("~{} {}", locale.format(n), if n == 1 { one } else { many }))
+    .collect();
+    let pace = match totals.as_slice() {
+        [] => "no contributions projected".to_string(),
+        [only] => format!("on pace for {}", only),
+        [rest @ .., last] => format!("on pace for {} and {}", rest.join(", "), last),
+    };
+    format!(
+        "{} this {} (through {})",
+        pace, forecast.period, forecast.through
+    )
+}
+
+/// How the issue, pull request, and review tables of Markdown and HTML reports are organized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupBy {
+    /// One table of each kind for the whole report.
+    #[default]
+    None,
+    /// One heading per repository with its own tables.
+    Repository,
+    /// One heading per category of the config's category rules with its own tables.
+    Category,
+    /// No tables; a row per week with its contribution, issue, pull request, and review
+    /// counts, plus a total.
+    Week,
+    /// No tables; a row per month with its contribution, issue, pull request, and review
+    /// counts, plus a total.
+    Month,
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "repo" | "repository" => Ok(Self::Repository),
+            "category" => Ok(Self::Category),
+            "week" => Ok(Self::Week),
+            "month" => Ok(Self::Month),
+            _ => Err(format!(
+                "Invalid grouping: {}. Use repo, category, week, month, or none",
+                s
+            )),
+        }
+    }
+}
+
+impl GroupBy {
+    /// The calendar granularity of a rollup grouping.
+    fn rollup(self) -> Option<CalendarGranularity> {
+        match self {
+            Self::Week => Some(CalendarGranularity::Weekly),
+            Self::Month => Some(CalendarGranularity::Monthly),
+            Self::None | Self::Repository | Self::Category => None,
+        }
+    }
+}
+
+/// A section of Markdown reports that a template snippet can replace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MarkdownSection {
+    /// The totals under the report title.
+    Summary,
+    /// The `--stats` section.
+    Statistics,
+    /// The commits not attributed to the user.
+    UnattributedCommits,
+    /// The category breakdown table.
+    Categories,
+    /// The review coverage table of `--review-coverage`.
+    ReviewCoverage,
+    /// The triage activity of `--triage`.
+    Triage,
+    /// The contribution calendar.
+    Calendar,
+    /// The commits per repository table.
+    Repositories,
+    /// The issue table, when the tables aren't grouped.
+    Issues,
+    /// The pull request table, when the tables aren't grouped.
+    PullRequests,
+    /// The review table, when the tables aren't grouped.
+    Reviews,
+}
+
+impl FromStr for MarkdownSection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "summary" => Ok(Self::Summary),
+            "statistics" => Ok(Self::Statistics),
+            "unattributed-commits" => Ok(Self::UnattributedCommits),
+            "categories" => Ok(Self::Categories),
+            "review-coverage" => Ok(Self::ReviewCoverage),
+            "triage" => Ok(Self::Triage),
+            "calendar" => Ok(Self::Calendar),
+            "repositories" => Ok(Self::Repositories),
+            "issues" => Ok(Self::Issues),
+            "pull-requests" => Ok(Self::PullRequests),
+            "reviews" => Ok(Self::Reviews),
+            _ => Err(format!(
+                "Invalid section: {}. Use summary, statistics, unattributed-commits, categories, review-coverage, triage, calendar, repositories, issues, pull-requests, or reviews",
+                s
+            )),
+        }
+    }
+}
+
+/// The rollups of `cc` and their total, labeled `Total`.
+fn rollups_with_total(
+    cc: &ContributionsCollection,
+    granularity: CalendarGranularity,
+) -> (Vec<Rollup>, Rollup) {
+    let rows = rollups(cc, granularity);
+    let total = rows.iter().fold(
+        Rollup {
+            label: "Total".to_string(),
+            ..Default::default()
+        },
+        |mut total, row| {
+            total.contribution_count += row.contribution_count;
+            total.issues += row.issues;
+            total.pull_requests += row.pull_requests;
+            total.reviews += row.reviews;
+            total
+        },
+    );
+    (rows, total)
+}
+
+/// The repositories of the issues, pull requests, and reviews in `cc`, in name order, each
+/// with a copy of `cc` that keeps only the contributions in that repository.
+fn by_repository(cc: &ContributionsCollection) -> Vec<(String, ContributionsCollection)> {
+    let issues = cc.issue_contributions.nodes.iter().flatten();
+    let prs = cc.pull_request_contributions.nodes.iter().flatten();
+    let reviews = cc.pull_request_review_contributions.nodes.iter().flatten();
+    let repositories: BTreeSet<String> = issues
+        .map(|node| node.issue.url.as_str())
+        .chain(prs.map(|node| node.pull_request.url.as_str()))
+        .chain(reviews.map(|node| node.pull_request_review.pull_request.url.as_str()))
+        .filter_map(repository_of)
+        .collect();
+    repositories
+        .into_iter()
+        .map(|repository| {
+            let cc = retain_contributions(cc, |url| {
+                repository_of(url).as_deref() == Some(repository.as_str())
+            });
+            (repository, cc)
+        })
+        .collect()
+}
+
+/// The categories of the issues, pull requests, and reviews in `cc`, in name order with
+/// uncategorized ones last, each with a copy of `cc` that keeps only the contributions in that
+/// category.
+fn by_category(
+    cc: &ContributionsCollection,
+    categories: &Categories,
+) -> Vec<(String, ContributionsCollection)> {
+    breakdown(cc, categories)
+        .into_iter()
+        .map(|counts| {
+            let cc = retain_contributions(cc, |url| {
+                categories.get(url).map_or(UNCATEGORIZED, String::as_str) == counts.name
+            });
+            (counts.name, cc)
+        })
+        .collect()
+}
+
+/// A copy of `cc` that keeps only the issues, pull requests, and reviews whose (reviewed) URL
+/// satisfies `keep`.
+fn retain_contributions(
+    cc: &ContributionsCollection,
+    keep: impl Fn(&str) -> bool,
+) -> ContributionsCollection {
+    let mut cc = cc.clone();
+    if let Some(nodes) = &mut cc.issue_contributions.nodes {
+        nodes.retain(|node| keep(&node.issue.url));
+    }
+    if let Some(nodes) = &mut cc.pull_request_contributions.nodes {
+        nodes.retain(|node| keep(&node.pull_request.url));
+    }
+    if let Some(nodes) = &mut cc.pull_request_review_contributions.nodes {
+        nodes.retain(|node| keep(&node.pull_request_review.pull_request.url));
+    }
+    cc
+}
+
+/// One author email of `--check-commit-emails`, e.g. `me@work.example: 2 commits in o/a
+/// (verify this email in your GitHub settings)`.
+fn unattributed_line(email: &UnattributedEmail, locale: NumberLocale) -> String {
+    format!(
+        "{}: {} {} in {} ({})",
+        email.email,
+        locale.format(email.commits as i64),
+        if email.commits == 1 {
+            "commit"
+        } else {
+            "commits"
+        },
+        email.repositories.join(", "),
+        identity::advice(email)
+    )
+}
+
+/// One repository of `--review-coverage`, e.g. `reviewed 4 of 10 merged pull requests (40%)`.
+fn coverage_line(coverage: &RepositoryCoverage, locale: NumberLocale) -> String {
+    format!(
+        "reviewed {} of {} merged pull {} ({})",
+        locale.format(coverage.reviewed),
+        locale.format(coverage.merged),
+        if coverage.merged == 1 {
+            "request"
+        } else {
+            "requests"
+        },
+        coverage_percent(coverage)
+    )
+}
+
+/// The reviewed share of a `--review-coverage` repository, e.g. `40%`.
+fn coverage_percent(coverage: &RepositoryCoverage) -> String {
+    coverage
+        .percent()
+        .map_or_else(|| "-".to_string(), |percent| format!("{}%", percent))
+}
+
+/// The contributions of each category of `report`, when it has categories.
+fn category_breakdown(report: &Report) -> Vec<CategoryCounts> {
+    match &report.activity.user {
+        Some(user) => breakdown(&user.contributions_collection, &report.categories),
+        None => Vec::new(),
+    }
 }
 
 /// A plain text formatter for GitHub activity.
-pub struct PlainTextFormatter;
+#[derive(Default)]
+pub struct PlainTextFormatter {
+    /// How totals are grouped, e.g. `12,345`.
+    pub locale: NumberLocale,
+    /// How the contribution calendar is shown; daily shows a heatmap.
+    pub calendar: CalendarGranularity,
+    /// List every day of a daily calendar on its own line instead of the heatmap.
+    pub calendar_detail: bool,
+    /// Whether sections list contributions or summarize them in sentences.
+    pub tone: Tone,
+}
 
 impl FormatData for PlainTextFormatter {
-    fn format(
-        &self,
-        activity: &user_activity::ResponseData,
-        start_date: ChronoDateTime<Utc>,
-        end_date: ChronoDateTime<Utc>,
-        username: &str,
-    ) -> String {
+    fn format(&self, report: &Report) -> String {
+        let (activity, username) = (&report.activity, &report.username);
+        let (start_date, end_date) = (report.start_date, report.end_date);
         let mut output = String::new();
         if let Some(user) = &activity.user {
             let cc = &user.contributions_collection;
@@ -36,45 +473,159 @@ impl FormatData for PlainTextFormatter {
                 start_date.to_rfc3339(),
                 end_date.to_rfc3339()
             ));
+            for warning in &report.warnings {
+                output.push_str(&format!("Warning: {}\n", warning));
+            }
+            if self.tone == Tone::Narrative {
+                for paragraph in paragraphs(report, self.locale) {
+                    output.push_str(&format!("\n{}: {}\n", paragraph.heading, paragraph.text));
+                }
+                return output;
+            }
             output.push_str(&format!(
                 "Total Commit Contributions: {}\n",
-                cc.total_commit_contributions
+                self.locale.format(cc.total_commit_contributions)
             ));
             output.push_str(&format!(
                 "Total Issue Contributions: {}\n",
-                cc.total_issue_contributions
+                self.locale.format(cc.total_issue_contributions)
             ));
             output.push_str(&format!(
                 "Total Pull Request Contributions: {}\n",
-                cc.total_pull_request_contributions
+                self.locale.format(cc.total_pull_request_contributions)
             ));
             output.push_str(&format!(
-                "Total Pull Request Review Contributions: {}\n\n",
-                cc.total_pull_request_review_contributions
+                "Total Pull Request Review Contributions: {}\n",
+                self.locale
+                    .format(cc.total_pull_request_review_contributions)
             ));
-
-            // Contribution Calendar
-            output.push_str("Contribution Calendar:\n");
+            let (additions, deletions) = lines_changed(cc);
             output.push_str(&format!(
-                "  Total Contributions: {}\n",
-                cc.contribution_calendar.total_contributions
+                "Lines Changed: {}\n",
+                diff_stat(additions, deletions, self.locale)
             ));
-            for week in &cc.contribution_calendar.weeks {
-                for day in &week.contribution_days {
+            if let Some(mix) = contribution_mix(cc) {
+                output.push_str(&format!("Contribution Mix: {}\n", mix_line(&mix)));
+            }
+            output.push('\n');
+
+            // Statistics
+            if let Some(statistics) = &report.statistics {
+                output.push_str("Statistics:\n");
+                for (label, value) in statistics_rows(statistics, self.locale) {
+                    output.push_str(&format!("  {}: {}\n", label, value));
+                }
+                output.push('\n');
+            }
+
+            // Unattributed Commits
+            if !report.unattributed_commits.is_empty() {
+                output.push_str("Unattributed Commits:\n");
+                for email in &report.unattributed_commits {
+                    output.push_str(&format!("  {}\n", unattributed_line(email, self.locale)));
+                }
+                output.push('\n');
+            }
+
+            // Category Breakdown
+            let categories = category_breakdown(report);
+            if !categories.is_empty() {
+                output.push_str("Category Breakdown:\n");
+                for counts in &categories {
                     output.push_str(&format!(
-                        "    {}: {} contributions (weekday {})\n",
-                        day.date, day.contribution_count, day.weekday
+                        "  {}: {} issues, {} pull requests, {} reviews\n",
+                        counts.name,
+                        self.locale.format(counts.issues as i64),
+                        self.locale.format(counts.pull_requests as i64),
+                        self.locale.format(counts.reviews as i64)
                     ));
                 }
+                output.push('\n');
+            }
+
+            // Review Coverage
+            if !report.review_coverage.is_empty() {
+                output.push_str("Review Coverage:\n");
+                for coverage in &report.review_coverage {
+                    output.push_str(&format!(
+                        "  {}: {}\n",
+                        coverage.repository,
+                        coverage_line(coverage, self.locale)
+                    ));
+                }
+                output.push('\n');
+            }
+
+            // Triage Activity
+            if let Some(triage) = &report.triage {
+                output.push_str(&format!("Triage Activity in {}:\n", triage.repository));
+                for (heading, actions) in triage_sections(triage) {
+                    output.push_str(&format!("  {}: {}\n", heading, actions.len()));
+                    for action in actions {
+                        output.push_str(&format!(
+                            "    - Issue #{}: {}{}\n      URL: {}\n      Occurred At: {}\n",
+                            action.number,
+                            text::title(&action.title),
+                            action
+                                .detail
+                                .as_ref()
+                                .map(|d| format!(" ({})", d))
+                                .unwrap_or_default(),
+                            action.url,
+                            action.occurred_at
+                        ));
+                    }
+                }
+                output.push('\n');
+            }
+
+            // Contribution Calendar
+            if self.calendar != CalendarGranularity::Off {
+                output.push_str("Contribution Calendar:\n");
+                output.push_str(&format!(
+                    "  Total Contributions: {}\n",
+                    self.locale
+                        .format(cc.contribution_calendar.total_contributions)
+                ));
+                if self.calendar == CalendarGranularity::Daily && !self.calendar_detail {
+                    output.push_str(&heatmap(
+                        &cc.contribution_calendar,
+                        self.locale,
+                        "    ",
+                        str::to_string,
+                        ascii_cell,
+                    ));
+                } else if self.calendar == CalendarGranularity::Daily {
+                    for week in &cc.contribution_calendar.weeks {
+                        for day in &week.contribution_days {
+                            output.push_str(&format!(
+                                "    {}: {} contributions ({})\n",
+                                day.date,
+                                day.contribution_count,
+                                self.locale.weekday_name(day.weekday)
+                            ));
+                        }
+                    }
+                }
+                for period in calendar_periods(&cc.contribution_calendar, self.calendar) {
+                    output.push_str(&format!(
+                        "    {}: {} contributions\n",
+                        self.locale.month_label(&period.label),
+                        self.locale.format(period.contribution_count)
+                    ));
+                }
+                output.push('\n');
             }
-            output.push('\n');
 
             // Repository Contributions
             output.push_str("Repository Contributions:\n");
+            let sparklines = Sparklines::new(report);
             for repo_contrib in &cc.commit_contributions_by_repository {
                 output.push_str(&format!(
-                    "- {}: {} commits\n",
-                    repo_contrib.repository.name_with_owner, repo_contrib.contributions.total_count
+                    "- {}: {} commits {}\n",
+                    repository_name(report, &repo_contrib.repository.name_with_owner),
+                    self.locale.format(repo_contrib.contributions.total_count),
+                    sparklines.unicode(&repo_contrib.repository.name_with_owner)
                 ));
             }
             output.push('\n');
@@ -87,12 +638,19 @@ impl FormatData for PlainTextFormatter {
                     output.push_str(&format!(
                         "- Issue #{}: {}\n  URL: {}\n  Created: {}\n  State: {}\n  Closed: {:?}\n",
                         issue.number,
-                        issue.title,
+                        text::title(&issue.title),
                         issue.url,
                         issue.created_at,
                         issue.state,
                         issue.closed_at
                     ));
+                    let labels = label_names(&issue.labels);
+                    if !labels.is_empty() {
+                        output.push_str(&format!("  Labels: {}\n", labels.join(", ")));
+                    }
+                    if let Some(events) = report.issue_histories.get(&issue.url) {
+                        output.push_str(&format!("  History: {}\n", history_line(events)));
+                    }
                 }
             }
             output.push('\n');
@@ -103,16 +661,43 @@ impl FormatData for PlainTextFormatter {
                 for node in nodes {
                     let pr = &node.pull_request;
                     output.push_str(&format!(
-                        "- PR #{}: {}\n  URL: {}\n  Created: {}\n  State: {}\n  Merged: {}\n  Merged At: {:?}\n  Closed: {:?}\n",
+                        "- PR #{}: {}\n  URL: {}\n  Created: {}\n  State: {}\n  Merged: {}\n  Merged At: {:?}\n  Closed: {:?}\n  Changes: {}\n",
                         pr.number,
-                        pr.title,
+                        text::title(&pr.title),
                         pr.url,
                         pr.created_at,
                         pr.state,
                         pr.merged,
                         pr.merged_at,
-                        pr.closed_at
+                        pr.closed_at,
+                        pr_size(pr.additions, pr.deletions, pr.changed_files, self.locale)
                     ));
+                    let labels = label_names(&pr.labels);
+                    if !labels.is_empty() {
+                        output.push_str(&format!("  Labels: {}\n", labels.join(", ")));
+                    }
+                    if let Some(repo) =
+                        repository_of(&pr.url).filter(|repo| report.forks.contains_key(repo))
+                    {
+                        output.push_str(&format!(
+                            "  Repository: {}\n",
+                            repository_name(report, &repo)
+                        ));
+                    }
+                    if let Some(revert) = report.reverted_prs.get(&pr.url) {
+                        output.push_str(&format!(
+                            "  Reverted: by PR #{} ({}) at {}\n",
+                            revert.number,
+                            revert.url,
+                            revert.merged_at.as_deref().unwrap_or("unknown time")
+                        ));
+                    }
+                    if let Some(release) = report.shipped_in.get(&pr.url) {
+                        output.push_str(&format!(
+                            "  Released In: {} ({})\n",
+                            release.tag_name, release.url
+                        ));
+                    }
                 }
             }
             output.push('\n');
@@ -123,12 +708,22 @@ impl FormatData for PlainTextFormatter {
                 for node in nodes {
                     let pr_review = &node.pull_request_review;
                     output.push_str(&format!(
-                        "- PR Review for PR #{}: {}\n  URL: {}\n  Occurred At: {}\n",
+                        "- PR Review for PR #{}: {}\n  URL: {}\n",
                         pr_review.pull_request.number,
-                        pr_review.pull_request.title,
+                        text::title(&pr_review.pull_request.title),
                         pr_review.pull_request.url,
-                        node.occurred_at
                     ));
+                    if !pr_review.state.is_empty() {
+                        output.push_str(&format!("  State: {}\n", review_state(&pr_review.state)));
+                    }
+                    output.push_str(&format!("  Occurred At: {}\n", node.occurred_at));
+                    if let Some(body) = review_body(&pr_review.body) {
+                        output.push_str("  Body:\n");
+                        for line in body.lines() {
+                            output.push_str(format!("    {}", line).trim_end());
+                            output.push('\n');
+                        }
+                    }
                 }
             }
         } else {
@@ -138,138 +733,1882 @@ impl FormatData for PlainTextFormatter {
     }
 }
 
+/// ANSI 256-color foreground codes of the heatmap shading levels (0-4), after GitHub's greens.
+pub(crate) const HEATMAP_COLORS: [u8; 5] = [238, 22, 28, 34, 40];
+
+/// Characters of the heatmap shading levels (0-4) in text output.
+const HEATMAP_CHARS: [char; 5] = ['.', '-', '+', '*', '#'];
+
+/// The calendar's contribution counts laid out as a grid: one row per weekday (Sunday first)
+/// and one column per week, with `None` for days outside the range.
+pub(crate) fn heatmap_grid(calendar: &ContributionCalendar) -> [Vec<Option<i64>>; 7] {
+    let mut rows: [Vec<Option<i64>>; 7] = Default::default();
+    for row in rows.iter_mut() {
+        row.resize(calendar.weeks.len(), None);
+    }
+    for (column, week) in calendar.weeks.iter().enumerate() {
+        for day in &week.contribution_days {
+            if let Some(row) = rows.get_mut(day.weekday as usize) {
+                row[column] = Some(day.contribution_count);
+            }
+        }
+    }
+    rows
+}
+
+/// The contribution calendar as a GitHub-style heatmap: weekdays as rows, weeks as columns,
+/// and a legend, each line starting with `indent`. `label` renders the weekday names of
+/// `locale` and the legend words, and `cell` a day at a shading level (0-4).
+fn heatmap(
+    calendar: &ContributionCalendar,
+    locale: NumberLocale,
+    indent: &str,
+    label: impl Fn(&str) -> String,
+    cell: impl Fn(i64) -> String,
+) -> String {
+    let grid = heatmap_grid(calendar);
+    let max = grid.iter().flatten().flatten().copied().max().unwrap_or(0);
+    let mut output = String::new();
+    let weekdays = padded_weekdays(locale);
+    for (weekday, row) in weekdays.iter().zip(&grid) {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|count| match count {
+                Some(count) => cell(calendar_level(*count, max)),
+                None => " ".to_string(),
+            })
+            .collect();
+        let line = format!("{}{} {}", indent, label(weekday), cells.join(" "));
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+    let legend: Vec<String> = (0..=4).map(&cell).collect();
+    output.push_str(&format!(
+        "{}{} {} {}\n",
+        indent,
+        label("Less"),
+        legend.join(" "),
+        label("More")
+    ));
+    output
+}
+
+/// The abbreviated weekday names of `locale`, Sunday first, padded to the same width so the
+/// heatmap rows line up.
+pub(crate) fn padded_weekdays(locale: NumberLocale) -> [String; 7] {
+    let names = locale.weekday_abbreviations();
+    let width = names
+        .iter()
+        .map(|name| text::width(name))
+        .max()
+        .unwrap_or(0);
+    names.map(|name| text::pad(name, width))
+}
+
+/// A heatmap cell drawn with a character of increasing density for each shading level.
+pub(crate) fn ascii_cell(level: i64) -> String {
+    HEATMAP_CHARS[level.clamp(0, 4) as usize].to_string()
+}
+
+/// A formatter for reading reports in a terminal: a compact layout with colored headings and
+/// pull request states, and the contribution calendar as a heatmap.
+#[derive(Default)]
+pub struct ColorTerminalFormatter {
+    /// How totals are grouped, e.g. `12,345`.
+    pub locale: NumberLocale,
+    /// How the contribution calendar is shown; daily shows the heatmap.
+    pub calendar: CalendarGranularity,
+    /// Whether to emit ANSI color codes; without them the layout is kept and the heatmap is
+    /// drawn with characters of increasing density.
+    pub color: bool,
+}
+
+impl ColorTerminalFormatter {
+    /// Wraps `text` in the ANSI SGR sequence `code`, when colors are on.
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn heading(&self, text: &str) -> String {
+        self.paint("1;36", text)
+    }
+
+    fn dim(&self, text: &str) -> String {
+        self.paint("2", text)
+    }
+
+    /// One heatmap cell at shading `level`.
+    fn cell(&self, level: i64) -> String {
+        if self.color {
+            let color = HEATMAP_COLORS[level.clamp(0, 4) as usize];
+            self.paint(&format!("38;5;{}", color), "■")
+        } else {
+            ascii_cell(level)
+        }
+    }
+
+    /// The state of a pull request: merged in green, closed without merging in red.
+    fn pr_state(&self, merged: bool, state: &str) -> String {
+        if merged {
+            self.paint("32", "merged")
+        } else if state.eq_ignore_ascii_case("closed") {
+            self.paint("31", "closed")
+        } else {
+            self.paint("33", &state.to_lowercase())
+        }
+    }
+
+    /// The state of a pull request review: approved in green, changes requested in red.
+    fn review_state(&self, state: &str) -> String {
+        let code = match state {
+            "APPROVED" => "32",
+            "CHANGES_REQUESTED" => "31",
+            _ => "33",
+        };
+        self.paint(code, &review_state(state))
+    }
+}
+
+impl FormatData for ColorTerminalFormatter {
+    fn format(&self, report: &Report) -> String {
+        let Some(user) = &report.activity.user else {
+            return "No user data available.\n".to_string();
+        };
+        let cc = &user.contributions_collection;
+        let mut output = format!(
+            "{} {}\n",
+            self.paint("1", &report.username),
+            self.dim(&format!(
+                "{} to {}",
+                report.start_date.format("%Y-%m-%d %H:%M"),
+                report.end_date.format("%Y-%m-%d %H:%M")
+            ))
+        );
+        for warning in &report.warnings {
+            output.push_str(&format!(
+                "  {}\n",
+                self.paint("33", &format!("Warning: {}", warning))
+            ));
+        }
+        output.push_str(&format!(
+            "  Commits {}  Issues {}  Pull requests {}  Reviews {}\n",
+            self.paint("1", &self.locale.format(cc.total_commit_contributions)),
+            self.paint("1", &self.locale.format(cc.total_issue_contributions)),
+            self.paint(
+                "1",
+                &self.locale.format(cc.total_pull_request_contributions)
+            ),
+            self.paint(
+                "1",
+                &self
+                    .locale
+                    .format(cc.total_pull_request_review_contributions)
+            )
+        ));
+        let (additions, deletions) = lines_changed(cc);
+        output.push_str(&format!(
+            "  Lines changed {} {}\n",
+            self.paint("32", &format!("+{}", self.locale.format(additions))),
+            self.paint("31", &format!("-{}", self.locale.format(deletions)))
+        ));
+        if let Some(mix) = contribution_mix(cc) {
+            output.push_str(&format!("  {}\n", self.dim(&mix_line(&mix))));
+        }
+
+        if let Some(statistics) = &report.statistics {
+            output.push_str(&format!("\n{}\n", self.heading("Statistics")));
+            for (label, value) in statistics_rows(statistics, self.locale) {
+                output.push_str(&format!("  {} {}\n", self.dim(label), value));
+            }
+        }
+
+        if !report.unattributed_commits.is_empty() {
+            output.push_str(&format!("\n{}\n", self.heading("Unattributed Commits")));
+            for email in &report.unattributed_commits {
+                output.push_str(&format!(
+                    "  {}\n",
+                    self.paint("33", &unattributed_line(email, self.locale))
+                ));
+            }
+        }
+
+        let categories = category_breakdown(report);
+        if !categories.is_empty() {
+            output.push_str(&format!("\n{}\n", self.heading("Category Breakdown")));
+            for counts in &categories {
+                output.push_str(&format!(
+                    "  {} {} issues, {} pull requests, {} reviews\n",
+                    self.dim(&counts.name),
+                    self.locale.format(counts.issues as i64),
+                    self.locale.format(counts.pull_requests as i64),
+                    self.locale.format(counts.reviews as i64)
+                ));
+            }
+        }
+
+        if !report.review_coverage.is_empty() {
+            output.push_str(&format!("\n{}\n", self.heading("Review Coverage")));
+            for coverage in &report.review_coverage {
+                output.push_str(&format!(
+                    "  {} {}\n",
+                    self.dim(&coverage.repository),
+                    coverage_line(coverage, self.locale)
+                ));
+            }
+        }
+
+        if let Some(triage) = &report.triage {
+            output.push_str(&format!(
+                "\n{}\n",
+                self.heading(&format!("Triage Activity in {}", triage.repository))
+            ));
+            for (heading, actions) in triage_sections(triage) {
+                output.push_str(&format!("  {}: {}\n", heading, actions.len()));
+                for action in actions {
+                    output.push_str(&format!(
+                        "    #{} {} {}\n",
+                        action.number,
+                        text::title(&action.title),
+                        self.dim(&action.url)
+                    ));
+                }
+            }
+        }
+
+        if self.calendar != CalendarGranularity::Off {
+            output.push_str(&format!(
+                "\n{} {}\n",
+                self.heading("Contribution Calendar"),
+                self.dim(&format!(
+                    "({} contributions)",
+                    self.locale
+                        .format(cc.contribution_calendar.total_contributions)
+                ))
+            ));
+            if self.calendar == CalendarGranularity::Daily {
+                output.push_str(&heatmap(
+                    &cc.contribution_calendar,
+                    self.locale,
+                    "  ",
+                    |text| self.dim(text),
+                    |level| self.cell(level),
+                ));
+            }
+            for period in calendar_periods(&cc.contribution_calendar, self.calendar) {
+                output.push_str(&format!(
+                    "  {}: {}\n",
+                    self.locale.month_label(&period.label),
+                    self.locale.format(period.contribution_count)
+                ));
+            }
+        }
+
+        output.push_str(&format!("\n{}\n", self.heading("Repositories")));
+        let sparklines = Sparklines::new(report);
+        for repo_contrib in &cc.commit_contributions_by_repository {
+            output.push_str(&format!(
+                "  {} {} {}\n",
+                repository_name(report, &repo_contrib.repository.name_with_owner),
+                self.dim(&format!(
+                    "{} commits",
+                    self.locale.format(repo_contrib.contributions.total_count)
+                )),
+                sparklines.unicode(&repo_contrib.repository.name_with_owner)
+            ));
+        }
+
+        output.push_str(&format!("\n{}\n", self.heading("Issues")));
+        for node in cc.issue_contributions.nodes.iter().flatten() {
+            let issue = &node.issue;
+            output.push_str(&format!(
+                "  #{} {} [{}] {}\n",
+                issue.number,
+                text::title(&issue.title),
+                issue.state.to_lowercase(),
+                self.dim(&issue.url)
+            ));
+            if let Some(events) = report.issue_histories.get(&issue.url) {
+                output.push_str(&format!("    {}\n", self.dim(&history_line(events))));
+            }
+        }
+
+        output.push_str(&format!("\n{}\n", self.heading("Pull Requests")));
+        for node in cc.pull_request_contributions.nodes.iter().flatten() {
+            let pr = &node.pull_request;
+            output.push_str(&format!(
+                "  #{} {} [{}] {} {} {}\n",
+                pr.number,
+                text::title(&pr.title),
+                self.pr_state(pr.merged, &pr.state),
+                self.paint("32", &format!("+{}", self.locale.format(pr.additions))),
+                self.paint("31", &format!("-{}", self.locale.format(pr.deletions))),
+                self.dim(&pr.url)
+            ));
+            if let Some(revert) = report.reverted_prs.get(&pr.url) {
+                output.push_str(&format!(
+                    "    {}\n",
+                    self.paint("31", &format!("Reverted by #{}", revert.number))
+                ));
+            }
+            if let Some(release) = report.shipped_in.get(&pr.url) {
+                output.push_str(&format!(
+                    "    {}\n",
+                    self.dim(&format!("Released in {}", release.tag_name))
+                ));
+            }
+        }
+
+        output.push_str(&format!("\n{}\n", self.heading("Reviews")));
+        for node in cc.pull_request_review_contributions.nodes.iter().flatten() {
+            let review = &node.pull_request_review;
+            let pr = &review.pull_request;
+            let state = if review.state.is_empty() {
+                String::new()
+            } else {
+                format!("[{}] ", self.review_state(&review.state))
+            };
+            output.push_str(&format!(
+                "  #{} {} {}{}\n",
+                pr.number,
+                text::title(&pr.title),
+                state,
+                self.dim(&pr.url)
+            ));
+            if let Some(body) = review_body(&review.body) {
+                for line in body.lines() {
+                    output.push_str(format!("    {}", self.dim(line)).trim_end());
+                    output.push('\n');
+                }
+            }
+        }
+        output
+    }
+}
+
+/// Escapes text for use in a Markdown table cell: pipes would split the cell, newlines end
+/// the row, and backticks could open a code span across cells.
+pub(crate) fn escape_markdown_cell(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' | '|' | '`' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 /// A Markdown formatter for GitHub activity.
-pub struct MarkdownFormatter;
+#[derive(Default)]
+pub struct MarkdownFormatter {
+    /// Render URLs as reference-style links with definitions collected at the end of the
+    /// report, keeping the tables narrow.
+    pub footnote_links: bool,
+    /// How totals are grouped, e.g. `12,345`.
+    pub locale: NumberLocale,
+    /// How the contribution calendar is shown; daily shows a heatmap.
+    pub calendar: CalendarGranularity,
+    /// List every day of a daily calendar on its own line instead of the heatmap.
+    pub calendar_detail: bool,
+    /// Whether sections list contributions or summarize them in sentences.
+    pub tone: Tone,
+    /// Whether the issue, pull request, and review tables are split by repository.
+    pub group_by: GroupBy,
+    /// Leave out column padding and the time of day of the period, and list repositories by
+    /// name, so successive reports committed to git differ only where the activity does.
+    pub stable: bool,
+    /// Text replacing built-in sections, e.g. rendered from per-section templates; a section
+    /// replaced by blank text is left out.
+    pub sections: BTreeMap<MarkdownSection, String>,
+}
 
-impl FormatData for MarkdownFormatter {
-    fn format(
+impl MarkdownFormatter {
+    /// Appends the replacement of `section` to `output`, returning whether there was one.
+    fn push_replacement(&self, section: MarkdownSection, output: &mut String) -> bool {
+        let Some(text) = self.sections.get(&section) else {
+            return false;
+        };
+        let text = text.trim_end();
+        if !text.is_empty() {
+            output.push_str(text);
+            output.push_str("\n\n");
+        }
+        true
+    }
+
+    /// Renders a URL table cell, either inline or as a reference to a footnote in `links`.
+    fn link(&self, url: &str, links: &mut Vec<String>) -> String {
+        if !self.footnote_links {
+            return url.to_string();
+        }
+        links.push(url.to_string());
+        format!("[link][{}]", links.len())
+    }
+
+    /// The issue, pull request, and review tables of `cc`, under headings of `level`, e.g.
+    /// `##`.
+    fn contribution_tables(
         &self,
-        activity: &user_activity::ResponseData,
-        start_date: ChronoDateTime<Utc>,
-        end_date: ChronoDateTime<Utc>,
-        username: &str,
+        report: &Report,
+        cc: &ContributionsCollection,
+        level: &str,
+        links: &mut Vec<String>,
     ) -> String {
         let mut output = String::new();
+        // Only the ungrouped tables can be replaced; templates see the whole report.
+        let replace = |section, output: &mut String| {
+            self.group_by == GroupBy::None && self.push_replacement(section, output)
+        };
+        // Issue Contributions
+        if !replace(MarkdownSection::Issues, &mut output) {
+            output.push_str(&format!("{} Issue Contributions\n\n", level));
+            output
+                .push_str("| Issue # | Title | URL | Created At | State | Closed At | Labels |\n");
+            output
+                .push_str("|---------|-------|-----|------------|-------|-----------|--------|\n");
+            if let Some(nodes) = &cc.issue_contributions.nodes {
+                for node in nodes {
+                    let issue = &node.issue;
+                    output.push_str(&format!(
+                        "| {} | {} | {} | {} | {} | {} | {} |\n",
+                        issue.number,
+                        escape_markdown_cell(&text::title(&issue.title)),
+                        self.link(&issue.url, links),
+                        issue.created_at,
+                        issue.state,
+                        issue.closed_at.as_deref().unwrap_or("N/A"),
+                        escape_markdown_cell(&label_names(&issue.labels).join(", "))
+                    ));
+                }
+                let histories: Vec<_> = nodes
+                    .iter()
+                    .filter_map(|node| {
+                        let events = report.issue_histories.get(&node.issue.url)?;
+                        Some((node.issue.number, events))
+                    })
+                    .collect();
+                if !histories.is_empty() {
+                    output.push_str(&format!("\n{}# Issue History\n\n", level));
+                    for (number, events) in histories {
+                        output.push_str(&format!("- #{}: {}\n", number, history_line(events)));
+                    }
+                }
+            }
+            output.push('\n');
+        }
+
+        // Pull Request Contributions
+        if !replace(MarkdownSection::PullRequests, &mut output) {
+            output.push_str(&format!("{} Pull Request Contributions\n\n", level));
+            output.push_str(
+                "| PR # | Title | URL | Created At | State | Merged | Merged At | Closed At | Changes | Labels |\n",
+            );
+            output.push_str(
+                "|------|-------|-----|------------|-------|--------|-----------|-----------|---------|--------|\n",
+            );
+            if let Some(nodes) = &cc.pull_request_contributions.nodes {
+                for node in nodes {
+                    let pr = &node.pull_request;
+                    let mut title = text::title(&pr.title);
+                    if let Some(note) = url_fork_note(report, &pr.url) {
+                        title.push_str(&format!(" (in {})", note));
+                    }
+                    if let Some(release) = report.shipped_in.get(&pr.url) {
+                        title.push_str(&format!(" (shipped in {})", release.tag_name));
+                    }
+                    if let Some(revert) = report.reverted_prs.get(&pr.url) {
+                        title.push_str(&format!(" (reverted in #{})", revert.number));
+                    }
+                    output.push_str(&format!(
+                        "| {} | {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                        pr.number,
+                        escape_markdown_cell(&title),
+                        self.link(&pr.url, links),
+                        pr.created_at,
+                        pr.state,
+                        pr.merged,
+                        pr.merged_at.as_deref().unwrap_or("N/A"),
+                        pr.closed_at.as_deref().unwrap_or("N/A"),
+                        pr_size(pr.additions, pr.deletions, pr.changed_files, self.locale),
+                        escape_markdown_cell(&label_names(&pr.labels).join(", "))
+                    ));
+                }
+            }
+            output.push('\n');
+        }
+
+        // Pull Request Review Contributions
+        if !replace(MarkdownSection::Reviews, &mut output) {
+            output.push_str(&format!("{} Pull Request Review Contributions\n\n", level));
+            output.push_str("| PR # | Title | State | URL | Occurred At |\n");
+            output.push_str("|------|-------|-------|-----|-------------|\n");
+            if let Some(nodes) = &cc.pull_request_review_contributions.nodes {
+                for node in nodes {
+                    let pr_review = &node.pull_request_review;
+                    output.push_str(&format!(
+                        "| {} | {} | {} | {} | {} |\n",
+                        pr_review.pull_request.number,
+                        escape_markdown_cell(&text::title(&pr_review.pull_request.title)),
+                        review_state(&pr_review.state),
+                        self.link(&pr_review.pull_request.url, links),
+                        node.occurred_at
+                    ));
+                }
+            }
+        }
+        output
+    }
+}
+
+impl FormatData for MarkdownFormatter {
+    fn format(&self, report: &Report) -> String {
+        let (activity, username) = (&report.activity, &report.username);
+        let (start_date, end_date) = (report.start_date, report.end_date);
+        let mut output = String::new();
+        let mut links = Vec::new();
         if let Some(user) = &activity.user {
             let cc = &user.contributions_collection;
             output.push_str(&format!("# GitHub Activity Report for {}\n\n", username));
+            let (start, end) = if self.stable {
+                (
+                    start_date.format("%Y-%m-%d").to_string(),
+                    end_date.format("%Y-%m-%d").to_string(),
+                )
+            } else {
+                (start_date.to_rfc3339(), end_date.to_rfc3339())
+            };
+            output.push_str(&format!("**Time Period:** {} to {}\n\n", start, end));
+            for warning in &report.warnings {
+                output.push_str(&format!("> **Warning:** {}\n\n", warning));
+            }
+            if self.tone == Tone::Narrative {
+                let sections: Vec<String> = paragraphs(report, self.locale)
+                    .into_iter()
+                    .map(|paragraph| format!("## {}\n\n{}\n", paragraph.heading, paragraph.text))
+                    .collect();
+                output.push_str(&sections.join("\n"));
+                return output;
+            }
+            if !self.push_replacement(MarkdownSection::Summary, &mut output) {
+                output.push_str("## Summary\n\n");
+                output.push_str(&format!(
+                    "- **Total Commit Contributions:** {}\n",
+                    self.locale.format(cc.total_commit_contributions)
+                ));
+                output.push_str(&format!(
+                    "- **Total Issue Contributions:** {}\n",
+                    self.locale.format(cc.total_issue_contributions)
+                ));
+                output.push_str(&format!(
+                    "- **Total Pull Request Contributions:** {}\n",
+                    self.locale.format(cc.total_pull_request_contributions)
+                ));
+                output.push_str(&format!(
+                    "- **Total Pull Request Review Contributions:** {}\n",
+                    self.locale
+                        .format(cc.total_pull_request_review_contributions)
+                ));
+                let (additions, deletions) = lines_changed(cc);
+                output.push_str(&format!(
+                    "- **Lines Changed:** {}\n",
+                    diff_stat(additions, deletions, self.locale)
+                ));
+                if let Some(mix) = contribution_mix(cc) {
+                    output.push_str(&format!("- **Contribution Mix:** {}\n", mix_line(&mix)));
+                }
+                output.push('\n');
+            }
+
+            // Statistics
+            if !self.push_replacement(MarkdownSection::Statistics, &mut output)
+                && let Some(statistics) = &report.statistics
+            {
+                output.push_str("## Statistics\n\n");
+                for (label, value) in statistics_rows(statistics, self.locale) {
+                    output.push_str(&format!("- **{}:** {}\n", label, value));
+                }
+                output.push('\n');
+            }
+
+            // Unattributed Commits
+            if !self.push_replacement(MarkdownSection::UnattributedCommits, &mut output)
+                && !report.unattributed_commits.is_empty()
+            {
+                output.push_str("## Unattributed Commits\n\n");
+                output.push_str(
+                    "GitHub didn't count these commits because it couldn't link their author email to the account:\n\n",
+                );
+                for email in &report.unattributed_commits {
+                    output.push_str(&format!("- {}\n", unattributed_line(email, self.locale)));
+                }
+                output.push('\n');
+            }
+
+            // Category Breakdown
+            let categories = category_breakdown(report);
+            if !self.push_replacement(MarkdownSection::Categories, &mut output)
+                && !categories.is_empty()
+            {
+                output.push_str("## Category Breakdown\n\n");
+                output.push_str("| Category | Issues | Pull Requests | Reviews |\n");
+                output.push_str("|----------|--------|---------------|---------|\n");
+                for counts in &categories {
+                    output.push_str(&format!(
+                        "| {} | {} | {} | {} |\n",
+                        escape_markdown_cell(&counts.name),
+                        self.locale.format(counts.issues as i64),
+                        self.locale.format(counts.pull_requests as i64),
+                        self.locale.format(counts.reviews as i64)
+                    ));
+                }
+                output.push('\n');
+            }
+
+            // Review Coverage
+            if !self.push_replacement(MarkdownSection::ReviewCoverage, &mut output)
+                && !report.review_coverage.is_empty()
+            {
+                output.push_str("## Review Coverage\n\n");
+                output.push_str("| Repository | Merged | Reviewed | Coverage |\n");
+                output.push_str("|------------|--------|----------|----------|\n");
+                for coverage in &report.review_coverage {
+                    output.push_str(&format!(
+                        "| {} | {} | {} | {} |\n",
+                        escape_markdown_cell(&coverage.repository),
+                        self.locale.format(coverage.merged),
+                        self.locale.format(coverage.reviewed),
+                        coverage_percent(coverage)
+                    ));
+                }
+                output.push('\n');
+            }
+
+            // Triage Activity
+            if !self.push_replacement(MarkdownSection::Triage, &mut output)
+                && let Some(triage) = &report.triage
+            {
+                output.push_str(&format!("## Triage Activity in {}\n\n", triage.repository));
+                for (heading, actions) in triage_sections(triage) {
+                    output.push_str(&format!("- **{}:** {}\n", heading, actions.len()));
+                }
+                output.push('\n');
+                output.push_str("| Action | Issue # | Title | URL | Detail | Occurred At |\n");
+                output.push_str("|--------|---------|-------|-----|--------|-------------|\n");
+                for (heading, actions) in triage_sections(triage) {
+                    for action in actions {
+                        output.push_str(&format!(
+                            "| {} | {} | {} | {} | {} | {} |\n",
+                            heading,
+                            action.number,
+                            escape_markdown_cell(&text::title(&action.title)),
+                            self.link(&action.url, &mut links),
+                            escape_markdown_cell(action.detail.as_deref().unwrap_or("")),
+                            action.occurred_at
+                        ));
+                    }
+                }
+                output.push('\n');
+            }
+
+            // Contribution Calendar
+            if !self.push_replacement(MarkdownSection::Calendar, &mut output)
+                && self.calendar != CalendarGranularity::Off
+            {
+                output.push_str("## Contribution Calendar\n\n");
+                output.push_str(&format!(
+                    "**Total Contributions:** {}\n\n",
+                    self.locale
+                        .format(cc.contribution_calendar.total_contributions)
+                ));
+                if self.calendar == CalendarGranularity::Daily && !self.calendar_detail {
+                    output.push_str("```text\n");
+                    output.push_str(&heatmap(
+                        &cc.contribution_calendar,
+                        self.locale,
+                        "",
+                        str::to_string,
+                        ascii_cell,
+                    ));
+                    output.push_str("```\n");
+                } else if self.calendar == CalendarGranularity::Daily {
+                    for week in &cc.contribution_calendar.weeks {
+                        for day in &week.contribution_days {
+                            output.push_str(&format!(
+                                "* {}: {} contributions ({})\n",
+                                day.date,
+                                day.contribution_count,
+                                self.locale.weekday_name(day.weekday)
+                            ));
+                        }
+                    }
+                }
+                for period in calendar_periods(&cc.contribution_calendar, self.calendar) {
+                    output.push_str(&format!(
+                        "* {}: {} contributions\n",
+                        self.locale.month_label(&period.label),
+                        self.locale.format(period.contribution_count)
+                    ));
+                }
+                output.push('\n');
+            }
+
+            // Repository Contributions
+            if !self.push_replacement(MarkdownSection::Repositories, &mut output) {
+                output.push_str("## Repository Contributions\n\n");
+                let sparklines = Sparklines::new(report);
+                if self.stable {
+                    output.push_str("| Repository | Commits | Activity |\n");
+                    output.push_str("|------------|---------|----------|\n");
+                    let mut repositories: Vec<_> =
+                        cc.commit_contributions_by_repository.iter().collect();
+                    repositories.sort_by(|a, b| {
+                        a.repository
+                            .name_with_owner
+                            .cmp(&b.repository.name_with_owner)
+                    });
+                    for repo_contrib in repositories {
+                        output.push_str(&format!(
+                            "| {} | {} | {} |\n",
+                            escape_markdown_cell(&repository_name(
+                                report,
+                                &repo_contrib.repository.name_with_owner
+                            )),
+                            self.locale.format(repo_contrib.contributions.total_count),
+                            sparklines.unicode(&repo_contrib.repository.name_with_owner)
+                        ));
+                    }
+                } else {
+                    output.push_str("| Repository             | Commits | Activity |\n");
+                    output.push_str("|------------------------|---------|----------|\n");
+                    for repo_contrib in &cc.commit_contributions_by_repository {
+                        output.push_str(&format!(
+                            "| {:<22} | {:>7} | {:<8} |\n",
+                            escape_markdown_cell(&repository_name(
+                                report,
+                                &repo_contrib.repository.name_with_owner
+                            )),
+                            self.locale.format(repo_contrib.contributions.total_count),
+                            sparklines.unicode(&repo_contrib.repository.name_with_owner)
+                        ));
+                    }
+                }
+                output.push('\n');
+            }
+
+            match self.group_by {
+                GroupBy::None => {
+                    output.push_str(&self.contribution_tables(report, cc, "##", &mut links))
+                }
+                GroupBy::Week | GroupBy::Month => {
+                    let granularity = self.group_by.rollup().unwrap_or_default();
+                    let period = if self.group_by == GroupBy::Week {
+                        "Weekly"
+                    } else {
+                        "Monthly"
+                    };
+                    output.push_str(&format!("## {} Rollup\n\n", period));
+                    output.push_str(
+                        "| Period | Contributions | Issues | Pull Requests | Reviews |\n",
+                    );
+                    output.push_str(
+                        "|--------|---------------|--------|---------------|---------|\n",
+                    );
+                    let (rows, mut total) = rollups_with_total(cc, granularity);
+                    total.label = format!("**{}**", total.label);
+                    for row in rows.iter().chain([&total]) {
+                        output.push_str(&format!(
+                            "| {} | {} | {} | {} | {} |\n",
+                            self.locale.month_label(&row.label),
+                            self.locale.format(row.contribution_count),
+                            self.locale.format(row.issues as i64),
+                            self.locale.format(row.pull_requests as i64),
+                            self.locale.format(row.reviews as i64)
+                        ));
+                    }
+                }
+                GroupBy::Repository | GroupBy::Category => {
+                    let groups = match self.group_by {
+                        GroupBy::Category => by_category(cc, &report.categories),
+                        _ => by_repository(cc),
+                    };
+                    for (group, cc) in groups {
+                        output.push_str(&format!("## {}\n\n", group));
+                        output.push_str(&self.contribution_tables(report, &cc, "###", &mut links));
+                        output.push('\n');
+                    }
+                }
+            }
+
+            // Link reference definitions collected by `--footnote-links`.
+            if !links.is_empty() {
+                output.push('\n');
+                for (i, url) in links.iter().enumerate() {
+                    output.push_str(&format!("[{}]: {}\n", i + 1, url));
+                }
+            }
+        } else {
+            output.push_str("No user data available.\n");
+        }
+        output
+    }
+}
+
+/// Stylesheet embedded in every HTML report so the page is self-contained.
+pub(crate) const HTML_STYLE: &str = "
+body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Helvetica, Arial, sans-serif; margin: 2rem auto; max-width: 1100px; padding: 0 1rem; color: #1f2328; }
+h1, h2, h3 { border-bottom: 1px solid #d1d9e0; padding-bottom: 0.3em; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1.5rem; }
+th, td { border: 1px solid #d1d9e0; padding: 6px 12px; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+.summary { display: flex; flex-wrap: wrap; gap: 1rem; padding: 0; list-style: none; }
+.summary li { border: 1px solid #d1d9e0; border-radius: 6px; padding: 0.75rem 1rem; }
+.summary strong { display: block; font-size: 1.5rem; }
+.calendar { display: flex; gap: 3px; overflow-x: auto; margin-bottom: 1.5rem; }
+.week { display: flex; flex-direction: column; gap: 3px; }
+.day { width: 11px; height: 11px; border-radius: 2px; }
+.level-0 { background: #ebedf0; }
+.level-1 { background: #9be9a8; }
+.level-2 { background: #40c463; }
+.level-3 { background: #30a14e; }
+.level-4 { background: #216e39; }
+.note { color: #59636e; font-size: 0.9em; }
+.warning { border-left: 4px solid #bf8700; background: #fff8c5; padding: 0.5rem 1rem; }
+.mix { display: flex; align-items: center; gap: 1.5rem; margin-bottom: 1.5rem; }
+.pie { width: 120px; height: 120px; border-radius: 50%; }
+.legend { list-style: none; padding: 0; }
+.swatch { display: inline-block; width: 11px; height: 11px; border-radius: 2px; margin-right: 0.5em; }
+.sparkline { vertical-align: middle; }
+.sparkline rect { fill: #40c463; }
+";
+
+/// Colors of the contribution mix pie chart slices, in contribution mix order.
+const MIX_COLORS: [&str; 4] = ["#216e39", "#40c463", "#0969da", "#bf8700"];
+
+/// Escapes text for use in HTML element content and attribute values.
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Renders an escaped hyperlink whose text is the URL itself.
+fn html_link(url: &str) -> String {
+    let url = escape_html(url);
+    format!("<a href=\"{}\">{}</a>", url, url)
+}
+
+/// Shading level (0-4) of a calendar day, relative to the busiest day of the range.
+pub(crate) fn calendar_level(count: i64, max: i64) -> i64 {
+    if count <= 0 || max <= 0 {
+        0
+    } else {
+        (count * 4 + max - 1) / max
+    }
+}
+
+/// A CSS pie chart of the contribution mix with a legend.
+fn mix_pie_chart(mix: &[(&str, i64)]) -> String {
+    let mut start = 0;
+    let mut slices = Vec::new();
+    let mut legend = String::new();
+    for ((label, percent), color) in mix.iter().zip(MIX_COLORS) {
+        slices.push(format!("{} {}% {}%", color, start, start + percent));
+        start += percent;
+        legend.push_str(&format!(
+            "<li><span class=\"swatch\" style=\"background: {}\"></span>{}% {}</li>\n",
+            color, percent, label
+        ));
+    }
+    format!(
+        "<div class=\"mix\">\n<div class=\"pie\" style=\"background: conic-gradient({})\"></div>\n<ul class=\"legend\">\n{}</ul>\n</div>\n",
+        slices.join(", "),
+        legend
+    )
+}
+
+/// An HTML formatter for GitHub activity, producing a standalone page.
+#[derive(Default)]
+pub struct HtmlFormatter {
+    /// How totals are grouped, e.g. `12,345`.
+    pub locale: NumberLocale,
+    /// How the contribution calendar is shown.
+    pub calendar: CalendarGranularity,
+    /// Draw the contribution mix as a pie chart below the summary.
+    pub mix_chart: bool,
+    /// Whether the issue, pull request, and review tables are split by repository.
+    pub group_by: GroupBy,
+}
+
+impl HtmlFormatter {
+    /// The issue, pull request, and review tables of `cc`, under headings of element
+    /// `level`, e.g. `h2`.
+    fn contribution_tables(
+        &self,
+        report: &Report,
+        cc: &ContributionsCollection,
+        level: &str,
+    ) -> String {
+        let mut output = String::new();
+        // Issue Contributions
+        output.push_str(&format!("<{level}>Issue Contributions</{level}>\n<table>\n<tr><th>Issue #</th><th>Title</th><th>URL</th><th>Created At</th><th>State</th><th>Closed At</th></tr>\n", level = level));
+        if let Some(nodes) = &cc.issue_contributions.nodes {
+            for node in nodes {
+                let issue = &node.issue;
+                let mut title = escape_html(&text::title(&issue.title));
+                if let Some(events) = report.issue_histories.get(&issue.url) {
+                    title.push_str(&format!(
+                        "<br><span class=\"note\">{}</span>",
+                        escape_html(&history_line(events))
+                    ));
+                }
+                output.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    issue.number,
+                    title,
+                    html_link(&issue.url),
+                    escape_html(&issue.created_at),
+                    escape_html(&issue.state),
+                    escape_html(issue.closed_at.as_deref().unwrap_or("N/A"))
+                ));
+            }
+        }
+        output.push_str("</table>\n");
+
+        // Pull Request Contributions
+        output.push_str(&format!("<{level}>Pull Request Contributions</{level}>\n<table>\n<tr><th>PR #</th><th>Title</th><th>URL</th><th>Created At</th><th>State</th><th>Merged</th><th>Merged At</th><th>Closed At</th><th>Changes</th></tr>\n", level = level));
+        if let Some(nodes) = &cc.pull_request_contributions.nodes {
+            for node in nodes {
+                let pr = &node.pull_request;
+                let mut title = escape_html(&text::title(&pr.title));
+                if let Some(note) = url_fork_note(report, &pr.url) {
+                    title.push_str(&format!(
+                        " <span class=\"note\">(in {})</span>",
+                        escape_html(&note)
+                    ));
+                }
+                if let Some(release) = report.shipped_in.get(&pr.url) {
+                    title.push_str(&format!(
+                        " <span class=\"note\">(shipped in {})</span>",
+                        escape_html(&release.tag_name)
+                    ));
+                }
+                if let Some(revert) = report.reverted_prs.get(&pr.url) {
+                    title.push_str(&format!(
+                        " <span class=\"note\">(reverted in #{})</span>",
+                        revert.number
+                    ));
+                }
+                output.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    pr.number,
+                    title,
+                    html_link(&pr.url),
+                    escape_html(&pr.created_at),
+                    escape_html(&pr.state),
+                    pr.merged,
+                    escape_html(pr.merged_at.as_deref().unwrap_or("N/A")),
+                    escape_html(pr.closed_at.as_deref().unwrap_or("N/A")),
+                    escape_html(&pr_size(
+                        pr.additions,
+                        pr.deletions,
+                        pr.changed_files,
+                        self.locale
+                    ))
+                ));
+            }
+        }
+        output.push_str("</table>\n");
+
+        // Pull Request Review Contributions
+        output.push_str(&format!("<{level}>Pull Request Review Contributions</{level}>\n<table>\n<tr><th>PR #</th><th>Title</th><th>State</th><th>URL</th><th>Occurred At</th></tr>\n", level = level));
+        if let Some(nodes) = &cc.pull_request_review_contributions.nodes {
+            for node in nodes {
+                let pr_review = &node.pull_request_review;
+                output.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    pr_review.pull_request.number,
+                    escape_html(&text::title(&pr_review.pull_request.title)),
+                    escape_html(&review_state(&pr_review.state)),
+                    html_link(&pr_review.pull_request.url),
+                    escape_html(&node.occurred_at)
+                ));
+            }
+        }
+        output.push_str("</table>\n");
+        output
+    }
+}
+
+impl FormatData for HtmlFormatter {
+    fn format(&self, report: &Report) -> String {
+        let (activity, username) = (&report.activity, &report.username);
+        let (start_date, end_date) = (report.start_date, report.end_date);
+        let mut output = String::new();
+        output.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        output.push_str(&format!(
+            "<title>GitHub Activity Report for {}</title>\n<style>{}</style>\n</head>\n<body>\n",
+            escape_html(username),
+            HTML_STYLE
+        ));
+        if let Some(user) = &activity.user {
+            let cc = &user.contributions_collection;
             output.push_str(&format!(
-                "**Time Period:** {} to {}\n\n",
-                start_date.to_rfc3339(),
-                end_date.to_rfc3339()
-            ));
-            output.push_str("## Summary\n\n");
-            output.push_str(&format!(
-                "- **Total Commit Contributions:** {}\n",
-                cc.total_commit_contributions
-            ));
-            output.push_str(&format!(
-                "- **Total Issue Contributions:** {}\n",
-                cc.total_issue_contributions
+                "<h1>GitHub Activity Report for {}</h1>\n",
+                escape_html(username)
             ));
             output.push_str(&format!(
-                "- **Total Pull Request Contributions:** {}\n",
-                cc.total_pull_request_contributions
+                "<p><strong>Time Period:</strong> {} to {}</p>\n",
+                start_date.to_rfc3339(),
+                end_date.to_rfc3339()
             ));
+            for warning in &report.warnings {
+                output.push_str(&format!(
+                    "<p class=\"warning\"><strong>Warning:</strong> {}</p>\n",
+                    escape_html(warning)
+                ));
+            }
+
+            // Summary
+            output.push_str("<h2>Summary</h2>\n<ul class=\"summary\">\n");
+            for (label, total) in [
+                ("Commit Contributions", cc.total_commit_contributions),
+                ("Issue Contributions", cc.total_issue_contributions),
+                (
+                    "Pull Request Contributions",
+                    cc.total_pull_request_contributions,
+                ),
+                (
+                    "Pull Request Review Contributions",
+                    cc.total_pull_request_review_contributions,
+                ),
+            ] {
+                output.push_str(&format!(
+                    "<li><strong>{}</strong>{}</li>\n",
+                    self.locale.format(total),
+                    label
+                ));
+            }
+            let (additions, deletions) = lines_changed(cc);
             output.push_str(&format!(
-                "- **Total Pull Request Review Contributions:** {}\n\n",
-                cc.total_pull_request_review_contributions
+                "<li><strong>{}</strong>Lines Changed</li>\n",
+                escape_html(&diff_stat(additions, deletions, self.locale))
             ));
+            output.push_str("</ul>\n");
+            if let Some(mix) = contribution_mix(cc) {
+                output.push_str(&format!(
+                    "<p><strong>Contribution Mix:</strong> {}</p>\n",
+                    mix_line(&mix)
+                ));
+                if self.mix_chart {
+                    output.push_str(&mix_pie_chart(&mix));
+                }
+            }
+
+            // Statistics
+            if let Some(statistics) = &report.statistics {
+                output.push_str("<h2>Statistics</h2>\n<table>\n");
+                for (label, value) in statistics_rows(statistics, self.locale) {
+                    output.push_str(&format!(
+                        "<tr><th>{}</th><td>{}</td></tr>\n",
+                        label,
+                        escape_html(&value)
+                    ));
+                }
+                output.push_str("</table>\n");
+            }
+
+            // Unattributed Commits
+            if !report.unattributed_commits.is_empty() {
+                output.push_str("<h2>Unattributed Commits</h2>\n<ul>\n");
+                for email in &report.unattributed_commits {
+                    output.push_str(&format!(
+                        "<li>{}</li>\n",
+                        escape_html(&unattributed_line(email, self.locale))
+                    ));
+                }
+                output.push_str("</ul>\n");
+            }
+
+            // Category Breakdown
+            let categories = category_breakdown(report);
+            if !categories.is_empty() {
+                output.push_str("<h2>Category Breakdown</h2>\n<table>\n<tr><th>Category</th><th>Issues</th><th>Pull Requests</th><th>Reviews</th></tr>\n");
+                for counts in &categories {
+                    output.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                        escape_html(&counts.name),
+                        self.locale.format(counts.issues as i64),
+                        self.locale.format(counts.pull_requests as i64),
+                        self.locale.format(counts.reviews as i64)
+                    ));
+                }
+                output.push_str("</table>\n");
+            }
+
+            // Review Coverage
+            if !report.review_coverage.is_empty() {
+                output.push_str("<h2>Review Coverage</h2>\n<table>\n<tr><th>Repository</th><th>Merged</th><th>Reviewed</th><th>Coverage</th></tr>\n");
+                for coverage in &report.review_coverage {
+                    output.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                        escape_html(&coverage.repository),
+                        self.locale.format(coverage.merged),
+                        self.locale.format(coverage.reviewed),
+                        coverage_percent(coverage)
+                    ));
+                }
+                output.push_str("</table>\n");
+            }
+
+            // Triage Activity
+            if let Some(triage) = &report.triage {
+                output.push_str(&format!(
+                    "<h2>Triage Activity in {}</h2>\n<ul>\n",
+                    escape_html(&triage.repository)
+                ));
+                for (heading, actions) in triage_sections(triage) {
+                    output.push_str(&format!(
+                        "<li><strong>{}:</strong> {}</li>\n",
+                        heading,
+                        actions.len()
+                    ));
+                }
+                output.push_str("</ul>\n<table>\n<tr><th>Action</th><th>Issue #</th><th>Title</th><th>URL</th><th>Detail</th><th>Occurred At</th></tr>\n");
+                for (heading, actions) in triage_sections(triage) {
+                    for action in actions {
+                        output.push_str(&format!(
+                            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                            heading,
+                            action.number,
+                            escape_html(&text::title(&action.title)),
+                            html_link(&action.url),
+                            escape_html(action.detail.as_deref().unwrap_or("")),
+                            escape_html(&action.occurred_at)
+                        ));
+                    }
+                }
+                output.push_str("</table>\n");
+            }
 
             // Contribution Calendar
-            output.push_str("## Contribution Calendar\n\n");
-            output.push_str(&format!(
-                "**Total Contributions:** {}\n\n",
-                cc.contribution_calendar.total_contributions
-            ));
-            for week in &cc.contribution_calendar.weeks {
-                for day in &week.contribution_days {
+            if self.calendar != CalendarGranularity::Off {
+                output.push_str(&format!(
+                    "<h2>Contribution Calendar</h2>\n<p><strong>Total Contributions:</strong> {}</p>\n",
+                    self.locale.format(cc.contribution_calendar.total_contributions)
+                ));
+            }
+            if self.calendar == CalendarGranularity::Daily {
+                output.push_str("<div class=\"calendar\">\n");
+                let max = cc
+                    .contribution_calendar
+                    .weeks
+                    .iter()
+                    .flat_map(|week| &week.contribution_days)
+                    .map(|day| day.contribution_count)
+                    .max()
+                    .unwrap_or(0);
+                for week in &cc.contribution_calendar.weeks {
+                    output.push_str("<div class=\"week\">");
+                    for day in &week.contribution_days {
+                        output.push_str(&format!(
+                            "<div class=\"day level-{}\" title=\"{}: {} contributions\"></div>",
+                            calendar_level(day.contribution_count, max),
+                            escape_html(&day.date),
+                            day.contribution_count
+                        ));
+                    }
+                    output.push_str("</div>\n");
+                }
+                output.push_str("</div>\n");
+            }
+            let periods = calendar_periods(&cc.contribution_calendar, self.calendar);
+            if !periods.is_empty() {
+                output.push_str("<table>\n<tr><th>Period</th><th>Contributions</th></tr>\n");
+                for period in periods {
                     output.push_str(&format!(
-                        "* {}: {} contributions (weekday {})\n",
-                        day.date, day.contribution_count, day.weekday
+                        "<tr><td>{}</td><td>{}</td></tr>\n",
+                        escape_html(&self.locale.month_label(&period.label)),
+                        self.locale.format(period.contribution_count)
                     ));
                 }
+                output.push_str("</table>\n");
             }
-            output.push('\n');
 
-            // Repository Contributions
-            output.push_str("## Repository Contributions\n\n");
-            output.push_str("| Repository             | Commits |\n");
-            output.push_str("|------------------------|---------|\n");
-            for repo_contrib in &cc.commit_contributions_by_repository {
-                output.push_str(&format!(
-                    "| {:<22} | {:>7} |\n",
-                    repo_contrib.repository.name_with_owner, repo_contrib.contributions.total_count
-                ));
+            // Repository Contributions
+            output.push_str("<h2>Repository Contributions</h2>\n<table>\n<tr><th>Repository</th><th>Commits</th><th>Activity</th></tr>\n");
+            let sparklines = Sparklines::new(report);
+            for repo_contrib in &cc.commit_contributions_by_repository {
+                output.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    escape_html(&repository_name(
+                        report,
+                        &repo_contrib.repository.name_with_owner
+                    )),
+                    self.locale.format(repo_contrib.contributions.total_count),
+                    sparklines.svg(&repo_contrib.repository.name_with_owner)
+                ));
+            }
+            output.push_str("</table>\n");
+
+            match self.group_by {
+                GroupBy::None => output.push_str(&self.contribution_tables(report, cc, "h2")),
+                GroupBy::Week | GroupBy::Month => {
+                    let granularity = self.group_by.rollup().unwrap_or_default();
+                    let period = if self.group_by == GroupBy::Week {
+                        "Weekly"
+                    } else {
+                        "Monthly"
+                    };
+                    output.push_str(&format!("<h2>{} Rollup</h2>\n<table>\n<tr><th>Period</th><th>Contributions</th><th>Issues</th><th>Pull Requests</th><th>Reviews</th></tr>\n", period));
+                    let (rows, total) = rollups_with_total(cc, granularity);
+                    for (row, cell) in rows.iter().map(|row| (row, "td")).chain([(&total, "th")]) {
+                        output.push_str(&format!(
+                            "<tr><{cell}>{}</{cell}><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                            escape_html(&self.locale.month_label(&row.label)),
+                            self.locale.format(row.contribution_count),
+                            self.locale.format(row.issues as i64),
+                            self.locale.format(row.pull_requests as i64),
+                            self.locale.format(row.reviews as i64)
+                        ));
+                    }
+                    output.push_str("</table>\n");
+                }
+                GroupBy::Repository | GroupBy::Category => {
+                    let groups = match self.group_by {
+                        GroupBy::Category => by_category(cc, &report.categories),
+                        _ => by_repository(cc),
+                    };
+                    for (group, cc) in groups {
+                        output.push_str(&format!("<h2>{}</h2>\n", escape_html(&group)));
+                        output.push_str(&self.contribution_tables(report, &cc, "h3"));
+                    }
+                }
+            }
+        } else {
+            output.push_str("<p>No user data available.</p>\n");
+        }
+        output.push_str("</body>\n</html>\n");
+        output
+    }
+}
+
+/// A table of the CSV export.
+pub struct CsvTable {
+    /// Table name, used as the `section` value and as the file stem in directory output.
+    pub name: &'static str,
+    /// Column headers.
+    pub columns: &'static [&'static str],
+    /// Row values, in column order.
+    pub rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    /// Renders the table as a standalone CSV document.
+    pub fn to_csv(&self) -> String {
+        write_csv(self.columns, self.rows.iter().map(|row| row.as_slice()))
+    }
+}
+
+/// Columns of the single-file CSV export: every table's columns plus a leading `section`.
+const CSV_COLUMNS: &[&str] = &[
+    "section",
+    "repository",
+    "number",
+    "title",
+    "url",
+    "created_at",
+    "state",
+    "merged",
+    "merged_at",
+    "closed_at",
+    "additions",
+    "deletions",
+    "changed_files",
+    "occurred_at",
+    "commits",
+];
+
+/// Writes rows of string values as CSV.
+fn write_csv<'a, S: AsRef<str> + 'a>(
+    columns: &[&str],
+    rows: impl IntoIterator<Item = &'a [S]>,
+) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    // Writing into memory cannot fail, so the results are ignored.
+    let _ = writer.write_record(columns);
+    for row in rows {
+        let _ = writer.write_record(row.iter().map(AsRef::as_ref));
+    }
+    String::from_utf8(writer.into_inner().unwrap_or_default()).unwrap_or_default()
+}
+
+/// A CSV formatter for GitHub activity, for importing reports into spreadsheets.
+pub struct CsvFormatter;
+
+impl CsvFormatter {
+    /// The repository, issue, pull request, and review tables of the report.
+    pub fn tables(&self, report: &Report) -> Vec<CsvTable> {
+        let mut repositories = Vec::new();
+        let mut issues = Vec::new();
+        let mut pull_requests = Vec::new();
+        let mut reviews = Vec::new();
+        let repository = |url: &str| repository_of(url).unwrap_or_default();
+
+        if let Some(user) = &report.activity.user {
+            let cc = &user.contributions_collection;
+            for repo_contrib in &cc.commit_contributions_by_repository {
+                repositories.push(vec![
+                    repo_contrib.repository.name_with_owner.clone(),
+                    repo_contrib.contributions.total_count.to_string(),
+                ]);
+            }
+            for node in cc.issue_contributions.nodes.iter().flatten() {
+                let issue = &node.issue;
+                issues.push(vec![
+                    repository(&issue.url),
+                    issue.number.to_string(),
+                    issue.title.clone(),
+                    issue.url.clone(),
+                    issue.created_at.clone(),
+                    issue.state.clone(),
+                    issue.closed_at.clone().unwrap_or_default(),
+                ]);
+            }
+            for node in cc.pull_request_contributions.nodes.iter().flatten() {
+                let pr = &node.pull_request;
+                pull_requests.push(vec![
+                    repository(&pr.url),
+                    pr.number.to_string(),
+                    pr.title.clone(),
+                    pr.url.clone(),
+                    pr.created_at.clone(),
+                    pr.state.clone(),
+                    pr.merged.to_string(),
+                    pr.merged_at.clone().unwrap_or_default(),
+                    pr.closed_at.clone().unwrap_or_default(),
+                    pr.additions.to_string(),
+                    pr.deletions.to_string(),
+                    pr.changed_files.to_string(),
+                ]);
+            }
+            for node in cc.pull_request_review_contributions.nodes.iter().flatten() {
+                let pr = &node.pull_request_review.pull_request;
+                reviews.push(vec![
+                    repository(&pr.url),
+                    pr.number.to_string(),
+                    pr.title.clone(),
+                    pr.url.clone(),
+                    node.occurred_at.clone(),
+                    node.pull_request_review.state.clone(),
+                ]);
+            }
+        }
+
+        vec![
+            CsvTable {
+                name: "repositories",
+                columns: &["repository", "commits"],
+                rows: repositories,
+            },
+            CsvTable {
+                name: "issues",
+                columns: &[
+                    "repository",
+                    "number",
+                    "title",
+                    "url",
+                    "created_at",
+                    "state",
+                    "closed_at",
+                ],
+                rows: issues,
+            },
+            CsvTable {
+                name: "pull_requests",
+                columns: &[
+                    "repository",
+                    "number",
+                    "title",
+                    "url",
+                    "created_at",
+                    "state",
+                    "merged",
+                    "merged_at",
+                    "closed_at",
+                    "additions",
+                    "deletions",
+                    "changed_files",
+                ],
+                rows: pull_requests,
+            },
+            CsvTable {
+                name: "reviews",
+                columns: &[
+                    "repository",
+                    "number",
+                    "title",
+                    "url",
+                    "occurred_at",
+                    "state",
+                ],
+                rows: reviews,
+            },
+        ]
+    }
+
+    /// The `--calendar-only` table of contribution calendar days: date and count, after the
+    /// username when the days are labeled with one.
+    pub fn calendar(&self, days: &[CalendarDay]) -> CsvTable {
+        let labeled = days.iter().any(|day| day.username.is_some());
+        let rows = days
+            .iter()
+            .map(|day| {
+                let mut row = Vec::with_capacity(3);
+                if labeled {
+                    row.push(day.username.clone().unwrap_or_default());
+                }
+                row.extend([day.date.clone(), day.count.to_string()]);
+                row
+            })
+            .collect();
+        CsvTable {
+            name: "calendar",
+            columns: if labeled {
+                &["username", "date", "count"]
+            } else {
+                &["date", "count"]
+            },
+            rows,
+        }
+    }
+}
+
+impl FormatData for CsvFormatter {
+    fn format(&self, report: &Report) -> String {
+        let mut rows = Vec::new();
+        for table in self.tables(report) {
+            for row in &table.rows {
+                let mut combined = vec![table.name.to_string()];
+                combined.extend(CSV_COLUMNS[1..].iter().map(|column| {
+                    table
+                        .columns
+                        .iter()
+                        .position(|c| c == column)
+                        .map(|i| row[i].clone())
+                        .unwrap_or_default()
+                }));
+                rows.push(combined);
+            }
+        }
+        write_csv(CSV_COLUMNS, rows.iter().map(|row| row.as_slice()))
+    }
+}
+
+/// Most items listed per Slack section; Slack caps a section's text at 3000 characters.
+const SLACK_MAX_ITEMS: usize = 10;
+
+/// Escapes the characters Slack's mrkdwn treats as control characters.
+fn escape_slack(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// A Slack Block Kit formatter, producing a JSON payload that can be posted to a Slack
+/// incoming webhook as-is.
+#[derive(Default)]
+pub struct SlackFormatter {
+    /// How totals are grouped, e.g. `12,345`.
+    pub locale: NumberLocale,
+}
+
+impl SlackFormatter {
+    /// A section listing at most `SLACK_MAX_ITEMS` lines under a bold heading.
+    fn list_section(heading: &str, lines: &[String]) -> Value {
+        let mut text = format!("*{}*", heading);
+        for line in lines.iter().take(SLACK_MAX_ITEMS) {
+            text.push_str(&format!("\n• {}", line));
+        }
+        if lines.len() > SLACK_MAX_ITEMS {
+            text.push_str(&format!("\n…and {} more", lines.len() - SLACK_MAX_ITEMS));
+        }
+        json!({ "type": "section", "text": { "type": "mrkdwn", "text": text } })
+    }
+}
+
+impl FormatData for SlackFormatter {
+    fn format(&self, report: &Report) -> String {
+        let (activity, username) = (&report.activity, &report.username);
+        let (start_date, end_date) = (report.start_date, report.end_date);
+        let mut blocks = vec![json!({
+            "type": "header",
+            "text": {
+                "type": "plain_text",
+                "text": format!("GitHub Activity Report for {}", username),
+            }
+        })];
+        let mut context = vec![json!({
+            "type": "mrkdwn",
+            "text": format!(
+                "{} to {}",
+                start_date.format("%Y-%m-%d"),
+                end_date.format("%Y-%m-%d")
+            ),
+        })];
+        if let Some(mix) = activity
+            .user
+            .as_ref()
+            .and_then(|user| contribution_mix(&user.contributions_collection))
+        {
+            context.push(json!({ "type": "mrkdwn", "text": format!("Mix: {}", mix_line(&mix)) }));
+        }
+        blocks.push(json!({ "type": "context", "elements": context }));
+
+        if let Some(user) = &activity.user {
+            let cc = &user.contributions_collection;
+            let (additions, deletions) = lines_changed(cc);
+            let field = |label: &str, total: i64| json!({ "type": "mrkdwn", "text": format!("*{}*\n{}", label, self.locale.format(total)) });
+            blocks.push(json!({
+                "type": "section",
+                "fields": [
+                    field("Commits", cc.total_commit_contributions),
+                    field("Issues", cc.total_issue_contributions),
+                    field("Pull Requests", cc.total_pull_request_contributions),
+                    field("Reviews", cc.total_pull_request_review_contributions),
+                    json!({ "type": "mrkdwn", "text": format!("*Lines Changed*\n{}", diff_stat(additions, deletions, self.locale)) }),
+                ]
+            }));
+            if let Some(statistics) = &report.statistics {
+                let fields: Vec<Value> = statistics_rows(statistics, self.locale)
+                    .into_iter()
+                    .map(|(label, value)| json!({ "type": "mrkdwn", "text": format!("*{}*\n{}", label, value) }))
+                    .collect();
+                blocks.push(json!({ "type": "section", "fields": fields }));
             }
-            output.push('\n');
+            blocks.push(json!({ "type": "divider" }));
 
-            // Issue Contributions
-            output.push_str("## Issue Contributions\n\n");
-            output.push_str("| Issue # | Title | URL | Created At | State | Closed At |\n");
-            output.push_str("|---------|-------|-----|------------|-------|-----------|\n");
-            if let Some(nodes) = &cc.issue_contributions.nodes {
-                for node in nodes {
-                    let issue = &node.issue;
-                    output.push_str(&format!(
-                        "| {} | {} | {} | {} | {} | {} |\n",
-                        issue.number,
-                        issue.title,
-                        issue.url,
-                        issue.created_at,
-                        issue.state,
-                        issue.closed_at.as_deref().unwrap_or("N/A")
-                    ));
+            let link = |number: i64, title: &str, url: &str| {
+                format!(
+                    "<{}|#{} {}>",
+                    url,
+                    number,
+                    escape_slack(&text::title(title))
+                )
+            };
+            let repositories: Vec<String> = cc
+                .commit_contributions_by_repository
+                .iter()
+                .map(|r| {
+                    format!(
+                        "{}: {} commits",
+                        escape_slack(&r.repository.name_with_owner),
+                        self.locale.format(r.contributions.total_count)
+                    )
+                })
+                .collect();
+            let pull_requests: Vec<String> = cc
+                .pull_request_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(|n| {
+                    let pr = &n.pull_request;
+                    format!(
+                        "{} ({})",
+                        link(pr.number, &pr.title, &pr.url),
+                        diff_stat(pr.additions, pr.deletions, self.locale)
+                    )
+                })
+                .collect();
+            let issues: Vec<String> = cc
+                .issue_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(|n| link(n.issue.number, &n.issue.title, &n.issue.url))
+                .collect();
+            let reviews: Vec<String> = cc
+                .pull_request_review_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(|n| {
+                    let review = &n.pull_request_review;
+                    let pr = &review.pull_request;
+                    if review.state.is_empty() {
+                        link(pr.number, &pr.title, &pr.url)
+                    } else {
+                        format!(
+                            "{} ({})",
+                            link(pr.number, &pr.title, &pr.url),
+                            review_state(&review.state)
+                        )
+                    }
+                })
+                .collect();
+            for (heading, lines) in [
+                ("Repositories", repositories),
+                ("Pull Requests", pull_requests),
+                ("Issues", issues),
+                ("Reviews", reviews),
+            ] {
+                if !lines.is_empty() {
+                    blocks.push(Self::list_section(heading, &lines));
                 }
             }
-            output.push('\n');
+        } else {
+            blocks.push(json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": "No user data available." }
+            }));
+        }
 
-            // Pull Request Contributions
-            output.push_str("## Pull Request Contributions\n\n");
-            output.push_str(
-                "| PR # | Title | URL | Created At | State | Merged | Merged At | Closed At |\n",
-            );
-            output.push_str(
-                "|------|-------|-----|------------|-------|--------|-----------|-----------|\n",
+        json!({ "blocks": blocks }).to_string()
+    }
+}
+
+/// Longest iCalendar content line in octets, without the line break; longer lines are folded.
+const ICS_LINE_OCTETS: usize = 75;
+
+/// Escapes text for an iCalendar TEXT value.
+fn escape_ics(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+        .replace('\r', "")
+}
+
+/// Folds an iCalendar content line into lines of at most `ICS_LINE_OCTETS` octets, each
+/// continuation starting with a space, without splitting a UTF-8 character; every line ends
+/// with CRLF.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 2);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > ICS_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line.
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// The `YYYY-MM-DD` day of a GitHub date or timestamp.
+fn day_of(date: &str) -> &str {
+    date.get(..10).unwrap_or(date)
+}
+
+/// An iCalendar formatter, so activity can be overlaid on a calendar app: one all-day event
+/// per contribution day, summarizing its counts, and one event per merged pull request at the
+/// time it was merged.
+pub struct IcsFormatter;
+
+impl IcsFormatter {
+    /// A single calendar with the events of every report.
+    pub fn calendar(&self, reports: &[Report]) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:-//{}//EN", env!("CARGO_PKG_NAME")),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+        ];
+        for report in reports {
+            Self::push_events(report, &mut lines);
+        }
+        lines.push("END:VCALENDAR".to_string());
+        lines.iter().map(|line| fold_ics_line(line)).collect()
+    }
+
+    /// Appends the events of `report` to `lines`. Events are stamped with the end of the
+    /// report's range rather than the time of rendering, so re-rendering a saved report gives
+    /// the same calendar.
+    fn push_events(report: &Report, lines: &mut Vec<String>) {
+        let Some(user) = &report.activity.user else {
+            return;
+        };
+        let cc = &user.contributions_collection;
+        let username = &report.username;
+        let stamp = format!("DTSTAMP:{}", report.end_date.format("%Y%m%dT%H%M%SZ"));
+
+        // Issues, pull requests, and reviews per day; commits are only counted per repository.
+        let mut per_day: BTreeMap<&str, [i64; 3]> = BTreeMap::new();
+        for node in cc.issue_contributions.nodes.iter().flatten() {
+            per_day.entry(day_of(&node.issue.created_at)).or_default()[0] += 1;
+        }
+        for node in cc.pull_request_contributions.nodes.iter().flatten() {
+            per_day
+                .entry(day_of(&node.pull_request.created_at))
+                .or_default()[1] += 1;
+        }
+        for node in cc.pull_request_review_contributions.nodes.iter().flatten() {
+            per_day.entry(day_of(&node.occurred_at)).or_default()[2] += 1;
+        }
+
+        let days = cc
+            .contribution_calendar
+            .weeks
+            .iter()
+            .flat_map(|week| &week.contribution_days);
+        for day in days.filter(|day| day.contribution_count > 0) {
+            let date = day_of(&day.date);
+            let Ok(start) = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+                continue;
+            };
+            let mut summary = format!(
+                "{}: {} contribution{}",
+                username,
+                day.contribution_count,
+                if day.contribution_count == 1 { "" } else { "s" }
             );
-            if let Some(nodes) = &cc.pull_request_contributions.nodes {
-                for node in nodes {
-                    let pr = &node.pull_request;
-                    output.push_str(&format!(
-                        "| {} | {} | {} | {} | {} | {} | {} | {} |\n",
-                        pr.number,
-                        pr.title,
-                        pr.url,
-                        pr.created_at,
-                        pr.state,
-                        pr.merged,
-                        pr.merged_at.as_deref().unwrap_or("N/A"),
-                        pr.closed_at.as_deref().unwrap_or("N/A")
-                    ));
-                }
+            let kinds = per_day.get(date).copied().unwrap_or_default();
+            let parts: Vec<String> = kinds
+                .iter()
+                .zip([
+                    ("issue", "issues"),
+                    ("pull request", "pull requests"),
+                    ("review", "reviews"),
+                ])
+                .filter(|(count, _)| **count > 0)
+                .map(|(count, (one, many))| {
+                    format!("{} {}", count, if *count == 1 { one } else { many })
+                })
+                .collect();
+            if !parts.is_empty() {
+                summary.push_str(&format!(" ({})", parts.join(", ")));
             }
-            output.push('\n');
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!(
+                    "UID:{}-{}@{}",
+                    start.format("%Y%m%d"),
+                    username,
+                    env!("CARGO_PKG_NAME")
+                ),
+                stamp.clone(),
+                format!("DTSTART;VALUE=DATE:{}", start.format("%Y%m%d")),
+                format!(
+                    "DTEND;VALUE=DATE:{}",
+                    start.succ_opt().unwrap_or(start).format("%Y%m%d")
+                ),
+                format!("SUMMARY:{}", escape_ics(&summary)),
+                "TRANSP:TRANSPARENT".to_string(),
+                "END:VEVENT".to_string(),
+            ]);
+        }
 
-            // Pull Request Review Contributions
-            output.push_str("## Pull Request Review Contributions\n\n");
-            output.push_str("| PR # | Title | URL | Occurred At |\n");
-            output.push_str("|------|-------|-----|-------------|\n");
-            if let Some(nodes) = &cc.pull_request_review_contributions.nodes {
-                for node in nodes {
-                    let pr_review = &node.pull_request_review;
-                    output.push_str(&format!(
-                        "| {} | {} | {} | {} |\n",
-                        pr_review.pull_request.number,
-                        pr_review.pull_request.title,
-                        pr_review.pull_request.url,
-                        node.occurred_at
-                    ));
-                }
-            }
-        } else {
-            output.push_str("No user data available.\n");
+        for node in cc.pull_request_contributions.nodes.iter().flatten() {
+            let pr = &node.pull_request;
+            let Some(merged_at) = pr
+                .merged_at
+                .as_deref()
+                .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+            else {
+                continue;
+            };
+            let description = format!(
+                "{}: +{} -{} in {} file{}",
+                repository_of(&pr.url).unwrap_or_default(),
+                pr.additions,
+                pr.deletions,
+                pr.changed_files,
+                if pr.changed_files == 1 { "" } else { "s" }
+            );
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:{}", pr.url),
+                stamp.clone(),
+                format!(
+                    "DTSTART:{}",
+                    merged_at
+                        .with_timezone(&chrono::Utc)
+                        .format("%Y%m%dT%H%M%SZ")
+                ),
+                format!(
+                    "SUMMARY:{}",
+                    escape_ics(&format!(
+                        "Merged #{}: {}",
+                        pr.number,
+                        text::title(&pr.title)
+                    ))
+                ),
+                format!("DESCRIPTION:{}", escape_ics(&description)),
+                format!("URL:{}", pr.url),
+                "TRANSP:TRANSPARENT".to_string(),
+                "END:VEVENT".to_string(),
+            ]);
         }
-        output
+    }
+}
+
+impl FormatData for IcsFormatter {
+    fn format(&self, report: &Report) -> String {
+        self.calendar(std::slice::from_ref(report))
+    }
+}
+
+/// A formatter rendering the report through a user-provided Handlebars template, for teams
+/// whose report layout doesn't match any built-in format.
+///
+/// The template sees the report as it is serialized to JSON: `username`, `start_date`,
+/// `end_date`, `activity` (the filtered activity, e.g. `activity.totals.commits` or
+/// `activity.pull_requests`; see [`crate::model`]), and any optional sections. Values are inserted
+/// without HTML escaping.
+pub struct TemplateFormatter {
+    registry: Handlebars<'static>,
+}
+
+impl TemplateFormatter {
+    const TEMPLATE_NAME: &'static str = "report";
+
+    /// Compiles a template from its source.
+    pub fn new(template: &str) -> Result<Self> {
+        let mut registry = Handlebars::new();
+        // Reports are mostly text or Markdown, where HTML-escaping titles would garble them.
+        registry.register_escape_fn(handlebars::no_escape);
+        registry
+            .register_template_string(Self::TEMPLATE_NAME, template)
+            .context("Failed to compile template")?;
+        Ok(Self { registry })
+    }
+
+    /// Reads and compiles a template file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let template = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read template from {:?}", path))?;
+        Self::new(&template).with_context(|| format!("Invalid template {:?}", path))
+    }
+
+    /// Renders the report. Unlike `FormatData::format` this can fail, e.g. when the template
+    /// calls an unknown helper.
+    pub fn render(&self, report: &Report) -> Result<String> {
+        self.registry
+            .render(Self::TEMPLATE_NAME, report)
+            .context("Failed to render template")
     }
 }
 
@@ -277,8 +2616,21 @@ impl FormatData for MarkdownFormatter {
 mod tests {
     use super::*;
     use crate::github::user_activity;
+    use crate::release::ShippedRelease;
+    use crate::revert::RevertedBy;
+    use crate::statistics::ForecastPeriod;
+    use crate::timeline::{TimelineEvent, TimelineEventKind};
     use chrono::{TimeZone, Utc};
 
+    fn dummy_report() -> Report {
+        Report::new(
+            "dummy".into(),
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 12, 0, 0, 0).unwrap(),
+            dummy_response_data(),
+        )
+    }
+
     fn dummy_response_data() -> user_activity::ResponseData {
         user_activity::ResponseData {
             user: Some(user_activity::UserActivityUser {
@@ -321,12 +2673,19 @@ mod tests {
                         nodes: Some(vec![
                             user_activity::UserActivityUserContributionsCollectionIssueContributionsNodes {
                                 issue: user_activity::UserActivityUserContributionsCollectionIssueContributionsNodesIssue {
+                                    id: "I_42".into(),
                                     number: 42,
                                     title: "Test Issue".into(),
                                     url: "http://example.com/issue".into(),
                                     created_at: "2025-03-09T00:00:00Z".into(),
                                     state: "open".into(),
                                     closed_at: None,
+                                    labels: Some(user_activity::LabelNames {
+                                        nodes: Some(vec![
+                                            Some(user_activity::LabelNamesNodes { name: "bug".into() }),
+                                            Some(user_activity::LabelNamesNodes { name: "ui".into() }),
+                                        ]),
+                                    }),
                                 },
                             },
                         ]),
@@ -347,7 +2706,12 @@ mod tests {
                                     state: "closed".into(),
                                     merged: false,
                                     merged_at: None,
+                                    merge_commit: None,
                                     closed_at: None,
+                                    additions: 120,
+                                    deletions: 15,
+                                    changed_files: 4,
+                                    labels: None,
                                 },
                             },
                         ]),
@@ -365,7 +2729,10 @@ mod tests {
                                         number: 202,
                                         title: "Test PR Review".into(),
                                         url: "http://example.com/pr_review".into(),
+                                        labels: None,
                                     },
+                                    state: "CHANGES_REQUESTED".into(),
+                                    body: Some("Please add a test.".into()),
                                 },
                                 occurred_at: "2025-03-07T00:00:00Z".into(),
                             },
@@ -373,6 +2740,7 @@ mod tests {
                     },
                 },
             }),
+            rate_limit: None,
         }
     }
 
@@ -380,8 +2748,7 @@ mod tests {
     fn test_format_plain_contains_required_data() {
         let start_date = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
         let end_date = Utc.with_ymd_and_hms(2025, 3, 12, 0, 0, 0).unwrap();
-        let data = dummy_response_data();
-        let output = PlainTextFormatter.format(&data, start_date, end_date, "dummy");
+        let output = PlainTextFormatter::default().format(&dummy_report());
 
         // Check for header and time period.
         assert!(output.contains("User: dummy"));
@@ -397,11 +2764,21 @@ mod tests {
         assert!(output.contains("Total Issue Contributions: 5"));
         assert!(output.contains("Total Pull Request Contributions: 3"));
         assert!(output.contains("Total Pull Request Review Contributions: 2"));
+        assert!(output.contains("Lines Changed: +120 -15\n"));
 
         // Check contribution calendar.
         assert!(output.contains("Contribution Calendar:"));
         assert!(output.contains("Total Contributions: 20"));
-        assert!(output.contains("2025-03-11T00:00:00Z: 1 contributions (weekday 2)"));
+        assert!(output.contains("    Mon\n    Tue #\n    Wed\n"));
+        assert!(output.contains("    Less . - + * # More\n"));
+        assert!(!output.contains("(weekday"));
+        let detail = PlainTextFormatter {
+            calendar_detail: true,
+            ..Default::default()
+        }
+        .format(&dummy_report());
+        assert!(detail.contains("2025-03-11T00:00:00Z: 1 contributions (Tuesday)"));
+        assert!(!detail.contains("Less"));
 
         // Check repository contributions.
         assert!(output.contains("Repository Contributions:"));
@@ -411,25 +2788,75 @@ mod tests {
         // Check issue contributions.
         assert!(output.contains("Issue Contributions:"));
         assert!(output.contains("Issue #42: Test Issue"));
+        assert!(output.contains("  Labels: bug, ui\n"));
         assert!(output.contains("http://example.com/issue"));
 
         // Check pull request contributions.
         assert!(output.contains("Pull Request Contributions:"));
         assert!(output.contains("PR #101: Test PR"));
+        assert!(output.contains("  Changes: +120 -15 in 4 files\n"));
         assert!(output.contains("http://example.com/pr"));
 
         // Check pull request review contributions.
         assert!(output.contains("Pull Request Review Contributions:"));
         assert!(output.contains("PR Review for PR #202: Test PR Review"));
         assert!(output.contains("http://example.com/pr_review"));
+        assert!(output.contains("  State: changes requested\n"));
+        assert!(output.contains("  Body:\n    Please add a test.\n"));
+    }
+
+    #[test]
+    fn test_format_color_without_colors() {
+        let output = ColorTerminalFormatter::default().format(&dummy_report());
+        assert!(!output.contains('\x1b'));
+        assert!(output.starts_with("dummy 2025-03-01 00:00 to 2025-03-12 00:00\n"));
+        assert!(output.contains("Commits 10  Issues 5  Pull requests 3  Reviews 2"));
+        assert!(
+            output.contains("Contribution Calendar (20 contributions)\n  Sun\n  Mon\n  Tue #\n")
+        );
+        assert!(output.contains("  Less . - + * # More\n"));
+        assert!(output.contains("  owner/repo 5 commits"));
+        assert!(output.contains("  #42 Test Issue [open] http://example.com/issue"));
+        assert!(output.contains("  Lines changed +120 -15\n"));
+        assert!(output.contains("  #101 Test PR [closed] +120 -15 http://example.com/pr"));
+        assert!(output.contains(
+            "  #202 Test PR Review [changes requested] http://example.com/pr_review\n    Please add a test.\n"
+        ));
+    }
+
+    #[test]
+    fn test_format_color_paints_states_and_heatmap() {
+        let mut report = dummy_report();
+        let formatter = ColorTerminalFormatter {
+            color: true,
+            ..Default::default()
+        };
+        let output = formatter.format(&report);
+        assert!(output.contains("\x1b[1;36mPull Requests\x1b[0m"));
+        assert!(output.contains("[\x1b[31mclosed\x1b[0m]"));
+        assert!(output.contains("\x1b[2mTue\x1b[0m \x1b[38;5;40m■\x1b[0m\n"));
+
+        let cc = &mut report
+            .activity
+            .user
+            .as_mut()
+            .unwrap()
+            .contributions_collection;
+        cc.pull_request_contributions.nodes.as_mut().unwrap()[0]
+            .pull_request
+            .merged = true;
+        assert!(
+            formatter
+                .format(&report)
+                .contains("[\x1b[32mmerged\x1b[0m]")
+        );
     }
 
     #[test]
     fn test_format_markdown_contains_required_data() {
         let start_date = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
         let end_date = Utc.with_ymd_and_hms(2025, 3, 12, 0, 0, 0).unwrap();
-        let data = dummy_response_data();
-        let output = MarkdownFormatter.format(&data, start_date, end_date, "dummy");
+        let output = MarkdownFormatter::default().format(&dummy_report());
 
         // Check header and time period.
         assert!(output.contains("# GitHub Activity Report for dummy"));
@@ -445,11 +2872,19 @@ mod tests {
         assert!(output.contains("- **Total Issue Contributions:** 5"));
         assert!(output.contains("- **Total Pull Request Contributions:** 3"));
         assert!(output.contains("- **Total Pull Request Review Contributions:** 2"));
+        assert!(output.contains("- **Lines Changed:** +120 -15\n"));
 
         // Check contribution calendar.
         assert!(output.contains("## Contribution Calendar"));
         assert!(output.contains("**Total Contributions:** 20"));
-        assert!(output.contains("* 2025-03-11T00:00:00Z: 1 contributions (weekday 2)"));
+        assert!(output.contains("```text\nSun\nMon\nTue #\n"));
+        assert!(output.contains("Less . - + * # More\n```\n"));
+        let detail = MarkdownFormatter {
+            calendar_detail: true,
+            ..Default::default()
+        }
+        .format(&dummy_report());
+        assert!(detail.contains("* 2025-03-11T00:00:00Z: 1 contributions (Tuesday)"));
 
         // Check repository contributions table.
         assert!(output.contains("## Repository Contributions"));
@@ -468,10 +2903,724 @@ mod tests {
         assert!(output.contains("| PR #"));
         assert!(output.contains("Test PR"));
         assert!(output.contains("http://example.com/pr"));
+        assert!(output.contains("| N/A | N/A | +120 -15 in 4 files |  |\n"));
+        assert!(output.contains("| open | N/A | bug, ui |\n"));
 
         // Check pull request review contributions table.
         assert!(output.contains("## Pull Request Review Contributions"));
         assert!(output.contains("Test PR Review"));
         assert!(output.contains("http://example.com/pr_review"));
     }
+
+    #[test]
+    fn test_format_markdown_stable() {
+        let mut report = dummy_report();
+        report.end_date = Utc.with_ymd_and_hms(2025, 3, 10, 17, 42, 9).unwrap();
+        if let Some(user) = report.activity.user.as_mut() {
+            let repos = &mut user
+                .contributions_collection
+                .commit_contributions_by_repository;
+            let mut other = repos[0].clone();
+            other.repository.name_with_owner = "aaa/first".into();
+            repos.push(other);
+        }
+        let output = MarkdownFormatter {
+            stable: true,
+            ..Default::default()
+        }
+        .format(&report);
+
+        assert!(output.contains("**Time Period:** 2025-03-01 to 2025-03-10\n"));
+        assert!(!output.contains("17:42"));
+        assert!(output.contains(
+            "| Repository | Commits | Activity |\n|------------|---------|----------|\n| aaa/first | 5 | ▁▁ |\n| owner/repo | 5 | ▁▁ |\n"
+        ));
+    }
+
+    #[test]
+    fn test_format_markdown_section_replacements() {
+        let report = dummy_report();
+        let pull_requests = TemplateFormatter::new(
+            "## PRs\n{{#each activity.pull_requests}}- {{title}}\n{{/each}}",
+        )
+        .unwrap()
+        .render(&report)
+        .unwrap();
+        let output = MarkdownFormatter {
+            sections: BTreeMap::from([
+                (MarkdownSection::PullRequests, pull_requests),
+                (MarkdownSection::Calendar, " \n".to_string()),
+            ]),
+            ..Default::default()
+        }
+        .format(&report);
+
+        assert!(output.contains("## PRs\n- Test PR\n\n## Pull Request Review Contributions\n"));
+        assert!(!output.contains("## Pull Request Contributions"));
+        assert!(!output.contains("## Contribution Calendar"));
+        // Sections without a replacement keep their built-in rendering.
+        assert!(output.contains("## Issue Contributions\n"));
+        assert_eq!("pull_requests".parse(), Ok(MarkdownSection::PullRequests));
+        assert!("footer".parse::<MarkdownSection>().is_err());
+    }
+
+    #[test]
+    fn test_format_markdown_footnote_links() {
+        let formatter = MarkdownFormatter {
+            footnote_links: true,
+            ..Default::default()
+        };
+        let output = formatter.format(&dummy_report());
+
+        // URLs are replaced by references in the tables...
+        assert!(output.contains("| 42 | Test Issue | [link][1] |"));
+        assert!(output.contains("| 101 | Test PR | [link][2] |"));
+        assert!(output.contains("| 202 | Test PR Review | changes requested | [link][3] |"));
+
+        // ...and defined once at the end of the report.
+        assert!(output.ends_with(
+            "[1]: http://example.com/issue\n[2]: http://example.com/pr\n[3]: http://example.com/pr_review\n"
+        ));
+    }
+
+    #[test]
+    fn test_format_issue_history() {
+        let mut report = dummy_report();
+        report.issue_histories.insert(
+            "http://example.com/issue".into(),
+            vec![TimelineEvent {
+                kind: TimelineEventKind::Labeled,
+                actor: Some("maintainer".into()),
+                created_at: "2025-03-10T00:00:00Z".into(),
+                detail: Some("bug".into()),
+            }],
+        );
+
+        let plain = PlainTextFormatter::default().format(&report);
+        assert!(plain.contains("  History: labeled bug by maintainer on 2025-03-10\n"));
+
+        let markdown = MarkdownFormatter::default().format(&report);
+        assert!(markdown.contains("### Issue History"));
+        assert!(markdown.contains("- #42: labeled bug by maintainer on 2025-03-10\n"));
+    }
+
+    #[test]
+    fn test_format_reverted_pr() {
+        let mut report = dummy_report();
+        report.reverted_prs.insert(
+            "http://example.com/pr".into(),
+            RevertedBy {
+                number: 102,
+                title: "Revert \"Test PR\"".into(),
+                url: "http://example.com/pr/102".into(),
+                merged_at: Some("2025-03-10T00:00:00Z".into()),
+            },
+        );
+
+        let plain = PlainTextFormatter::default().format(&report);
+        assert!(plain.contains(
+            "  Reverted: by PR #102 (http://example.com/pr/102) at 2025-03-10T00:00:00Z\n"
+        ));
+
+        let markdown = MarkdownFormatter::default().format(&report);
+        assert!(markdown.contains("| 101 | Test PR (reverted in #102) |"));
+    }
+
+    #[test]
+    fn test_format_shipped_pr() {
+        let mut report = dummy_report();
+        report.shipped_in.insert(
+            "http://example.com/pr".into(),
+            ShippedRelease {
+                tag_name: "v1.2.0".into(),
+                url: "http://example.com/releases/v1.2.0".into(),
+                published_at: Some("2025-03-11T00:00:00Z".into()),
+            },
+        );
+
+        let plain = PlainTextFormatter::default().format(&report);
+        assert!(plain.contains("  Released In: v1.2.0 (http://example.com/releases/v1.2.0)\n"));
+
+        let markdown = MarkdownFormatter::default().format(&report);
+        assert!(markdown.contains("| 101 | Test PR (shipped in v1.2.0) |"));
+    }
+
+    #[test]
+    fn test_format_forks() {
+        let mut report = dummy_report();
+        report
+            .forks
+            .insert("owner/repo".into(), Some("upstream/repo".into()));
+
+        let plain = PlainTextFormatter::default().format(&report);
+        assert!(plain.contains("- owner/repo (fork of upstream/repo): 5 commits ▁▁\n"));
+
+        let html = HtmlFormatter::default().format(&report);
+        assert!(html.contains(
+            "<tr><td>owner/repo (fork of upstream/repo)</td><td>5</td><td><svg class=\"sparkline\""
+        ));
+
+        report.forks.insert("owner/repo".into(), None);
+        let markdown = MarkdownFormatter {
+            stable: true,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(markdown.contains("| owner/repo (fork) | 5 | ▁▁ |\n"));
+    }
+
+    #[test]
+    fn test_html_formatter() {
+        let mut report = dummy_report();
+        report.username = "<dummy>".into();
+        let formatter = HtmlFormatter::default();
+        let output = formatter.format(&report);
+
+        assert!(output.starts_with("<!DOCTYPE html>"));
+        assert!(output.trim_end().ends_with("</html>"));
+        // User-controlled text is escaped.
+        assert!(output.contains("<h1>GitHub Activity Report for &lt;dummy&gt;</h1>"));
+        assert!(output.contains("<li><strong>10</strong>Commit Contributions</li>"));
+        assert!(output.contains("<td>42</td><td>Test Issue</td>"));
+        assert!(output.contains("<a href=\"http://example.com/pr\">http://example.com/pr</a>"));
+        assert!(output.contains("class=\"day level-4\""));
+    }
+
+    #[test]
+    fn test_escape_adversarial_titles() {
+        assert_eq!(
+            escape_markdown_cell("a | b\r\nc\n`d` \\|"),
+            "a \\| b c \\`d\\` \\\\\\|"
+        );
+
+        let mut report = dummy_report();
+        if let Some(user) = report.activity.user.as_mut() {
+            let cc = &mut user.contributions_collection;
+            cc.issue_contributions.nodes.as_mut().unwrap()[0]
+                .issue
+                .title = "Fix | pipe\nand `tick".into();
+            cc.pull_request_contributions.nodes.as_mut().unwrap()[0]
+                .pull_request
+                .title = "<script>|</script>".into();
+        }
+        let markdown = MarkdownFormatter::default().format(&report);
+        assert!(markdown.contains("| 42 | Fix \\| pipe and \\`tick | http://example.com/issue |"));
+        assert!(markdown.contains("| 101 | <script>\\|</script> |"));
+        // Every table row keeps its column count.
+        for line in markdown.lines().filter(|l| l.starts_with("| 42 ")) {
+            assert_eq!(line.replace("\\|", "").matches('|').count(), 8);
+        }
+
+        let html = HtmlFormatter::default().format(&report);
+        assert!(html.contains("<td>Fix | pipe and `tick</td>"));
+        assert!(html.contains("&lt;script&gt;|&lt;/script&gt;"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn test_format_normalizes_titles() {
+        let mut report = dummy_report();
+        if let Some(user) = report.activity.user.as_mut() {
+            let cc = &mut user.contributions_collection;
+            cc.issue_contributions.nodes.as_mut().unwrap()[0]
+                .issue
+                .title = "תיקון\u{202E} באג 🎉".into();
+            cc.pull_request_contributions.nodes.as_mut().unwrap()[0]
+                .pull_request
+                .title = "Refactor ".repeat(20);
+        }
+        let isolated = "\u{2068}תיקון באג 🎉\u{2069}";
+        let plain = PlainTextFormatter::default().format(&report);
+        assert!(plain.contains(&format!("Issue #42: {}\n", isolated)));
+        assert!(!plain.contains('\u{202E}'));
+        let truncated = text::truncate(&"Refactor ".repeat(20), text::MAX_TITLE_WIDTH);
+        assert!(plain.contains(&format!("PR #101: {}\n", truncated)));
+
+        let slack = SlackFormatter::default().format(&report);
+        assert!(slack.contains(isolated));
+    }
+
+    #[test]
+    fn test_calendar_level() {
+        assert_eq!(calendar_level(0, 10), 0);
+        assert_eq!(calendar_level(1, 10), 1);
+        assert_eq!(calendar_level(5, 10), 2);
+        assert_eq!(calendar_level(10, 10), 4);
+        assert_eq!(calendar_level(3, 0), 0);
+    }
+
+    #[test]
+    fn test_csv_formatter_single_file() {
+        let output = CsvFormatter.format(&dummy_report());
+        let mut lines = output.lines();
+        assert_eq!(
+            lines.next(),
+            Some(
+                "section,repository,number,title,url,created_at,state,merged,merged_at,closed_at,additions,deletions,changed_files,occurred_at,commits"
+            )
+        );
+        assert_eq!(lines.next(), Some("repositories,owner/repo,,,,,,,,,,,,,5"));
+        assert_eq!(
+            lines.next(),
+            Some(
+                "issues,,42,Test Issue,http://example.com/issue,2025-03-09T00:00:00Z,open,,,,,,,,"
+            )
+        );
+        assert_eq!(
+            lines.next(),
+            Some(
+                "pull_requests,,101,Test PR,http://example.com/pr,2025-03-08T00:00:00Z,closed,false,,,120,15,4,,"
+            )
+        );
+        assert_eq!(lines.count(), 1);
+    }
+
+    #[test]
+    fn test_ics_formatter() {
+        let mut report = dummy_report();
+        if let Some(user) = report.activity.user.as_mut() {
+            let cc = &mut user.contributions_collection;
+            let day = &mut cc.contribution_calendar.weeks[0].contribution_days[0];
+            (day.date, day.contribution_count) = ("2025-03-08".into(), 3);
+            let pr = &mut cc.pull_request_contributions.nodes.as_mut().unwrap()[0].pull_request;
+            pr.merged = true;
+            pr.merged_at = Some("2025-03-10T15:30:00Z".into());
+            pr.title = format!("Fix parsing, again; {}", "very ".repeat(12));
+        }
+        let output = IcsFormatter.format(&report);
+        assert!(output.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(output.ends_with("END:VCALENDAR\r\n"));
+        assert!(output.lines().all(|line| line.len() <= 75));
+        assert!(output.contains(
+            "DTSTART;VALUE=DATE:20250308\r\nDTEND;VALUE=DATE:20250309\r\nSUMMARY:dummy: 3 contributions (1 pull request)\r\n"
+        ));
+        assert!(output.contains("DTSTART:20250310T153000Z\r\n"));
+        let unfolded = output.replace("\r\n ", "");
+        assert!(unfolded.contains("SUMMARY:Merged #101: Fix parsing\\, again\\; very very"));
+        assert!(unfolded.contains("URL:http://example.com/pr\r\n"));
+        assert_eq!(output.matches("BEGIN:VEVENT").count(), 2);
+    }
+
+    #[test]
+    fn test_csv_formatter_tables() {
+        let mut report = dummy_report();
+        if let Some(user) = report.activity.user.as_mut() {
+            user.contributions_collection
+                .issue_contributions
+                .nodes
+                .as_mut()
+                .unwrap()[0]
+                .issue
+                .title = "Fix \"quotes\", commas".into();
+        }
+        let tables = CsvFormatter.tables(&report);
+        let names: Vec<_> = tables.iter().map(|t| t.name).collect();
+        assert_eq!(
+            names,
+            ["repositories", "issues", "pull_requests", "reviews"]
+        );
+        assert!(
+            tables[1]
+                .to_csv()
+                .contains("42,\"Fix \"\"quotes\"\", commas\",http://example.com/issue")
+        );
+    }
+
+    #[test]
+    fn test_template_formatter() {
+        let formatter = TemplateFormatter::new(
+            "{{username}}: {{activity.totals.commits}} commits\n\
+             {{#each activity.issues}}- #{{number}} {{title}}\n{{/each}}",
+        )
+        .unwrap();
+        let mut report = dummy_report();
+        report.username = "<dummy>".into();
+        let output = formatter.render(&report).unwrap();
+        assert_eq!(output, "<dummy>: 10 commits\n- #42 Test Issue\n");
+    }
+
+    #[test]
+    fn test_template_formatter_rejects_invalid_template() {
+        assert!(TemplateFormatter::new("{{#each}}").is_err());
+    }
+
+    #[test]
+    fn test_slack_formatter() {
+        let output = SlackFormatter::default().format(&dummy_report());
+        let payload: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let blocks = payload["blocks"].as_array().unwrap();
+
+        assert_eq!(blocks[0]["type"], "header");
+        assert_eq!(
+            blocks[0]["text"]["text"],
+            "GitHub Activity Report for dummy"
+        );
+        assert_eq!(blocks[2]["fields"][0]["text"], "*Commits*\n10");
+        assert_eq!(blocks[3]["type"], "divider");
+        assert!(blocks.iter().any(|b| b["text"]["text"]
+            == "*Pull Requests*\n• <http://example.com/pr|#101 Test PR> (+120 -15)"));
+    }
+
+    #[test]
+    fn test_escape_slack() {
+        assert_eq!(escape_slack("<a> & <b>"), "&lt;a&gt; &amp; &lt;b&gt;");
+    }
+
+    #[test]
+    fn test_slack_list_section_truncates() {
+        let lines: Vec<String> = (0..12).map(|i| format!("PR {}", i)).collect();
+        let section = SlackFormatter::list_section("Pull Requests", &lines);
+        let text = section["text"]["text"].as_str().unwrap();
+        assert_eq!(text.lines().count(), 1 + SLACK_MAX_ITEMS + 1);
+        assert!(text.ends_with("• PR 9\n…and 2 more"));
+    }
+
+    #[test]
+    fn test_format_triage_section() {
+        let mut report = dummy_report();
+        report.triage = Some(TriageSummary {
+            repository: "owner/repo".into(),
+            labeled: vec![TriageAction {
+                number: 7,
+                title: "Needs label".into(),
+                url: "http://example.com/issues/7".into(),
+                occurred_at: "2025-03-05T00:00:00Z".into(),
+                detail: Some("bug".into()),
+            }],
+            ..Default::default()
+        });
+
+        let plain = PlainTextFormatter::default().format(&report);
+        assert!(plain.contains("Triage Activity in owner/repo:"));
+        assert!(plain.contains("  Issues Labeled: 1"));
+        assert!(plain.contains("  First Responses: 0"));
+        assert!(plain.contains("    - Issue #7: Needs label (bug)"));
+
+        let markdown = MarkdownFormatter::default().format(&report);
+        assert!(markdown.contains("## Triage Activity in owner/repo"));
+        assert!(markdown.contains("- **Issues Labeled:** 1"));
+        assert!(markdown.contains(
+            "| Issues Labeled | 7 | Needs label | http://example.com/issues/7 | bug | 2025-03-05T00:00:00Z |"
+        ));
+        // Triage activity is emphasized ahead of the regular sections.
+        assert!(
+            markdown.find("## Triage Activity").unwrap()
+                < markdown.find("## Contribution Calendar").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_format_statistics_section() {
+        let mut report = dummy_report();
+        assert!(
+            !PlainTextFormatter::default()
+                .format(&report)
+                .contains("Statistics")
+        );
+        report.statistics = Statistics::from_report(&report, Some(ForecastPeriod::Month));
+        let statistics = report.statistics.as_ref().unwrap();
+        let busiest = statistics.busiest_day.as_ref().unwrap();
+        let busiest = format!(
+            "{} ({} contributions)",
+            busiest.date, busiest.contribution_count
+        );
+
+        let plain = PlainTextFormatter::default().format(&report);
+        assert!(plain.contains("Statistics:\n  Longest Streak: "));
+        assert!(plain.contains(&format!("  Busiest Day: {}\n", busiest)));
+        assert!(plain.contains("  Forecast: on pace for ~"));
+        assert!(plain.contains(" this month (through 2025-03-31)\n"));
+
+        let markdown = MarkdownFormatter::default().format(&report);
+        assert!(markdown.contains(&format!(
+            "## Statistics\n\n- **Longest Streak:** {} day",
+            statistics.longest_streak
+        )));
+
+        let html = HtmlFormatter::default().format(&report);
+        assert!(html.contains(&format!(
+            "<tr><th>Busiest Day</th><td>{}</td></tr>",
+            busiest
+        )));
+
+        let color = ColorTerminalFormatter::default().format(&report);
+        assert!(color.contains(&format!("  Busiest Day {}\n", busiest)));
+
+        let slack = SlackFormatter::default().format(&report);
+        assert!(slack.contains("*Average per Day*"));
+    }
+
+    #[test]
+    fn test_group_by_repository() {
+        let mut report = dummy_report();
+        if let Some(user) = &mut report.activity.user {
+            let cc = &mut user.contributions_collection;
+            for node in cc.issue_contributions.nodes.iter_mut().flatten() {
+                node.issue.url = "https://github.com/octo/api/issues/1".into();
+            }
+            for node in cc.pull_request_contributions.nodes.iter_mut().flatten() {
+                node.pull_request.url = "https://github.com/octo/web/pull/2".into();
+            }
+            for node in cc
+                .pull_request_review_contributions
+                .nodes
+                .iter_mut()
+                .flatten()
+            {
+                node.pull_request_review.pull_request.url =
+                    "https://github.com/octo/api/pull/3".into();
+            }
+        }
+
+        let markdown = MarkdownFormatter {
+            group_by: GroupBy::Repository,
+            ..Default::default()
+        }
+        .format(&report);
+        let api = markdown
+            .find("## octo/api\n\n### Issue Contributions")
+            .unwrap();
+        let web = markdown
+            .find("## octo/web\n\n### Issue Contributions")
+            .unwrap();
+        assert!(api < web);
+        assert!(markdown[api..web].contains("https://github.com/octo/api/pull/3"));
+        assert!(!markdown[api..web].contains("https://github.com/octo/web/pull/2"));
+        assert!(markdown[web..].contains("https://github.com/octo/web/pull/2"));
+        assert!(!markdown.contains("\n## Issue Contributions"));
+
+        let html = HtmlFormatter {
+            group_by: GroupBy::Repository,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(html.contains("<h2>octo/api</h2>\n<h3>Issue Contributions</h3>\n<table>"));
+        assert_eq!(
+            html.matches("<h3>Pull Request Contributions</h3>").count(),
+            2
+        );
+
+        assert_eq!("repo".parse(), Ok(GroupBy::Repository));
+        assert!("org".parse::<GroupBy>().is_err());
+    }
+
+    #[test]
+    fn test_category_breakdown_and_grouping() {
+        let mut report = dummy_report();
+        assert!(
+            !MarkdownFormatter::default()
+                .format(&report)
+                .contains("Category Breakdown")
+        );
+        let cc = &report
+            .activity
+            .user
+            .as_ref()
+            .unwrap()
+            .contributions_collection;
+        let pr_url = cc.pull_request_contributions.nodes.as_ref().unwrap()[0]
+            .pull_request
+            .url
+            .clone();
+        report.categories.insert(pr_url.clone(), "feature".into());
+
+        let markdown = MarkdownFormatter {
+            group_by: GroupBy::Category,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(markdown.contains(
+            "## Category Breakdown\n\n| Category | Issues | Pull Requests | Reviews |\n"
+        ));
+        assert!(markdown.contains("| feature | 0 | 1 | 0 |\n"));
+        let feature = markdown.find("## feature\n\n### ").unwrap();
+        let uncategorized = markdown.find("## uncategorized\n\n### ").unwrap();
+        assert!(feature < uncategorized);
+        assert!(markdown[feature..uncategorized].contains(&pr_url));
+
+        let plain = PlainTextFormatter::default().format(&report);
+        assert!(
+            plain
+                .contains("Category Breakdown:\n  feature: 0 issues, 1 pull requests, 0 reviews\n")
+        );
+        let html = HtmlFormatter {
+            group_by: GroupBy::Category,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(html.contains("<h2>Category Breakdown</h2>"));
+        assert!(html.contains("<h2>feature</h2>\n<h3>"));
+        assert_eq!("category".parse(), Ok(GroupBy::Category));
+    }
+
+    #[test]
+    fn test_group_by_month_rollup() {
+        let report = dummy_report();
+        let markdown = MarkdownFormatter {
+            group_by: GroupBy::Month,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(markdown.contains(
+            "## Monthly Rollup\n\n| Period | Contributions | Issues | Pull Requests | Reviews |\n"
+        ));
+        assert!(
+            markdown.contains("| March 2025 | 1 | 1 | 1 | 1 |\n| **Total** | 1 | 1 | 1 | 1 |\n")
+        );
+        assert!(!markdown.contains("## Issue Contributions"));
+
+        let html = HtmlFormatter {
+            group_by: GroupBy::Month,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(html.contains("<h2>Monthly Rollup</h2>"));
+        assert!(html.contains("<tr><th>Total</th><td>1</td><td>1</td><td>1</td><td>1</td></tr>"));
+        assert_eq!("week".parse(), Ok(GroupBy::Week));
+    }
+
+    #[test]
+    fn test_totals_use_locale_grouping() {
+        let mut report = dummy_report();
+        if let Some(user) = &mut report.activity.user {
+            user.contributions_collection.total_commit_contributions = 12345;
+        }
+        let de: NumberLocale = "de".parse().unwrap();
+
+        let plain = PlainTextFormatter::default().format(&report);
+        assert!(plain.contains("Total Commit Contributions: 12,345\n"));
+        let markdown = MarkdownFormatter {
+            locale: de,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(markdown.contains("- **Total Commit Contributions:** 12.345\n"));
+        let html = HtmlFormatter {
+            locale: de,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(html.contains("<li><strong>12.345</strong>Commit Contributions</li>"));
+        // CSV stays machine-readable.
+        assert!(!CsvFormatter.format(&report).contains("12,345"));
+    }
+
+    #[test]
+    fn test_contribution_mix() {
+        let report = dummy_report();
+        let cc = &report
+            .activity
+            .user
+            .as_ref()
+            .unwrap()
+            .contributions_collection;
+        // 10 commits, 5 issues, 3 pull requests, 2 reviews.
+        let mix = contribution_mix(cc).unwrap();
+        assert_eq!(
+            mix_line(&mix),
+            "50% commits, 25% issues, 15% pull requests, 10% reviews"
+        );
+        assert!(
+            PlainTextFormatter::default()
+                .format(&report)
+                .contains("Contribution Mix: 50% commits, 25% issues")
+        );
+
+        let html = HtmlFormatter {
+            mix_chart: true,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(html.contains("conic-gradient(#216e39 0% 50%, #40c463 50% 75%"));
+        assert!(
+            !HtmlFormatter::default()
+                .format(&report)
+                .contains("conic-gradient")
+        );
+    }
+
+    #[test]
+    fn test_contribution_mix_rounds_to_100() {
+        let mut report = dummy_report();
+        let cc = &mut report
+            .activity
+            .user
+            .as_mut()
+            .unwrap()
+            .contributions_collection;
+        cc.total_commit_contributions = 1;
+        cc.total_issue_contributions = 1;
+        cc.total_pull_request_contributions = 1;
+        cc.total_pull_request_review_contributions = 0;
+        let mix = contribution_mix(cc).unwrap();
+        assert_eq!(mix.map(|(_, p)| p), [34, 33, 33, 0]);
+
+        cc.total_commit_contributions = 0;
+        cc.total_issue_contributions = 0;
+        cc.total_pull_request_contributions = 0;
+        assert!(contribution_mix(cc).is_none());
+    }
+
+    #[test]
+    fn test_narrative_tone() {
+        let report = dummy_report();
+        let plain = PlainTextFormatter {
+            tone: Tone::Narrative,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(plain.contains(
+            "\nSummary: Made 20 contributions: 10 commits, 5 issues, 3 pull requests and 2 reviews.\n\nCommits: Pushed 10 commits to owner/repo.\n"
+        ));
+        assert!(
+            plain.contains(
+                "\nPull Requests: Opened 1 pull request, changing 135 lines (+120 -15).\n"
+            )
+        );
+        assert!(!plain.contains("Repository Contributions:"));
+
+        let markdown = MarkdownFormatter {
+            tone: Tone::Narrative,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(markdown.ends_with("## Reviews\n\nSubmitted 1 review, requesting changes on 1.\n"));
+        assert!(!markdown.contains('|'));
+    }
+
+    #[test]
+    fn test_calendar_granularity() {
+        let report = dummy_report();
+        let monthly = PlainTextFormatter {
+            calendar: CalendarGranularity::Monthly,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(monthly.contains(
+            "Contribution Calendar:\n  Total Contributions: 20\n    March 2025: 1 contributions\n"
+        ));
+        assert!(!monthly.contains("(Tuesday)"));
+        let german = PlainTextFormatter {
+            calendar: CalendarGranularity::Monthly,
+            locale: "de".parse().unwrap(),
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(german.contains("    März 2025: 1 contributions\n"));
+
+        let off = MarkdownFormatter {
+            calendar: CalendarGranularity::Off,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(!off.contains("## Contribution Calendar"));
+
+        let weekly = HtmlFormatter {
+            calendar: CalendarGranularity::Weekly,
+            ..Default::default()
+        }
+        .format(&report);
+        assert!(weekly.contains("<tr><td>Week of 2025-03-11T00:00:00Z</td><td>1</td></tr>"));
+        assert!(!weekly.contains("class=\"calendar\""));
+    }
 }
@@ -0,0 +1,78 @@
+//! Organization access detection: tokens of contractors and other outside collaborators often
+//! can't read an organization's data, because the organization restricts OAuth app or token
+//! access, enforces an IP allow list, or the token lacks the `read:org` scope. GitHub refuses
+//! such queries with GraphQL errors rather than an HTTP status, so they are recognized by
+//! their messages.
+
+use graphql_client::Error as GraphQLError;
+use std::fmt;
+
+/// Parts of GitHub's messages for queries refused for lack of organization access, compared
+/// case-insensitively.
+const ACCESS_MESSAGES: [&str; 5] = [
+    "not accessible by",
+    "access restrictions",
+    "ip allow list",
+    "has not been approved",
+    "read:org",
+];
+
+/// An organization-scoped query was refused because the token can't read the organization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrganizationAccessDenied {
+    /// GitHub's explanation.
+    pub message: String,
+}
+
+impl fmt::Display for OrganizationAccessDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The token can't read the organization: {}", self.message)
+    }
+}
+
+impl std::error::Error for OrganizationAccessDenied {}
+
+/// The access error among GraphQL `errors`, if any: one with a `FORBIDDEN` code or a message
+/// GitHub sends when it refuses organization data.
+pub fn access_error(errors: &[GraphQLError]) -> Option<OrganizationAccessDenied> {
+    errors
+        .iter()
+        .find(|error| {
+            let message = error.message.to_lowercase();
+            ACCESS_MESSAGES.iter().any(|part| message.contains(part))
+                || error
+                    .extensions
+                    .as_ref()
+                    .and_then(|extensions| extensions.get("code"))
+                    .and_then(|code| code.as_str())
+                    == Some("FORBIDDEN")
+        })
+        .map(|error| OrganizationAccessDenied {
+            message: error.message.clone(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_access_error() {
+        let errors = |value| serde_json::from_value::<Vec<GraphQLError>>(value).unwrap();
+        let restricted = errors(json!([{
+            "type": "FORBIDDEN",
+            "message": "Although you appear to have the correct authorization credentials, the `example` organization has enabled OAuth App access restrictions, meaning that data access to third-parties is limited."
+        }]));
+        let error = access_error(&restricted).unwrap();
+        assert!(error.message.contains("`example` organization"));
+
+        let forbidden =
+            errors(json!([{ "message": "Nope", "extensions": { "code": "FORBIDDEN" } }]));
+        assert!(access_error(&forbidden).is_some());
+
+        let other =
+            errors(json!([{ "message": "Something went wrong while executing your query." }]));
+        assert_eq!(access_error(&other), None);
+    }
+}
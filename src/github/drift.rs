@@ -0,0 +1,253 @@
+//! Schema drift check: validates the bundled GraphQL queries against a GitHub schema, so a
+//! refreshed schema reports the fields the generated client still selects but GitHub removed.
+
+use anyhow::{Context, Result, anyhow};
+use graphql_parser::query::{self, Definition, OperationDefinition, Selection, TypeCondition};
+use graphql_parser::schema::{self, Type, TypeDefinition};
+use reqwest::header::USER_AGENT;
+use std::collections::HashMap;
+use std::fmt;
+
+/// GitHub's published GraphQL schema, in SDL.
+pub const SCHEMA_URL: &str = "https://docs.github.com/public/fpt/schema.docs.graphql";
+
+/// The queries compiled into the client, by file name.
+pub const BUNDLED_QUERIES: &[(&str, &str)] = &[
+    ("github.graphql", include_str!("github.graphql")),
+    ("issues_page.graphql", include_str!("issues_page.graphql")),
+    ("prs_page.graphql", include_str!("prs_page.graphql")),
+    ("reviews_page.graphql", include_str!("reviews_page.graphql")),
+    ("triage.graphql", include_str!("triage.graphql")),
+    ("timeline.graphql", include_str!("timeline.graphql")),
+    ("reverts.graphql", include_str!("reverts.graphql")),
+    ("reviewed.graphql", include_str!("reviewed.graphql")),
+    ("releases.graphql", include_str!("releases.graphql")),
+    ("team.graphql", include_str!("team.graphql")),
+    ("organization.graphql", include_str!("organization.graphql")),
+    ("coverage.graphql", include_str!("coverage.graphql")),
+];
+
+/// Download a schema in SDL, e.g. GitHub's published one at [`SCHEMA_URL`].
+pub async fn fetch_schema(url: &str) -> Result<String> {
+    reqwest::Client::new()
+        .get(url)
+        .header(USER_AGENT, "github-activity-rs")
+        .send()
+        .await
+        .context("Failed to send schema request")?
+        .error_for_status()
+        .context("Failed to download schema")?
+        .text()
+        .await
+        .context("Failed to read schema response")
+}
+
+/// A selection of a bundled query the schema no longer has.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingField {
+    /// File name of the query making the selection.
+    pub query: String,
+    /// The type the field was selected on.
+    pub type_name: String,
+    /// The selected field, or the type itself when `None` (e.g. a fragment on a removed type).
+    pub field: Option<String>,
+}
+
+impl fmt::Display for MissingField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(
+                f,
+                "{}: {}.{} no longer exists",
+                self.query, self.type_name, field
+            ),
+            None => write!(
+                f,
+                "{}: type {} no longer exists",
+                self.query, self.type_name
+            ),
+        }
+    }
+}
+
+/// Field name to the name of its (unwrapped) return type, per object or interface type.
+type Fields = HashMap<String, HashMap<String, String>>;
+
+/// Check every bundled query against the schema, returning the selections it lacks.
+pub fn check_bundled_queries(schema_sdl: &str) -> Result<Vec<MissingField>> {
+    let (root, fields) = schema_fields(schema_sdl)?;
+    let mut missing = Vec::new();
+    for (name, source) in BUNDLED_QUERIES {
+        missing.extend(check_query(name, source, &root, &fields)?);
+    }
+    Ok(missing)
+}
+
+/// The query root type name and the fields of each type in the schema.
+fn schema_fields(schema_sdl: &str) -> Result<(String, Fields)> {
+    let document = schema::parse_schema::<String>(schema_sdl)
+        .map_err(|err| anyhow!("{}", err))
+        .context("Failed to parse GraphQL schema")?;
+    let mut root = "Query".to_string();
+    let mut fields = Fields::new();
+    for definition in document.definitions {
+        match definition {
+            schema::Definition::SchemaDefinition(schema) => {
+                if let Some(query) = schema.query {
+                    root = query;
+                }
+            }
+            schema::Definition::TypeDefinition(TypeDefinition::Object(object)) => {
+                fields.insert(object.name, field_types(object.fields));
+            }
+            schema::Definition::TypeDefinition(TypeDefinition::Interface(interface)) => {
+                fields.insert(interface.name, field_types(interface.fields));
+            }
+            // Unions and scalars have no fields of their own; `__typename` is always valid.
+            schema::Definition::TypeDefinition(other) => {
+                fields.entry(type_definition_name(&other)).or_default();
+            }
+            _ => {}
+        }
+    }
+    Ok((root, fields))
+}
+
+fn field_types(fields: Vec<schema::Field<String>>) -> HashMap<String, String> {
+    fields
+        .into_iter()
+        .map(|field| (field.name, named_type(&field.field_type).to_string()))
+        .collect()
+}
+
+fn named_type<'a>(field_type: &'a Type<String>) -> &'a str {
+    match field_type {
+        Type::NamedType(name) => name,
+        Type::ListType(inner) | Type::NonNullType(inner) => named_type(inner),
+    }
+}
+
+fn type_definition_name(definition: &TypeDefinition<String>) -> String {
+    match definition {
+        TypeDefinition::Scalar(t) => t.name.clone(),
+        TypeDefinition::Object(t) => t.name.clone(),
+        TypeDefinition::Interface(t) => t.name.clone(),
+        TypeDefinition::Union(t) => t.name.clone(),
+        TypeDefinition::Enum(t) => t.name.clone(),
+        TypeDefinition::InputObject(t) => t.name.clone(),
+    }
+}
+
+fn check_query(name: &str, source: &str, root: &str, fields: &Fields) -> Result<Vec<MissingField>> {
+    let document = query::parse_query::<String>(source)
+        .map_err(|err| anyhow!("{}", err))
+        .with_context(|| format!("Failed to parse query {}", name))?;
+    let fragments: HashMap<&str, &query::FragmentDefinition<String>> = document
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Fragment(fragment) => Some((fragment.name.as_str(), fragment)),
+            Definition::Operation(_) => None,
+        })
+        .collect();
+    let mut walker = Walker {
+        query: name,
+        fields,
+        fragments,
+        missing: Vec::new(),
+    };
+    for definition in &document.definitions {
+        match definition {
+            Definition::Operation(OperationDefinition::Query(query)) => {
+                walker.selection_set(root, &query.selection_set)
+            }
+            Definition::Operation(OperationDefinition::SelectionSet(set)) => {
+                walker.selection_set(root, set)
+            }
+            // The client only runs queries; fragments are checked where they are spread.
+            _ => {}
+        }
+    }
+    Ok(walker.missing)
+}
+
+/// Walks the selections of one query document, collecting what the schema lacks.
+struct Walker<'a> {
+    query: &'a str,
+    fields: &'a Fields,
+    fragments: HashMap<&'a str, &'a query::FragmentDefinition<'a, String>>,
+    missing: Vec<MissingField>,
+}
+
+impl Walker<'_> {
+    fn selection_set(&mut self, type_name: &str, set: &query::SelectionSet<String>) {
+        let Some(type_fields) = self.fields.get(type_name) else {
+            self.report(type_name, None);
+            return;
+        };
+        for selection in &set.items {
+            match selection {
+                Selection::Field(field) if field.name == "__typename" => {}
+                Selection::Field(field) => match type_fields.get(&field.name) {
+                    Some(field_type) if !field.selection_set.items.is_empty() => {
+                        self.selection_set(field_type, &field.selection_set)
+                    }
+                    Some(_) => {}
+                    None => self.report(type_name, Some(&field.name)),
+                },
+                Selection::InlineFragment(fragment) => {
+                    let target = match &fragment.type_condition {
+                        Some(TypeCondition::On(name)) => name.as_str(),
+                        None => type_name,
+                    };
+                    self.selection_set(target, &fragment.selection_set);
+                }
+                Selection::FragmentSpread(spread) => {
+                    if let Some(fragment) = self.fragments.get(spread.fragment_name.as_str()) {
+                        let TypeCondition::On(target) = &fragment.type_condition;
+                        self.selection_set(target, &fragment.selection_set);
+                    }
+                }
+            }
+        }
+    }
+
+    fn report(&mut self, type_name: &str, field: Option<&str>) {
+        let missing = MissingField {
+            query: self.query.to_string(),
+            type_name: type_name.to_string(),
+            field: field.map(str::to_string),
+        };
+        if !self.missing.contains(&missing) {
+            self.missing.push(missing);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_bundled_queries() {
+        let bundled = include_str!("schema.graphql");
+        assert_eq!(check_bundled_queries(bundled).unwrap(), vec![]);
+
+        let drifted = bundled.replace("  mergeCommit: Commit\n", "");
+        let missing = check_bundled_queries(&drifted).unwrap();
+        assert!(!missing.is_empty());
+        assert!(missing.iter().all(|m| m.type_name == "PullRequest"));
+        assert!(
+            missing.iter().any(
+                |m| m.to_string() == "github.graphql: PullRequest.mergeCommit no longer exists"
+            )
+        );
+
+        let drifted = bundled.replace("type Release {", "type GitHubRelease {");
+        let missing = check_bundled_queries(&drifted).unwrap();
+        assert_eq!(
+            missing.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["releases.graphql: type Release no longer exists"]
+        );
+    }
+}
@@ -1,36 +1,442 @@
+//! GitHub API module: the GraphQL queries and the client that runs them, paginating and
+//! merging results, plus the few REST calls GraphQL has no equivalent for.
+
+mod access;
+pub mod drift;
+mod progress;
+mod rate_limit;
+mod sso;
 #[cfg(test)]
 mod tests;
 
+pub use access::OrganizationAccessDenied;
+pub use progress::{ContributionCallback, ProgressCallback, ProgressEvent};
+pub use rate_limit::{RateLimitStatus, RateLimited};
+pub use sso::SsoAuthorizationRequired;
+
+use crate::identity::{AccountEmail, UnlinkedCommit};
+use crate::jsonl::{Contribution, ContributionLine};
+use crate::merge::merge_activity;
 use anyhow::{Context, Result, bail};
-use chrono::{DateTime as ChronoDateTime, Utc};
+use chrono::{DateTime as ChronoDateTime, Duration, Utc};
 use futures::join;
-use graphql_client::{GraphQLQuery, Response};
-use log::{debug, error, info};
-use reqwest::Client;
-use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue, USER_AGENT};
+use graphql_client::{GraphQLQuery, QueryBody, Response};
+use log::{debug, error, info, warn};
+use reqwest::header::{ACCEPT, AUTHORIZATION, DATE, HeaderMap, HeaderValue, USER_AGENT};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 // GraphQL DateTime scalar type.
 type DateTime = String;
+// GraphQL GitObjectID scalar type (a commit SHA).
+type GitObjectID = String;
 
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "src/github/schema.graphql",
     query_path = "src/github/github.graphql",
-    response_derives = "Debug, Default, serde::Serialize, Clone",
+    response_derives = "Debug, Default, serde::Serialize, Clone, PartialEq",
     variables_derives = "Debug"
 )]
 pub struct UserActivity;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/schema.graphql",
+    query_path = "src/github/issues_page.graphql",
+    response_derives = "Debug",
+    variables_derives = "Debug"
+)]
+pub struct IssuesPage;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/schema.graphql",
+    query_path = "src/github/prs_page.graphql",
+    response_derives = "Debug",
+    variables_derives = "Debug"
+)]
+pub struct PrsPage;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/schema.graphql",
+    query_path = "src/github/reviews_page.graphql",
+    response_derives = "Debug",
+    variables_derives = "Debug"
+)]
+pub struct ReviewsPage;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/schema.graphql",
+    query_path = "src/github/triage.graphql",
+    response_derives = "Debug, Serialize, Clone, PartialEq",
+    variables_derives = "Debug"
+)]
+pub struct RepoTriage;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/schema.graphql",
+    query_path = "src/github/timeline.graphql",
+    response_derives = "Debug, Serialize, Clone, PartialEq",
+    variables_derives = "Debug"
+)]
+pub struct IssueTimelines;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/schema.graphql",
+    query_path = "src/github/reverts.graphql",
+    response_derives = "Debug, Serialize, Clone, PartialEq",
+    variables_derives = "Debug"
+)]
+pub struct RevertPullRequests;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/schema.graphql",
+    query_path = "src/github/reviewed.graphql",
+    response_derives = "Debug, Serialize, Clone, PartialEq",
+    variables_derives = "Debug"
+)]
+pub struct ReviewedPullRequests;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/schema.graphql",
+    query_path = "src/github/releases.graphql",
+    response_derives = "Debug, Serialize, Clone, PartialEq",
+    variables_derives = "Debug"
+)]
+pub struct RepoReleases;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/schema.graphql",
+    query_path = "src/github/forks.graphql",
+    response_derives = "Debug, Serialize, Clone, PartialEq",
+    variables_derives = "Debug"
+)]
+pub struct RepoFork;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/schema.graphql",
+    query_path = "src/github/team.graphql",
+    response_derives = "Debug, Serialize, Clone, PartialEq",
+    variables_derives = "Debug"
+)]
+pub struct TeamMembers;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/schema.graphql",
+    query_path = "src/github/organization.graphql",
+    response_derives = "Debug",
+    variables_derives = "Debug"
+)]
+pub struct OrganizationId;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/github/schema.graphql",
+    query_path = "src/github/coverage.graphql",
+    response_derives = "Debug",
+    variables_derives = "Debug"
+)]
+pub struct SearchCount;
+
+/// Page size of the REST pull request files endpoint; it lists at most 3000 files per pull
+/// request.
+const FILES_PER_PAGE: usize = 100;
+
+/// Page size of the REST commits endpoint.
+const COMMITS_PER_PAGE: usize = 100;
+
+/// How many pages of a repository's commits are checked for unattributed emails.
+const COMMIT_PAGES: usize = 10;
+
+/// Maximum number of node IDs GitHub accepts in a single `nodes` lookup.
+const MAX_NODE_IDS: usize = 100;
+
+/// How many contributions of each kind are requested per page (1 to 100).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageSizes {
+    /// Page size of issue contributions.
+    pub issues: i64,
+    /// Page size of pull request contributions.
+    pub prs: i64,
+    /// Page size of pull request review contributions.
+    pub reviews: i64,
+}
+
+impl Default for PageSizes {
+    fn default() -> Self {
+        Self {
+            issues: 10,
+            prs: 10,
+            reviews: 10,
+        }
+    }
+}
+
+/// The GraphQL endpoint of github.com.
+pub const DEFAULT_API_URL: &str = "https://api.github.com/graphql";
+
+/// The REST API root matching a GraphQL endpoint: `https://api.github.com` for github.com and
+/// `https://HOST/api/v3` for GitHub Enterprise Server (`https://HOST/api/graphql`).
+fn rest_url_for(api_url: &str) -> String {
+    let base = api_url.trim_end_matches('/');
+    match base.strip_suffix("/api/graphql") {
+        Some(host) => format!("{}/api/v3", host),
+        None => base.strip_suffix("/graphql").unwrap_or(base).to_string(),
+    }
+}
+
+/// The `html_url` of `created`, a `what` created with the REST API.
+fn html_url(created: &Value, what: &str) -> Result<String> {
+    created["html_url"]
+        .as_str()
+        .map(str::to_string)
+        .with_context(|| format!("The {} response has no html_url", what))
+}
+
+/// A REST request publishing a report, built apart from sending it so it can be shown
+/// instead of sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestRequest {
+    /// The HTTP method.
+    pub method: reqwest::Method,
+    /// The full URL.
+    pub url: String,
+    /// The JSON body.
+    pub body: Value,
+}
+
+/// A GitHub API client fetching the activity of one user over one time range.
 pub struct GithubClient {
     client: Client,
+    graphql_url: String,
+    rest_url: String,
     username: String,
     start_date: ChronoDateTime<Utc>,
     end_date: ChronoDateTime<Utc>,
+    wait_on_rate_limit: bool,
+    persisted_queries: bool,
+    requests: Arc<AtomicUsize>,
+    clock_checked: Arc<AtomicBool>,
+    page_sizes: PageSizes,
+    review_bodies: bool,
+    organization_id: Option<String>,
+    progress: Option<ProgressCallback>,
+    contributions: Option<ContributionCallback>,
+}
+
+/// How many times a GraphQL request that hits a rate limit is sent in all, waiting for the
+/// limit to lift between attempts.
+const MAX_RATE_LIMIT_ATTEMPTS: usize = 4;
+
+/// How far the local clock may be off GitHub's before a warning is logged.
+const MAX_CLOCK_SKEW_SECS: i64 = 300;
+
+/// How far the local clock (`now`) is ahead of the server's, given the `Date` header of a
+/// response, if that is more than [`MAX_CLOCK_SKEW_SECS`] either way.
+fn clock_skew(date_header: &str, now: ChronoDateTime<Utc>) -> Option<Duration> {
+    let server_time = ChronoDateTime::parse_from_rfc2822(date_header).ok()?;
+    let skew = now - server_time.with_timezone(&Utc);
+    (skew.num_seconds().abs() > MAX_CLOCK_SKEW_SECS).then_some(skew)
+}
+
+/// A clock skew as e.g. `2h 5m`, ignoring its direction.
+fn humanize_skew(skew: Duration) -> String {
+    let minutes = skew.num_minutes().abs();
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+/// The `extensions` entry identifying `query` as an automatic persisted query: its SHA-256
+/// hash, which caching gateways use in place of the query text.
+fn persisted_query_extensions(query: &str) -> Value {
+    json!({
+        "persistedQuery": {
+            "version": 1,
+            "sha256Hash": format!("{:x}", Sha256::digest(query)),
+        }
+    })
+}
+
+/// Whether a GraphQL response reports that the server doesn't know, or doesn't accept,
+/// persisted queries.
+fn is_persisted_query_miss(body: &Value) -> bool {
+    body["errors"].as_array().is_some_and(|errors| {
+        errors.iter().any(|error| {
+            matches!(
+                error["extensions"]["code"].as_str(),
+                Some("PERSISTED_QUERY_NOT_FOUND" | "PERSISTED_QUERY_NOT_SUPPORTED")
+            ) || matches!(
+                error["message"].as_str(),
+                Some("PersistedQueryNotFound" | "PersistedQueryNotSupported")
+            )
+        })
+    })
+}
+
+/// A page of a connection that came with another query, e.g. the first page of each
+/// contribution connection in the activity query.
+struct FirstPage<T> {
+    nodes: Vec<T>,
+    end_cursor: Option<String>,
+    has_next_page: bool,
+    total: Option<i64>,
+}
+
+/// Converts the nodes of a page query into the matching nodes of the activity query.
+fn activity_nodes<T: Into<U>, U>(nodes: Option<Vec<T>>) -> Option<Vec<U>> {
+    nodes.map(|nodes| nodes.into_iter().map(Into::into).collect())
+}
+
+macro_rules! impl_from_label_names {
+    ($($query:ident),*) => {
+        $(
+            impl From<$query::LabelNames> for user_activity::LabelNames {
+                fn from(labels: $query::LabelNames) -> Self {
+                    Self {
+                        nodes: labels.nodes.map(|nodes| {
+                            nodes
+                                .into_iter()
+                                .map(|node| {
+                                    node.map(|node| user_activity::LabelNamesNodes {
+                                        name: node.name,
+                                    })
+                                })
+                                .collect()
+                        }),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_label_names!(issues_page, prs_page, reviews_page);
+
+impl From<issues_page::IssuesPageUserContributionsCollectionIssueContributionsNodes>
+    for user_activity::UserActivityUserContributionsCollectionIssueContributionsNodes
+{
+    fn from(
+        node: issues_page::IssuesPageUserContributionsCollectionIssueContributionsNodes,
+    ) -> Self {
+        let issue = node.issue;
+        Self {
+            issue:
+                user_activity::UserActivityUserContributionsCollectionIssueContributionsNodesIssue {
+                    id: issue.id,
+                    number: issue.number,
+                    title: issue.title,
+                    url: issue.url,
+                    created_at: issue.created_at,
+                    state: issue.state,
+                    closed_at: issue.closed_at,
+                    labels: issue.labels.map(Into::into),
+                },
+        }
+    }
+}
+
+impl From<prs_page::PrsPageUserContributionsCollectionPullRequestContributionsNodes>
+    for user_activity::UserActivityUserContributionsCollectionPullRequestContributionsNodes
+{
+    fn from(
+        node: prs_page::PrsPageUserContributionsCollectionPullRequestContributionsNodes,
+    ) -> Self {
+        let pr = node.pull_request;
+        Self {
+            pull_request: user_activity::UserActivityUserContributionsCollectionPullRequestContributionsNodesPullRequest {
+                number: pr.number,
+                title: pr.title,
+                url: pr.url,
+                created_at: pr.created_at,
+                state: pr.state,
+                merged: pr.merged,
+                merged_at: pr.merged_at,
+                merge_commit: pr.merge_commit.map(|commit| {
+                    user_activity::UserActivityUserContributionsCollectionPullRequestContributionsNodesPullRequestMergeCommit {
+                        oid: commit.oid,
+                    }
+                }),
+                closed_at: pr.closed_at,
+                additions: pr.additions,
+                deletions: pr.deletions,
+                changed_files: pr.changed_files,
+                labels: pr.labels.map(Into::into),
+            },
+        }
+    }
+}
+
+impl From<reviews_page::ReviewsPageUserContributionsCollectionPullRequestReviewContributionsNodes>
+    for user_activity::UserActivityUserContributionsCollectionPullRequestReviewContributionsNodes
+{
+    fn from(
+        node: reviews_page::ReviewsPageUserContributionsCollectionPullRequestReviewContributionsNodes,
+    ) -> Self {
+        let review = node.pull_request_review;
+        let pr = review.pull_request;
+        Self {
+            pull_request_review: user_activity::UserActivityUserContributionsCollectionPullRequestReviewContributionsNodesPullRequestReview {
+                pull_request: user_activity::UserActivityUserContributionsCollectionPullRequestReviewContributionsNodesPullRequestReviewPullRequest {
+                    number: pr.number,
+                    title: pr.title,
+                    url: pr.url,
+                    labels: pr.labels.map(Into::into),
+                },
+                state: review.state,
+                body: review.body,
+            },
+            occurred_at: node.occurred_at,
+        }
+    }
+}
+
+/// The longest time range a single `contributionsCollection` may span.
+const MAX_RANGE_DAYS: i64 = 365;
+
+/// Splits `start..end` into consecutive chunks of at most [`MAX_RANGE_DAYS`], each ending a
+/// second before the next begins so no contribution is fetched twice.
+fn range_chunks(
+    start: ChronoDateTime<Utc>,
+    end: ChronoDateTime<Utc>,
+) -> Vec<(ChronoDateTime<Utc>, ChronoDateTime<Utc>)> {
+    let mut chunks = Vec::new();
+    let mut chunk_start = start;
+    loop {
+        let chunk_end = chunk_start + Duration::days(MAX_RANGE_DAYS);
+        if chunk_end >= end {
+            chunks.push((chunk_start, end));
+            return chunks;
+        }
+        chunks.push((chunk_start, chunk_end - Duration::seconds(1)));
+        chunk_start = chunk_end;
+    }
 }
 
 impl GithubClient {
+    /// Creates a client authenticated with `github_token` for `username`'s activity between
+    /// `start_date` and `end_date`, talking to the GraphQL endpoint `api_url` (see
+    /// [`DEFAULT_API_URL`]; GitHub Enterprise Server uses `https://HOST/api/graphql`).
     pub fn new(
         github_token: String,
+        api_url: String,
         username: String,
         start_date: ChronoDateTime<Utc>,
         end_date: ChronoDateTime<Utc>,
@@ -53,57 +459,260 @@ impl GithubClient {
 
         Ok(Self {
             client,
+            rest_url: rest_url_for(&api_url),
+            graphql_url: api_url,
             username,
             start_date,
             end_date,
+            wait_on_rate_limit: true,
+            persisted_queries: false,
+            requests: Arc::new(AtomicUsize::new(0)),
+            clock_checked: Arc::new(AtomicBool::new(false)),
+            page_sizes: PageSizes::default(),
+            review_bodies: false,
+            organization_id: None,
+            progress: None,
+            contributions: None,
         })
     }
 
-    /// Main fetch_activity function that fetches base data and concurrently fetches paginated nodes.
-    pub async fn fetch_activity(&self) -> Result<user_activity::ResponseData> {
-        let first = 10;
+    /// Sets whether to sleep until the GraphQL rate limit resets when it runs out mid-pagination,
+    /// and to retry requests that hit a rate limit once it lifts (the default), or to fail right
+    /// away.
+    pub fn wait_on_rate_limit(mut self, wait: bool) -> Self {
+        self.wait_on_rate_limit = wait;
+        self
+    }
+
+    /// Sets whether to send GraphQL operations as GET requests carrying a persisted-query hash
+    /// instead of the query text, so caching GraphQL gateways can serve them. Operations the
+    /// endpoint doesn't know or accept are re-sent as a regular POST.
+    pub fn persisted_queries(mut self, enabled: bool) -> Self {
+        self.persisted_queries = enabled;
+        self
+    }
+
+    /// Sets how many issue, pull request, and review contributions are requested per page,
+    /// so each connection can be paged through at a size matching how many nodes it has.
+    pub fn page_sizes(mut self, page_sizes: PageSizes) -> Self {
+        self.page_sizes = page_sizes;
+        self
+    }
+
+    /// Sets whether the bodies of pull request reviews are fetched along with their state.
+    pub fn review_bodies(mut self, enabled: bool) -> Self {
+        self.review_bodies = enabled;
+        self
+    }
+
+    /// Whether the bodies of pull request reviews are fetched.
+    pub fn fetches_review_bodies(&self) -> bool {
+        self.review_bodies
+    }
+
+    /// Sets the node ID of the organization contributions are scoped to (see
+    /// [`fetch_organization_id`](Self::fetch_organization_id)), so GitHub counts and lists only
+    /// contributions to that organization, totals included.
+    pub fn organization_scope(mut self, organization_id: Option<String>) -> Self {
+        self.organization_id = organization_id;
+        self
+    }
+
+    /// Sets the callback every fetched page of a paginated connection is reported to.
+    pub fn on_progress(mut self, callback: Option<ProgressCallback>) -> Self {
+        self.progress = callback;
+        self
+    }
+
+    /// Sets the callback every fetched contribution node is passed to as soon as its page
+    /// arrives, before the whole activity is fetched. Repositories with commits are passed
+    /// once the activity is complete, since ranges longer than a year sum them across chunks.
+    pub fn on_contributions(mut self, callback: Option<ContributionCallback>) -> Self {
+        self.contributions = callback;
+        self
+    }
+
+    /// Passes `node` to the contribution callback, if one is set.
+    fn emit(&self, node: impl Into<Contribution>) {
+        if let Some(callback) = &self.contributions {
+            callback(&ContributionLine {
+                username: &self.username,
+                contribution: node.into(),
+            });
+        }
+    }
+
+    /// The node ID of the organization contributions are scoped to, if any.
+    pub fn organization_id(&self) -> Option<&str> {
+        self.organization_id.as_deref()
+    }
+
+    /// Creates a client for another user and time range that shares this client's HTTP
+    /// connection pool, credentials, endpoint, and request count.
+    pub fn for_user(
+        &self,
+        username: String,
+        start_date: ChronoDateTime<Utc>,
+        end_date: ChronoDateTime<Utc>,
+    ) -> Self {
+        Self {
+            client: self.client.clone(),
+            graphql_url: self.graphql_url.clone(),
+            rest_url: self.rest_url.clone(),
+            username,
+            start_date,
+            end_date,
+            wait_on_rate_limit: self.wait_on_rate_limit,
+            persisted_queries: self.persisted_queries,
+            requests: self.requests.clone(),
+            clock_checked: self.clock_checked.clone(),
+            page_sizes: self.page_sizes,
+            review_bodies: self.review_bodies,
+            organization_id: self.organization_id.clone(),
+            progress: self.progress.clone(),
+            contributions: self.contributions.clone(),
+        }
+    }
+
+    /// How many HTTP requests this client, and every client created from it with
+    /// [`for_user`](Self::for_user), has sent so far.
+    pub fn request_count(&self) -> usize {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// The GraphQL endpoint this client talks to.
+    pub fn api_url(&self) -> &str {
+        &self.graphql_url
+    }
+
+    /// The user whose activity this client fetches.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Start of the time range this client fetches.
+    pub fn start_date(&self) -> ChronoDateTime<Utc> {
+        self.start_date
+    }
 
-        // Fetch base data (non-paginated fields).
-        let base_variables = user_activity::Variables {
+    /// End of the time range this client fetches.
+    pub fn end_date(&self) -> ChronoDateTime<Utc> {
+        self.end_date
+    }
+
+    /// Variables of the activity query, which requests the first page of every connection.
+    fn activity_variables(&self) -> user_activity::Variables {
+        user_activity::Variables {
             username: self.username.to_string(),
             from: self.start_date.to_rfc3339(),
             to: self.end_date.to_rfc3339(),
-            issues_first: first,
-            issues_after: None,
-            prs_first: first,
-            prs_after: None,
-            pr_reviews_first: first,
-            pr_reviews_after: None,
-        };
+            issues_first: self.page_sizes.issues,
+            prs_first: self.page_sizes.prs,
+            pr_reviews_first: self.page_sizes.reviews,
+            review_bodies: self.review_bodies,
+            organization_id: self.organization_id.clone(),
+        }
+    }
 
-        let base_request = UserActivity::build_query(base_variables);
-        debug!("Base GraphQL request: {:?}", base_request);
+    /// Fetches the activity of the client's whole time range.
+    ///
+    /// `contributionsCollection` covers at most a year, so longer ranges are fetched one
+    /// chunk of at most a year at a time and the chunks merged.
+    pub async fn fetch_activity(&self) -> Result<user_activity::ResponseData> {
+        let activity = self.fetch_activity_chunks().await?;
+        for repository in activity.user.iter().flat_map(|user| {
+            &user
+                .contributions_collection
+                .commit_contributions_by_repository
+        }) {
+            self.emit(repository);
+        }
+        Ok(activity)
+    }
 
-        let graphql_url = std::env::var("GITHUB_GRAPHQL_URL")
-            .unwrap_or_else(|_| "https://api.github.com/graphql".into());
+    /// Fetches the activity of the client's whole time range, one chunk of at most a year at a
+    /// time.
+    async fn fetch_activity_chunks(&self) -> Result<user_activity::ResponseData> {
+        let chunks = range_chunks(self.start_date, self.end_date);
+        if chunks.len() <= 1 {
+            return self.fetch_activity_chunk().await;
+        }
+        info!(
+            "Splitting {} to {} into {} chunks of at most a year",
+            self.start_date,
+            self.end_date,
+            chunks.len()
+        );
+        let mut merged: Option<user_activity::ResponseData> = None;
+        for (start, end) in chunks {
+            let activity = self
+                .for_user(self.username.clone(), start, end)
+                .fetch_activity_chunk()
+                .await
+                .with_context(|| format!("Failed to fetch activity from {} to {}", start, end))?;
+            merged = Some(match merged {
+                Some(older) => merge_activity(older, activity),
+                None => activity,
+            });
+        }
+        merged.context("No activity fetched")
+    }
 
-        let res = self
-            .client
-            .post(&graphql_url)
-            .json(&base_request)
-            .send()
-            .await
-            .context("Failed to send base request")?;
+    /// Fetches base data with the first page of every connection, then concurrently pages
+    /// through the rest of each connection, for a range of at most a year.
+    async fn fetch_activity_chunk(&self) -> Result<user_activity::ResponseData> {
+        // Fetch base data, with the first page of every connection.
+        let base_request = UserActivity::build_query(self.activity_variables());
+        debug!("Base GraphQL request: {:?}", base_request);
 
         let response_body: Response<user_activity::ResponseData> =
-            res.json().await.context("Failed to parse base response")?;
+            self.send_graphql(&base_request, "base").await?;
         if let Some(errors) = response_body.errors {
+            if let Some(denied) = access::access_error(&errors) {
+                return Err(denied.into());
+            }
             bail!("GraphQL errors in base request: {:?}", errors);
         }
         let mut base_data = response_body
             .data
             .ok_or_else(|| anyhow::anyhow!("No data received in base response"))?;
 
-        // Run paginated queries concurrently.
+        let user = base_data
+            .user
+            .as_mut()
+            .context("No user data in base response")?;
+        let cc = &mut user.contributions_collection;
+        let issues = &mut cc.issue_contributions;
+        let first_issues = FirstPage {
+            nodes: issues.nodes.take().unwrap_or_default(),
+            end_cursor: issues.page_info.end_cursor.clone(),
+            has_next_page: issues.page_info.has_next_page,
+            total: Some(issues.total_count),
+        };
+        let prs = &mut cc.pull_request_contributions;
+        let first_prs = FirstPage {
+            nodes: prs.nodes.take().unwrap_or_default(),
+            end_cursor: prs.page_info.end_cursor.clone(),
+            has_next_page: prs.page_info.has_next_page,
+            total: Some(prs.total_count),
+        };
+        let pr_reviews = &mut cc.pull_request_review_contributions;
+        let first_pr_reviews = FirstPage {
+            nodes: pr_reviews.nodes.take().unwrap_or_default(),
+            end_cursor: pr_reviews.page_info.end_cursor.clone(),
+            has_next_page: pr_reviews.page_info.has_next_page,
+            total: Some(pr_reviews.total_count),
+        };
+        if first_issues.has_next_page || first_prs.has_next_page || first_pr_reviews.has_next_page {
+            self.respect_rate_limit(base_data.rate_limit()).await?;
+        }
+
+        // Page through the rest of each connection concurrently, with queries selecting only
+        // that connection.
         let (issues, prs, pr_reviews) = join!(
-            self.fetch_issue_nodes(first),
-            self.fetch_pr_nodes(first),
-            self.fetch_pr_review_nodes(first)
+            self.fetch_issue_nodes(first_issues),
+            self.fetch_pr_nodes(first_prs),
+            self.fetch_pr_review_nodes(first_pr_reviews)
         );
         let issues = issues.context("Failed to fetch issue nodes")?;
         let prs = prs.context("Failed to fetch PR nodes")?;
@@ -120,66 +729,858 @@ impl GithubClient {
                 .nodes = Some(pr_reviews);
         }
 
+        // The rate limit only matters while fetching; keep it out of reports.
+        base_data.rate_limit = None;
+
         info!("All pagination complete; returning merged data.");
         Ok(base_data)
     }
 
-    /// Generic helper function to fetch all nodes from a paginated connection.
+    /// Fetch the issues of a repository updated since the start date, together with the
+    /// timeline events needed to summarize triage activity.
+    ///
+    /// Only the first 100 matching timeline events of each issue are fetched.
+    pub async fn fetch_repo_triage(
+        &self,
+        repo: &str,
+    ) -> Result<Vec<repo_triage::RepoTriageRepositoryIssuesNodes>> {
+        let (owner, name) = repo
+            .split_once('/')
+            .with_context(|| format!("Repository must be in the format owner/repo: {}", repo))?;
+        let nodes = self
+            .fetch_paginated_nodes::<RepoTriage, _, _>(
+                "issues",
+                |cursor| repo_triage::Variables {
+                    owner: owner.to_string(),
+                    name: name.to_string(),
+                    since: self.start_date.to_rfc3339(),
+                    first: 50,
+                    after: cursor,
+                },
+                |data| {
+                    let issues = data
+                        .repository
+                        .with_context(|| format!("Repository {} not found", repo))?
+                        .issues;
+                    Ok((issues.nodes, issues.page_info, None))
+                },
+                |page_info: repo_triage::RepoTriageRepositoryIssuesPageInfo| {
+                    (page_info.end_cursor, page_info.has_next_page)
+                },
+            )
+            .await?;
+        Ok(nodes.into_iter().flatten().collect())
+    }
+
+    /// Fetch the timeline events (labeled, assigned, closed, reopened, cross-referenced) of
+    /// the issues with the given node IDs.
+    ///
+    /// Issues are looked up in batches of 100; only the first 50 events of each issue are fetched.
+    pub async fn fetch_issue_timelines(
+        &self,
+        ids: &[String],
+    ) -> Result<Vec<issue_timelines::IssueTimelinesNodesOnIssue>> {
+        let mut issues = Vec::with_capacity(ids.len());
+
+        let chunks = ids.chunks(MAX_NODE_IDS);
+        let last_chunk = chunks.len().saturating_sub(1);
+        for (i, chunk) in chunks.enumerate() {
+            debug!("Fetching timelines of {} issue(s)", chunk.len());
+            let request_body = IssueTimelines::build_query(issue_timelines::Variables {
+                ids: chunk.to_vec(),
+            });
+            let response_body: Response<issue_timelines::ResponseData> =
+                self.send_graphql(&request_body, "issue timelines").await?;
+            if let Some(errors) = response_body.errors {
+                error!("GraphQL errors: {:?}", errors);
+                bail!("GraphQL errors: {:?}", errors);
+            }
+            let data = response_body
+                .data
+                .context("No data returned in issue timelines response")?;
+            if i < last_chunk {
+                self.respect_rate_limit(data.rate_limit()).await?;
+            }
+            issues.extend(
+                data.nodes
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|node| match node {
+                        issue_timelines::IssueTimelinesNodes::Issue(issue) => Some(issue),
+                        _ => None,
+                    }),
+            );
+        }
+
+        Ok(issues)
+    }
+
+    /// Search a repository for merged pull requests that look like reverts, merged since the
+    /// start date.
+    pub async fn fetch_revert_candidates(
+        &self,
+        repo: &str,
+    ) -> Result<Vec<revert_pull_requests::RevertPullRequestsSearchNodesOnPullRequest>> {
+        let query = format!(
+            "repo:{} is:pr is:merged in:title revert merged:>={}",
+            repo,
+            self.start_date.format("%Y-%m-%d")
+        );
+        let nodes = self
+            .fetch_paginated_nodes::<RevertPullRequests, _, _>(
+                "search",
+                |cursor| revert_pull_requests::Variables {
+                    query: query.clone(),
+                    first: 50,
+                    after: cursor,
+                },
+                |data| Ok((data.search.nodes, data.search.page_info, None)),
+                |page_info: revert_pull_requests::RevertPullRequestsSearchPageInfo| {
+                    (page_info.end_cursor, page_info.has_next_page)
+                },
+            )
+            .await?;
+        Ok(nodes
+            .into_iter()
+            .flatten()
+            .filter_map(|node| match node {
+                revert_pull_requests::RevertPullRequestsSearchNodes::PullRequest(pr) => Some(pr),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Search for the pull requests of others the user reviewed, updated within the date range,
+    /// with the user's first review of each.
+    pub async fn fetch_reviewed_pull_requests(
+        &self,
+    ) -> Result<Vec<reviewed_pull_requests::ReviewedPullRequestsSearchNodesOnPullRequest>> {
+        let query = format!(
+            "is:pr reviewed-by:{0} -author:{0} updated:{1}..{2}",
+            self.username,
+            self.start_date.format("%Y-%m-%d"),
+            self.end_date.format("%Y-%m-%d")
+        );
+        let nodes = self
+            .fetch_paginated_nodes::<ReviewedPullRequests, _, _>(
+                "search",
+                |cursor| reviewed_pull_requests::Variables {
+                    query: query.clone(),
+                    author: self.username.clone(),
+                    first: 50,
+                    after: cursor,
+                },
+                |data| Ok((data.search.nodes, data.search.page_info, None)),
+                |page_info: reviewed_pull_requests::ReviewedPullRequestsSearchPageInfo| {
+                    (page_info.end_cursor, page_info.has_next_page)
+                },
+            )
+            .await?;
+        Ok(nodes
+            .into_iter()
+            .flatten()
+            .filter_map(|node| match node {
+                reviewed_pull_requests::ReviewedPullRequestsSearchNodes::PullRequest(pr) => {
+                    Some(pr)
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Fetch the logins of the members of `team`, given as `org/team-slug`, including members
+    /// of its child teams. Fails with [`OrganizationAccessDenied`] when the token can't read
+    /// the organization's teams.
+    pub async fn fetch_team_members(&self, team: &str) -> Result<Vec<String>> {
+        let (org, slug) = team
+            .split_once('/')
+            .with_context(|| format!("Team must be in the format org/team-slug: {}", team))?;
+        let members = self
+            .fetch_paginated_nodes::<TeamMembers, _, _>(
+                "members",
+                |cursor| team_members::Variables {
+                    org: org.to_string(),
+                    slug: slug.to_string(),
+                    first: 100,
+                    after: cursor,
+                },
+                |data| {
+                    let members = data
+                        .organization
+                        .and_then(|org| org.team)
+                        .with_context(|| format!("Team {} not found", team))?
+                        .members;
+                    Ok((members.nodes, members.page_info, None))
+                },
+                |page_info: team_members::TeamMembersOrganizationTeamMembersPageInfo| {
+                    (page_info.end_cursor, page_info.has_next_page)
+                },
+            )
+            .await?;
+        Ok(members
+            .into_iter()
+            .flatten()
+            .map(|member| member.login)
+            .collect())
+    }
+
+    /// Look up the node ID of the organization with `login`, e.g. to scope contributions to it.
+    /// Fails with [`OrganizationAccessDenied`] when the token can't read the organization.
+    pub async fn fetch_organization_id(&self, login: &str) -> Result<String> {
+        let request_body = OrganizationId::build_query(organization_id::Variables {
+            login: login.to_string(),
+        });
+        let response_body: Response<organization_id::ResponseData> =
+            self.send_graphql(&request_body, "organization").await?;
+        if let Some(errors) = response_body.errors {
+            if let Some(denied) = access::access_error(&errors) {
+                return Err(denied.into());
+            }
+            bail!("GraphQL errors: {:?}", errors);
+        }
+        Ok(response_body
+            .data
+            .and_then(|data| data.organization)
+            .with_context(|| format!("Organization {} not found", login))?
+            .id)
+    }
+
+    /// Count the issues and pull requests matching a search query, without fetching them.
+    pub async fn count_search_results(&self, query: &str) -> Result<i64> {
+        let request_body = SearchCount::build_query(search_count::Variables {
+            query: query.to_string(),
+        });
+        let response_body: Response<search_count::ResponseData> =
+            self.send_graphql(&request_body, "search").await?;
+        if let Some(errors) = response_body.errors {
+            bail!("GraphQL errors: {:?}", errors);
+        }
+        Ok(response_body
+            .data
+            .context("Search response contained no data")?
+            .search
+            .issue_count)
+    }
+
+    /// Fetch the 100 most recently created releases of a repository.
+    pub async fn fetch_releases(
+        &self,
+        repo: &str,
+    ) -> Result<Vec<repo_releases::RepoReleasesRepositoryReleasesNodes>> {
+        let (owner, name) = repo
+            .split_once('/')
+            .with_context(|| format!("Repository must be in the format owner/repo: {}", repo))?;
+        let request_body = RepoReleases::build_query(repo_releases::Variables {
+            owner: owner.to_string(),
+            name: name.to_string(),
+        });
+        let response_body: Response<repo_releases::ResponseData> =
+            self.send_graphql(&request_body, "releases").await?;
+        if let Some(errors) = response_body.errors {
+            error!("GraphQL errors: {:?}", errors);
+            bail!("GraphQL errors: {:?}", errors);
+        }
+        let repository = response_body
+            .data
+            .and_then(|data| data.repository)
+            .with_context(|| format!("Repository {} not found", repo))?;
+        Ok(repository
+            .releases
+            .nodes
+            .into_iter()
+            .flatten()
+            .flatten()
+            .collect())
+    }
+
+    /// Fetch whether a repository is a fork, and of which repository.
+    pub async fn fetch_fork(&self, repo: &str) -> Result<repo_fork::RepoForkRepository> {
+        let (owner, name) = repo
+            .split_once('/')
+            .with_context(|| format!("Repository must be in the format owner/repo: {}", repo))?;
+        let request_body = RepoFork::build_query(repo_fork::Variables {
+            owner: owner.to_string(),
+            name: name.to_string(),
+        });
+        let response_body: Response<repo_fork::ResponseData> =
+            self.send_graphql(&request_body, "fork").await?;
+        if let Some(errors) = response_body.errors {
+            error!("GraphQL errors: {:?}", errors);
+            bail!("GraphQL errors: {:?}", errors);
+        }
+        response_body
+            .data
+            .and_then(|data| data.repository)
+            .with_context(|| format!("Repository {} not found", repo))
+    }
+
+    /// Check whether the commit `sha` is contained in the git ref `tag`, using the REST
+    /// compare API.
+    pub async fn tag_contains(&self, repo: &str, tag: &str, sha: &str) -> Result<bool> {
+        let url = format!("{}/repos/{}/compare/{}...{}", self.rest_url, repo, sha, tag);
+        let response = self
+            .send(self.client.get(&url))
+            .await
+            .context("Failed to send compare request")?
+            .error_for_status()
+            .with_context(|| format!("Failed to compare {} with {} in {}", sha, tag, repo))?;
+        let comparison: serde_json::Value = response
+            .json()
+            .await
+            .context("Failed to parse compare response")?;
+        // "ahead" means the tag has every commit of `sha` plus more; "identical" means the
+        // tag points at `sha` itself.
+        Ok(matches!(
+            comparison["status"].as_str(),
+            Some("ahead" | "identical")
+        ))
+    }
+
+    /// Fetch the paths of the files changed by a pull request, using the REST API.
+    pub async fn fetch_pr_files(&self, repo: &str, number: i64) -> Result<Vec<String>> {
+        let mut paths = Vec::new();
+        for page in 1.. {
+            let url = format!(
+                "{}/repos/{}/pulls/{}/files?per_page={}&page={}",
+                self.rest_url, repo, number, FILES_PER_PAGE, page
+            );
+            let files: Vec<serde_json::Value> = self
+                .send(self.client.get(&url))
+                .await
+                .context("Failed to send pull request files request")?
+                .error_for_status()
+                .with_context(|| format!("Failed to list files of {}#{}", repo, number))?
+                .json()
+                .await
+                .context("Failed to parse pull request files response")?;
+            let count = files.len();
+            paths.extend(
+                files
+                    .into_iter()
+                    .filter_map(|file| file["filename"].as_str().map(str::to_string)),
+            );
+            if count < FILES_PER_PAGE {
+                break;
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Fetch the raw contents of a file on a repository's default branch, using the REST API.
+    ///
+    /// Returns `None` when the file does not exist.
+    pub async fn fetch_file_contents(&self, repo: &str, path: &str) -> Result<Option<String>> {
+        let url = format!("{}/repos/{}/contents/{}", self.rest_url, repo, path);
+        let response = self
+            .send(
+                self.client
+                    .get(&url)
+                    .header(ACCEPT, "application/vnd.github.raw+json"),
+            )
+            .await
+            .context("Failed to send file contents request")?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let contents = response
+            .error_for_status()
+            .with_context(|| format!("Failed to fetch {} from {}", path, repo))?
+            .text()
+            .await
+            .context("Failed to read file contents response")?;
+        Ok(Some(contents))
+    }
+
+    /// Fetch the login and profile name of the authenticated user, using the REST API.
+    pub async fn fetch_viewer(&self) -> Result<(String, Option<String>)> {
+        let user: Value = self
+            .send(self.client.get(format!("{}/user", self.rest_url)))
+            .await
+            .context("Failed to send user request")?
+            .error_for_status()
+            .context("Failed to fetch the authenticated user")?
+            .json()
+            .await
+            .context("Failed to parse user response")?;
+        let login = user["login"]
+            .as_str()
+            .context("User response has no login")?
+            .to_string();
+        Ok((login, user["name"].as_str().map(str::to_string)))
+    }
+
+    /// Fetch the email addresses of the authenticated user's account, using the REST API;
+    /// the token needs the `user:email` scope.
+    pub async fn fetch_viewer_emails(&self) -> Result<Vec<AccountEmail>> {
+        self.send(self.client.get(format!("{}/user/emails", self.rest_url)))
+            .await
+            .context("Failed to send user emails request")?
+            .error_for_status()
+            .context(
+                "Failed to list the account's emails (does the token have the user:email scope?)",
+            )?
+            .json()
+            .await
+            .context("Failed to parse user emails response")
+    }
+
+    /// The request publishing `files` (each a file name and its contents) as a secret gist, or
+    /// replacing those files in the gist `id`.
+    pub fn gist_request(
+        &self,
+        id: Option<&str>,
+        description: &str,
+        files: &[(String, String)],
+    ) -> RestRequest {
+        let files: serde_json::Map<String, Value> = files
+            .iter()
+            .map(|(name, content)| (name.clone(), json!({ "content": content })))
+            .collect();
+        match id {
+            Some(id) => RestRequest {
+                method: reqwest::Method::PATCH,
+                url: format!("{}/gists/{}", self.rest_url, id),
+                body: json!({ "description": description, "files": files }),
+            },
+            None => RestRequest {
+                method: reqwest::Method::POST,
+                url: format!("{}/gists", self.rest_url),
+                body: json!({ "description": description, "public": false, "files": files }),
+            },
+        }
+    }
+
+    /// Publish `files` (each a file name and its contents) as a secret gist, or replace those
+    /// files in the gist `id`, using the REST API; the token needs the `gist` scope. Returns
+    /// the gist's URL.
+    pub async fn publish_gist(
+        &self,
+        id: Option<&str>,
+        description: &str,
+        files: &[(String, String)],
+    ) -> Result<String> {
+        let request = self.gist_request(id, description, files);
+        let gist: Value = self
+            .send(self.rest_request(&request))
+            .await
+            .context("Failed to send gist request")?
+            .error_for_status()
+            .context("Failed to publish the gist (does the token have the gist scope?)")?
+            .json()
+            .await
+            .context("Failed to parse gist response")?;
+        gist["html_url"]
+            .as_str()
+            .map(str::to_string)
+            .context("Gist response has no html_url")
+    }
+
+    /// The request opening an issue in `repo` with `title` and `body`.
+    pub fn issue_request(&self, repo: &str, title: &str, body: &str) -> RestRequest {
+        RestRequest {
+            method: reqwest::Method::POST,
+            url: format!("{}/repos/{}/issues", self.rest_url, repo),
+            body: json!({ "title": title, "body": body }),
+        }
+    }
+
+    /// Open an issue in `repo` with `title` and `body`, using the REST API. Returns the
+    /// issue's number and URL.
+    pub async fn create_issue(&self, repo: &str, title: &str, body: &str) -> Result<(u64, String)> {
+        let request = self.issue_request(repo, title, body);
+        let created = async {
+            let issue = self.post(self.rest_request(&request), "issue").await?;
+            let number = issue["number"]
+                .as_u64()
+                .context("The issue response has no number")?;
+            anyhow::Ok((number, html_url(&issue, "issue")?))
+        };
+        created
+            .await
+            .with_context(|| format!("Failed to open an issue in {}", repo))
+    }
+
+    /// The request commenting `body` on issue `number` of `repo`.
+    pub fn comment_request(
+        &self,
+        repo: &str,
+        number: impl std::fmt::Display,
+        body: &str,
+    ) -> RestRequest {
+        RestRequest {
+            method: reqwest::Method::POST,
+            url: format!(
+                "{}/repos/{}/issues/{}/comments",
+                self.rest_url, repo, number
+            ),
+            body: json!({ "body": body }),
+        }
+    }
+
+    /// Comment `body` on issue `number` of `repo`, using the REST API. Returns the comment's
+    /// URL.
+    pub async fn comment_on_issue(&self, repo: &str, number: u64, body: &str) -> Result<String> {
+        let request = self.comment_request(repo, number, body);
+        let created = async {
+            let comment = self.post(self.rest_request(&request), "comment").await?;
+            html_url(&comment, "comment")
+        };
+        created
+            .await
+            .with_context(|| format!("Failed to comment on {}#{}", repo, number))
+    }
+
+    /// The builder sending `request`.
+    fn rest_request(&self, request: &RestRequest) -> RequestBuilder {
+        self.client
+            .request(request.method.clone(), &request.url)
+            .json(&request.body)
+    }
+
+    /// Sends a REST request creating `what` and returns what it created.
+    async fn post(&self, request: RequestBuilder, what: &str) -> Result<Value> {
+        self.send(request)
+            .await
+            .with_context(|| format!("Failed to send {} request", what))?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("Failed to parse {} response", what))
+    }
+
+    /// Fetch the commits of a repository in the client's time range whose author GitHub
+    /// couldn't link to an account, using the REST API. At most `COMMIT_PAGES` pages of
+    /// commits are scanned.
+    pub async fn fetch_unlinked_commits(&self, repo: &str) -> Result<Vec<UnlinkedCommit>> {
+        let mut unlinked = Vec::new();
+        for page in 1..=COMMIT_PAGES {
+            let url = format!("{}/repos/{}/commits", self.rest_url, repo);
+            let commits: Vec<Value> = self
+                .send(self.client.get(&url).query(&[
+                    ("since", self.start_date.to_rfc3339()),
+                    ("until", self.end_date.to_rfc3339()),
+                    ("per_page", COMMITS_PER_PAGE.to_string()),
+                    ("page", page.to_string()),
+                ]))
+                .await
+                .context("Failed to send commits request")?
+                .error_for_status()
+                .with_context(|| format!("Failed to list commits of {}", repo))?
+                .json()
+                .await
+                .context("Failed to parse commits response")?;
+            let count = commits.len();
+            unlinked.extend(
+                commits
+                    .iter()
+                    .filter(|commit| commit["author"].is_null())
+                    .filter_map(|commit| {
+                        let author = &commit["commit"]["author"];
+                        Some(UnlinkedCommit {
+                            repository: repo.to_string(),
+                            author_name: author["name"].as_str()?.to_string(),
+                            author_email: author["email"].as_str()?.to_string(),
+                        })
+                    }),
+            );
+            if count < COMMITS_PER_PAGE {
+                return Ok(unlinked);
+            }
+        }
+        warn!(
+            "Only the latest {} commits of {} were checked for unattributed emails",
+            COMMIT_PAGES * COMMITS_PER_PAGE,
+            repo
+        );
+        Ok(unlinked)
+    }
+
+    /// Sends an HTTP request to the API, counting it. The first response carrying a `Date`
+    /// header is checked for clock skew, since periods are computed from the local clock.
+    ///
+    /// A 403 asking for SAML SSO authorization fails with [`SsoAuthorizationRequired`], so the
+    /// user is pointed at the authorization URL instead of shown a bare status code.
+    async fn send(&self, request: RequestBuilder) -> Result<reqwest::Response> {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        let response = request.send().await?;
+        if let Some(date) = response.headers().get(DATE).and_then(|d| d.to_str().ok())
+            && !self.clock_checked.swap(true, Ordering::Relaxed)
+            && let Some(skew) = clock_skew(date, Utc::now())
+        {
+            let direction = if skew > Duration::zero() {
+                "ahead of"
+            } else {
+                "behind"
+            };
+            warn!(
+                "The local clock is {} {} GitHub's ({}); time ranges like --period are computed \
+                 from it and may miss recent activity",
+                humanize_skew(skew),
+                direction,
+                date
+            );
+        }
+        if let Some(sso) = sso::sso_error(response.status(), response.headers()) {
+            return Err(sso.into());
+        }
+        Ok(response)
+    }
+
+    /// Sends a GraphQL operation and parses its response; `what` names the request in errors.
+    ///
+    /// With persisted queries enabled, the operation is first sent as a GET carrying only the
+    /// query hash. If the endpoint rejects the GET or doesn't know the hash, it is POSTed with
+    /// both the query and the hash, which also registers the query with gateways that support
+    /// automatic persisted queries.
+    async fn send_graphql<V, R>(&self, body: &QueryBody<V>, what: &str) -> Result<Response<R>>
+    where
+        V: Serialize,
+        R: DeserializeOwned,
+    {
+        if !self.persisted_queries {
+            let (_, response_body) = self
+                .send_graphql_request(|| self.client.post(&self.graphql_url).json(body), what)
+                .await?;
+            return serde_json::from_str(&response_body)
+                .with_context(|| format!("Failed to parse {} response", what));
+        }
+
+        let extensions = persisted_query_extensions(body.query);
+        let variables =
+            serde_json::to_string(&body.variables).context("Failed to serialize variables")?;
+        let (status, response_body) = self
+            .send_graphql_request(
+                || {
+                    self.client.get(&self.graphql_url).query(&[
+                        ("operationName", body.operation_name),
+                        ("variables", &variables),
+                        ("extensions", &extensions.to_string()),
+                    ])
+                },
+                what,
+            )
+            .await?;
+        if status.is_success() {
+            let response_body: Value = serde_json::from_str(&response_body)
+                .with_context(|| format!("Failed to parse {} response", what))?;
+            if !is_persisted_query_miss(&response_body) {
+                return serde_json::from_value(response_body)
+                    .with_context(|| format!("Failed to parse {} response", what));
+            }
+            debug!(
+                "Persisted query {} not found; sending it with POST",
+                body.operation_name
+            );
+        } else {
+            debug!(
+                "GET {} request failed with {}; falling back to POST",
+                what, status
+            );
+        }
+
+        let post_body = json!({
+            "query": body.query,
+            "operationName": body.operation_name,
+            "variables": body.variables,
+            "extensions": extensions,
+        });
+        let (_, response_body) = self
+            .send_graphql_request(
+                || self.client.post(&self.graphql_url).json(&post_body),
+                what,
+            )
+            .await?;
+        serde_json::from_str(&response_body)
+            .with_context(|| format!("Failed to parse {} response", what))
+    }
+
+    /// Sends the GraphQL request built by `request` and returns its status and body. Unless
+    /// waiting on the rate limit is disabled, a response showing that the request hit a rate
+    /// limit is waited out and the request sent again, up to [`MAX_RATE_LIMIT_ATTEMPTS`]
+    /// attempts in all.
+    async fn send_graphql_request(
+        &self,
+        request: impl Fn() -> RequestBuilder,
+        what: &str,
+    ) -> Result<(StatusCode, String)> {
+        for attempt in 1..=MAX_RATE_LIMIT_ATTEMPTS {
+            let response = self
+                .send(request())
+                .await
+                .with_context(|| format!("Failed to send {} request", what))?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response
+                .text()
+                .await
+                .with_context(|| format!("Failed to read {} response", what))?;
+            if !self.wait_on_rate_limit || attempt == MAX_RATE_LIMIT_ATTEMPTS {
+                return Ok((status, body));
+            }
+            let parsed = serde_json::from_str(&body).unwrap_or(Value::Null);
+            let Some(wait) = rate_limit::retry_wait(status, &headers, &parsed, Utc::now()) else {
+                return Ok((status, body));
+            };
+            warn!(
+                "GitHub API rate limit hit by the {} request; retrying in {}s (attempt {}/{})",
+                what,
+                wait.as_secs(),
+                attempt + 1,
+                MAX_RATE_LIMIT_ATTEMPTS
+            );
+            tokio::time::sleep(wait).await;
+        }
+        unreachable!("the last attempt always returns")
+    }
+
+    /// Before the next request of a multi-request fetch, sleeps until the rate limit resets if
+    /// `status` shows the budget is exhausted, or fails if waiting is disabled.
+    async fn respect_rate_limit(&self, status: Option<RateLimitStatus>) -> Result<()> {
+        let Some(status) = status else {
+            return Ok(());
+        };
+        debug!("Rate limit: {:?}", status);
+        let Some(wait) = status.wait_duration(Utc::now())? else {
+            return Ok(());
+        };
+        if !self.wait_on_rate_limit {
+            bail!(
+                "GitHub API rate limit exhausted ({} points left); it resets at {}",
+                status.remaining,
+                status.reset_at
+            );
+        }
+        warn!(
+            "GitHub API rate limit exhausted; waiting {}s until it resets at {}",
+            wait.as_secs(),
+            status.reset_at
+        );
+        tokio::time::sleep(wait).await;
+        Ok(())
+    }
+
+    /// Generic helper function to fetch all nodes from a paginated connection of query `Q`.
+    /// - `connection`: the connection's name, for progress events.
     /// - `build_vars`: a closure that accepts an optional cursor and returns query variables.
-    /// - `extract`: a closure that extracts (Option<Vec<T>>, &P) from ResponseData.
-    /// - `extract_page_info`: a closure that converts a reference to page info (of type P) into (Option<String>, bool).
-    async fn fetch_paginated_nodes<T, P>(
+    /// - `extract`: a closure that takes the response data apart into (Option<Vec<T>>, P,
+    ///   Option<i64>), the last being the connection's total count if the query selects it.
+    /// - `extract_page_info`: a closure that converts page info (of type P) into (Option<String>, bool).
+    ///
+    /// Pages are moved out of the responses rather than cloned, so only the accumulated nodes
+    /// stay in memory.
+    async fn fetch_paginated_nodes<Q, T, P>(
         &self,
-        build_vars: impl Fn(Option<String>) -> user_activity::Variables,
-        extract: impl Fn(&user_activity::ResponseData) -> (&Option<Vec<T>>, &P),
-        extract_page_info: impl Fn(&P) -> (Option<String>, bool),
+        connection: &str,
+        build_vars: impl Fn(Option<String>) -> Q::Variables,
+        extract: impl Fn(Q::ResponseData) -> Result<(Option<Vec<T>>, P, Option<i64>)>,
+        extract_page_info: impl Fn(P) -> (Option<String>, bool),
     ) -> Result<Vec<T>>
     where
-        T: Clone,
+        Q: GraphQLQuery,
+        Q::Variables: std::fmt::Debug,
+        Q::ResponseData: std::fmt::Debug + RateLimited,
     {
-        let mut all_nodes = Vec::new();
-        let mut cursor: Option<String> = None;
-        loop {
-            let variables = build_vars(cursor.clone());
-            let request_body = UserActivity::build_query(variables);
-            debug!("Pagination request: {:?}", request_body);
+        self.fetch_pages::<Q, T, P>(
+            connection,
+            1,
+            Vec::new(),
+            None,
+            build_vars,
+            extract,
+            extract_page_info,
+        )
+        .await
+    }
 
-            let res = self
-                .client
-                .post(
-                    std::env::var("GITHUB_GRAPHQL_URL")
-                        .unwrap_or_else(|_| "https://api.github.com/graphql".into()),
-                )
-                .json(&request_body)
-                .send()
-                .await
-                .context("Failed to send pagination request")?;
-            info!("Pagination request sent, awaiting response.");
+    /// Like [`Self::fetch_paginated_nodes`], for a connection whose first page was already
+    /// fetched as part of another query: its nodes are kept and paging continues after it, if
+    /// it has a next page.
+    async fn fetch_remaining_nodes<Q, T, P>(
+        &self,
+        connection: &str,
+        first_page: FirstPage<T>,
+        build_vars: impl Fn(Option<String>) -> Q::Variables,
+        extract: impl Fn(Q::ResponseData) -> Result<(Option<Vec<T>>, P, Option<i64>)>,
+        extract_page_info: impl Fn(P) -> (Option<String>, bool),
+    ) -> Result<Vec<T>>
+    where
+        Q: GraphQLQuery,
+        Q::Variables: std::fmt::Debug,
+        Q::ResponseData: std::fmt::Debug + RateLimited,
+    {
+        self.report_progress(
+            connection,
+            1,
+            first_page.nodes.len(),
+            first_page.total,
+            !first_page.has_next_page,
+        );
+        if !first_page.has_next_page {
+            return Ok(first_page.nodes);
+        }
+        self.fetch_pages::<Q, T, P>(
+            connection,
+            2,
+            first_page.nodes,
+            first_page.end_cursor,
+            build_vars,
+            extract,
+            extract_page_info,
+        )
+        .await
+    }
 
-            let response_body: Response<user_activity::ResponseData> = res
-                .json()
-                .await
-                .context("Failed to parse pagination response")?;
+    /// Fetches the pages of a connection from page number `first_page` on, starting after
+    /// `cursor`, adding their nodes to `all_nodes`.
+    #[allow(clippy::too_many_arguments)]
+    async fn fetch_pages<Q, T, P>(
+        &self,
+        connection: &str,
+        first_page: usize,
+        mut all_nodes: Vec<T>,
+        mut cursor: Option<String>,
+        build_vars: impl Fn(Option<String>) -> Q::Variables,
+        extract: impl Fn(Q::ResponseData) -> Result<(Option<Vec<T>>, P, Option<i64>)>,
+        extract_page_info: impl Fn(P) -> (Option<String>, bool),
+    ) -> Result<Vec<T>>
+    where
+        Q: GraphQLQuery,
+        Q::Variables: std::fmt::Debug,
+        Q::ResponseData: std::fmt::Debug + RateLimited,
+    {
+        for page in first_page.. {
+            let variables = build_vars(cursor.take());
+            let request_body = Q::build_query(variables);
+            debug!("Pagination request: {:?}", request_body);
+
+            let response_body: Response<Q::ResponseData> =
+                self.send_graphql(&request_body, "pagination").await?;
             debug!("Pagination response: {:?}", response_body);
 
             if let Some(errors) = response_body.errors {
                 error!("GraphQL pagination errors: {:?}", errors);
+                if let Some(denied) = access::access_error(&errors) {
+                    return Err(denied.into());
+                }
                 bail!("GraphQL pagination errors: {:?}", errors);
             }
 
             let data = response_body
                 .data
                 .ok_or_else(|| anyhow::anyhow!("No data received in pagination response"))?;
-            let (nodes_opt, page_info) = extract(&data);
+            let rate_limit = data.rate_limit();
+            let (nodes_opt, page_info, total) = extract(data)?;
             if let Some(nodes) = nodes_opt {
                 debug!("Fetched {} nodes", nodes.len());
-                all_nodes.extend(nodes.clone());
+                all_nodes.extend(nodes);
             } else {
                 debug!("No nodes found in this page");
             }
             let (end_cursor, has_next_page) = extract_page_info(page_info);
+            self.report_progress(connection, page, all_nodes.len(), total, !has_next_page);
             if has_next_page {
                 debug!("Has next page; setting cursor to {:?}", end_cursor);
+                self.respect_rate_limit(rate_limit).await?;
                 cursor = end_cursor;
             } else {
                 info!("No further pages; pagination complete.");
@@ -189,90 +1590,134 @@ impl GithubClient {
         Ok(all_nodes)
     }
 
-    /// Fetch all issue contribution nodes.
+    /// Reports a fetched page of `connection` to the progress callback, if any.
+    fn report_progress(
+        &self,
+        connection: &str,
+        page: usize,
+        fetched: usize,
+        total: Option<i64>,
+        done: bool,
+    ) {
+        if let Some(progress) = &self.progress {
+            progress(&ProgressEvent {
+                user: self.username.clone(),
+                connection: connection.to_string(),
+                page,
+                fetched,
+                total,
+                done,
+            });
+        }
+    }
+
+    /// Fetch all issue contribution nodes, continuing after `first_page`.
     async fn fetch_issue_nodes(
         &self,
-        first: i64,
+        first_page: FirstPage<
+            user_activity::UserActivityUserContributionsCollectionIssueContributionsNodes,
+        >,
     ) -> Result<Vec<user_activity::UserActivityUserContributionsCollectionIssueContributionsNodes>>
     {
-        self.fetch_paginated_nodes(
-          |cursor| user_activity::Variables {
+        for node in &first_page.nodes {
+            self.emit(node);
+        }
+        self.fetch_remaining_nodes::<IssuesPage, _, _>(
+          "issueContributions",
+          first_page,
+          |cursor| issues_page::Variables {
               username: self.username.to_string(),
               from: self.start_date.to_rfc3339(),
               to: self.end_date.to_rfc3339(),
-              issues_first: first,
-              issues_after: cursor,
-              prs_first: first,           // Dummy values for unused fields.
-              prs_after: None,
-              pr_reviews_first: first,
-              pr_reviews_after: None,
+              first: self.page_sizes.issues,
+              after: cursor,
+              organization_id: self.organization_id.clone(),
           },
           |data| {
-              let issue_conn = &data.user.as_ref().unwrap().contributions_collection.issue_contributions;
-              (&issue_conn.nodes, &issue_conn.page_info)
+              let issue_conn = data.user.context("No user data in pagination response")?.contributions_collection.issue_contributions;
+              let nodes: Option<Vec<user_activity::UserActivityUserContributionsCollectionIssueContributionsNodes>> = activity_nodes(issue_conn.nodes);
+              for node in nodes.iter().flatten() {
+                  self.emit(node);
+              }
+              Ok((nodes, issue_conn.page_info, Some(issue_conn.total_count)))
           },
-          |page_info: &user_activity::UserActivityUserContributionsCollectionIssueContributionsPageInfo| {
-              (page_info.end_cursor.clone(), page_info.has_next_page)
+          |page_info: issues_page::IssuesPageUserContributionsCollectionIssueContributionsPageInfo| {
+              (page_info.end_cursor, page_info.has_next_page)
           },
       )
       .await
     }
 
-    /// Fetch all pull request contribution nodes.
+    /// Fetch all pull request contribution nodes, continuing after `first_page`.
     async fn fetch_pr_nodes(
         &self,
-        first: i64,
+        first_page: FirstPage<
+            user_activity::UserActivityUserContributionsCollectionPullRequestContributionsNodes,
+        >,
     ) -> Result<
         Vec<user_activity::UserActivityUserContributionsCollectionPullRequestContributionsNodes>,
     > {
-        self.fetch_paginated_nodes(
-          |cursor| user_activity::Variables {
+        for node in &first_page.nodes {
+            self.emit(node);
+        }
+        self.fetch_remaining_nodes::<PrsPage, _, _>(
+          "pullRequestContributions",
+          first_page,
+          |cursor| prs_page::Variables {
               username: self.username.to_string(),
               from: self.start_date.to_rfc3339(),
               to: self.end_date.to_rfc3339(),
-              issues_first: first,
-              issues_after: None,
-              prs_first: first,
-              prs_after: cursor,
-              pr_reviews_first: first,
-              pr_reviews_after: None,
+              first: self.page_sizes.prs,
+              after: cursor,
+              organization_id: self.organization_id.clone(),
           },
           |data| {
-              let pr_conn = &data.user.as_ref().unwrap().contributions_collection.pull_request_contributions;
-              (&pr_conn.nodes, &pr_conn.page_info)
+              let pr_conn = data.user.context("No user data in pagination response")?.contributions_collection.pull_request_contributions;
+              let nodes: Option<Vec<user_activity::UserActivityUserContributionsCollectionPullRequestContributionsNodes>> = activity_nodes(pr_conn.nodes);
+              for node in nodes.iter().flatten() {
+                  self.emit(node);
+              }
+              Ok((nodes, pr_conn.page_info, Some(pr_conn.total_count)))
           },
-          |page_info: &user_activity::UserActivityUserContributionsCollectionPullRequestContributionsPageInfo| {
-              (page_info.end_cursor.clone(), page_info.has_next_page)
+          |page_info: prs_page::PrsPageUserContributionsCollectionPullRequestContributionsPageInfo| {
+              (page_info.end_cursor, page_info.has_next_page)
           },
       )
       .await
     }
 
-    /// Fetch all pull request review contribution nodes.
+    /// Fetch all pull request review contribution nodes, continuing after `first_page`.
     async fn fetch_pr_review_nodes(
         &self,
-        first: i64,
+        first_page: FirstPage<user_activity::UserActivityUserContributionsCollectionPullRequestReviewContributionsNodes>,
     ) -> Result<
         Vec<user_activity::UserActivityUserContributionsCollectionPullRequestReviewContributionsNodes>,
     >{
-        self.fetch_paginated_nodes(
-          |cursor| user_activity::Variables {
+        for node in &first_page.nodes {
+            self.emit(node);
+        }
+        self.fetch_remaining_nodes::<ReviewsPage, _, _>(
+          "pullRequestReviewContributions",
+          first_page,
+          |cursor| reviews_page::Variables {
               username: self.username.to_string(),
               from: self.start_date.to_rfc3339(),
               to: self.end_date.to_rfc3339(),
-              issues_first: first,
-              issues_after: None,
-              prs_first: first,
-              prs_after: None,
-              pr_reviews_first: first,
-              pr_reviews_after: cursor,
+              first: self.page_sizes.reviews,
+              after: cursor,
+              review_bodies: self.review_bodies,
+              organization_id: self.organization_id.clone(),
           },
           |data| {
-              let pr_review_conn = &data.user.as_ref().unwrap().contributions_collection.pull_request_review_contributions;
-              (&pr_review_conn.nodes, &pr_review_conn.page_info)
+              let pr_review_conn = data.user.context("No user data in pagination response")?.contributions_collection.pull_request_review_contributions;
+              let nodes: Option<Vec<user_activity::UserActivityUserContributionsCollectionPullRequestReviewContributionsNodes>> = activity_nodes(pr_review_conn.nodes);
+              for node in nodes.iter().flatten() {
+                  self.emit(node);
+              }
+              Ok((nodes, pr_review_conn.page_info, Some(pr_review_conn.total_count)))
           },
-          |page_info: &user_activity::UserActivityUserContributionsCollectionPullRequestReviewContributionsPageInfo| {
-              (page_info.end_cursor.clone(), page_info.has_next_page)
+          |page_info: reviews_page::ReviewsPageUserContributionsCollectionPullRequestReviewContributionsPageInfo| {
+              (page_info.end_cursor, page_info.has_next_page)
           },
       )
       .await
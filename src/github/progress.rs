@@ -0,0 +1,32 @@
+//! Progress reporting: the client reports every page of a paginated connection it fetches to
+//! an optional callback, so wrappers can show structured progress instead of parsing logs.
+//! Another callback can receive the contribution nodes of those pages themselves.
+
+use crate::jsonl::ContributionLine;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// A page of a paginated connection was fetched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProgressEvent {
+    /// The user whose client fetched the page.
+    pub user: String,
+    /// The connection being paged through, as the query names it (e.g. `issueContributions`).
+    pub connection: String,
+    /// The number of the page, starting at 1.
+    pub page: usize,
+    /// Nodes fetched from the connection so far, this page included.
+    pub fetched: usize,
+    /// Nodes in the whole connection, for connections whose query selects `totalCount`.
+    pub total: Option<i64>,
+    /// Whether this was the connection's last page.
+    pub done: bool,
+}
+
+/// A callback receiving the client's progress events; shared by every client created with
+/// [`for_user`](super::GithubClient::for_user), so it may be called concurrently.
+pub type ProgressCallback = Arc<dyn Fn(&ProgressEvent) + Send + Sync>;
+
+/// A callback receiving each contribution node as soon as the client fetched it; shared like
+/// [`ProgressCallback`], so it may be called concurrently.
+pub type ContributionCallback = Arc<dyn Fn(&ContributionLine<'_>) + Send + Sync>;
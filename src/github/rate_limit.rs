@@ -0,0 +1,206 @@
+//! GraphQL rate limit tracking: every paginated query selects `rateLimit`, so the client can
+//! wait for the budget to reset instead of failing halfway through a report. Requests that
+//! hit a limit anyway, the primary one or a secondary one, are recognized from the response
+//! so they can be retried once it lifts.
+
+use super::{
+    issue_timelines, issues_page, prs_page, repo_triage, revert_pull_requests,
+    reviewed_pull_requests, reviews_page, team_members, user_activity,
+};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use serde_json::Value;
+
+/// How long to wait after hitting a secondary rate limit that doesn't say when to retry;
+/// GitHub asks for at least a minute.
+const SECONDARY_LIMIT_WAIT_SECS: u64 = 60;
+
+/// The rate limit budget reported with a GraphQL response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitStatus {
+    /// Points left in the current window.
+    pub remaining: i64,
+    /// Points the query that returned this status cost.
+    pub cost: i64,
+    /// When the budget resets (RFC 3339).
+    pub reset_at: String,
+}
+
+impl RateLimitStatus {
+    /// How long to wait before the next query of the same cost can run, or `None` if it can
+    /// run right away.
+    pub fn wait_duration(&self, now: DateTime<Utc>) -> Result<Option<std::time::Duration>> {
+        if self.remaining >= self.cost.max(1) {
+            return Ok(None);
+        }
+        let reset_at = DateTime::parse_from_rfc3339(&self.reset_at)
+            .with_context(|| format!("Invalid rate limit reset time: {}", self.reset_at))?;
+        // Wait one extra second so the request doesn't race the reset.
+        let wait = (reset_at.with_timezone(&Utc) - now).num_seconds().max(0) + 1;
+        Ok(Some(std::time::Duration::from_secs(wait as u64)))
+    }
+}
+
+/// How long to wait before retrying a GraphQL request whose response shows it hit a rate
+/// limit, or `None` if it didn't. The primary limit is reported as a `RATE_LIMITED` error, and
+/// secondary limits as a 403 or 429 response. The wait is taken from the `Retry-After`
+/// header, else from the reset time in the response or its `x-ratelimit-reset` header.
+pub fn retry_wait(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &Value,
+    now: DateTime<Utc>,
+) -> Option<std::time::Duration> {
+    let rate_limited = body["errors"]
+        .as_array()
+        .is_some_and(|errors| errors.iter().any(|error| error["type"] == "RATE_LIMITED"));
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let limited_status = matches!(
+        status,
+        StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+    ) && (header(RETRY_AFTER.as_str()).is_some()
+        || header("x-ratelimit-remaining") == Some("0")
+        || body["message"]
+            .as_str()
+            .is_some_and(|message| message.contains("rate limit")));
+    if !rate_limited && !limited_status {
+        return None;
+    }
+    if let Some(seconds) = header(RETRY_AFTER.as_str()).and_then(|value| value.parse().ok()) {
+        return Some(std::time::Duration::from_secs(seconds));
+    }
+    let reset_at = body["data"]["rateLimit"]["resetAt"]
+        .as_str()
+        .and_then(|reset_at| DateTime::parse_from_rfc3339(reset_at).ok())
+        .map(|reset_at| reset_at.timestamp())
+        .or_else(|| header("x-ratelimit-reset")?.parse().ok());
+    let seconds = match reset_at {
+        // Wait one extra second so the retry doesn't race the reset.
+        Some(reset_at) => (reset_at - now.timestamp()).max(0) as u64 + 1,
+        None => SECONDARY_LIMIT_WAIT_SECS,
+    };
+    Some(std::time::Duration::from_secs(seconds))
+}
+
+/// Response data of queries that select `rateLimit { remaining cost resetAt }`.
+pub trait RateLimited {
+    /// The rate limit status returned with the response, if any.
+    fn rate_limit(&self) -> Option<RateLimitStatus>;
+}
+
+macro_rules! impl_rate_limited {
+    ($($query:ident),*) => {
+        $(
+            impl RateLimited for $query::ResponseData {
+                fn rate_limit(&self) -> Option<RateLimitStatus> {
+                    self.rate_limit.as_ref().map(|r| RateLimitStatus {
+                        remaining: r.remaining,
+                        cost: r.cost,
+                        reset_at: r.reset_at.clone(),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_rate_limited!(
+    user_activity,
+    issues_page,
+    prs_page,
+    reviews_page,
+    repo_triage,
+    revert_pull_requests,
+    reviewed_pull_requests,
+    issue_timelines,
+    team_members
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn test_wait_duration() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let status = |remaining| RateLimitStatus {
+            remaining,
+            cost: 2,
+            reset_at: "2025-03-01T12:10:00Z".into(),
+        };
+        assert_eq!(status(5).wait_duration(now).unwrap(), None);
+        assert_eq!(
+            status(1).wait_duration(now).unwrap(),
+            Some(std::time::Duration::from_secs(601))
+        );
+        // A reset time in the past only waits the safety second.
+        let later = Utc.with_ymd_and_hms(2025, 3, 1, 13, 0, 0).unwrap();
+        assert_eq!(
+            status(0).wait_duration(later).unwrap(),
+            Some(std::time::Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn test_retry_wait() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let headers = |pairs: &[(&'static str, &str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.insert(*name, value.parse().unwrap());
+            }
+            headers
+        };
+        let rate_limited = json!({
+            "data": null,
+            "errors": [{ "type": "RATE_LIMITED", "message": "API rate limit exceeded" }]
+        });
+        let reset = now.timestamp() + 120;
+        assert_eq!(
+            retry_wait(
+                StatusCode::OK,
+                &headers(&[("x-ratelimit-reset", &reset.to_string())]),
+                &rate_limited,
+                now
+            ),
+            Some(std::time::Duration::from_secs(121))
+        );
+        let secondary = json!({ "message": "You have exceeded a secondary rate limit." });
+        assert_eq!(
+            retry_wait(
+                StatusCode::FORBIDDEN,
+                &headers(&[("retry-after", "30")]),
+                &secondary,
+                now
+            ),
+            Some(std::time::Duration::from_secs(30))
+        );
+        assert_eq!(
+            retry_wait(StatusCode::FORBIDDEN, &HeaderMap::new(), &secondary, now),
+            Some(std::time::Duration::from_secs(SECONDARY_LIMIT_WAIT_SECS))
+        );
+        // Other errors and forbidden responses aren't retried.
+        assert_eq!(
+            retry_wait(
+                StatusCode::OK,
+                &HeaderMap::new(),
+                &json!({ "errors": [{ "type": "NOT_FOUND" }] }),
+                now
+            ),
+            None
+        );
+        assert_eq!(
+            retry_wait(
+                StatusCode::FORBIDDEN,
+                &HeaderMap::new(),
+                &json!({ "message": "Resource not accessible by integration" }),
+                now
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! SAML single sign-on detection: organizations that enforce SSO reject tokens that haven't
+//! been authorized for them with a 403 carrying an `X-GitHub-SSO` header, which names the URL
+//! where the token can be authorized.
+
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+use std::fmt;
+
+/// Header GitHub sets on responses affected by SAML SSO enforcement.
+const SSO_HEADER: &str = "x-github-sso";
+
+/// A request was refused because the token isn't authorized for an organization's SAML SSO.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SsoAuthorizationRequired {
+    /// Where the token can be authorized, if GitHub sent one.
+    pub url: Option<String>,
+}
+
+impl fmt::Display for SsoAuthorizationRequired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The token is not authorized for an organization that enforces SAML single sign-on"
+        )?;
+        match &self.url {
+            Some(url) => write!(f, "; authorize it at {} and run again", url),
+            None => write!(
+                f,
+                "; authorize it under \"Configure SSO\" in the token settings and run again"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SsoAuthorizationRequired {}
+
+/// The SSO error a response stands for: a 403 whose `X-GitHub-SSO` header reads
+/// `required; url=<authorization URL>`. Other values (like `partial-results`, sent when only
+/// some organizations are hidden) are not errors.
+pub fn sso_error(status: StatusCode, headers: &HeaderMap) -> Option<SsoAuthorizationRequired> {
+    if status != StatusCode::FORBIDDEN {
+        return None;
+    }
+    let value = headers.get(SSO_HEADER)?.to_str().ok()?;
+    let mut parts = value.split(';').map(str::trim);
+    if parts.next() != Some("required") {
+        return None;
+    }
+    let url = parts
+        .find_map(|part| part.strip_prefix("url="))
+        .filter(|url| !url.is_empty())
+        .map(str::to_string);
+    Some(SsoAuthorizationRequired { url })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(SSO_HEADER, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_sso_error() {
+        let required =
+            headers("required; url=https://github.com/orgs/acme/sso?authorization_request=abc");
+        assert_eq!(
+            sso_error(StatusCode::FORBIDDEN, &required),
+            Some(SsoAuthorizationRequired {
+                url: Some("https://github.com/orgs/acme/sso?authorization_request=abc".into())
+            })
+        );
+        assert_eq!(
+            sso_error(StatusCode::FORBIDDEN, &headers("required")),
+            Some(SsoAuthorizationRequired { url: None })
+        );
+        assert_eq!(sso_error(StatusCode::OK, &required), None);
+        assert_eq!(
+            sso_error(
+                StatusCode::FORBIDDEN,
+                &headers("partial-results; organizations=21955855")
+            ),
+            None
+        );
+        assert_eq!(sso_error(StatusCode::FORBIDDEN, &HeaderMap::new()), None);
+    }
+}
@@ -1,11 +1,13 @@
-use crate::github::GithubClient;
-use chrono::Utc;
+use crate::github::{
+    ContributionCallback, GithubClient, OrganizationAccessDenied, PageSizes, ProgressCallback,
+    ProgressEvent, SsoAuthorizationRequired, range_chunks,
+};
+use crate::jsonl::ContributionLine;
+use chrono::{TimeZone, Utc};
 use serde_json::{Value, json};
-use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use temp_env::with_var;
+use std::sync::{Arc, Mutex};
 use tokio::runtime::Runtime;
-use wiremock::matchers::{method, path};
+use wiremock::matchers::{body_partial_json, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 // Helper: Build a full response containing all three connections.
@@ -53,14 +55,15 @@ fn build_full_response(
     })
 }
 
-// Helper to create a dummy GithubClient for testing.
+// Helper to create a dummy GithubClient for testing, pointed at the mock server.
 // We use a dummy token since wiremock intercepts the HTTP requests.
-fn create_test_client() -> GithubClient {
+fn create_test_client(mock_server: &MockServer) -> GithubClient {
     let dummy_token = "dummy_token".to_string();
+    let api_url = format!("{}/graphql", mock_server.uri());
     let username = "dummy".to_string();
     let start_date = Utc::now();
     let end_date = Utc::now();
-    GithubClient::new(dummy_token, username, start_date, end_date).unwrap()
+    GithubClient::new(dummy_token, api_url, username, start_date, end_date).unwrap()
 }
 
 #[test]
@@ -85,99 +88,62 @@ fn test_fetch_activity_base_error() {
         server
     });
 
-    // Now that the server is set up, use temp_env::with_var (closure-based).
-    with_var(
-        "GITHUB_GRAPHQL_URL",
-        Some(format!("{}/graphql", mock_server.uri())),
-        || {
-            // Create a fresh runtime inside the closure.
-            let rt2 = Runtime::new().unwrap();
-            rt2.block_on(async {
-                let client = create_test_client();
-                let result = client.fetch_activity().await;
-                assert!(
-                    result.is_err(),
-                    "Expected fetch_activity to fail due to base query errors"
-                );
-                let err_str = format!("{:?}", result.err().unwrap());
-                assert!(
-                    err_str.contains("GraphQL errors in base request"),
-                    "Error message did not contain expected text: {}",
-                    err_str
-                );
-            });
-        },
-    );
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let result = client.fetch_activity().await;
+        assert!(
+            result.is_err(),
+            "Expected fetch_activity to fail due to base query errors"
+        );
+        let err_str = format!("{:?}", result.err().unwrap());
+        assert!(
+            err_str.contains("GraphQL errors in base request"),
+            "Error message did not contain expected text: {}",
+            err_str
+        );
+    });
 }
 #[test]
 fn test_fetch_activity_merge_data() {
     // Create an initial runtime for async setup.
     let rt = Runtime::new().unwrap();
 
+    let issue = |number: i64| {
+        json!({
+            "issue": {
+                "id": format!("I_{}", number),
+                "number": number,
+                "title": format!("Issue {}", number),
+                "url": format!("http://example.com/issue{}", number),
+                "createdAt": "2025-03-01T00:00:00Z",
+                "state": "open",
+                "closedAt": null
+            }
+        })
+    };
+    let review = |number: i64| {
+        json!({
+            "occurredAt": "2025-03-01T00:00:00Z",
+            "pullRequestReview": {
+                "pullRequest": {
+                    "number": number,
+                    "title": format!("Review {}", number),
+                    "url": format!("http://example.com/prreview{}", number)
+                },
+                "state": "APPROVED"
+            }
+        })
+    };
+
     // Start the mock server and mount the responses.
     let mock_server = rt.block_on(async {
         let server = MockServer::start().await;
+        let no_next_page = json!({ "endCursor": null, "hasNextPage": false });
 
-        // Build the base response.
-        let base_response = json!({
-            "data": {
-                "user": {
-                    "contributionsCollection": {
-                        "totalCommitContributions": 5,
-                        "totalIssueContributions": 0,
-                        "totalPullRequestContributions": 0,
-                        "totalPullRequestReviewContributions": 0,
-                        "contributionCalendar": {
-                            "totalContributions": 5,
-                            "weeks": []
-                        },
-                        "commitContributionsByRepository": [],
-                        "issueContributions": {
-                            "totalCount": 0,
-                            "pageInfo": { "endCursor": null, "hasNextPage": false },
-                            "nodes": []
-                        },
-                        "pullRequestContributions": {
-                            "totalCount": 0,
-                            "pageInfo": { "endCursor": null, "hasNextPage": false },
-                            "nodes": []
-                        },
-                        "pullRequestReviewContributions": {
-                            "totalCount": 0,
-                            "pageInfo": { "endCursor": null, "hasNextPage": false },
-                            "nodes": []
-                        }
-                    }
-                }
-            }
-        });
-
-        // Build paginated responses.
-        let issue_response = build_full_response(
-            Some(json!({
-                "issue": {
-                    "number": 1,
-                    "title": "Issue 1",
-                    "url": "http://example.com/issue1",
-                    "createdAt": "2025-03-01T00:00:00Z",
-                    "state": "open",
-                    "closedAt": null,
-                    "repository": {
-                        "nameWithOwner": "owner/repo1",
-                        "updatedAt": "2025-03-01T00:00:00Z"
-                    }
-                }
-            })),
-            json!({ "endCursor": null, "hasNextPage": false }),
-            None,
-            json!({ "endCursor": null, "hasNextPage": false }),
-            None,
-            json!({ "endCursor": null, "hasNextPage": false }),
-        );
-
-        let pr_response = build_full_response(
-            None,
-            json!({ "endCursor": null, "hasNextPage": false }),
+        // The base request brings the first page of every connection.
+        let mut base_response = build_full_response(
+            Some(issue(1)),
+            json!({ "endCursor": "i1", "hasNextPage": true }),
             Some(json!({
                 "pullRequest": {
                     "number": 101,
@@ -188,90 +154,1156 @@ fn test_fetch_activity_merge_data() {
                     "merged": false,
                     "mergedAt": null,
                     "closedAt": null,
-                    "repository": {
-                        "nameWithOwner": "owner/repo1",
-                        "updatedAt": "2025-03-01T00:00:00Z"
-                    }
+                    "additions": 10,
+                    "deletions": 2,
+                    "changedFiles": 1
                 }
             })),
-            json!({ "endCursor": null, "hasNextPage": false }),
-            None,
-            json!({ "endCursor": null, "hasNextPage": false }),
+            no_next_page.clone(),
+            Some(review(201)),
+            json!({ "endCursor": "r1", "hasNextPage": true }),
         );
+        base_response["data"]["user"]["contributionsCollection"]["totalCommitContributions"] =
+            json!(5);
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(
+                json!({ "operationName": "UserActivity" }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(base_response))
+            .expect(1)
+            .mount(&server)
+            .await;
 
-        let pr_review_response = build_full_response(
+        // Only the connections with a next page are paged through, after the base cursors.
+        let issue_response = build_full_response(
+            Some(issue(2)),
+            no_next_page.clone(),
             None,
-            json!({ "endCursor": null, "hasNextPage": false }),
+            no_next_page.clone(),
             None,
-            json!({ "endCursor": null, "hasNextPage": false }),
-            Some(json!({
-                "occurredAt": "2025-03-01T00:00:00Z",
-                "pullRequestReview": {
-                    "createdAt": "2025-03-01T00:00:00Z",
-                    "pullRequest": {
-                        "number": 201,
-                        "title": "Review 1",
-                        "url": "http://example.com/prreview1",
-                        "createdAt": "2025-03-01T00:00:00Z",
-                        "state": "open"
+            no_next_page.clone(),
+        );
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(json!({
+                "operationName": "IssuesPage",
+                "variables": { "after": "i1" }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(issue_response))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let review_response = build_full_response(
+            None,
+            no_next_page.clone(),
+            None,
+            no_next_page.clone(),
+            Some(review(202)),
+            no_next_page,
+        );
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(json!({
+                "operationName": "ReviewsPage",
+                "variables": { "after": "r1" }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(review_response))
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    });
+
+    let streamed = Arc::new(Mutex::new(Vec::new()));
+    let recorded = streamed.clone();
+    let callback: ContributionCallback = Arc::new(move |line: &ContributionLine<'_>| {
+        let value = serde_json::to_value(line).unwrap();
+        recorded
+            .lock()
+            .unwrap()
+            .push(value["type"].as_str().unwrap().to_string());
+    });
+    rt.block_on(async {
+        let client = create_test_client(&mock_server).on_contributions(Some(callback));
+        let merged_data = client
+            .fetch_activity()
+            .await
+            .expect("fetch_activity failed");
+        let mut streamed = streamed.lock().unwrap().clone();
+        streamed.sort();
+        assert_eq!(
+            streamed,
+            ["issue", "issue", "pull_request", "review", "review"]
+        );
+        let user = merged_data.user.expect("Expected user data");
+        let contributions = user.contributions_collection;
+        assert_eq!(contributions.total_commit_contributions, 5);
+        let issue_nodes = contributions
+            .issue_contributions
+            .nodes
+            .expect("Expected issue nodes");
+        let pr_nodes = contributions
+            .pull_request_contributions
+            .nodes
+            .expect("Expected PR nodes");
+        let pr_review_nodes = contributions
+            .pull_request_review_contributions
+            .nodes
+            .expect("Expected PR review nodes");
+
+        let issue_numbers: Vec<i64> = issue_nodes.iter().map(|n| n.issue.number).collect();
+        assert_eq!(issue_numbers, [1, 2]);
+        assert_eq!(pr_nodes.len(), 1, "Expected 1 PR node");
+        let review_numbers: Vec<i64> = pr_review_nodes
+            .iter()
+            .map(|n| n.pull_request_review.pull_request.number)
+            .collect();
+        assert_eq!(review_numbers, [201, 202]);
+    });
+}
+
+#[test]
+fn test_fetch_repo_triage() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        let response = json!({
+            "data": {
+                "repository": {
+                    "issues": {
+                        "pageInfo": { "endCursor": null, "hasNextPage": false },
+                        "nodes": [{
+                            "number": 7,
+                            "title": "Needs triage",
+                            "url": "http://example.com/issues/7",
+                            "createdAt": "2025-03-01T00:00:00Z",
+                            "author": { "__typename": "User", "login": "reporter" },
+                            "timelineItems": { "nodes": [{
+                                "__typename": "LabeledEvent",
+                                "actor": { "__typename": "User", "login": "dummy" },
+                                "createdAt": "2025-03-02T00:00:00Z",
+                                "label": { "name": "bug" }
+                            }] }
+                        }]
                     }
                 }
-            })),
-            json!({ "endCursor": null, "hasNextPage": false }),
+            }
+        });
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let issues = client
+            .fetch_repo_triage("owner/repo")
+            .await
+            .expect("fetch_repo_triage failed");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].number, 7);
+    });
+}
+
+#[test]
+fn test_fetch_repo_triage_missing_repository() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "data": { "repository": null } })),
+            )
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let result = client.fetch_repo_triage("owner/missing").await;
+        let err_str = format!("{:?}", result.err().unwrap());
+        assert!(
+            err_str.contains("Repository owner/missing not found"),
+            "Error message did not contain expected text: {}",
+            err_str
         );
+    });
+}
+
+#[test]
+fn test_fetch_issue_timelines() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        let response = json!({
+            "data": {
+                "nodes": [
+                    {
+                        "__typename": "Issue",
+                        "url": "http://example.com/issues/1",
+                        "timelineItems": { "nodes": [{
+                            "__typename": "ReopenedEvent",
+                            "actor": { "__typename": "User", "login": "dummy" },
+                            "createdAt": "2025-03-02T00:00:00Z"
+                        }] }
+                    },
+                    // Deleted or inaccessible nodes come back as null.
+                    null
+                ]
+            }
+        });
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let issues = client
+            .fetch_issue_timelines(&["I_1".to_string(), "I_2".to_string()])
+            .await
+            .expect("fetch_issue_timelines failed");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].url, "http://example.com/issues/1");
+    });
+}
+
+#[test]
+fn test_fetch_reviewed_pull_requests() {
+    let rt = Runtime::new().unwrap();
 
-        // Use an atomic counter to sequence the responses.
-        let call_counter = Arc::new(AtomicUsize::new(0));
-        let counter_clone = call_counter.clone();
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        let response = json!({
+            "data": {
+                "search": {
+                    "pageInfo": { "endCursor": null, "hasNextPage": false },
+                    "nodes": [
+                        {
+                            "__typename": "PullRequest",
+                            "createdAt": "2025-03-02T00:00:00Z",
+                            "reviews": { "nodes": [{ "submittedAt": "2025-03-02T06:00:00Z" }] }
+                        },
+                        { "__typename": "Issue" }
+                    ]
+                },
+                "rateLimit": null
+            }
+        });
         Mock::given(method("POST"))
             .and(path("/graphql"))
-            .respond_with(move |_req: &wiremock::Request| {
-                let call_num = counter_clone.fetch_add(1, Ordering::SeqCst);
-                match call_num {
-                    0 => ResponseTemplate::new(200).set_body_json(base_response.clone()),
-                    1 => ResponseTemplate::new(200).set_body_json(issue_response.clone()),
-                    2 => ResponseTemplate::new(200).set_body_json(pr_response.clone()),
-                    3 => ResponseTemplate::new(200).set_body_json(pr_review_response.clone()),
-                    _ => ResponseTemplate::new(200).set_body_string("{\"data\":{\"user\":null}}"),
+            .and(body_partial_json(json!({
+                "variables": {
+                    "author": "dummy",
+                    "query": "is:pr reviewed-by:dummy -author:dummy updated:2025-03-01..2025-03-31"
+                }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = GithubClient::new(
+            "dummy_token".to_string(),
+            format!("{}/graphql", mock_server.uri()),
+            "dummy".to_string(),
+            "2025-03-01T00:00:00Z".parse().unwrap(),
+            "2025-03-31T23:59:59Z".parse().unwrap(),
+        )
+        .unwrap();
+        let prs = client
+            .fetch_reviewed_pull_requests()
+            .await
+            .expect("fetch_reviewed_pull_requests failed");
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].created_at, "2025-03-02T00:00:00Z");
+    });
+}
+
+#[test]
+fn test_count_search_results() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(json!({
+                "variables": { "query": "repo:owner/repo is:pr is:merged" }
+            })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "data": { "search": { "issueCount": 42 } } })),
+            )
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let count = client
+            .count_search_results("repo:owner/repo is:pr is:merged")
+            .await
+            .expect("count_search_results failed");
+        assert_eq!(count, 42);
+    });
+}
+
+#[test]
+fn test_tag_contains() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/compare/abc123...v1.0.0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "status": "ahead" })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/compare/abc123...v0.9.0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "status": "behind" })))
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        assert!(
+            client
+                .tag_contains("owner/repo", "v1.0.0", "abc123")
+                .await
+                .unwrap()
+        );
+        assert!(
+            !client
+                .tag_contains("owner/repo", "v0.9.0", "abc123")
+                .await
+                .unwrap()
+        );
+    });
+}
+
+#[test]
+fn test_fetch_pr_files() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/pulls/7/files"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "filename": "packages/frontend/app.ts", "status": "modified" },
+                { "filename": "README.md", "status": "modified" }
+            ])))
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let files = client
+            .fetch_pr_files("owner/repo", 7)
+            .await
+            .expect("fetch_pr_files failed");
+        assert_eq!(files, ["packages/frontend/app.ts", "README.md"]);
+    });
+}
+
+#[test]
+fn test_fetch_unlinked_commits_and_emails() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/owner/repo/commits"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                {
+                    "sha": "abc",
+                    "author": null,
+                    "commit": { "author": { "name": "Dummy", "email": "dummy@laptop.local" } }
+                },
+                {
+                    "sha": "def",
+                    "author": { "login": "dummy" },
+                    "commit": { "author": { "name": "Dummy", "email": "dummy@example.com" } }
+                }
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/user/emails"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "email": "dummy@example.com", "verified": true, "primary": true, "visibility": null }
+            ])))
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let commits = client
+            .fetch_unlinked_commits("owner/repo")
+            .await
+            .expect("fetch_unlinked_commits failed");
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].author_email, "dummy@laptop.local");
+        let emails = client
+            .fetch_viewer_emails()
+            .await
+            .expect("fetch_viewer_emails failed");
+        assert_eq!(emails.len(), 1);
+        assert!(emails[0].verified);
+    });
+}
+
+#[test]
+fn test_sso_authorization_required() {
+    let rt = Runtime::new().unwrap();
+    let url = "https://github.com/orgs/acme/sso?authorization_request=abc";
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .insert_header("X-GitHub-SSO", format!("required; url={}", url).as_str())
+                    .set_body_json(json!({ "message": "Resource protected by organization SAML enforcement." })),
+            )
+            .mount(&server)
+            .await;
+        server
+    });
+
+    let err = rt.block_on(async {
+        create_test_client(&mock_server)
+            .fetch_activity()
+            .await
+            .unwrap_err()
+    });
+    let sso = err
+        .downcast_ref::<SsoAuthorizationRequired>()
+        .expect("expected an SSO error");
+    assert_eq!(sso.url.as_deref(), Some(url));
+    assert!(sso.to_string().contains(url));
+}
+
+#[test]
+fn test_publish_gist() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/gists"))
+            .and(body_partial_json(json!({
+                "public": false,
+                "files": { "dummy.md": { "content": "# Report" } }
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "id": "abc",
+                "html_url": "https://gist.github.com/abc"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/gists/abc"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "abc",
+                "html_url": "https://gist.github.com/abc"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let files = [("dummy.md".to_string(), "# Report".to_string())];
+        for id in [None, Some("abc")] {
+            let url = client
+                .publish_gist(id, "GitHub activity", &files)
+                .await
+                .expect("publish_gist failed");
+            assert_eq!(url, "https://gist.github.com/abc");
+        }
+    });
+}
+
+#[test]
+fn test_create_issue_and_comment() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/octo/reports/issues"))
+            .and(body_partial_json(
+                json!({ "title": "Weekly", "body": "# Report" }),
+            ))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "number": 8,
+                "html_url": "https://github.com/octo/reports/issues/8"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/octo/reports/issues/7/comments"))
+            .and(body_partial_json(json!({ "body": "# Report" })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "html_url": "https://github.com/octo/reports/issues/7#issuecomment-1"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let issue = client
+            .create_issue("octo/reports", "Weekly", "# Report")
+            .await
+            .expect("create_issue failed");
+        assert_eq!(
+            issue,
+            (8, "https://github.com/octo/reports/issues/8".to_string())
+        );
+        let comment = client
+            .comment_on_issue("octo/reports", 7, "# Report")
+            .await
+            .expect("comment_on_issue failed");
+        assert!(comment.ends_with("#issuecomment-1"));
+    });
+}
+
+#[test]
+fn test_progress_events() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        let page = |login: &str, cursor: Option<&str>| {
+            json!({
+                "data": {
+                    "organization": {
+                        "team": {
+                            "members": {
+                                "pageInfo": { "endCursor": cursor, "hasNextPage": cursor.is_some() },
+                                "nodes": [{ "login": login }]
+                            }
+                        }
+                    },
+                    "rateLimit": null
                 }
             })
+        };
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "variables": { "after": null } })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page("alice", Some("c1"))))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "variables": { "after": "c1" } })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page("bob", None)))
             .mount(&server)
             .await;
         server
     });
 
-    // Now use temp_env::with_var in a synchronous closure.
-    with_var(
-        "GITHUB_GRAPHQL_URL",
-        Some(format!("{}/graphql", mock_server.uri())),
-        || {
-            // Create a new runtime inside the closure.
-            let rt2 = Runtime::new().unwrap();
-            rt2.block_on(async {
-                let client = create_test_client();
-                let merged_data = client
-                    .fetch_activity()
-                    .await
-                    .expect("fetch_activity failed");
-                let user = merged_data.user.expect("Expected user data");
-                let contributions = user.contributions_collection;
-                let issue_nodes = contributions
-                    .issue_contributions
-                    .nodes
-                    .expect("Expected issue nodes");
-                let pr_nodes = contributions
-                    .pull_request_contributions
-                    .nodes
-                    .expect("Expected PR nodes");
-                let pr_review_nodes = contributions
-                    .pull_request_review_contributions
-                    .nodes
-                    .expect("Expected PR review nodes");
-
-                assert_eq!(issue_nodes.len(), 1, "Expected 1 issue node");
-                assert_eq!(pr_nodes.len(), 1, "Expected 1 PR node");
-                assert_eq!(pr_review_nodes.len(), 1, "Expected 1 PR review node");
-            });
-        },
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let callback: ProgressCallback =
+        Arc::new(move |event: &ProgressEvent| recorded.lock().unwrap().push(event.clone()));
+    let members = rt.block_on(async {
+        create_test_client(&mock_server)
+            .on_progress(Some(callback))
+            .fetch_team_members("org/team")
+            .await
+            .expect("fetch_team_members failed")
+    });
+
+    assert_eq!(members, ["alice", "bob"]);
+    let events = events.lock().unwrap();
+    let pages: Vec<(usize, usize, bool)> = events
+        .iter()
+        .map(|event| (event.page, event.fetched, event.done))
+        .collect();
+    assert_eq!(pages, [(1, 1, false), (2, 2, true)]);
+    assert_eq!(events[0].connection, "members");
+    assert_eq!(events[0].user, "dummy");
+    assert_eq!(events[0].total, None);
+}
+
+#[test]
+fn test_pagination_fails_fast_when_rate_limit_is_exhausted() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {
+                    "search": {
+                        "pageInfo": { "endCursor": "cursor1", "hasNextPage": true },
+                        "nodes": []
+                    },
+                    "rateLimit": {
+                        "remaining": 0,
+                        "cost": 1,
+                        "resetAt": "2099-01-01T00:00:00Z"
+                    }
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server).wait_on_rate_limit(false);
+        let err = client
+            .fetch_revert_candidates("owner/repo")
+            .await
+            .expect_err("fetch should fail once the rate limit is exhausted");
+        assert!(err.to_string().contains("rate limit exhausted"));
+    });
+}
+
+#[test]
+fn test_rate_limited_request_is_retried() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("retry-after", "0")
+                    .set_body_json(json!({
+                        "data": null,
+                        "errors": [{ "type": "RATE_LIMITED", "message": "API rate limit exceeded" }]
+                    })),
+            )
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .insert_header("retry-after", "0")
+                    .set_body_json(json!({
+                        "message": "You have exceeded a secondary rate limit. Please wait a few minutes before you try again."
+                    })),
+            )
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "data": { "search": { "issueCount": 42 } } })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let count = client
+            .count_search_results("repo:owner/repo is:pr is:merged")
+            .await
+            .expect("the rate limited request should be retried");
+        assert_eq!(count, 42);
+        assert_eq!(client.request_count(), 3);
+    });
+}
+
+#[test]
+fn test_rate_limited_request_is_not_retried_without_waiting() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("retry-after", "0")
+                    .set_body_json(json!({
+                        "data": null,
+                        "errors": [{ "type": "RATE_LIMITED", "message": "API rate limit exceeded" }]
+                    })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server).wait_on_rate_limit(false);
+        let err = client
+            .count_search_results("repo:owner/repo is:pr is:merged")
+            .await
+            .expect_err("the rate limited request should fail");
+        assert!(err.to_string().contains("API rate limit exceeded"));
+    });
+}
+
+#[test]
+fn test_clock_skew() {
+    use super::{clock_skew, humanize_skew};
+    let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+    assert_eq!(clock_skew("Sat, 01 Mar 2025 11:58:00 GMT", now), None);
+    assert_eq!(clock_skew("not a date", now), None);
+    let ahead = clock_skew("Fri, 28 Feb 2025 09:55:00 GMT", now).unwrap();
+    assert_eq!(ahead, chrono::Duration::minutes(26 * 60 + 5));
+    assert_eq!(humanize_skew(ahead), "26h 5m");
+    let behind = clock_skew("Sat, 01 Mar 2025 12:10:00 GMT", now).unwrap();
+    assert!(behind < chrono::Duration::zero());
+    assert_eq!(humanize_skew(behind), "10m");
+}
+
+#[test]
+fn test_rest_url_for() {
+    use super::rest_url_for;
+    assert_eq!(
+        rest_url_for("https://api.github.com/graphql"),
+        "https://api.github.com"
+    );
+    assert_eq!(
+        rest_url_for("https://github.example.com/api/graphql"),
+        "https://github.example.com/api/v3"
     );
 }
+
+#[test]
+fn test_persisted_query_falls_back_to_post() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/graphql"))
+            .and(query_param("operationName", "RepoReleases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "errors": [{
+                    "message": "PersistedQueryNotFound",
+                    "extensions": { "code": "PERSISTED_QUERY_NOT_FOUND" }
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(json!({
+                "operationName": "RepoReleases",
+                "extensions": { "persistedQuery": { "version": 1 } }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": { "repository": { "releases": { "nodes": [] } } }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server).persisted_queries(true);
+        let releases = client
+            .fetch_releases("owner/repo")
+            .await
+            .expect("fetch_releases failed");
+        assert!(releases.is_empty());
+        assert_eq!(client.request_count(), 2);
+    });
+}
+
+#[test]
+fn test_persisted_query_served_over_get() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/graphql"))
+            .and(query_param("operationName", "RepoReleases"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": { "repository": { "releases": { "nodes": [] } } }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server).persisted_queries(true);
+        client
+            .fetch_releases("owner/repo")
+            .await
+            .expect("fetch_releases failed");
+    });
+}
+
+#[test]
+fn test_fetch_activity_uses_page_size_per_connection() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        let next_page = json!({ "endCursor": "c1", "hasNextPage": true });
+        let response = build_full_response(
+            None,
+            next_page.clone(),
+            None,
+            next_page.clone(),
+            None,
+            next_page,
+        );
+        let no_next_page = json!({ "endCursor": null, "hasNextPage": false });
+        let last_page = build_full_response(
+            None,
+            no_next_page.clone(),
+            None,
+            no_next_page.clone(),
+            None,
+            no_next_page,
+        );
+        // The base request asks for the first page of every connection with its own size.
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(json!({
+                "operationName": "UserActivity",
+                "variables": {
+                    "issuesFirst": 50,
+                    "prsFirst": 5,
+                    "prReviewsFirst": 20,
+                    "reviewBodies": true
+                }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response.clone()))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // Each page query asks for the next page of its own connection.
+        for (operation, first) in [("IssuesPage", 50), ("PrsPage", 5), ("ReviewsPage", 20)] {
+            Mock::given(method("POST"))
+                .and(path("/graphql"))
+                .and(body_partial_json(json!({
+                    "operationName": operation,
+                    "variables": { "first": first, "after": "c1" }
+                })))
+                .respond_with(ResponseTemplate::new(200).set_body_json(last_page.clone()))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server)
+            .page_sizes(PageSizes {
+                issues: 50,
+                prs: 5,
+                reviews: 20,
+            })
+            .review_bodies(true);
+        client
+            .fetch_activity()
+            .await
+            .expect("fetch_activity failed");
+    });
+}
+
+#[test]
+fn test_fetch_activity_scoped_to_organization() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(
+                json!({ "variables": { "login": "acme" } }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": { "organization": { "id": "O_kgDOABCDEF" } }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let next_page = json!({ "endCursor": "c1", "hasNextPage": true });
+        let response = build_full_response(
+            None,
+            next_page.clone(),
+            None,
+            next_page.clone(),
+            None,
+            next_page,
+        );
+        let no_next_page = json!({ "endCursor": null, "hasNextPage": false });
+        let last_page = build_full_response(
+            None,
+            no_next_page.clone(),
+            None,
+            no_next_page.clone(),
+            None,
+            no_next_page,
+        );
+        // The base request and every pagination request carry the organization scope.
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(json!({
+                "variables": { "organizationId": "O_kgDOABCDEF", "after": "c1" }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(last_page))
+            .expect(3)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(json!({
+                "operationName": "UserActivity",
+                "variables": { "organizationId": "O_kgDOABCDEF" }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(response))
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let id = client
+            .fetch_organization_id("acme")
+            .await
+            .expect("fetch_organization_id failed");
+        let client = client.organization_scope(Some(id));
+        assert_eq!(client.organization_id(), Some("O_kgDOABCDEF"));
+        client
+            .fetch_activity()
+            .await
+            .expect("fetch_activity failed");
+    });
+}
+
+#[test]
+fn test_organization_access_denied() {
+    let rt = Runtime::new().unwrap();
+    let message = "Although you appear to have the correct authorization credentials, the `acme` organization has enabled OAuth App access restrictions, meaning that data access to third-parties is limited.";
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        let denied = json!({
+            "data": { "organization": null },
+            "errors": [{ "type": "FORBIDDEN", "path": ["organization"], "message": message }]
+        });
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(
+                json!({ "variables": { "login": "acme" } }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(denied))
+            .mount(&server)
+            .await;
+        // A scope taken from elsewhere, e.g. a cached ID, fails the base request the same way.
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(json!({
+                "variables": { "organizationId": "O_kgDOABCDEF" }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": null,
+                "errors": [{ "type": "FORBIDDEN", "message": message }]
+            })))
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let err = client
+            .fetch_organization_id("acme")
+            .await
+            .expect_err("fetch_organization_id should fail");
+        let denied = err
+            .downcast_ref::<OrganizationAccessDenied>()
+            .expect("expected an OrganizationAccessDenied error");
+        assert_eq!(denied.message, message);
+
+        let err = client
+            .organization_scope(Some("O_kgDOABCDEF".into()))
+            .fetch_activity()
+            .await
+            .expect_err("fetch_activity should fail");
+        assert!(err.downcast_ref::<OrganizationAccessDenied>().is_some());
+    });
+}
+
+#[test]
+fn test_range_chunks() {
+    let start = Utc.with_ymd_and_hms(2022, 1, 1, 0, 0, 0).unwrap();
+    let year = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(range_chunks(start, year), [(start, year)]);
+
+    let end = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        range_chunks(start, end),
+        [
+            (
+                start,
+                Utc.with_ymd_and_hms(2022, 12, 31, 23, 59, 59).unwrap()
+            ),
+            (
+                year,
+                Utc.with_ymd_and_hms(2023, 12, 31, 23, 59, 59).unwrap()
+            ),
+            (Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(), end),
+        ]
+    );
+}
+
+#[test]
+fn test_fetch_activity_chunks_long_ranges() {
+    let rt = Runtime::new().unwrap();
+    let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        let no_next_page = json!({ "endCursor": null, "hasNextPage": false });
+        // Each chunk is fetched with its own base request, which brings every contribution.
+        for (from, commits, pr_number) in [
+            ("2023-01-01T00:00:00+00:00", 3, 1),
+            ("2024-01-01T00:00:00+00:00", 4, 2),
+        ] {
+            let mut response = build_full_response(
+                None,
+                no_next_page.clone(),
+                Some(json!({
+                    "pullRequest": {
+                        "number": pr_number,
+                        "title": "PR",
+                        "url": format!("http://example.com/pull/{}", pr_number),
+                        "createdAt": from,
+                        "state": "OPEN",
+                        "merged": false,
+                        "mergedAt": null,
+                        "closedAt": null,
+                        "additions": 1,
+                        "deletions": 0,
+                        "changedFiles": 1
+                    }
+                })),
+                no_next_page.clone(),
+                None,
+                no_next_page.clone(),
+            );
+            response["data"]["user"]["contributionsCollection"]["totalCommitContributions"] =
+                json!(commits);
+            Mock::given(method("POST"))
+                .and(path("/graphql"))
+                .and(body_partial_json(json!({ "variables": { "from": from } })))
+                .respond_with(ResponseTemplate::new(200).set_body_json(response))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+        server
+    });
+
+    let activity = rt.block_on(async {
+        create_test_client(&mock_server)
+            .for_user("dummy".into(), start, end)
+            .fetch_activity()
+            .await
+            .expect("fetch_activity failed")
+    });
+
+    let cc = activity.user.unwrap().contributions_collection;
+    assert_eq!(cc.total_commit_contributions, 7);
+    let prs = cc.pull_request_contributions.nodes.unwrap();
+    let numbers: Vec<i64> = prs.iter().map(|node| node.pull_request.number).collect();
+    assert_eq!(numbers, [1, 2]);
+}
+
+#[test]
+fn test_fetch_fork() {
+    let rt = Runtime::new().unwrap();
+
+    let mock_server = rt.block_on(async {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/graphql"))
+            .and(body_partial_json(json!({
+                "variables": { "owner": "octocat", "name": "rust" }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": { "repository": {
+                    "isFork": true,
+                    "parent": { "nameWithOwner": "rust-lang/rust" }
+                } }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    });
+
+    rt.block_on(async {
+        let client = create_test_client(&mock_server);
+        let repository = client
+            .fetch_fork("octocat/rust")
+            .await
+            .expect("fetch_fork failed");
+        assert!(repository.is_fork);
+        assert_eq!(
+            repository.parent.map(|parent| parent.name_with_owner),
+            Some("rust-lang/rust".to_string())
+        );
+    });
+}
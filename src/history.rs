@@ -0,0 +1,88 @@
+//! History module: remembers the usernames recently reported on, most recent first, so they
+//! can be offered by shell completion and by the interactive username prompt.
+
+use crate::last_run;
+use anyhow::{Context, Result};
+use log::debug;
+use std::fs;
+use std::path::PathBuf;
+
+/// How many usernames are remembered.
+const MAX_USERNAMES: usize = 20;
+
+/// A file of recently used usernames.
+pub struct RecentUsernames {
+    path: PathBuf,
+}
+
+impl RecentUsernames {
+    /// Recent usernames stored at `path`.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Recent usernames stored in the user's state directory, if there is one.
+    pub fn open() -> Option<Self> {
+        last_run::state_dir().map(|dir| Self::new(dir.join("recent-usernames.json")))
+    }
+
+    /// The remembered usernames, most recent first; none when the file is missing or
+    /// unreadable.
+    pub fn load(&self) -> Vec<String> {
+        let Ok(json) = fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|err| {
+            debug!(
+                "Ignoring unreadable username history {:?}: {}",
+                self.path, err
+            );
+            Vec::new()
+        })
+    }
+
+    /// Moves `usernames` to the front of the history, in the given order, dropping the oldest
+    /// beyond the limit. Usernames are compared case-insensitively, as GitHub does.
+    pub fn record(&self, usernames: &[String]) -> Result<()> {
+        let mut recent = usernames.to_vec();
+        for username in self.load() {
+            if !recent.iter().any(|u| u.eq_ignore_ascii_case(&username)) {
+                recent.push(username);
+            }
+        }
+        recent.truncate(MAX_USERNAMES);
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create state directory {:?}", dir))?;
+        }
+        let json = serde_json::to_string(&recent).context("Failed to serialize usernames")?;
+        fs::write(&self.path, json)
+            .with_context(|| format!("Failed to write username history {:?}", self.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_record_moves_usernames_to_front() {
+        let dir = env::temp_dir().join(format!("github-activity-history-{}", std::process::id()));
+        let history = RecentUsernames::new(dir.join("recent-usernames.json"));
+        assert!(history.load().is_empty());
+
+        history.record(&["alice".into(), "bob".into()]).unwrap();
+        history.record(&["carol".into(), "Alice".into()]).unwrap();
+        assert_eq!(history.load(), ["carol", "Alice", "bob"]);
+
+        let many: Vec<String> = (0..MAX_USERNAMES + 5)
+            .map(|i| format!("user{}", i))
+            .collect();
+        history.record(&many).unwrap();
+        assert_eq!(history.load(), many[..MAX_USERNAMES]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+//! Hook module: shell commands the config runs when a run finishes, `on_success` or
+//! `on_failure`, for custom post-processing like uploading the report or paging someone.
+//!
+//! ```toml
+//! [hooks]
+//! on_success = "aws s3 cp \"$GITHUB_ACTIVITY_REPORT\" s3://reports/"
+//! on_failure = "notify-send 'Activity report failed' \"$GITHUB_ACTIVITY_ERROR\""
+//! ```
+//!
+//! The command runs in the system shell (`sh -c`, or `cmd /C` on Windows) and learns about the
+//! run from `GITHUB_ACTIVITY_*` environment variables, gathered from the run's manifest.
+
+use crate::manifest::Manifest;
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::process::Command;
+
+/// The hooks of the configuration.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    /// Run after a run succeeded.
+    pub on_success: Option<String>,
+    /// Run after a run failed.
+    pub on_failure: Option<String>,
+}
+
+/// The environment variables describing a finished run to its hook: how it ended, the files it
+/// wrote, and totals over its reports. `error` is the failure message of a failed run.
+pub fn environment(manifest: &Manifest, error: Option<&str>) -> Vec<(&'static str, String)> {
+    let outputs: Vec<String> = manifest
+        .outputs
+        .iter()
+        .map(|output| output.path.display().to_string())
+        .collect();
+    let mut usernames: Vec<&str> = Vec::new();
+    for report in &manifest.reports {
+        if !usernames.contains(&report.username.as_str()) {
+            usernames.push(&report.username);
+        }
+    }
+    let contributions: i64 = manifest
+        .reports
+        .iter()
+        .map(|report| report.contributions)
+        .sum();
+    let status = match error {
+        Some(_) => "failure",
+        None => "success",
+    };
+    let mut env = vec![
+        ("GITHUB_ACTIVITY_STATUS", status.to_string()),
+        (
+            "GITHUB_ACTIVITY_REPORT",
+            outputs.first().cloned().unwrap_or_default(),
+        ),
+        ("GITHUB_ACTIVITY_OUTPUTS", outputs.join("\n")),
+        ("GITHUB_ACTIVITY_USERS", usernames.join(",")),
+        (
+            "GITHUB_ACTIVITY_REPORTS",
+            manifest.reports.len().to_string(),
+        ),
+        ("GITHUB_ACTIVITY_CONTRIBUTIONS", contributions.to_string()),
+        (
+            "GITHUB_ACTIVITY_API_REQUESTS",
+            manifest.api_requests.to_string(),
+        ),
+        (
+            "GITHUB_ACTIVITY_WARNINGS",
+            manifest.warnings.len().to_string(),
+        ),
+        (
+            "GITHUB_ACTIVITY_DURATION_SECONDS",
+            (manifest.finished_at - manifest.started_at)
+                .num_seconds()
+                .to_string(),
+        ),
+    ];
+    if let Some(error) = error {
+        env.push(("GITHUB_ACTIVITY_ERROR", error.to_string()));
+    }
+    env
+}
+
+/// Runs `command` in the system shell with `env` added to its environment, failing when it
+/// can't be started or exits unsuccessfully.
+pub fn run(name: &str, command: &str, env: &[(&'static str, String)]) -> Result<()> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let status = shell
+        .arg(command)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .status()
+        .with_context(|| format!("Failed to run the {} hook", name))?;
+    if !status.success() {
+        bail!("The {} hook failed ({})", name, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::{OutputFile, ReportInput};
+    use chrono::{Duration, TimeZone, Utc};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_environment() {
+        let started_at = Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap();
+        let report = |username: &str, contributions| ReportInput {
+            username: username.into(),
+            start_date: started_at - Duration::days(7),
+            end_date: started_at,
+            contributions,
+        };
+        let output = |path: &str| OutputFile {
+            path: PathBuf::from(path),
+            bytes: 1,
+            sha256: String::new(),
+        };
+        let manifest = Manifest {
+            manifest_version: 1,
+            generator: String::new(),
+            started_at,
+            finished_at: started_at + Duration::seconds(12),
+            arguments: Vec::new(),
+            reports: vec![
+                report("octocat", 5),
+                report("hubot", 2),
+                report("octocat", 1),
+            ],
+            api_requests: 9,
+            warnings: vec!["Slow".into()],
+            outputs: vec![output("out/report.md"), output("out/report.json")],
+        };
+        let env = environment(&manifest, None);
+        let value = |key| env.iter().find(|(k, _)| *k == key).map(|(_, v)| v.as_str());
+        assert_eq!(value("GITHUB_ACTIVITY_STATUS"), Some("success"));
+        assert_eq!(value("GITHUB_ACTIVITY_REPORT"), Some("out/report.md"));
+        assert_eq!(
+            value("GITHUB_ACTIVITY_OUTPUTS"),
+            Some("out/report.md\nout/report.json")
+        );
+        assert_eq!(value("GITHUB_ACTIVITY_USERS"), Some("octocat,hubot"));
+        assert_eq!(value("GITHUB_ACTIVITY_REPORTS"), Some("3"));
+        assert_eq!(value("GITHUB_ACTIVITY_CONTRIBUTIONS"), Some("8"));
+        assert_eq!(value("GITHUB_ACTIVITY_DURATION_SECONDS"), Some("12"));
+        assert_eq!(value("GITHUB_ACTIVITY_ERROR"), None);
+
+        let env = environment(&manifest, Some("Rate limited"));
+        let value = |key| env.iter().find(|(k, _)| *k == key).map(|(_, v)| v.as_str());
+        assert_eq!(value("GITHUB_ACTIVITY_STATUS"), Some("failure"));
+        assert_eq!(value("GITHUB_ACTIVITY_ERROR"), Some("Rate limited"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run() {
+        let env = [("GITHUB_ACTIVITY_STATUS", "success".to_string())];
+        run(
+            "on_success",
+            "test \"$GITHUB_ACTIVITY_STATUS\" = success",
+            &env,
+        )
+        .unwrap();
+        let err = run("on_success", "exit 3", &env).unwrap_err();
+        assert!(err.to_string().starts_with("The on_success hook failed"));
+    }
+}
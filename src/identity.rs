@@ -0,0 +1,158 @@
+//! Identity module: finds commits GitHub didn't attribute to the user because their author
+//! email isn't a verified email of the account, so users can fix their git config or add the
+//! email to their account instead of wondering where their commits went.
+//!
+//! GitHub leaves the author of such commits unlinked. An unlinked commit is taken to be the
+//! user's when its email is an unverified email of the account, or when its author name is the
+//! user's login or profile name.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// An email address of the authenticated user's account.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AccountEmail {
+    /// The address.
+    pub email: String,
+    /// Whether the address is verified; only verified addresses attribute commits.
+    pub verified: bool,
+}
+
+/// A commit whose author GitHub couldn't link to an account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnlinkedCommit {
+    /// Repository in the format owner/name.
+    pub repository: String,
+    /// The git author name.
+    pub author_name: String,
+    /// The git author email.
+    pub author_email: String,
+}
+
+/// An author email whose commits weren't attributed to the user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnattributedEmail {
+    /// The git author email of the commits.
+    pub email: String,
+    /// Whether the email is on the account but unverified; otherwise it isn't on the account.
+    pub unverified: bool,
+    /// How many commits were authored with the email.
+    pub commits: usize,
+    /// The repositories the commits are in, in name order.
+    pub repositories: Vec<String>,
+}
+
+/// Groups the unlinked commits that are the user's (`login`, with profile name `name`) by
+/// author email, most commits first. Commits of verified emails are linked by GitHub and never
+/// reported.
+pub fn unattributed_emails(
+    commits: &[UnlinkedCommit],
+    emails: &[AccountEmail],
+    login: &str,
+    name: Option<&str>,
+) -> Vec<UnattributedEmail> {
+    let account_email = |email: &str| {
+        emails
+            .iter()
+            .find(|account| account.email.eq_ignore_ascii_case(email))
+    };
+    let is_user = |author: &str| {
+        author.eq_ignore_ascii_case(login)
+            || name.is_some_and(|name| !name.is_empty() && author.eq_ignore_ascii_case(name))
+    };
+    let mut by_email: BTreeMap<String, (bool, usize, BTreeSet<&str>)> = BTreeMap::new();
+    for commit in commits {
+        let email = commit.author_email.to_lowercase();
+        let unverified = match account_email(&email) {
+            Some(account) if account.verified => continue,
+            Some(_) => true,
+            None if is_user(&commit.author_name) => false,
+            None => continue,
+        };
+        let entry = by_email
+            .entry(email)
+            .or_insert_with(|| (unverified, 0, BTreeSet::new()));
+        entry.1 += 1;
+        entry.2.insert(&commit.repository);
+    }
+    let mut unattributed: Vec<UnattributedEmail> = by_email
+        .into_iter()
+        .map(
+            |(email, (unverified, commits, repositories))| UnattributedEmail {
+                email,
+                unverified,
+                commits,
+                repositories: repositories.into_iter().map(str::to_string).collect(),
+            },
+        )
+        .collect();
+    // The sort is stable, so ties stay in email order.
+    unattributed.sort_by_key(|email| std::cmp::Reverse(email.commits));
+    unattributed
+}
+
+/// How to get the commits of `email` attributed.
+pub fn advice(email: &UnattributedEmail) -> &'static str {
+    if email.unverified {
+        "verify this email in your GitHub settings"
+    } else {
+        "add this email to your GitHub account, or set `git config user.email` to one of its emails"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(repository: &str, author_name: &str, author_email: &str) -> UnlinkedCommit {
+        UnlinkedCommit {
+            repository: repository.into(),
+            author_name: author_name.into(),
+            author_email: author_email.into(),
+        }
+    }
+
+    #[test]
+    fn test_unattributed_emails() {
+        let emails = [
+            AccountEmail {
+                email: "me@example.com".into(),
+                verified: true,
+            },
+            AccountEmail {
+                email: "me@work.example".into(),
+                verified: false,
+            },
+        ];
+        let commits = [
+            commit("o/a", "Someone", "Me@Work.example"),
+            commit("o/b", "Someone", "me@work.example"),
+            commit("o/a", "Mona Lisa", "mona@laptop.local"),
+            commit("o/a", "mona", "mona@laptop.local"),
+            commit("o/b", "mona", "me@example.com"),
+            commit("o/b", "Other Person", "other@example.com"),
+        ];
+        let unattributed = unattributed_emails(&commits, &emails, "mona", Some("Mona Lisa"));
+        assert_eq!(
+            unattributed,
+            [
+                UnattributedEmail {
+                    email: "me@work.example".into(),
+                    unverified: true,
+                    commits: 2,
+                    repositories: vec!["o/a".into(), "o/b".into()],
+                },
+                UnattributedEmail {
+                    email: "mona@laptop.local".into(),
+                    unverified: false,
+                    commits: 2,
+                    repositories: vec!["o/a".into()],
+                },
+            ]
+        );
+        assert_eq!(
+            advice(&unattributed[0]),
+            "verify this email in your GitHub settings"
+        );
+    }
+}
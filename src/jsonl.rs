@@ -0,0 +1,192 @@
+//! JSON Lines module: reports as one JSON object per contribution node, for piping into `jq`
+//! or log pipelines.
+//!
+//! Each line carries the `username` it belongs to and a `type` discriminator (`repository`,
+//! `issue`, `pull_request`, or `review`), followed by the fields of the matching item of the
+//! report JSON model (see [`crate::model`]), e.g.:
+//!
+//! ```json
+//! {"username":"octocat","type":"pull_request","number":1,"title":"Fix",...}
+//! ```
+//!
+//! Lines are small and self-contained, so they can be written while pages are still being
+//! fetched; see [`GithubClient::on_contributions`](crate::GithubClient::on_contributions).
+
+use crate::github::user_activity::{
+    UserActivityUserContributionsCollectionCommitContributionsByRepository as RepositoryNode,
+    UserActivityUserContributionsCollectionIssueContributionsNodes as IssueNode,
+    UserActivityUserContributionsCollectionPullRequestContributionsNodes as PullRequestNode,
+    UserActivityUserContributionsCollectionPullRequestReviewContributionsNodes as ReviewNode,
+};
+use crate::model::{Issue, PullRequest, RepositoryCommits, Review};
+use crate::report::Report;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::Write;
+
+/// A contribution, tagged with its kind.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Contribution {
+    /// The user's commits to a repository.
+    Repository(RepositoryCommits),
+    /// An issue the user opened.
+    Issue(Issue),
+    /// A pull request the user opened.
+    PullRequest(PullRequest),
+    /// A pull request review the user submitted.
+    Review(Review),
+}
+
+impl From<&RepositoryNode> for Contribution {
+    fn from(node: &RepositoryNode) -> Self {
+        Self::Repository(node.into())
+    }
+}
+
+impl From<&IssueNode> for Contribution {
+    fn from(node: &IssueNode) -> Self {
+        Self::Issue(node.into())
+    }
+}
+
+impl From<&PullRequestNode> for Contribution {
+    fn from(node: &PullRequestNode) -> Self {
+        Self::PullRequest(node.into())
+    }
+}
+
+impl From<&ReviewNode> for Contribution {
+    fn from(node: &ReviewNode) -> Self {
+        Self::Review(node.into())
+    }
+}
+
+/// A line of the JSON Lines output: a contribution of a user.
+#[derive(Debug, Clone, Serialize)]
+pub struct ContributionLine<'a> {
+    /// The user the contribution belongs to.
+    pub username: &'a str,
+    /// The contribution.
+    #[serde(flatten)]
+    pub contribution: Contribution,
+}
+
+impl ContributionLine<'_> {
+    /// Writes the line, terminated by a newline, into `writer`.
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<()> {
+        serde_json::to_writer(&mut writer, self).context("Failed to serialize contribution")?;
+        writer
+            .write_all(b"\n")
+            .context("Failed to write JSON Lines")
+    }
+}
+
+/// The contributions of `report`: repositories with commits, then issues, pull requests, and
+/// reviews.
+pub fn contributions(report: &Report) -> Vec<Contribution> {
+    let Some(user) = &report.activity.user else {
+        return Vec::new();
+    };
+    let cc = &user.contributions_collection;
+    let repositories = cc
+        .commit_contributions_by_repository
+        .iter()
+        .map(Contribution::from);
+    let issues = cc
+        .issue_contributions
+        .nodes
+        .iter()
+        .flatten()
+        .map(Contribution::from);
+    let pull_requests = cc
+        .pull_request_contributions
+        .nodes
+        .iter()
+        .flatten()
+        .map(Contribution::from);
+    let reviews = cc
+        .pull_request_review_contributions
+        .nodes
+        .iter()
+        .flatten()
+        .map(Contribution::from);
+    repositories
+        .chain(issues)
+        .chain(pull_requests)
+        .chain(reviews)
+        .collect()
+}
+
+/// Writes the contributions of `reports` into `writer`, one line each.
+pub fn write_contribution_lines<W: Write>(reports: &[Report], mut writer: W) -> Result<()> {
+    for report in reports {
+        for contribution in contributions(report) {
+            ContributionLine {
+                username: &report.username,
+                contribution,
+            }
+            .write(&mut writer)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::{Value, json};
+
+    #[test]
+    fn test_write_contribution_lines() {
+        let page = |nodes: Value| json!({ "totalCount": 1, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes });
+        let activity = serde_json::from_value(json!({ "user": { "contributionsCollection": {
+            "totalCommitContributions": 3,
+            "totalIssueContributions": 0,
+            "totalPullRequestContributions": 1,
+            "totalPullRequestReviewContributions": 1,
+            "contributionCalendar": { "totalContributions": 5, "weeks": [] },
+            "commitContributionsByRepository": [{
+                "repository": { "nameWithOwner": "o/b", "updatedAt": "2025-03-02T00:00:00Z" },
+                "contributions": { "totalCount": 3 }
+            }],
+            "issueContributions": page(json!([])),
+            "pullRequestContributions": page(json!([{ "pullRequest": {
+                "number": 2, "title": "PR", "url": "https://github.com/o/b/pull/2",
+                "createdAt": "2025-03-02T10:00:00Z", "state": "OPEN", "merged": false,
+                "mergedAt": null, "closedAt": null, "additions": 5, "deletions": 1, "changedFiles": 2
+            } }])),
+            "pullRequestReviewContributions": page(json!([{
+                "pullRequestReview": { "pullRequest": { "number": 3, "title": "Other", "url": "https://github.com/o/b/pull/3" }, "state": "APPROVED" },
+                "occurredAt": "2025-03-03T00:00:00Z"
+            }]))
+        } } }))
+        .unwrap();
+        let report = Report::new(
+            "octocat".into(),
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 8, 0, 0, 0).unwrap(),
+            activity,
+        );
+
+        let mut output = Vec::new();
+        write_contribution_lines(&[report], &mut output).unwrap();
+        let lines: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let types: Vec<&str> = lines
+            .iter()
+            .map(|line| line["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(types, ["repository", "pull_request", "review"]);
+        assert!(lines.iter().all(|line| line["username"] == "octocat"));
+        assert_eq!(lines[0]["repository"], "o/b");
+        assert_eq!(lines[1]["number"], 2);
+        assert_eq!(lines[1]["labels"], json!([]));
+        assert_eq!(lines[2]["state"], "APPROVED");
+        assert_eq!(lines[2]["pull_request"]["number"], 3);
+    }
+}
@@ -0,0 +1,198 @@
+//! Last run module: remembers the unfiltered activity of each user's previous
+//! `--since-last-run` runs, so the next run only fetches what they don't cover.
+//!
+//! The activity is kept as segments, one per fetched time range, in the `last-run` namespace
+//! of the store, keyed by API endpoint and username. Each segment is a report in the regular
+//! JSON format, so older snapshots are migrated on load. A run reuses the segments that lie
+//! within its range and keeps only those, so a rolling `--period` neither grows the report nor
+//! the stored state: commit counts aren't dated, so a segment can't be cut down to a shorter
+//! range.
+
+use crate::storage::Storage;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use github_activity_rs::Report;
+use github_activity_rs::merge::merge_activity;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::env;
+use std::path::PathBuf;
+
+/// This tool's directory in the user's state directory, following the XDG base directory
+/// spec.
+pub fn state_dir() -> Option<PathBuf> {
+    let state_dir = env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))?;
+    Some(state_dir.join("github-activity-rs"))
+}
+
+/// Namespace of the reports of previous runs in the store.
+const NAMESPACE: &str = "last-run";
+
+/// The reports of previous runs in a store.
+pub struct LastRuns {
+    storage: Storage,
+}
+
+impl LastRuns {
+    /// Previous runs stored in `storage`.
+    pub fn new(storage: Storage) -> Self {
+        Self { storage }
+    }
+
+    /// The key of the previous run of `username` against `api_url`, fetched with `scope`: what else,
+    /// like an organization scope, sets the fetched activity apart.
+    pub fn key(api_url: &str, username: &str, scope: &str) -> String {
+        let digest = Sha256::digest(format!("{}\n{}{}", api_url, username.to_lowercase(), scope));
+        format!("{:x}", digest)
+    }
+
+    /// The segments stored under `key`; a single report stored by earlier versions is one
+    /// segment.
+    pub fn load(&self, key: &str) -> Result<Vec<Report>> {
+        let Some(json) = self.storage.get(NAMESPACE, key)? else {
+            return Ok(Vec::new());
+        };
+        let value: Value = serde_json::from_str(&json)
+            .with_context(|| format!("Failed to parse last run {}", key))?;
+        let segments = match value {
+            Value::Array(segments) => segments,
+            report => vec![report],
+        };
+        segments
+            .iter()
+            .map(|segment| Report::from_json(&segment.to_string()))
+            .collect::<Result<_>>()
+            .with_context(|| format!("Failed to load last run {}", key))
+    }
+
+    /// Stores `segments` under `key`, replacing the previous runs.
+    pub fn store(&self, key: &str, segments: &[Report]) -> Result<()> {
+        let json = serde_json::to_string(segments).context("Failed to serialize last run")?;
+        self.storage.put(NAMESPACE, key, &json)
+    }
+}
+
+/// A time range still to be fetched.
+pub type Gap = (DateTime<Utc>, DateTime<Utc>);
+
+/// Splits the requested range `start..end` into the stored `segments` that can be reused,
+/// oldest first, and the ranges between them that still have to be fetched.
+///
+/// Segments reaching outside the range, or overlapping an earlier reused one, are dropped.
+pub fn plan(
+    mut segments: Vec<Report>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> (Vec<Report>, Vec<Gap>) {
+    segments.sort_by_key(|segment| segment.start_date);
+    let mut reused = Vec::new();
+    let mut gaps = Vec::new();
+    let mut covered_until = start;
+    for segment in segments {
+        if segment.start_date < covered_until || segment.end_date > end {
+            continue;
+        }
+        if covered_until < segment.start_date {
+            gaps.push((covered_until, segment.start_date));
+        }
+        covered_until = segment.end_date;
+        reused.push(segment);
+    }
+    if covered_until < end || reused.is_empty() {
+        gaps.push((covered_until, end));
+    }
+    (reused, gaps)
+}
+
+/// The report of `username` for `start..end`, merged from `segments` that cover the range
+/// one after another.
+pub fn assemble(
+    username: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    mut segments: Vec<Report>,
+) -> Report {
+    segments.sort_by_key(|segment| segment.start_date);
+    let activity = segments
+        .into_iter()
+        .map(|segment| segment.activity)
+        .reduce(merge_activity)
+        .unwrap_or_default();
+    Report::new(username, start, end, activity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_store_and_load() {
+        let runs = LastRuns::new(std::sync::Arc::new(
+            crate::storage::SqliteBackend::in_memory().unwrap(),
+        ));
+        let key = LastRuns::key("https://api.github.com/graphql", "octocat", "");
+        assert!(runs.load(&key).unwrap().is_empty());
+
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 3, 2, 0, 0, 0).unwrap();
+        let report = Report::new("octocat".into(), start, end, Default::default());
+        runs.store(&key, std::slice::from_ref(&report)).unwrap();
+        assert_eq!(runs.load(&key).unwrap(), std::slice::from_ref(&report));
+
+        // A single report, as stored by earlier versions, is one segment.
+        runs.storage
+            .put(NAMESPACE, &key, &report.to_json().unwrap())
+            .unwrap();
+        assert_eq!(runs.load(&key).unwrap(), [report]);
+        assert_eq!(
+            key,
+            LastRuns::key("https://api.github.com/graphql", "OctoCat", "")
+        );
+        assert_ne!(
+            key,
+            LastRuns::key(
+                "https://api.github.com/graphql",
+                "octocat",
+                " in organization O_1"
+            )
+        );
+    }
+
+    #[test]
+    fn test_rolling_runs_keep_the_requested_range() {
+        let day = |d: u32| Utc.with_ymd_and_hms(2025, 3, d, 0, 0, 0).unwrap();
+        let segment = |start, end, commits| {
+            let mut activity = github_activity_rs::github::user_activity::ResponseData::default();
+            let mut user = github_activity_rs::github::user_activity::UserActivityUser::default();
+            user.contributions_collection.total_commit_contributions = commits;
+            activity.user = Some(user);
+            Report::new("octocat".into(), start, end, activity)
+        };
+
+        // A first `--period 2d` run on March 3rd, then another one on the 4th.
+        let (reused, gaps) = plan(Vec::new(), day(1), day(3));
+        assert!(reused.is_empty());
+        assert_eq!(gaps, [(day(1), day(3))]);
+        let stored = vec![segment(day(1), day(2), 1), segment(day(2), day(3), 2)];
+
+        let (reused, gaps) = plan(stored, day(2), day(4));
+        assert_eq!(reused.len(), 1);
+        assert_eq!(reused[0].start_date, day(2));
+        assert_eq!(gaps, [(day(3), day(4))]);
+
+        let mut segments = reused;
+        segments.push(segment(day(3), day(4), 4));
+        let report = assemble("octocat".into(), day(2), day(4), segments.clone());
+        assert_eq!(report.end_date - report.start_date, Duration::days(2));
+        let cc = report.activity.user.unwrap().contributions_collection;
+        assert_eq!(cc.total_commit_contributions, 6);
+
+        // A run already covered by the stored segments fetches nothing.
+        let (reused, gaps) = plan(segments, day(3), day(4));
+        assert_eq!(reused.len(), 1);
+        assert!(gaps.is_empty());
+    }
+}
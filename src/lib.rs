@@ -0,0 +1,54 @@
+//! GitHub Activity Reporter library: fetches a user's GitHub activity and formats it into
+//! reports. The `github-activity-rs` binary is a thin command-line wrapper around it.
+//!
+//! ```no_run
+//! use github_activity_rs::format::{FormatData, MarkdownFormatter};
+//! use github_activity_rs::github::DEFAULT_API_URL;
+//! use github_activity_rs::{GithubClient, Report, filter};
+//! use chrono::{Duration, Utc};
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let (end, start) = (Utc::now(), Utc::now() - Duration::days(7));
+//! let token = std::env::var("GITHUB_TOKEN")?;
+//! let client = GithubClient::new(token, DEFAULT_API_URL.into(), "octocat".into(), start, end)?;
+//! let activity = client.fetch_activity().await?;
+//! let activity = filter::filter_activity(activity, &Some("octocat/hello-world".into()), &None);
+//! let report = Report::new("octocat".into(), start, end, activity);
+//! println!("{}", MarkdownFormatter::default().format(&report));
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod aggregate;
+pub mod calendar;
+pub mod category;
+pub mod codeowners;
+#[cfg(feature = "parquet")]
+pub mod columnar;
+pub mod compare;
+pub mod coverage;
+pub mod filter;
+pub mod fork;
+pub mod format;
+pub mod github;
+pub mod identity;
+pub mod jsonl;
+pub mod locale;
+pub mod merge;
+pub mod model;
+pub mod narrative;
+pub mod newsletter;
+pub mod release;
+pub mod report;
+pub mod revert;
+pub mod review;
+pub mod scope;
+pub mod sparkline;
+pub mod statistics;
+pub mod text;
+pub mod timeline;
+pub mod triage;
+pub mod year_in_review;
+
+pub use github::GithubClient;
+pub use report::Report;
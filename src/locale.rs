@@ -0,0 +1,523 @@
+//! Locale module: groups the large totals in human-readable reports with the thousands
+//! separator of a chosen locale, and names weekdays and months in its language.
+//!
+//! The locale is always given explicitly (`--locale`), never read from the environment, so
+//! the same command renders the same report on every machine.
+
+use std::str::FromStr;
+
+/// Weekday and month names of a language.
+#[derive(Debug, PartialEq, Eq)]
+struct DateNames {
+    /// Sunday first, like GitHub's weekday numbers.
+    weekdays: [&'static str; 7],
+    /// Abbreviations of `weekdays`.
+    short_weekdays: [&'static str; 7],
+    /// January first.
+    months: [&'static str; 12],
+}
+
+const ENGLISH: DateNames = DateNames {
+    weekdays: [
+        "Sunday",
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+    ],
+    short_weekdays: ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"],
+    months: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+};
+
+const GERMAN: DateNames = DateNames {
+    weekdays: [
+        "Sonntag",
+        "Montag",
+        "Dienstag",
+        "Mittwoch",
+        "Donnerstag",
+        "Freitag",
+        "Samstag",
+    ],
+    short_weekdays: ["So", "Mo", "Di", "Mi", "Do", "Fr", "Sa"],
+    months: [
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ],
+};
+
+const FRENCH: DateNames = DateNames {
+    weekdays: [
+        "dimanche", "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi",
+    ],
+    short_weekdays: ["dim", "lun", "mar", "mer", "jeu", "ven", "sam"],
+    months: [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+};
+
+const SPANISH: DateNames = DateNames {
+    weekdays: [
+        "domingo",
+        "lunes",
+        "martes",
+        "miércoles",
+        "jueves",
+        "viernes",
+        "sábado",
+    ],
+    short_weekdays: ["dom", "lun", "mar", "mié", "jue", "vie", "sáb"],
+    months: [
+        "enero",
+        "febrero",
+        "marzo",
+        "abril",
+        "mayo",
+        "junio",
+        "julio",
+        "agosto",
+        "septiembre",
+        "octubre",
+        "noviembre",
+        "diciembre",
+    ],
+};
+
+const ITALIAN: DateNames = DateNames {
+    weekdays: [
+        "domenica",
+        "lunedì",
+        "martedì",
+        "mercoledì",
+        "giovedì",
+        "venerdì",
+        "sabato",
+    ],
+    short_weekdays: ["dom", "lun", "mar", "mer", "gio", "ven", "sab"],
+    months: [
+        "gennaio",
+        "febbraio",
+        "marzo",
+        "aprile",
+        "maggio",
+        "giugno",
+        "luglio",
+        "agosto",
+        "settembre",
+        "ottobre",
+        "novembre",
+        "dicembre",
+    ],
+};
+
+const DUTCH: DateNames = DateNames {
+    weekdays: [
+        "zondag",
+        "maandag",
+        "dinsdag",
+        "woensdag",
+        "donderdag",
+        "vrijdag",
+        "zaterdag",
+    ],
+    short_weekdays: ["zo", "ma", "di", "wo", "do", "vr", "za"],
+    months: [
+        "januari",
+        "februari",
+        "maart",
+        "april",
+        "mei",
+        "juni",
+        "juli",
+        "augustus",
+        "september",
+        "oktober",
+        "november",
+        "december",
+    ],
+};
+
+const PORTUGUESE: DateNames = DateNames {
+    weekdays: [
+        "domingo",
+        "segunda-feira",
+        "terça-feira",
+        "quarta-feira",
+        "quinta-feira",
+        "sexta-feira",
+        "sábado",
+    ],
+    short_weekdays: ["dom", "seg", "ter", "qua", "qui", "sex", "sáb"],
+    months: [
+        "janeiro",
+        "fevereiro",
+        "março",
+        "abril",
+        "maio",
+        "junho",
+        "julho",
+        "agosto",
+        "setembro",
+        "outubro",
+        "novembro",
+        "dezembro",
+    ],
+};
+
+const SWEDISH: DateNames = DateNames {
+    weekdays: [
+        "söndag", "måndag", "tisdag", "onsdag", "torsdag", "fredag", "lördag",
+    ],
+    short_weekdays: ["sön", "mån", "tis", "ons", "tor", "fre", "lör"],
+    months: [
+        "januari",
+        "februari",
+        "mars",
+        "april",
+        "maj",
+        "juni",
+        "juli",
+        "augusti",
+        "september",
+        "oktober",
+        "november",
+        "december",
+    ],
+};
+
+const DANISH: DateNames = DateNames {
+    weekdays: [
+        "søndag", "mandag", "tirsdag", "onsdag", "torsdag", "fredag", "lørdag",
+    ],
+    short_weekdays: ["søn", "man", "tir", "ons", "tor", "fre", "lør"],
+    months: [
+        "januar",
+        "februar",
+        "marts",
+        "april",
+        "maj",
+        "juni",
+        "juli",
+        "august",
+        "september",
+        "oktober",
+        "november",
+        "december",
+    ],
+};
+
+const NORWEGIAN: DateNames = DateNames {
+    weekdays: [
+        "søndag", "mandag", "tirsdag", "onsdag", "torsdag", "fredag", "lørdag",
+    ],
+    short_weekdays: ["søn", "man", "tir", "ons", "tor", "fre", "lør"],
+    months: [
+        "januar",
+        "februar",
+        "mars",
+        "april",
+        "mai",
+        "juni",
+        "juli",
+        "august",
+        "september",
+        "oktober",
+        "november",
+        "desember",
+    ],
+};
+
+const FINNISH: DateNames = DateNames {
+    weekdays: [
+        "sunnuntai",
+        "maanantai",
+        "tiistai",
+        "keskiviikko",
+        "torstai",
+        "perjantai",
+        "lauantai",
+    ],
+    short_weekdays: ["su", "ma", "ti", "ke", "to", "pe", "la"],
+    months: [
+        "tammikuu",
+        "helmikuu",
+        "maaliskuu",
+        "huhtikuu",
+        "toukokuu",
+        "kesäkuu",
+        "heinäkuu",
+        "elokuu",
+        "syyskuu",
+        "lokakuu",
+        "marraskuu",
+        "joulukuu",
+    ],
+};
+
+const POLISH: DateNames = DateNames {
+    weekdays: [
+        "niedziela",
+        "poniedziałek",
+        "wtorek",
+        "środa",
+        "czwartek",
+        "piątek",
+        "sobota",
+    ],
+    short_weekdays: ["nie", "pon", "wto", "śro", "czw", "pią", "sob"],
+    months: [
+        "styczeń",
+        "luty",
+        "marzec",
+        "kwiecień",
+        "maj",
+        "czerwiec",
+        "lipiec",
+        "sierpień",
+        "wrzesień",
+        "październik",
+        "listopad",
+        "grudzień",
+    ],
+};
+
+const RUSSIAN: DateNames = DateNames {
+    weekdays: [
+        "воскресенье",
+        "понедельник",
+        "вторник",
+        "среда",
+        "четверг",
+        "пятница",
+        "суббота",
+    ],
+    short_weekdays: ["вс", "пн", "вт", "ср", "чт", "пт", "сб"],
+    months: [
+        "январь",
+        "февраль",
+        "март",
+        "апрель",
+        "май",
+        "июнь",
+        "июль",
+        "август",
+        "сентябрь",
+        "октябрь",
+        "ноябрь",
+        "декабрь",
+    ],
+};
+
+/// How numbers are grouped, and weekdays and months named, in human-readable reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberLocale {
+    separator: &'static str,
+    names: &'static DateNames,
+}
+
+impl Default for NumberLocale {
+    fn default() -> Self {
+        Self {
+            separator: ",",
+            names: &ENGLISH,
+        }
+    }
+}
+
+impl NumberLocale {
+    /// Formats `n` with digits grouped in threes, e.g. `12,345` or `12.345`.
+    pub fn format(&self, n: i64) -> String {
+        let digits = n.unsigned_abs().to_string();
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                grouped.push_str(self.separator);
+            }
+            grouped.push(digit);
+        }
+        if n < 0 {
+            grouped.insert(0, '-');
+        }
+        grouped
+    }
+
+    /// Formats `x` rounded to one decimal place, e.g. `1,234.5`, or `1.234,5` where the
+    /// locale groups digits with periods or spaces.
+    pub fn format_decimal(&self, x: f64) -> String {
+        let tenths = (x * 10.0).round() as i64;
+        let mark = match self.separator {
+            "." | "\u{a0}" => ',',
+            _ => '.',
+        };
+        format!(
+            "{}{}{}{}",
+            if tenths < 0 { "-" } else { "" },
+            self.format((tenths / 10).abs()),
+            mark,
+            (tenths % 10).abs()
+        )
+    }
+
+    /// The name of a weekday by GitHub's weekday number (0 is Sunday), e.g. `Tuesday`.
+    pub fn weekday_name(&self, weekday: i64) -> &'static str {
+        self.names.weekdays[weekday.rem_euclid(7) as usize]
+    }
+
+    /// The abbreviated weekday names, Sunday first, e.g. `Sun`.
+    pub fn weekday_abbreviations(&self) -> [&'static str; 7] {
+        self.names.short_weekdays
+    }
+
+    /// The name of a month from 1 (January) to 12, e.g. `March`.
+    pub fn month_name(&self, month: u32) -> &'static str {
+        self.names.months[(month.clamp(1, 12) - 1) as usize]
+    }
+
+    /// A `YYYY-MM` calendar period label as its month's name and year, e.g. `March 2025`;
+    /// other labels, like weeks, are returned unchanged.
+    pub fn month_label(&self, label: &str) -> String {
+        let month = label
+            .split_once('-')
+            .filter(|(year, month)| year.len() == 4 && month.len() == 2)
+            .and_then(|(year, month)| Some((year, month.parse::<u32>().ok()?)))
+            .filter(|(_, month)| (1..=12).contains(month));
+        match month {
+            Some((year, month)) => format!("{} {}", self.month_name(month), year),
+            None => label.to_string(),
+        }
+    }
+}
+
+impl FromStr for NumberLocale {
+    type Err = String;
+
+    /// Parses a language tag such as `en`, `de-DE`, or `fr_FR`; `none` disables grouping.
+    /// Languages without translated names keep the English weekday and month names.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tag = s.to_lowercase().replace('_', "-");
+        let language = tag.split('-').next().unwrap_or_default();
+        let separator = match (language, tag.as_str()) {
+            ("none", _) => "",
+            (_, "de-ch" | "it-ch" | "fr-ch") => "\u{2019}",
+            ("en" | "ja" | "ko" | "zh" | "he" | "th", _) => ",",
+            ("de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el", _) => ".",
+            ("fr" | "ru" | "pl" | "sv" | "nb" | "no" | "fi" | "cs" | "sk" | "uk" | "hu", _) => {
+                "\u{a0}"
+            }
+            _ => {
+                return Err(format!(
+                    "Unsupported locale: {}. Use a language tag such as en, de, or fr, or none",
+                    s
+                ));
+            }
+        };
+        let names = match language {
+            "de" => &GERMAN,
+            "fr" => &FRENCH,
+            "es" => &SPANISH,
+            "it" => &ITALIAN,
+            "nl" => &DUTCH,
+            "pt" => &PORTUGUESE,
+            "sv" => &SWEDISH,
+            "da" => &DANISH,
+            "nb" | "no" => &NORWEGIAN,
+            "fi" => &FINNISH,
+            "pl" => &POLISH,
+            "ru" => &RUSSIAN,
+            _ => &ENGLISH,
+        };
+        Ok(Self { separator, names })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_groups_thousands() {
+        let en = NumberLocale::default();
+        assert_eq!(en.format(0), "0");
+        assert_eq!(en.format(999), "999");
+        assert_eq!(en.format(12345), "12,345");
+        assert_eq!(en.format(-1234567), "-1,234,567");
+        assert_eq!(
+            "none".parse::<NumberLocale>().unwrap().format(12345),
+            "12345"
+        );
+    }
+
+    #[test]
+    fn test_format_decimal() {
+        let en = NumberLocale::default();
+        assert_eq!(en.format_decimal(0.0), "0.0");
+        assert_eq!(en.format_decimal(1234.56), "1,234.6");
+        assert_eq!(en.format_decimal(-0.25), "-0.3");
+        assert_eq!(
+            "de".parse::<NumberLocale>().unwrap().format_decimal(1234.5),
+            "1.234,5"
+        );
+    }
+
+    #[test]
+    fn test_parse_locale_tags() {
+        let format = |tag: &str| tag.parse::<NumberLocale>().unwrap().format(1234567);
+        assert_eq!(format("en-US"), "1,234,567");
+        assert_eq!(format("de_DE"), "1.234.567");
+        assert_eq!(format("fr"), "1\u{a0}234\u{a0}567");
+        assert_eq!(format("de-CH"), "1\u{2019}234\u{2019}567");
+        assert!("xx".parse::<NumberLocale>().is_err());
+    }
+
+    #[test]
+    fn test_date_names() {
+        let en = NumberLocale::default();
+        assert_eq!(en.weekday_name(2), "Tuesday");
+        assert_eq!(en.weekday_abbreviations()[0], "Sun");
+        assert_eq!(en.month_label("2025-03"), "March 2025");
+        assert_eq!(en.month_label("Week of 2025-03-02"), "Week of 2025-03-02");
+        assert_eq!(en.month_label("2025-13"), "2025-13");
+
+        let de: NumberLocale = "de-AT".parse().unwrap();
+        assert_eq!(de.weekday_name(0), "Sonntag");
+        assert_eq!(de.month_label("2025-03"), "März 2025");
+        let ja: NumberLocale = "ja".parse().unwrap();
+        assert_eq!(ja.weekday_name(6), "Saturday");
+    }
+}
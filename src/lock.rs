@@ -0,0 +1,226 @@
+//! Lock module: keeps overlapping runs with the same arguments, such as a cron job whose
+//! previous run is still paginating, from hitting the API twice and clobbering each other's
+//! output files.
+//!
+//! Locks are files in the user's state directory, keyed by what the run resolved its arguments
+//! to (config file, profile, users, range, and outputs), held with an advisory file lock that the operating system releases when the process exits, so a
+//! crashed run never leaves a stale lock behind.
+
+use crate::args::Args;
+use crate::{config, last_run};
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs::{self, File, TryLockError};
+use std::path::{Path, PathBuf};
+
+/// Exit code of a run that found another run with the same arguments in progress
+/// (`EX_TEMPFAIL`, so schedulers can tell it apart from a failure).
+pub const ALREADY_RUNNING_EXIT_CODE: i32 = 75;
+
+/// The error of a run that found another run holding its lock.
+#[derive(Debug)]
+pub struct AlreadyRunning {
+    path: PathBuf,
+}
+
+impl fmt::Display for AlreadyRunning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Another run with the same arguments is in progress (lock {:?}); pass --wait-for-lock to wait for it",
+            self.path
+        )
+    }
+}
+
+impl std::error::Error for AlreadyRunning {}
+
+/// A held run lock, released when dropped.
+#[derive(Debug)]
+pub struct RunLock {
+    _file: File,
+}
+
+impl RunLock {
+    /// Takes the lock at `path`, waiting for its holder to finish when `wait` is set and
+    /// failing with [`AlreadyRunning`] otherwise.
+    pub fn acquire(path: &Path, wait: bool) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create lock directory {:?}", dir))?;
+        }
+        let file =
+            File::create(path).with_context(|| format!("Failed to open lock file {:?}", path))?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) if wait => {
+                eprintln!("Waiting for another run with the same arguments to finish...");
+                file.lock()
+                    .with_context(|| format!("Failed to lock {:?}", path))?;
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(AlreadyRunning {
+                    path: path.to_path_buf(),
+                }
+                .into());
+            }
+            Err(TryLockError::Error(err)) => {
+                return Err(err).with_context(|| format!("Failed to lock {:?}", path));
+            }
+        }
+        Ok(Self { _file: file })
+    }
+
+    /// The lock of runs with `args` in the user's state directory, if there is one.
+    pub fn path_for(args: &Args) -> Option<PathBuf> {
+        last_run::state_dir().map(|dir| dir.join("locks").join(Self::key(args)))
+    }
+
+    /// The file name of the lock of runs with `args`.
+    ///
+    /// The key is built from the resolved arguments rather than the command line, so runs of
+    /// the same config share a lock however their arguments were spelled, ordered, or taken
+    /// from the environment, and relative paths count the same as absolute ones.
+    fn key(args: &Args) -> String {
+        let config = args.config.clone().or_else(config::default_path);
+        let mut usernames: Vec<String> = args
+            .usernames
+            .iter()
+            .map(|username| username.0.to_lowercase())
+            .collect();
+        usernames.sort();
+        let outputs: Vec<PathBuf> = args
+            .output
+            .iter()
+            .chain(&args.output_dir)
+            .chain(&args.parquet_dir)
+            .map(|path| resolved(path))
+            .collect();
+        let parts = [
+            format!("{:?}", args.command),
+            format!("{:?}", config.as_deref().map(resolved)),
+            format!("{:?}", args.profile),
+            args.api_url.clone(),
+            usernames.join(","),
+            format!("{:?} {:?} {:?}", args.period, args.from, args.to),
+            args.since_last_run.to_string(),
+            format!("{:?}", outputs),
+            format!("{:?} {:?}", args.deliver, args.issue_target),
+        ];
+        let mut hasher = Sha256::new();
+        for part in parts {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}.lock", hasher.finalize())
+    }
+}
+
+/// `path` made absolute, with symbolic links resolved when it exists.
+fn resolved(path: &Path) -> PathBuf {
+    fs::canonicalize(path)
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_acquire_excludes_overlapping_runs() {
+        let dir = env::temp_dir().join(format!("github-activity-lock-{}", std::process::id()));
+        let path = dir.join("run.lock");
+
+        let lock = RunLock::acquire(&path, false).unwrap();
+        let err = RunLock::acquire(&path, false).unwrap_err();
+        assert!(err.downcast_ref::<AlreadyRunning>().is_some());
+        drop(lock);
+        RunLock::acquire(&path, false).unwrap();
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_key_depends_on_resolved_args() {
+        use clap::Parser;
+        let key = |args: &[&str]| {
+            let args = std::iter::once("github-activity-rs").chain(args.iter().copied());
+            RunLock::key(&Args::try_parse_from(args).unwrap())
+        };
+        let cwd = env::current_dir().unwrap();
+        let config = cwd.join("work.toml");
+        let output = cwd.join("report.md");
+
+        let work = key(&[
+            "--config",
+            "work.toml",
+            "--profile",
+            "work",
+            "-u",
+            "octocat",
+            "--period",
+            "7d",
+            "--output",
+            "report.md",
+        ]);
+        assert_eq!(
+            work,
+            key(&[
+                "--output=report.md",
+                "--period=7d",
+                "--username",
+                "OctoCat",
+                "--profile=work",
+                &format!("--config={}", config.display()),
+            ])
+        );
+        assert_eq!(
+            work,
+            key(&[
+                "--config",
+                "work.toml",
+                "--profile",
+                "work",
+                "-u",
+                "octocat",
+                "--period",
+                "7d",
+                "--output",
+                output.to_str().unwrap(),
+            ])
+        );
+        assert_ne!(
+            work,
+            key(&[
+                "--config",
+                "work.toml",
+                "--profile",
+                "home",
+                "-u",
+                "octocat",
+                "--period",
+                "7d",
+                "--output",
+                "report.md",
+            ])
+        );
+        assert_ne!(
+            work,
+            key(&[
+                "--config",
+                "work.toml",
+                "--profile",
+                "work",
+                "-u",
+                "octocat",
+                "--period",
+                "30d",
+                "--output",
+                "report.md",
+            ])
+        );
+    }
+}
@@ -1,26 +1,76 @@
 #![warn(missing_docs)]
-//! GitHub Activity Reporter: a command-line tool that fetches and formats GitHub activity.
+//! GitHub Activity Reporter: a command-line tool that fetches and formats GitHub activity,
+//! built on the `github_activity_rs` library.
 
+mod account;
 mod args;
-mod filter;
-mod format;
-mod github;
+mod backfill;
+mod batch;
+mod cache;
+mod config;
+mod config_check;
+mod deliver;
+mod encryption;
+mod history;
+mod hook;
+mod last_run;
+mod lock;
+mod manifest;
+mod storage;
+mod team;
+mod update_check;
+mod watch;
 
+use account::{Account, Accounts};
 use anyhow::Context;
-use args::{Args, OutputFormat};
-use clap::Parser;
+use args::{AccountAction, Args, CacheAction, Command, OutputFormat, ReviewState, SchemaAction};
+use backfill::{Backfill, BackfillStore, WindowStatus};
+use cache::{ActivityCache, CachedActivity};
+use chrono::Datelike;
+use clap::CommandFactory;
+use clap_complete::CompleteEnv;
+use config::Config;
+use deliver::{DeliveryTarget, SlackPayload};
 use dotenv::dotenv;
-use format::{FormatData, MarkdownFormatter, PlainTextFormatter};
-use log::{debug, info};
+use encryption::EncryptWriter;
+use github_activity_rs::compare::HeatmapComparison;
+use github_activity_rs::format::{
+    ColorTerminalFormatter, CsvFormatter, FormatData, HtmlFormatter, IcsFormatter,
+    MarkdownFormatter, MarkdownSection, PlainTextFormatter, SlackFormatter, TemplateFormatter,
+};
+use github_activity_rs::newsletter::Newsletter;
+use github_activity_rs::report::{self, Report};
+use github_activity_rs::review::ReportMode;
+use github_activity_rs::statistics::Statistics;
+use github_activity_rs::{
+    aggregate, calendar, category, codeowners, coverage, filter, fork, github, identity, jsonl,
+    release, revert, review, scope, timeline, triage, year_in_review,
+};
+use history::RecentUsernames;
+use last_run::LastRuns;
+use lock::{AlreadyRunning, RunLock};
+use log::{debug, error, info, warn};
+use manifest::{Manifest, WarningRecorder};
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
 use std::fs;
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 #[tokio::main]
 async fn main() {
+    CompleteEnv::with_factory(Args::command).complete();
     dotenv().ok();
-    env_logger::init();
+    let logger = env_logger::Builder::from_default_env().build();
+    let filter = logger.filter();
+    WarningRecorder::install(logger, filter).expect("a logger is installed only once");
 
     if let Err(err) = run().await {
+        if let Some(running) = err.downcast_ref::<AlreadyRunning>() {
+            eprintln!("{}", running);
+            std::process::exit(lock::ALREADY_RUNNING_EXIT_CODE);
+        }
         eprintln!("Error: {}", format_error(&err));
         std::process::exit(1);
     }
@@ -28,76 +78,2130 @@ async fn main() {
 
 /// Run the core logic of the program.
 async fn run() -> anyhow::Result<()> {
-    let args = Args::parse();
-    info!("Starting GitHub activity fetch for user: {}", args.username);
+    let started_at = chrono::Utc::now();
+    let args = Args::parse_for_terminal();
+    let _lock = acquire_run_lock(&args)?;
 
-    let github_token =
-        env::var("GITHUB_TOKEN").context("GITHUB_TOKEN environment variable is required")?;
-    debug!("GitHub token retrieved successfully.");
+    // Check for updates in the background so the check never delays the report.
+    let update_check = update_check_enabled(&args).then(|| tokio::spawn(update_check::check()));
+    let result = execute(&args).await;
+    // A check still running when the run is done is dropped rather than waited for.
+    if let Some(handle) = update_check {
+        if !handle.is_finished() {
+            handle.abort();
+        } else if let Ok(Some(notice)) = handle.await {
+            eprintln!("{}", notice);
+        }
+    }
+    if let Err(err) = result {
+        // The run's own error is the one to report; a failing hook is only logged.
+        if let Some(command) = hook_command(&args, |hooks| hooks.on_failure) {
+            let manifest = Manifest::collect(started_at, env::args().skip(1).collect());
+            let env = hook::environment(&manifest, Some(&format_error(&err)));
+            if let Err(hook_err) = hook::run("on_failure", &command, &env) {
+                warn!("{:#}", hook_err);
+            }
+        }
+        return Err(err);
+    }
+
+    let manifest = Manifest::collect(started_at, env::args().skip(1).collect());
+    if let Some(path) = &args.manifest {
+        manifest.write(path)?;
+        info!("Run manifest saved to {:?}", path);
+    }
+    if let Some(command) = hook_command(&args, |hooks| hooks.on_success) {
+        hook::run("on_success", &command, &hook::environment(&manifest, None))?;
+    }
+    Ok(())
+}
+
+/// The config's hook command picked by `select`, for commands that generate reports.
+fn hook_command(args: &Args, select: impl FnOnce(hook::Hooks) -> Option<String>) -> Option<String> {
+    if let Some(
+        Command::Validate { .. }
+        | Command::Cache { .. }
+        | Command::Schema { .. }
+        | Command::Account { .. },
+    ) = args.command
+    {
+        return None;
+    }
+    let config = Config::load_or_default(args.config.as_deref()).ok()?;
+    select(config.hooks)
+}
+
+/// Take the lock of runs with these resolved arguments, for commands that call the GitHub API or write
+/// output files.
+fn acquire_run_lock(args: &Args) -> anyhow::Result<Option<RunLock>> {
+    if let Some(
+        Command::Render { .. }
+        | Command::Validate { .. }
+        | Command::Cache { .. }
+        | Command::Schema { .. }
+        | Command::Account { .. },
+    ) = args.command
+    {
+        return Ok(None);
+    }
+    RunLock::path_for(args)
+        .map(|path| RunLock::acquire(&path, args.wait_for_lock))
+        .transpose()
+}
+
+/// Whether to check for a newer release: not when opted out, and only when a person is
+/// likely to read the notice.
+fn update_check_enabled(args: &Args) -> bool {
+    !args.no_update_check
+        && env::var_os(update_check::OPT_OUT_VAR).is_none()
+        && std::io::stderr().is_terminal()
+}
+
+/// Run the command described by the arguments.
+async fn execute(args: &Args) -> anyhow::Result<()> {
+    let reports = match &args.command {
+        Some(Command::Render { inputs, identity }) => inputs
+            .iter()
+            .map(|input| {
+                info!("Rendering saved report from {:?}", input);
+                load_report(input, identity.as_deref())
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?,
+        Some(Command::Validate { input }) => return validate_report(input),
+        Some(Command::Batch { specs }) => return run_batch(args, specs).await,
+        Some(Command::Newsletter { spec }) => return run_newsletter(args, spec).await,
+        Some(Command::AggregateAnon { spec }) => return run_aggregate_anon(args, spec).await,
+        Some(Command::Backfill { resume, window }) => run_backfill(args, *resume, *window).await?,
+        Some(Command::Compare {
+            previous_from,
+            previous_to,
+        }) => return run_compare(args, *previous_from, *previous_to).await,
+        Some(Command::YearInReview { year }) => return run_year_in_review(args, *year).await,
+        Some(Command::Cache {
+            action: CacheAction::Clear,
+        }) => return clear_cache(args),
+        Some(Command::Schema {
+            action: SchemaAction::Update { output, url },
+        }) => return update_schema(output, url).await,
+        Some(Command::Account { action }) => return manage_accounts(args, action),
+        Some(Command::Watch { every, cron }) => {
+            let schedule = match (every, cron) {
+                (Some(every), _) => watch::Schedule::Every(*every),
+                (None, Some(cron)) => watch::Schedule::Cron(cron.clone()),
+                (None, None) => anyhow::bail!("watch requires --every or --cron"),
+            };
+            return run_watch(args, &schedule).await;
+        }
+        None if args.mode == ReportMode::Review => return run_review(args).await,
+        None => fetch_reports(args).await?,
+    };
+    finish_reports(args, reports).await
+}
+
+/// Annotate, order, write, and deliver the reports of a run.
+async fn finish_reports(args: &Args, mut reports: Vec<Report>) -> anyhow::Result<()> {
+    let rules = Config::load_or_default(args.config.as_deref())?.category_rules()?;
+    let sample_seed = args.sample_seed.unwrap_or_else(|| fastrand::u64(..));
+    if let Some(size) = args.sample {
+        info!(
+            "Listing a random sample of {} items per section (--sample-seed {})",
+            size, sample_seed
+        );
+    }
+    for report in &mut reports {
+        manifest::record_report(report);
+        if args.stats {
+            report.statistics = Statistics::from_report(report, args.forecast);
+        }
+        if !rules.is_empty() {
+            report.categories = category::categorize(&report.activity, &rules);
+        }
+        if let Some(size) = args.sample {
+            report.activity =
+                filter::sample(std::mem::take(&mut report.activity), size, sample_seed);
+        }
+        // Stable Markdown doesn't depend on the order GitHub happens to return.
+        let sort = args
+            .sort
+            .or(args.stable_markdown.then_some(filter::SortKey::Created));
+        if sort.is_some() || args.top.is_some() {
+            report.activity =
+                filter::sort_and_limit(std::mem::take(&mut report.activity), sort, args.top);
+        }
+    }
+
+    if let Some(dir) = &args.parquet_dir {
+        write_parquet_tables(args, dir, &reports)?;
+    }
+    match &args.output_dir {
+        Some(dir) => write_team_reports(args, dir, &reports)?,
+        None => write_outputs(args, &reports)?,
+    }
+    if let Some(target) = args.deliver.or_else(DeliveryTarget::detect) {
+        deliver_reports(args, target, &reports).await?;
+    }
+    Ok(())
+}
+
+/// Regenerate the reports on `schedule` until the process is stopped. A failed run is logged
+/// and the next one still happens, so a transient API error doesn't end the watch.
+async fn run_watch(args: &Args, schedule: &watch::Schedule) -> anyhow::Result<()> {
+    let mut previous = None;
+    loop {
+        let now = chrono::Utc::now();
+        let next = schedule
+            .next_run(previous, now)
+            .context("The cron expression matches no future time")?;
+        if next > now {
+            info!("Next report at {}", next);
+            tokio::time::sleep((next - now).to_std().unwrap_or_default()).await;
+        }
+        // The interval counts from when the run actually started, however late that was.
+        previous = Some(chrono::Utc::now());
+        info!("Generating the scheduled report");
+        let run = async { finish_reports(args, fetch_reports(args).await?).await };
+        if let Err(err) = run.await {
+            error!("Scheduled report failed: {:#}", err);
+        }
+    }
+}
+
+/// Post every report to the --webhook-url of `target`, in as many numbered messages as the
+/// service's size limits need. Gists and issues are published on GitHub instead, see
+/// [`publish_gist`] and [`open_issues`], and job summaries written by [`write_step_summary`].
+/// With --dry-run-delivery, the messages are printed instead.
+async fn deliver_reports(
+    args: &Args,
+    target: DeliveryTarget,
+    reports: &[Report],
+) -> anyhow::Result<()> {
+    match target {
+        DeliveryTarget::Gist => return publish_gist(args, reports).await,
+        DeliveryTarget::Issue => return open_issues(args, reports).await,
+        DeliveryTarget::StepSummary => return write_step_summary(args, reports),
+        DeliveryTarget::Slack | DeliveryTarget::Discord => {}
+    }
+    let url = args
+        .webhook_url
+        .as_deref()
+        .context("--deliver requires --webhook-url")?;
+    let client = reqwest::Client::new();
+    for report in reports {
+        let messages = match (target, args.deliver_as) {
+            (DeliveryTarget::Slack, SlackPayload::Blocks) => {
+                let blocks = SlackFormatter {
+                    locale: args.locale,
+                }
+                .format(report);
+                deliver::slack_block_messages(
+                    serde_json::from_str(&blocks).context("Failed to build Slack message")?,
+                )
+            }
+            (DeliveryTarget::Slack, SlackPayload::Text) => {
+                deliver::slack_text_messages(&render_report(args, &OutputFormat::Plain, report)?)
+            }
+            (DeliveryTarget::Discord, _) => {
+                deliver::discord_messages(&render_report(args, &OutputFormat::Plain, report)?)
+            }
+            (DeliveryTarget::Gist | DeliveryTarget::Issue | DeliveryTarget::StepSummary, _) => {
+                unreachable!("only webhooks take messages")
+            }
+        };
+        info!(
+            "Delivering the report of {} to {:?} in {} message(s)",
+            report.username,
+            target,
+            messages.len()
+        );
+        for message in &messages {
+            if args.dry_run_delivery {
+                // The webhook URL is a secret, so it isn't printed.
+                print_dry_run("POST", "--webhook-url", message)?;
+                continue;
+            }
+            deliver::post_webhook(&client, url, message)
+                .await
+                .with_context(|| format!("Failed to deliver the report of {}", report.username))?;
+        }
+    }
+    Ok(())
+}
+
+/// Publish the Markdown reports, one file per user, as a secret gist (or into the --gist-id
+/// gist) with the --api-url token, and print its URL.
+async fn publish_gist(args: &Args, reports: &[Report]) -> anyhow::Result<()> {
+    let client = publishing_client(args)?;
+    let files = reports
+        .iter()
+        .map(|report| {
+            let markdown = render_report(args, &OutputFormat::Markdown, report)?;
+            Ok((format!("{}.md", report.username), markdown))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let usernames: Vec<&str> = reports.iter().map(|r| r.username.as_str()).collect();
+    let description = match reports.first() {
+        Some(first) => report_title(&usernames.join(", "), first),
+        None => "GitHub activity".to_string(),
+    };
+    if args.dry_run_delivery {
+        let request = client.gist_request(args.gist_id.as_deref(), &description, &files);
+        return print_dry_run(request.method.as_str(), &request.url, &request.body);
+    }
+    info!("Publishing {} report(s) as a gist", files.len());
+    let url = client
+        .publish_gist(args.gist_id.as_deref(), &description, &files)
+        .await?;
+    println!("Gist published at {}", url);
+    Ok(())
+}
+
+/// Open an issue with each Markdown report in the --target repository, or comment each one on
+/// its tracking issue, and print the URLs. A report too long for one body is split between
+/// sections into numbered parts, the rest of which are commented on the same issue.
+async fn open_issues(args: &Args, reports: &[Report]) -> anyhow::Result<()> {
+    let target = args
+        .issue_target
+        .as_ref()
+        .context("--deliver issue requires --target")?;
+    let client = publishing_client(args)?;
+    for report in reports {
+        let markdown = render_report(args, &OutputFormat::Markdown, report)?;
+        let bodies = deliver::issue_bodies(&markdown);
+        let title = report_title(&report.username, report);
+        if args.dry_run_delivery {
+            for (index, body) in bodies.iter().enumerate() {
+                let request = match target.number {
+                    Some(number) => client.comment_request(&target.repo, number, body),
+                    None if index == 0 => client.issue_request(&target.repo, &title, body),
+                    None => client.comment_request(&target.repo, "<new issue>", body),
+                };
+                print_dry_run(request.method.as_str(), &request.url, &request.body)?;
+            }
+            continue;
+        }
+        let (number, rest) = match target.number {
+            Some(number) => (number, &bodies[..]),
+            None => {
+                info!(
+                    "Opening an issue with the report of {} in {}",
+                    report.username, target.repo
+                );
+                let (number, url) = client
+                    .create_issue(&target.repo, &title, &bodies[0])
+                    .await?;
+                println!("Report of {} published at {}", report.username, url);
+                (number, &bodies[1..])
+            }
+        };
+        for body in rest {
+            info!(
+                "Commenting the report of {} on {}#{}",
+                report.username, target.repo, number
+            );
+            let url = client.comment_on_issue(&target.repo, number, body).await?;
+            println!("Report of {} published at {}", report.username, url);
+        }
+    }
+    Ok(())
+}
+
+/// Append the Markdown reports to the GitHub Actions job summary, and their totals to the
+/// step's outputs when Actions provides an outputs file.
+fn write_step_summary(args: &Args, reports: &[Report]) -> anyhow::Result<()> {
+    if args.dry_run_delivery {
+        for report in reports {
+            println!(
+                "Append to ${}:\n{}\n",
+                deliver::STEP_SUMMARY_VAR,
+                render_report(args, &OutputFormat::Markdown, report)?
+            );
+        }
+        print!(
+            "Append to ${}:\n{}",
+            deliver::STEP_OUTPUT_VAR,
+            deliver::step_outputs(reports)
+        );
+        return Ok(());
+    }
+    let summary = env::var_os(deliver::STEP_SUMMARY_VAR).with_context(|| {
+        format!(
+            "--deliver step-summary only works in GitHub Actions ({} is not set)",
+            deliver::STEP_SUMMARY_VAR
+        )
+    })?;
+    let mut markdown = String::new();
+    for report in reports {
+        markdown.push_str(&render_report(args, &OutputFormat::Markdown, report)?);
+        markdown.push_str("\n\n");
+    }
+    append_to(Path::new(&summary), &markdown)?;
+    info!("Added {} report(s) to the job summary", reports.len());
+    if let Some(outputs) = env::var_os(deliver::STEP_OUTPUT_VAR) {
+        append_to(Path::new(&outputs), &deliver::step_outputs(reports))?;
+    }
+    Ok(())
+}
+
+/// Print a request --dry-run-delivery holds back: its method, where it would go, and its JSON
+/// body.
+fn print_dry_run(method: &str, to: &str, body: &serde_json::Value) -> anyhow::Result<()> {
+    let body = serde_json::to_string_pretty(body).context("Failed to serialize the payload")?;
+    println!("{} {}\n{}\n", method, to, body);
+    Ok(())
+}
+
+/// Append `contents` to the file at `path`, creating it if needed.
+fn append_to(path: &Path, contents: &str) -> anyhow::Result<()> {
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .with_context(|| format!("Failed to append to {:?}", path))
+}
+
+/// A GitHub client for publishing reports with the --api-url token; a dry run, which only
+/// builds requests, doesn't need one.
+fn publishing_client(args: &Args) -> anyhow::Result<github::GithubClient> {
+    let now = chrono::Utc::now();
+    let token = match github_token(args) {
+        Err(_) if args.dry_run_delivery => String::new(),
+        token => token?,
+    };
+    github::GithubClient::new(token, args.api_url.clone(), String::new(), now, now)
+        .context("Failed to create GitHub client")
+}
+
+/// The title of a published report on the activity of `who`, e.g. `GitHub activity of octocat
+/// from 2025-03-01 to 2025-03-08`.
+fn report_title(who: &str, report: &Report) -> String {
+    format!(
+        "GitHub activity of {} from {} to {}",
+        who,
+        report.start_date.format("%Y-%m-%d"),
+        report.end_date.format("%Y-%m-%d")
+    )
+}
+
+/// Read a saved report, decrypting it with the identity file if it was saved encrypted.
+fn load_report(path: &Path, identity: Option<&Path>) -> anyhow::Result<Report> {
+    let contents = if path == Path::new("-") {
+        let mut contents = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut contents)
+            .context("Failed to read report from stdin")?;
+        contents
+    } else {
+        fs::read(path).with_context(|| format!("Failed to read report from {:?}", path))?
+    };
+    let contents = match identity {
+        _ if !encryption::is_encrypted(&contents) => contents,
+        Some(identity) => encryption::decrypt(&contents, identity)
+            .with_context(|| format!("Failed to decrypt report {:?}", path))?,
+        None => anyhow::bail!(
+            "Report {:?} is encrypted; pass the identity file to decrypt it with --identity",
+            path
+        ),
+    };
+    let json = String::from_utf8(contents)
+        .with_context(|| format!("Failed to read report from {:?}", path))?;
+    Report::from_json(&json).with_context(|| format!("Failed to load report from {:?}", path))
+}
+
+/// Add, list, or choose stored accounts.
+fn manage_accounts(args: &Args, action: &AccountAction) -> anyhow::Result<()> {
+    let accounts =
+        Accounts::open().context("No config directory to store accounts in; set HOME")?;
+    match action {
+        AccountAction::Add { name } => {
+            if std::io::stdin().is_terminal() {
+                eprint!("Token for {}: ", name);
+            }
+            let mut token = String::new();
+            std::io::stdin()
+                .read_line(&mut token)
+                .context("Failed to read the token from stdin")?;
+            let token = token.trim();
+            if token.is_empty() {
+                anyhow::bail!("No token given for account {}", name);
+            }
+            accounts.add(
+                name,
+                Account {
+                    api_url: args.api_url.clone(),
+                    token: token.to_string(),
+                },
+            )?;
+            println!("Account {} added for {}", name, args.api_url);
+        }
+        AccountAction::List => {
+            let list = accounts.load()?;
+            if list.accounts.is_empty() {
+                println!("No accounts stored; add one with `account add`");
+            }
+            for (name, account) in &list.accounts {
+                let marker = if list.current.as_deref() == Some(name) {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("{} {}\t{}", marker, name, account.api_url);
+            }
+        }
+        AccountAction::Use { name } => {
+            accounts.set_current(name)?;
+            println!("Runs now use account {}", name);
+        }
+    }
+    Ok(())
+}
 
+/// Delete all cached activity.
+fn clear_cache(args: &Args) -> anyhow::Result<()> {
+    let Some(storage) = storage::open(args.store.as_deref())? else {
+        anyhow::bail!("No state directory found; set HOME or XDG_STATE_HOME, or pass --store");
+    };
+    let cache = ActivityCache::new(storage, chrono::Duration::zero());
+    let removed = cache.clear()?;
+    println!("Removed {} cached report(s)", removed);
+    Ok(())
+}
+
+/// Download the GitHub GraphQL schema to `output` and check the bundled queries against it,
+/// failing if they select fields the schema no longer has.
+async fn update_schema(output: &Path, url: &str) -> anyhow::Result<()> {
+    info!("Downloading GraphQL schema from {}", url);
+    let schema = github::drift::fetch_schema(url).await?;
+    fs::write(output, &schema)
+        .with_context(|| format!("Failed to write schema to {:?}", output))?;
+    println!("Schema saved to {:?}", output);
+    let missing = github::drift::check_bundled_queries(&schema)?;
+    if missing.is_empty() {
+        println!("All bundled queries are valid against the schema");
+        return Ok(());
+    }
+    for field in &missing {
+        println!("{}", field);
+    }
+    anyhow::bail!(
+        "{} selection(s) of the bundled queries no longer exist in the schema",
+        missing.len()
+    )
+}
+
+/// The activity cache to use, unless `--no-cache` was given or contributions are streamed as
+/// they are fetched.
+fn open_cache(args: &Args) -> Option<ActivityCache> {
+    if args.no_cache || streams_contributions(args) {
+        return None;
+    }
+    let storage = match storage::open(args.store.as_deref()) {
+        Ok(storage) => storage?,
+        Err(err) => {
+            warn!("Not using the cache: {:#}", err);
+            return None;
+        }
+    };
+    Some(ActivityCache::new(
+        storage,
+        chrono::Duration::seconds(args.cache_ttl),
+    ))
+}
+
+/// Validate a saved report against the embedded report schema.
+fn validate_report(input: &Path) -> anyhow::Result<()> {
+    let json = fs::read_to_string(input)
+        .with_context(|| format!("Failed to read report from {:?}", input))?;
+    let value: serde_json::Value = serde_json::from_str(&json)
+        .with_context(|| format!("Report {:?} is not valid JSON", input))?;
+
+    let problems = report::validate(&value)?;
+    if problems.is_empty() {
+        println!(
+            "{:?} is a valid report (schema version {})",
+            input,
+            report::SCHEMA_VERSION
+        );
+        return Ok(());
+    }
+    for problem in &problems {
+        eprintln!("{:?}: {}", input, problem);
+    }
+    anyhow::bail!(
+        "{:?} failed validation with {} problem(s)",
+        input,
+        problems.len()
+    )
+}
+
+/// Fetch a report for every requested user concurrently, in the order the users were given.
+async fn fetch_reports(args: &Args) -> anyhow::Result<Vec<Report>> {
+    let usernames = if !args.usernames.is_empty() {
+        args.usernames.clone()
+    } else if args.output_dir.is_some() {
+        let teams = Config::load_or_default(args.config.as_deref())?.teams()?;
+        let mut members: Vec<args::GitHubUsername> = Vec::new();
+        for member in teams.into_values().flatten() {
+            if !members.iter().any(|m| m.0.eq_ignore_ascii_case(&member.0)) {
+                members.push(member);
+            }
+        }
+        members
+    } else if args::is_interactive() {
+        let recent = RecentUsernames::open()
+            .map(|history| history.load())
+            .unwrap_or_default();
+        vec![args::prompt_username(
+            std::io::stdin().lock(),
+            std::io::stderr(),
+            &recent,
+        )?]
+    } else {
+        anyhow::bail!("--username is required");
+    };
     let (start_date, end_date) = args
         .get_date_range()
         .map_err(|e| anyhow::anyhow!("Failed to get date range: {}", e))?;
     info!("Fetching activity from {} to {}", start_date, end_date);
 
-    let github_client = github::GithubClient::new(
-        github_token,
-        args.username.to_string(),
+    let base_client = github::GithubClient::new(
+        github_token(args)?,
+        args.api_url.clone(),
+        String::new(),
         start_date,
         end_date,
     )
-    .context("Failed to create GitHub client")?;
+    .context("Failed to create GitHub client")?
+    .wait_on_rate_limit(!args.no_wait)
+    .on_progress(progress_reporter(args))
+    .on_contributions(contribution_streamer(args))
+    .persisted_queries(args.persisted_queries)
+    .page_sizes(args.page_sizes())
+    .review_bodies(args.review_bodies);
+    let (base_client, warning) = scope_to_organization(args, base_client).await?;
+
+    let reports = usernames.iter().map(|username| {
+        let github_client = base_client.for_user(username.to_string(), start_date, end_date);
+        async move {
+            fetch_report(args, &github_client)
+                .await
+                .with_context(|| format!("Failed to generate report for {}", username))
+        }
+    });
+    let mut reports = futures::future::try_join_all(reports).await;
+    if let Ok(reports) = &mut reports {
+        add_warning(reports, &warning);
+    }
+    manifest::record_api_requests(base_client.request_count());
+    if reports.is_ok()
+        && let Some(history) = RecentUsernames::open()
+    {
+        let usernames: Vec<String> = usernames.iter().map(ToString::to_string).collect();
+        if let Err(err) = history.record(&usernames) {
+            warn!("Failed to remember usernames: {:#}", err);
+        }
+    }
+    reports
+}
+
+/// With --progress-json, a callback printing each progress event to stderr as a JSON line.
+fn progress_reporter(args: &Args) -> Option<github::ProgressCallback> {
+    args.progress_json.then(|| {
+        let callback: github::ProgressCallback = Arc::new(|event: &github::ProgressEvent| {
+            if let Ok(line) = serde_json::to_string(event) {
+                eprintln!("{}", line);
+            }
+        });
+        callback
+    })
+}
+
+/// Whether `--format jsonl` lines are written to stdout as pages arrive instead of once the
+/// reports are complete: only when nothing needs the whole report first, i.e. no filters,
+/// sorting, caching, encryption, template, or output file.
+fn streams_contributions(args: &Args) -> bool {
+    args.format == OutputFormat::Jsonl
+        && matches!(args.command, None | Some(Command::Watch { .. }))
+        && args.mode == ReportMode::Standard
+        && args.output.is_empty()
+        && args.output_dir.is_none()
+        && args.template.is_none()
+        && args.encrypt_to.is_empty()
+        && !args.calendar_only
+        && !args.since_last_run
+        && args.repo.is_none()
+        && args.org.is_none()
+        && args.labels.is_empty()
+        && args.review_states.is_empty()
+        && !args.exclude_forks
+        && args.paths.is_empty()
+        && args.codeowners.is_none()
+        && !args.codeowners_from_repo
+        && args.sort.is_none()
+        && args.top.is_none()
+        && args.sample.is_none()
+        && !args.stable_markdown
+}
+
+/// When streaming `--format jsonl`, a callback writing each fetched contribution to stdout as
+/// a JSON line.
+fn contribution_streamer(args: &Args) -> Option<github::ContributionCallback> {
+    streams_contributions(args).then(|| {
+        let callback: github::ContributionCallback =
+            Arc::new(|line: &jsonl::ContributionLine<'_>| {
+                if let Err(err) = line.write(std::io::stdout().lock()) {
+                    warn!("Failed to stream contribution: {:#}", err);
+                }
+            });
+        callback
+    })
+}
+
+/// Scope the client's contributions to the `--org-scope` organization, if one was given.
+///
+/// When the token can't read the organization, contributions are left unscoped instead of
+/// failing the run, and the returned warning says so; callers add it to their reports.
+async fn scope_to_organization(
+    args: &Args,
+    client: github::GithubClient,
+) -> anyhow::Result<(github::GithubClient, Option<String>)> {
+    let Some(org) = &args.org_scope else {
+        return Ok((client, None));
+    };
+    match client.fetch_organization_id(org).await {
+        Ok(id) => {
+            info!("Scoping contributions to organization {} ({})", org, id);
+            Ok((client.organization_scope(Some(id)), None))
+        }
+        Err(err) => match err.downcast_ref::<github::OrganizationAccessDenied>() {
+            Some(denied) => {
+                let warning = format!(
+                    "{}; contributions are not limited to organization {}",
+                    denied, org
+                );
+                warn!("{}", warning);
+                Ok((client, Some(warning)))
+            }
+            None => Err(err.context(format!(
+                "Failed to scope contributions to organization {}",
+                org
+            ))),
+        },
+    }
+}
+
+/// Add `warning`, if any, to each of `reports`.
+fn add_warning(reports: &mut [Report], warning: &Option<String>) {
+    for report in reports {
+        report.warnings.extend(warning.clone());
+    }
+}
+
+/// Fetch and filter the activity of the client's user described by the command-line arguments.
+async fn fetch_report(args: &Args, github_client: &github::GithubClient) -> anyhow::Result<Report> {
+    let username = github_client.username();
+    info!("Starting GitHub activity fetch for user: {}", username);
+
+    let mut report = if args.since_last_run {
+        fetch_since_last_run(args, github_client).await?
+    } else {
+        let range = cache::range_key(
+            args.rolling_period(),
+            github_client.start_date(),
+            github_client.end_date(),
+        );
+        fetch_filtered(
+            github_client,
+            &None,
+            &None,
+            open_cache(args).as_ref(),
+            &range,
+        )
+        .await?
+    };
+    let mut filters = filter::FilterLog::new(&report.activity);
+    if let Some(repo) = &args.repo {
+        report.activity = filters.apply(format!("--repo {}", repo), report.activity, |a| {
+            filter::filter_activity(a, &args.repo, &None)
+        });
+    }
+    if let Some(org) = &args.org {
+        report.activity = filters.apply(format!("--org {}", org), report.activity, |a| {
+            filter::filter_activity(a, &None, &args.org)
+        });
+    }
+    if !args.labels.is_empty() {
+        let name = format!("--label {}", args.labels.join(","));
+        report.activity = filters.apply(name, report.activity, |a| {
+            filter::retain_labeled(a, &args.labels)
+        });
+    }
+    if !args.review_states.is_empty() {
+        let states: Vec<&str> = args.review_states.iter().map(ReviewState::as_str).collect();
+        let name = format!(
+            "--review-state {}",
+            states.join(",").to_lowercase().replace('_', "-")
+        );
+        report.activity = filters.apply(name, report.activity, |a| {
+            filter::retain_review_states(a, &states)
+        });
+    }
+    if args.mark_forks || args.exclude_forks {
+        let forks = fetch_forks(github_client, &report.activity).await?;
+        if args.exclude_forks {
+            report.activity = filters.apply("--exclude-forks", report.activity, |a| {
+                fork::exclude_forks(a, &forks)
+            });
+        } else {
+            report.forks = forks;
+        }
+    }
+
+    let path_scope = if args.paths.is_empty() {
+        None
+    } else {
+        Some(scope::PathScope::new(&args.paths)?)
+    };
+    let codeowners = load_codeowners(args, github_client).await?;
+    if path_scope.is_some() || codeowners.is_some() {
+        let owners = if args.code_owners.is_empty() {
+            vec![format!("@{}", username)]
+        } else {
+            args.code_owners.clone()
+        };
+        let in_scope = |file: &str| {
+            path_scope.as_ref().is_none_or(|s| s.is_match(file))
+                && codeowners
+                    .as_ref()
+                    .is_none_or(|c| c.is_owned_by(file, &owners))
+        };
+        report.activity = scope::scope_activity(github_client, report.activity, in_scope)
+            .await
+            .context("Failed to scope activity to the requested paths")?;
+        let mut name = Vec::new();
+        if !args.paths.is_empty() {
+            name.push(format!("--path {}", args.paths.join(",")));
+        }
+        if codeowners.is_some() {
+            name.push(format!("--code-owner {}", owners.join(",")));
+        }
+        filters.record(name.join(" "), &report.activity);
+    }
+    if args.explain_filters {
+        eprintln!("Filters applied to {}'s activity:\n{}", username, filters);
+    }
+
+    if args.include_timeline {
+        let ids: Vec<String> = report
+            .activity
+            .user
+            .iter()
+            .flat_map(|user| &user.contributions_collection.issue_contributions.nodes)
+            .flatten()
+            .map(|node| node.issue.id.clone())
+            .collect();
+        info!("Fetching timelines of {} issue(s)", ids.len());
+        let issues = github_client
+            .fetch_issue_timelines(&ids)
+            .await
+            .context("Failed to fetch issue timelines from GitHub API")?;
+        report.issue_histories = timeline::histories_from_issues(&issues);
+    }
+
+    let merged_prs: Vec<_> = report
+        .activity
+        .user
+        .iter()
+        .flat_map(|user| {
+            &user
+                .contributions_collection
+                .pull_request_contributions
+                .nodes
+        })
+        .flatten()
+        .map(|node| node.pull_request.clone())
+        .filter(|pr| pr.merged)
+        .collect();
+
+    if args.detect_reverts {
+        let repos: BTreeSet<String> = merged_prs
+            .iter()
+            .filter_map(|pr| revert::repository_of(&pr.url))
+            .collect();
+        let mut candidates = Vec::new();
+        for repo in &repos {
+            info!("Searching {} for reverted pull requests", repo);
+            candidates.extend(
+                github_client
+                    .fetch_revert_candidates(repo)
+                    .await
+                    .with_context(|| format!("Failed to search {} for reverts", repo))?,
+            );
+        }
+        let reverts = revert::find_reverts(&merged_prs, &candidates);
+        info!("Found {} reverted pull request(s)", reverts.len());
+        report.reverted_prs = reverts;
+    }
+
+    if args.include_releases {
+        let shipped = release::find_releases(github_client, &merged_prs)
+            .await
+            .context("Failed to find releases of merged pull requests")?;
+        info!("Found releases for {} pull request(s)", shipped.len());
+        report.shipped_in = shipped;
+    }
+
+    if args.triage {
+        let repo = args.repo.as_deref().context("--triage requires --repo")?;
+        info!("Fetching issue timelines of {} for triage mode", repo);
+        let issues = github_client
+            .fetch_repo_triage(repo)
+            .await
+            .context("Failed to fetch triage activity from GitHub API")?;
+        let summary = triage::TriageSummary::from_issues(
+            repo,
+            username,
+            github_client.start_date(),
+            github_client.end_date(),
+            &issues,
+        );
+        info!("Found {} triage action(s) in {}", summary.total(), repo);
+        report.triage = Some(summary);
+    }
+
+    if args.check_commit_emails {
+        report.unattributed_commits = find_unattributed_commits(github_client, &report).await?;
+    }
+
+    if args.review_coverage
+        && let Some(user) = &report.activity.user
+    {
+        report.review_coverage =
+            coverage::review_coverage(github_client, &user.contributions_collection)
+                .await
+                .context("Failed to measure review coverage")?;
+    }
+
+    Ok(report)
+}
+
+/// Find the author emails of commits GitHub didn't attribute to the report's user, in the
+/// repositories they pushed to or opened pull requests in. Only the authenticated user's
+/// account emails can be listed, so other users are skipped with a warning.
+async fn find_unattributed_commits(
+    github_client: &github::GithubClient,
+    report: &Report,
+) -> anyhow::Result<Vec<identity::UnattributedEmail>> {
+    let (login, name) = github_client.fetch_viewer().await?;
+    if !login.eq_ignore_ascii_case(&report.username) {
+        warn!(
+            "--check-commit-emails only works for the token's own user ({}); skipping {}",
+            login, report.username
+        );
+        return Ok(Vec::new());
+    }
+    let emails = github_client.fetch_viewer_emails().await?;
+    let Some(user) = &report.activity.user else {
+        return Ok(Vec::new());
+    };
+    let cc = &user.contributions_collection;
+    let repos: BTreeSet<String> = cc
+        .commit_contributions_by_repository
+        .iter()
+        .map(|repo| repo.repository.name_with_owner.clone())
+        .chain(
+            cc.pull_request_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .filter_map(|node| revert::repository_of(&node.pull_request.url)),
+        )
+        .collect();
+    let mut commits = Vec::new();
+    for repo in &repos {
+        info!("Checking commits of {} for unattributed emails", repo);
+        commits.extend(
+            github_client
+                .fetch_unlinked_commits(repo)
+                .await
+                .with_context(|| format!("Failed to check commits of {}", repo))?,
+        );
+    }
+    let unattributed = identity::unattributed_emails(&commits, &emails, &login, name.as_deref());
+    if !unattributed.is_empty() {
+        warn!(
+            "{} email(s) authored commits that GitHub didn't attribute to {}",
+            unattributed.len(),
+            login
+        );
+    }
+    Ok(unattributed)
+}
+
+/// Load the CODEOWNERS file selected by --codeowners or --codeowners-from-repo, if any.
+async fn load_codeowners(
+    args: &Args,
+    github_client: &github::GithubClient,
+) -> anyhow::Result<Option<codeowners::CodeOwners>> {
+    let contents = if let Some(path) = &args.codeowners {
+        fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?
+    } else if args.codeowners_from_repo {
+        let repo = args
+            .repo
+            .as_deref()
+            .context("--codeowners-from-repo requires --repo")?;
+        let mut found = None;
+        for path in codeowners::CODEOWNERS_PATHS {
+            if let Some(contents) = github_client.fetch_file_contents(repo, path).await? {
+                info!("Using {} from {}", path, repo);
+                found = Some(contents);
+                break;
+            }
+        }
+        found.with_context(|| format!("No CODEOWNERS file found in {}", repo))?
+    } else {
+        return Ok(None);
+    };
+    codeowners::CodeOwners::parse(&contents).map(Some)
+}
+
+/// Read the GitHub token of the selected account, or else from the environment.
+///
+/// An account's token is only used against its own endpoint; with another `--api-url`, the
+/// token comes from the environment.
+fn github_token(args: &Args) -> anyhow::Result<String> {
+    if let Some(accounts) = Accounts::open()
+        && let Some((name, account)) = accounts.selected(args.account.as_deref())?
+    {
+        if let Some(token) = account.token_for(&args.api_url) {
+            debug!("Using the token of account {}", name);
+            return Ok(token.to_string());
+        }
+        return env::var("GITHUB_TOKEN").with_context(|| {
+            format!(
+                "Account {} belongs to {}, not --api-url {}; set GITHUB_TOKEN for {} or pick another account",
+                name, account.api_url, args.api_url, args.api_url
+            )
+        });
+    }
+    let github_token = env::var("GITHUB_TOKEN").context(
+        "GITHUB_TOKEN environment variable is required (or store an account with `account add`)",
+    )?;
+    debug!("GitHub token retrieved successfully.");
+    Ok(github_token)
+}
+
+/// Fetch the activity for the client's user and time range and apply the filters.
+///
+/// With a cache, a fresh entry for the same endpoint, user, and `range` is used instead of
+/// calling the API, and freshly fetched activity is stored for later runs.
+async fn fetch_filtered(
+    github_client: &github::GithubClient,
+    repo: &Option<String>,
+    org: &Option<String>,
+    cache: Option<&ActivityCache>,
+    range: &str,
+) -> anyhow::Result<Report> {
+    let range = format!("{}{}", range, fetch_scope(github_client));
+    let key = ActivityCache::key(github_client.api_url(), github_client.username(), &range);
+    let mut warning = None;
+    let entry = match cache.and_then(|cache| cache.load(&key, chrono::Utc::now())) {
+        Some(entry) => {
+            info!("Using activity cached at {}", entry.fetched_at);
+            entry
+        }
+        None => {
+            let activity;
+            (activity, warning) = fetch_activity_or_unscoped(github_client).await?;
+            info!("Activity fetched successfully.");
+            let entry = CachedActivity {
+                fetched_at: chrono::Utc::now(),
+                start_date: github_client.start_date(),
+                end_date: github_client.end_date(),
+                activity,
+            };
+            // Unscoped activity doesn't belong under the organization's cache key.
+            if warning.is_none()
+                && let Some(cache) = cache
+                && let Err(err) = cache.store(&key, &entry)
+            {
+                warn!("Failed to cache activity: {:#}", err);
+            }
+            entry
+        }
+    };
+
+    let filtered_activity = filter::filter_activity(entry.activity, repo, org);
+
+    let mut report = Report::new(
+        github_client.username().to_string(),
+        entry.start_date,
+        entry.end_date,
+        filtered_activity,
+    );
+    report.warnings.extend(warning);
+    Ok(report)
+}
 
+/// What sets the client's fetches apart besides their user and range, to key stored activity
+/// by: activity fetched without review bodies can't serve a request for them, and vice versa,
+/// and activity scoped to one organization can't serve another.
+fn fetch_scope(github_client: &github::GithubClient) -> String {
+    let mut scope = String::new();
+    if github_client.fetches_review_bodies() {
+        scope.push_str(" with review bodies");
+    }
+    if let Some(id) = github_client.organization_id() {
+        scope.push_str(&format!(" in organization {}", id));
+    }
+    scope
+}
+
+/// Look up which of the repositories the client's user owns in `activity` are forks.
+async fn fetch_forks(
+    github_client: &github::GithubClient,
+    activity: &github::user_activity::ResponseData,
+) -> anyhow::Result<fork::Forks> {
+    let mut forks = fork::Forks::new();
+    for repo in fork::own_repositories(activity, github_client.username()) {
+        info!("Checking whether {} is a fork", repo);
+        let repository = github_client
+            .fetch_fork(&repo)
+            .await
+            .with_context(|| format!("Failed to check whether {} is a fork", repo))?;
+        if repository.is_fork {
+            forks.insert(repo, repository.parent.map(|parent| parent.name_with_owner));
+        }
+    }
+    Ok(forks)
+}
+
+/// Fetch the activity of the client's user. When the token can't read the `--org-scope`
+/// organization, the activity is fetched unscoped instead, along with a warning saying so.
+async fn fetch_activity_or_unscoped(
+    github_client: &github::GithubClient,
+) -> anyhow::Result<(github::user_activity::ResponseData, Option<String>)> {
+    let err = match github_client.fetch_activity().await {
+        Ok(activity) => return Ok((activity, None)),
+        Err(err) => err,
+    };
+    let denied = err
+        .downcast_ref::<github::OrganizationAccessDenied>()
+        .filter(|_| github_client.organization_id().is_some());
+    let Some(denied) = denied else {
+        return Err(err.context("Failed to fetch activity from GitHub API"));
+    };
+    let warning = format!(
+        "{}; contributions are not limited to the --org-scope organization",
+        denied
+    );
+    warn!("{}", warning);
     let activity = github_client
+        .for_user(
+            github_client.username().to_string(),
+            github_client.start_date(),
+            github_client.end_date(),
+        )
+        .organization_scope(None)
         .fetch_activity()
         .await
         .context("Failed to fetch activity from GitHub API")?;
-    info!("Activity fetched successfully.");
+    Ok((activity, Some(warning)))
+}
+
+/// Fetch the activity for the client's user over the requested range, reusing what previous
+/// `--since-last-run` runs fetched within it; the filters are left to the caller.
+///
+/// Only the parts of the range the stored segments don't cover are fetched, and only the
+/// segments within the range are kept, so the report and the stored state span the requested
+/// range however often the run repeats.
+async fn fetch_since_last_run(
+    args: &Args,
+    github_client: &github::GithubClient,
+) -> anyhow::Result<Report> {
+    let runs = LastRuns::new(storage::open(args.store.as_deref())?.context(
+        "No state directory to record runs in; set HOME or XDG_STATE_HOME, or pass --store",
+    )?);
+    let key = LastRuns::key(
+        github_client.api_url(),
+        github_client.username(),
+        &fetch_scope(github_client),
+    );
+    let username = github_client.username().to_string();
+    let (start_date, end_date) = (github_client.start_date(), github_client.end_date());
+
+    let (mut segments, gaps) = last_run::plan(runs.load(&key)?, start_date, end_date);
+    if gaps.is_empty() {
+        info!(
+            "Nothing to fetch; the last runs already cover {} to {}",
+            start_date, end_date
+        );
+    }
+    let mut warning = None;
+    for (from, to) in gaps {
+        info!("Fetching contributions from {} to {}", from, to);
+        let (activity, unscoped) =
+            fetch_activity_or_unscoped(&github_client.for_user(username.clone(), from, to)).await?;
+        warning = warning.or(unscoped);
+        segments.push(Report::new(username.clone(), from, to, activity));
+    }
+    // Unscoped activity doesn't belong under the organization's key.
+    if warning.is_none() {
+        runs.store(&key, &segments)?;
+    }
+
+    let mut report = last_run::assemble(username, start_date, end_date, segments);
+    report.warnings.extend(warning);
+    Ok(report)
+}
 
-    let filtered_activity = filter::filter_activity(activity, &args.repo, &args.org);
+/// Generate every report in a batch specs file, sharing one HTTP client.
+///
+/// Reports are fetched one after another rather than concurrently so a large batch
+/// doesn't trip GitHub's secondary rate limits.
+async fn run_batch(args: &Args, specs: &Path) -> anyhow::Result<()> {
+    let reports = batch::load_specs(specs)?;
+    info!("Loaded {} report spec(s) from {:?}", reports.len(), specs);
 
-    // Infer output format from the output file extension if provided.
-    let output_format = if let Some(ref output_path) = args.output {
-        if let Some(ext) = output_path.extension().and_then(|s| s.to_str()) {
-            match ext.to_lowercase().as_str() {
-                "md" | "markdown" => OutputFormat::Markdown,
-                "txt" => OutputFormat::Plain,
-                "json" => OutputFormat::Json,
-                _ => args.format.clone(), // fall back to user-specified/default
+    let base_client = github::GithubClient::new(
+        github_token(args)?,
+        args.api_url.clone(),
+        String::new(),
+        Default::default(),
+        Default::default(),
+    )
+    .context("Failed to create GitHub client")?
+    .wait_on_rate_limit(!args.no_wait)
+    .on_progress(progress_reporter(args))
+    .persisted_queries(args.persisted_queries)
+    .page_sizes(args.page_sizes())
+    .review_bodies(args.review_bodies);
+
+    let template = load_template(args)?;
+    let cache = open_cache(args);
+    for spec in &reports {
+        info!(
+            "Fetching activity for {} from {} to {}",
+            spec.username, spec.start_date, spec.end_date
+        );
+        let github_client =
+            base_client.for_user(spec.username.to_string(), spec.start_date, spec.end_date);
+        let range = cache::range_key(spec.period, spec.start_date, spec.end_date);
+        let report = fetch_filtered(
+            &github_client,
+            &spec.repo,
+            &spec.org,
+            cache.as_ref(),
+            &range,
+        )
+        .await
+        .with_context(|| format!("Failed to generate report for {}", spec.username))?;
+
+        let rendered = match &template {
+            Some(template) => template.render(&report)?,
+            None => {
+                let output_format = spec
+                    .format
+                    .clone()
+                    .or_else(|| OutputFormat::from_path(&spec.output))
+                    .unwrap_or_else(|| args.format.clone());
+                render_report(args, &output_format, &report)?
+            }
+        };
+        let contents = sealed(args, rendered.as_bytes())?;
+        fs::write(&spec.output, &contents)
+            .with_context(|| format!("Failed to write report to {:?}", spec.output))?;
+        manifest::record_report(&report);
+        manifest::record_output(&spec.output, &contents);
+        println!("Report saved to {:?}", spec.output);
+    }
+
+    manifest::record_api_requests(base_client.request_count());
+    Ok(())
+}
+
+/// Fetch the report of every member of `team`, resolving its GitHub team if it names one,
+/// along with the client they were fetched with.
+async fn fetch_team_reports(
+    args: &Args,
+    team: &team::Team,
+) -> anyhow::Result<(github::GithubClient, Vec<Report>)> {
+    let base_client = github::GithubClient::new(
+        github_token(args)?,
+        args.api_url.clone(),
+        String::new(),
+        team.start_date,
+        team.end_date,
+    )
+    .context("Failed to create GitHub client")?
+    .wait_on_rate_limit(!args.no_wait)
+    .on_progress(progress_reporter(args))
+    .persisted_queries(args.persisted_queries)
+    .page_sizes(args.page_sizes())
+    .review_bodies(args.review_bodies);
+
+    let mut members: Vec<String> = team.members.iter().map(ToString::to_string).collect();
+    let mut warning = None;
+    if let Some(name) = &team.team {
+        let team_members = match base_client.fetch_team_members(name).await {
+            Ok(team_members) => team_members,
+            Err(err) => {
+                // Without access to the organization's teams, report on the listed members
+                // alone.
+                let denied = err.downcast_ref::<github::OrganizationAccessDenied>();
+                let Some(denied) = denied.filter(|_| !members.is_empty()) else {
+                    return Err(
+                        err.context(format!("Failed to fetch the members of team {}", name))
+                    );
+                };
+                let message = format!(
+                    "{}; only the listed members of team {} are included",
+                    denied, name
+                );
+                warn!("{}", message);
+                warning = Some(message);
+                Vec::new()
+            }
+        };
+        info!("Team {} has {} member(s)", name, team_members.len());
+        for member in team_members {
+            if !members.iter().any(|m| m.eq_ignore_ascii_case(&member)) {
+                members.push(member);
             }
-        } else {
-            args.format.clone()
         }
+    }
+
+    let cache = open_cache(args);
+    let range = cache::range_key(team.period, team.start_date, team.end_date);
+    let mut reports = Vec::with_capacity(members.len());
+    for member in &members {
+        info!("Fetching activity for {}", member);
+        let github_client = base_client.for_user(member.clone(), team.start_date, team.end_date);
+        let report = fetch_filtered(&github_client, &None, &team.org, cache.as_ref(), &range)
+            .await
+            .with_context(|| format!("Failed to generate report for {}", member))?;
+        manifest::record_report(&report);
+        reports.push(report);
+    }
+    add_warning(&mut reports, &warning);
+    Ok((base_client, reports))
+}
+
+/// Aggregate the activity of the team in `spec` into anonymized distributions, written as
+/// JSON to `.json` outputs and as Markdown otherwise.
+async fn run_aggregate_anon(args: &Args, spec: &Path) -> anyhow::Result<()> {
+    let team = team::load_spec(spec)?;
+    let (base_client, reports) = fetch_team_reports(args, &team).await?;
+    let mut members = Vec::with_capacity(reports.len());
+    for report in reports {
+        let github_client =
+            base_client.for_user(report.username.clone(), team.start_date, team.end_date);
+        let reviewed = github_client
+            .fetch_reviewed_pull_requests()
+            .await
+            .with_context(|| format!("Failed to fetch the reviews of {}", report.username))?;
+        members.push(aggregate::MemberActivity {
+            review_latencies: aggregate::review_latencies(
+                &reviewed,
+                team.start_date,
+                team.end_date,
+            ),
+            report,
+        });
+    }
+    manifest::record_api_requests(base_client.request_count());
+
+    let analytics = aggregate::TeamAnalytics::aggregate(&members, &aggregate::default_metrics())?;
+    let markdown = analytics.to_markdown("Team Analytics", args.locale);
+    let outputs: Vec<PathBuf> = team.output.into_iter().chain(args.output.clone()).collect();
+    if outputs.is_empty() {
+        print!("{}", markdown);
+        return Ok(());
+    }
+    for path in &outputs {
+        let rendered = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::to_string_pretty(&analytics).context("Failed to serialize analytics")?
+        } else {
+            markdown.clone()
+        };
+        let contents = sealed(args, rendered.as_bytes())?;
+        fs::write(path, &contents)
+            .with_context(|| format!("Failed to write team analytics to {:?}", path))?;
+        manifest::record_output(path, &contents);
+        println!("Team analytics saved to {:?}", path);
+    }
+    Ok(())
+}
+
+/// Fetch the activity of every member of the team described in a team spec file and write
+/// them as one newsletter, to the spec's output path, the --output paths, or stdout.
+///
+/// Members are fetched one after another, like batch reports.
+async fn run_newsletter(args: &Args, spec: &Path) -> anyhow::Result<()> {
+    let team = team::load_spec(spec)?;
+    let (base_client, reports) = fetch_team_reports(args, &team).await?;
+    manifest::record_api_requests(base_client.request_count());
+
+    let rendered = Newsletter {
+        title: team.title,
+        sections: team.sections,
+        top_repositories: team.top_repositories,
+        locale: args.locale,
+    }
+    .render(&reports);
+    let outputs: Vec<PathBuf> = team.output.into_iter().chain(args.output.clone()).collect();
+    if outputs.is_empty() {
+        print!("{}", rendered);
+        return Ok(());
+    }
+    let contents = sealed(args, rendered.as_bytes())?;
+    for path in &outputs {
+        fs::write(path, &contents)
+            .with_context(|| format!("Failed to write newsletter to {:?}", path))?;
+        manifest::record_output(path, &contents);
+        println!("Newsletter saved to {:?}", path);
+    }
+    Ok(())
+}
+
+/// Fetch each user's activity one window at a time, saving progress after every window, and
+/// build their reports once all windows are fetched.
+///
+/// With `resume`, the previous backfill of each user is picked up where it stopped: windows
+/// already fetched are kept and only the failed or unreached ones are requested again.
+async fn run_backfill(
+    args: &Args,
+    resume: bool,
+    window: chrono::Duration,
+) -> anyhow::Result<Vec<Report>> {
+    if args.usernames.is_empty() {
+        anyhow::bail!("--username is required");
+    }
+    let store = BackfillStore::new(storage::open(args.store.as_deref())?.context(
+        "No state directory to keep backfills in; set HOME or XDG_STATE_HOME, or pass --store",
+    )?);
+    // A resumed backfill keeps the range it was planned with, so none is needed.
+    let (start_date, end_date) = if resume {
+        let now = chrono::Utc::now();
+        (now, now)
     } else {
-        args.format.clone()
+        args.get_date_range()
+            .map_err(|e| anyhow::anyhow!("Failed to get date range: {}", e))?
     };
+    // Each window sets its own range on the client it is fetched with.
+    let base_client = github::GithubClient::new(
+        github_token(args)?,
+        args.api_url.clone(),
+        String::new(),
+        start_date,
+        end_date,
+    )
+    .context("Failed to create GitHub client")?
+    .wait_on_rate_limit(!args.no_wait)
+    .on_progress(progress_reporter(args))
+    .persisted_queries(args.persisted_queries)
+    .page_sizes(args.page_sizes())
+    .review_bodies(args.review_bodies);
+    let (base_client, warning) = scope_to_organization(args, base_client).await?;
 
-    // Generate the report in the specified format
-    let report = match output_format {
-        OutputFormat::Json => serde_json::to_string_pretty(&filtered_activity)
-            .context("Failed to serialize activity to JSON")?,
-        OutputFormat::Plain => {
-            PlainTextFormatter.format(&filtered_activity, start_date, end_date, &args.username.0)
+    let mut failed = Vec::new();
+    let mut reports = Vec::with_capacity(args.usernames.len());
+    for username in &args.usernames {
+        let key = BackfillStore::key(&args.api_url, &username.0, &fetch_scope(&base_client));
+        let mut backfill = if resume {
+            store.load(&key)?.with_context(|| {
+                format!("No backfill of {} to resume; run backfill first", username)
+            })?
+        } else {
+            Backfill::plan(username.to_string(), start_date, end_date, window)
+        };
+        store.store(&key, &backfill)?;
+
+        let total = backfill.windows.len();
+        for i in 0..total {
+            let window = &mut backfill.windows[i];
+            if window.status == WindowStatus::Done {
+                continue;
+            }
+            info!(
+                "Fetching window {}/{} of {}: {} to {}",
+                i + 1,
+                total,
+                username,
+                window.start,
+                window.end
+            );
+            let github_client =
+                base_client.for_user(username.to_string(), window.start, window.end);
+            match github_client.fetch_activity().await {
+                Ok(activity) => {
+                    window.status = WindowStatus::Done;
+                    window.error = None;
+                    window.activity = Some(activity);
+                }
+                Err(err) => {
+                    warn!(
+                        "Window {} to {} failed: {:#}",
+                        window.start, window.end, err
+                    );
+                    window.status = WindowStatus::Failed;
+                    window.error = Some(format!("{:#}", err));
+                }
+            }
+            store.store(&key, &backfill)?;
+        }
+
+        match backfill.merged_activity() {
+            Some(activity) => {
+                let (first, last) = (&backfill.windows[0], &backfill.windows[total - 1]);
+                reports.push(Report::new(
+                    username.to_string(),
+                    first.start,
+                    last.end,
+                    filter::filter_activity(activity, &args.repo, &args.org),
+                ));
+            }
+            None => failed.push(format!(
+                "{} ({} of {} windows)",
+                username,
+                backfill.failed(),
+                total
+            )),
         }
-        OutputFormat::Markdown => {
-            MarkdownFormatter.format(&filtered_activity, start_date, end_date, &args.username.0)
+    }
+    manifest::record_api_requests(base_client.request_count());
+    if !failed.is_empty() {
+        anyhow::bail!(
+            "Backfill incomplete for {}; rerun with `backfill --resume` to retry the failed windows",
+            failed.join(", ")
+        );
+    }
+    add_warning(&mut reports, &warning);
+    Ok(reports)
+}
+
+/// A time range to fetch: its `--period`, if it is the one the range was computed from, and
+/// its start and end.
+type FetchRange = (
+    Option<chrono::Duration>,
+    chrono::DateTime<chrono::Utc>,
+    chrono::DateTime<chrono::Utc>,
+);
+
+/// Fetch the activity of `username` over each of `ranges`, in order.
+async fn fetch_ranges(
+    args: &Args,
+    username: &str,
+    ranges: &[FetchRange],
+) -> anyhow::Result<Vec<Report>> {
+    let base_client = github::GithubClient::new(
+        github_token(args)?,
+        args.api_url.clone(),
+        String::new(),
+        Default::default(),
+        Default::default(),
+    )
+    .context("Failed to create GitHub client")?
+    .wait_on_rate_limit(!args.no_wait)
+    .on_progress(progress_reporter(args))
+    .persisted_queries(args.persisted_queries)
+    .page_sizes(args.page_sizes())
+    .review_bodies(args.review_bodies);
+    let (base_client, warning) = scope_to_organization(args, base_client).await?;
+
+    let cache = open_cache(args);
+    let mut reports = Vec::with_capacity(ranges.len());
+    for &(period, start, end) in ranges {
+        info!(
+            "Fetching activity of {} from {} to {}",
+            username, start, end
+        );
+        let github_client = base_client.for_user(username.to_string(), start, end);
+        let range = cache::range_key(period, start, end);
+        let report = fetch_filtered(
+            &github_client,
+            &args.repo,
+            &args.org,
+            cache.as_ref(),
+            &range,
+        )
+        .await
+        .with_context(|| format!("Failed to generate report for {}", username))?;
+        manifest::record_report(&report);
+        reports.push(report);
+    }
+    manifest::record_api_requests(base_client.request_count());
+    add_warning(&mut reports, &warning);
+    Ok(reports)
+}
+
+/// Fetch the user's activity over the requested range and the period of the same length
+/// before it, and print or write the performance review summary; `.json` outputs get the
+/// summary as JSON, other outputs and the terminal Markdown.
+async fn run_review(args: &Args) -> anyhow::Result<()> {
+    let [username] = args.usernames.as_slice() else {
+        anyhow::bail!("--mode review takes exactly one --username");
+    };
+    let (start_date, end_date) = args
+        .get_date_range()
+        .map_err(|e| anyhow::anyhow!("Failed to get date range: {}", e))?;
+    let ranges = [
+        (None, start_date - (end_date - start_date), start_date),
+        (args.rolling_period(), start_date, end_date),
+    ];
+    let reports = fetch_ranges(args, &username.0, &ranges).await?;
+
+    let summary = review::ReviewSummary::new(&reports[1], &reports[0], args.locale);
+    let markdown = summary.to_markdown(args.locale);
+    if args.output.is_empty() {
+        print!("{}", markdown);
+        return Ok(());
+    }
+    for path in &args.output {
+        let rendered = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::to_string_pretty(&summary).context("Failed to serialize review summary")?
+        } else {
+            markdown.clone()
+        };
+        let contents = sealed(args, rendered.as_bytes())?;
+        fs::write(path, &contents)
+            .with_context(|| format!("Failed to write review summary to {:?}", path))?;
+        manifest::record_output(path, &contents);
+        println!("Review summary saved to {:?}", path);
+    }
+    Ok(())
+}
+
+/// Fetch the user's activity over `year` (by default the current one, up to now) and print
+/// or write its year in review; `.html` outputs get an HTML page, `.json` outputs JSON, and
+/// other outputs and the terminal Markdown, or HTML with --format html.
+async fn run_year_in_review(args: &Args, year: Option<i32>) -> anyhow::Result<()> {
+    let [username] = args.usernames.as_slice() else {
+        anyhow::bail!("year-in-review takes exactly one --username");
+    };
+    let now = chrono::Utc::now();
+    let year = year.unwrap_or(now.year());
+    let start_of = |year: i32| {
+        chrono::NaiveDate::from_ymd_opt(year, 1, 1)
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|midnight| midnight.and_utc())
+            .with_context(|| format!("Invalid year: {}", year))
+    };
+    let start_date = start_of(year)?;
+    let end_date = start_of(year + 1)?.min(now);
+    if start_date >= end_date {
+        anyhow::bail!("{} hasn't started yet", year);
+    }
+    let reports = fetch_ranges(args, &username.0, &[(None, start_date, end_date)]).await?;
+
+    let review = year_in_review::YearInReview::new(&reports[0], year);
+    let render = |format: &OutputFormat| match format {
+        OutputFormat::Html => Ok(review.to_html(args.locale)),
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(&review).context("Failed to serialize year in review")
         }
+        _ => Ok(review.to_markdown(args.locale)),
     };
+    if args.output.is_empty() {
+        let format = match args.format {
+            OutputFormat::Html => OutputFormat::Html,
+            _ => OutputFormat::Markdown,
+        };
+        print!("{}", render(&format)?);
+        return Ok(());
+    }
+    for path in &args.output {
+        let format = OutputFormat::from_path(path).unwrap_or(OutputFormat::Markdown);
+        let contents = sealed(args, render(&format)?.as_bytes())?;
+        fs::write(path, &contents)
+            .with_context(|| format!("Failed to write year in review to {:?}", path))?;
+        manifest::record_output(path, &contents);
+        println!("Year in review saved to {:?}", path);
+    }
+    Ok(())
+}
 
-    // Write report to a file if specified, otherwise print it.
-    if let Some(output_path) = args.output {
-        fs::write(&output_path, report)
+/// Fetch the user's activity over the requested range and an earlier period, and print or
+/// write the comparison of their contribution calendars; `.svg` outputs get an SVG image.
+async fn run_compare(
+    args: &Args,
+    previous_from: Option<chrono::DateTime<chrono::Utc>>,
+    previous_to: Option<chrono::DateTime<chrono::Utc>>,
+) -> anyhow::Result<()> {
+    let [username] = args.usernames.as_slice() else {
+        anyhow::bail!("compare takes exactly one --username");
+    };
+    let (start_date, end_date) = args
+        .get_date_range()
+        .map_err(|e| anyhow::anyhow!("Failed to get date range: {}", e))?;
+    let (previous_start, previous_end) = match (previous_from, previous_to) {
+        (Some(from), Some(to)) if from < to => (from, to),
+        (Some(_), Some(_)) => anyhow::bail!("--previous-from must be before --previous-to"),
+        _ => (start_date - (end_date - start_date), start_date),
+    };
+    let ranges = [
+        (None, previous_start, previous_end),
+        (args.rolling_period(), start_date, end_date),
+    ];
+    let reports = fetch_ranges(args, &username.0, &ranges).await?;
+
+    let comparison = HeatmapComparison {
+        previous: &reports[0],
+        current: &reports[1],
+        locale: args.locale,
+    };
+    if args.output.is_empty() {
+        print!(
+            "{}",
+            comparison.terminal(std::io::stdout().is_terminal() && args.color())
+        );
+        return Ok(());
+    }
+    for path in &args.output {
+        let rendered = if path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"))
+        {
+            comparison.svg()
+        } else {
+            comparison.terminal(false)
+        };
+        let contents = sealed(args, rendered.as_bytes())?;
+        fs::write(path, &contents)
+            .with_context(|| format!("Failed to write comparison to {:?}", path))?;
+        manifest::record_output(path, &contents);
+        println!("Comparison saved to {:?}", path);
+    }
+    Ok(())
+}
+
+/// Placeholder in `--output` paths that is replaced by each user's name, writing one file per
+/// user instead of a combined report.
+const USERNAME_PLACEHOLDER: &str = "{username}";
+
+/// Print the reports, or write them to every requested output file.
+fn write_outputs(args: &Args, reports: &[Report]) -> anyhow::Result<()> {
+    let template = load_template(args)?;
+    if args.output.is_empty() && !args.encrypt_to.is_empty() {
+        let mut stdout = std::io::stdout().lock();
+        let mut encrypted = EncryptWriter::new(&mut stdout, &args.encrypt_to, true)?;
+        write_reports(
+            args,
+            template.as_ref(),
+            &args.format,
+            reports,
+            &mut encrypted,
+        )?;
+        writeln!(encrypted)
+            .and_then(|_| encrypted.finish())
+            .context("Failed to write report to stdout")?;
+        return Ok(());
+    }
+    if args.output.is_empty() {
+        if streams_contributions(args) {
+            // Written while fetching.
+            return Ok(());
+        }
+        let mut stdout = std::io::BufWriter::new(std::io::stdout().lock());
+        write_reports(args, template.as_ref(), &args.format, reports, &mut stdout)?;
+        // JSON Lines end every line already.
+        if !(is_streamed(args, template.as_ref(), &args.format)
+            && args.format == OutputFormat::Jsonl)
+        {
+            writeln!(stdout).context("Failed to write report to stdout")?;
+        }
+        stdout.flush().context("Failed to write report to stdout")?;
+        return Ok(());
+    }
+
+    // Write every requested output file from the single fetch, inferring each file's format
+    // from its extension.
+    for output_path in &args.output {
+        let per_user_pattern = output_path
+            .to_str()
+            .filter(|path| path.contains(USERNAME_PLACEHOLDER));
+        match per_user_pattern {
+            Some(pattern) => {
+                for report in reports {
+                    let path =
+                        PathBuf::from(pattern.replace(USERNAME_PLACEHOLDER, &report.username));
+                    write_output(args, template.as_ref(), &path, std::slice::from_ref(report))?;
+                }
+            }
+            None => write_output(args, template.as_ref(), output_path, reports)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// With --output-dir, write the reports of each team of the config, combined, into a file
+/// named after the team, plus an index.md linking them.
+fn write_team_reports(args: &Args, dir: &Path, reports: &[Report]) -> anyhow::Result<()> {
+    let teams = Config::load_or_default(args.config.as_deref())?.teams()?;
+    if teams.is_empty() {
+        anyhow::bail!(
+            "--output-dir writes one report per team, but the config has no [teams]; add e.g. platform = [\"octocat\", \"hubot\"] under [teams]"
+        );
+    }
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create directory {:?}", dir))?;
+    let template = load_template(args)?;
+    let sealed_extension = |extension: &str| {
+        if args.encrypt_to.is_empty() {
+            extension.to_string()
+        } else {
+            format!("{}.age", extension)
+        }
+    };
+
+    let mut files = Vec::new();
+    for (name, members) in &teams {
+        let team_reports: Vec<Report> = members
+            .iter()
+            .filter_map(|member| {
+                reports
+                    .iter()
+                    .find(|report| report.username.eq_ignore_ascii_case(&member.0))
+            })
+            .cloned()
+            .collect();
+        if team_reports.is_empty() {
+            warn!("No reports for any member of team {}; skipping it", name);
+            continue;
+        }
+        let file = format!("{}.{}", name, sealed_extension(args.format.extension()));
+        write_output(args, template.as_ref(), &dir.join(&file), &team_reports)?;
+        files.push(team::TeamFile {
+            name: name.clone(),
+            file,
+            reports: team_reports,
+        });
+    }
+    for report in reports {
+        if !teams
+            .values()
+            .flatten()
+            .any(|m| m.0.eq_ignore_ascii_case(&report.username))
+        {
+            warn!(
+                "{} isn't a member of any team; leaving them out",
+                report.username
+            );
+        }
+    }
+
+    let path = dir.join(sealed_extension("index.md"));
+    let contents = sealed(args, team::team_index(&files).as_bytes())?;
+    fs::write(&path, &contents).with_context(|| format!("Failed to write index to {:?}", path))?;
+    manifest::record_output(&path, &contents);
+    println!("Index saved to {:?}", path);
+    Ok(())
+}
+
+/// Write the reports, combined, to a single output path.
+fn write_output(
+    args: &Args,
+    template: Option<&TemplateFormatter>,
+    output_path: &Path,
+    reports: &[Report],
+) -> anyhow::Result<()> {
+    let output_format = OutputFormat::from_path(output_path).unwrap_or_else(|| args.format.clone());
+    if template.is_none()
+        && !args.calendar_only
+        && output_format == OutputFormat::Csv
+        && is_directory_path(output_path)
+    {
+        let [report] = reports else {
+            anyhow::bail!(
+                "CSV tables of several users can't share {:?}; put {} in --output to write one directory per user",
+                output_path,
+                USERNAME_PLACEHOLDER
+            );
+        };
+        write_csv_tables(args, output_path, report)?;
+    } else {
+        let file = fs::File::create(output_path)
+            .with_context(|| format!("Failed to create {:?}", output_path))?;
+        let mut writer = manifest::ChecksumWriter::new(std::io::BufWriter::new(file));
+        if args.encrypt_to.is_empty() {
+            write_reports(args, template, &output_format, reports, &mut writer)?;
+        } else {
+            let mut encrypted = EncryptWriter::new(&mut writer, &args.encrypt_to, false)?;
+            write_reports(args, template, &output_format, reports, &mut encrypted)?;
+            encrypted
+                .finish()
+                .with_context(|| format!("Failed to write report to {:?}", output_path))?;
+        }
+        writer
+            .finish(output_path)
             .with_context(|| format!("Failed to write report to {:?}", output_path))?;
-        println!("Report saved to {:?}", output_path);
+    }
+    println!("Report saved to {:?}", output_path);
+    Ok(())
+}
+
+/// Render the reports as one document; see [`write_reports`].
+fn render_reports(
+    args: &Args,
+    template: Option<&TemplateFormatter>,
+    output_format: &OutputFormat,
+    reports: &[Report],
+) -> anyhow::Result<String> {
+    let mut rendered = Vec::new();
+    write_reports(args, template, output_format, reports, &mut rendered)?;
+    String::from_utf8(rendered).context("Rendered report is not valid UTF-8")
+}
+
+/// Write the reports into `writer` as one document: a single report as usual, several as
+/// per-user sections (or a JSON array) in formats that can hold more than one.
+///
+/// Each user's section is written as soon as it's rendered, so a combined report never holds
+/// more than one rendered section besides the fetched activity.
+fn write_reports(
+    args: &Args,
+    template: Option<&TemplateFormatter>,
+    output_format: &OutputFormat,
+    reports: &[Report],
+    mut writer: impl std::io::Write,
+) -> anyhow::Result<()> {
+    if is_streamed(args, template, output_format) {
+        return write_json(args, output_format, reports, writer);
+    }
+    let mut write = |rendered: &str| {
+        writer
+            .write_all(rendered.as_bytes())
+            .context("Failed to write report")
+    };
+    if args.calendar_only {
+        return write(&render_calendar(args, output_format, reports)?);
+    }
+    let render = |report: &Report| match template {
+        Some(template) => template.render(report),
+        None => render_report(args, output_format, report),
+    };
+    if let [report] = reports {
+        return write(&render(report)?);
+    }
+    // Several users' events share one calendar.
+    if template.is_none() && *output_format == OutputFormat::Ics {
+        return write(&IcsFormatter.calendar(reports));
+    }
+    let separator = match (template, output_format) {
+        (Some(_), _) | (None, OutputFormat::Plain | OutputFormat::Color) => "\n\n",
+        (None, OutputFormat::Markdown) => "\n\n---\n\n",
+        (None, format) => anyhow::bail!(
+            "Reports of several users can't be combined as {}; use plain, color, markdown, json, jsonl, or ics, or put {} in --output to write one file per user",
+            format!("{:?}", format).to_lowercase(),
+            USERNAME_PLACEHOLDER
+        ),
+    };
+    for (i, report) in reports.iter().enumerate() {
+        if i > 0 {
+            write(separator)?;
+        }
+        write(&render(report)?)?;
+    }
+    Ok(())
+}
+
+/// Render the --calendar-only export of the reports: their contribution calendars as
+/// date,count rows.
+fn render_calendar(
+    args: &Args,
+    output_format: &OutputFormat,
+    reports: &[Report],
+) -> anyhow::Result<String> {
+    let days = calendar::calendar_days(reports);
+    match output_format {
+        OutputFormat::Csv => Ok(CsvFormatter.calendar(&days).to_csv()),
+        OutputFormat::Json if args.compact_json => {
+            serde_json::to_string(&days).context("Failed to serialize calendar to JSON")
+        }
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(&days).context("Failed to serialize calendar to JSON")
+        }
+        OutputFormat::Jsonl => days
+            .iter()
+            .map(|day| serde_json::to_string(day).context("Failed to serialize calendar to JSON"))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map(|lines| lines.join("\n")),
+        format => anyhow::bail!(
+            "--calendar-only writes csv, json, or jsonl, not {}",
+            format!("{:?}", format).to_lowercase()
+        ),
+    }
+}
+
+/// Whether reports are serialized straight into the output (JSON and JSON Lines without a
+/// template or --calendar-only) rather than rendered into a string first.
+fn is_streamed(
+    args: &Args,
+    template: Option<&TemplateFormatter>,
+    output_format: &OutputFormat,
+) -> bool {
+    template.is_none()
+        && !args.calendar_only
+        && matches!(output_format, OutputFormat::Json | OutputFormat::Jsonl)
+}
+
+/// Serialize the reports into `writer`, one report at a time: as JSON Lines of their
+/// contribution nodes, or as JSON (an array when there are several reports).
+fn write_json(
+    args: &Args,
+    output_format: &OutputFormat,
+    reports: &[Report],
+    writer: impl std::io::Write,
+) -> anyhow::Result<()> {
+    match (output_format, reports) {
+        (OutputFormat::Jsonl, _) => jsonl::write_contribution_lines(reports, writer),
+        (_, [report]) => report.write_json(writer, args.compact_json),
+        _ => report::write_json_array(reports, writer, args.compact_json),
+    }
+}
+
+/// Compile the --template file, if one was given.
+fn load_template(args: &Args) -> anyhow::Result<Option<TemplateFormatter>> {
+    args.template
+        .as_deref()
+        .map(TemplateFormatter::from_file)
+        .transpose()
+}
+
+/// Whether an output path names a directory: an existing one, or one written with a
+/// trailing separator.
+fn is_directory_path(path: &Path) -> bool {
+    path.is_dir()
+        || path
+            .as_os_str()
+            .to_string_lossy()
+            .ends_with(std::path::is_separator)
+}
+
+/// Write each CSV table of the report into its own file inside `dir`.
+fn write_csv_tables(args: &Args, dir: &Path, report: &Report) -> anyhow::Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create directory {:?}", dir))?;
+    let extension = if args.encrypt_to.is_empty() {
+        "csv"
     } else {
-        println!("{}", report);
+        "csv.age"
+    };
+    for table in CsvFormatter.tables(report) {
+        let path = dir.join(format!("{}.{}", table.name, extension));
+        let csv = sealed(args, table.to_csv().as_bytes())?;
+        fs::write(&path, &csv).with_context(|| format!("Failed to write report to {:?}", path))?;
+        manifest::record_output(&path, &csv);
     }
+    Ok(())
+}
 
+/// Write each Parquet table of the reports into its own file inside `dir`.
+#[cfg(feature = "parquet")]
+fn write_parquet_tables(args: &Args, dir: &Path, reports: &[Report]) -> anyhow::Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create directory {:?}", dir))?;
+    let extension = if args.encrypt_to.is_empty() {
+        "parquet"
+    } else {
+        "parquet.age"
+    };
+    for table in github_activity_rs::columnar::tables(reports)? {
+        let path = dir.join(format!("{}.{}", table.name, extension));
+        let parquet = sealed(args, &table.to_parquet()?)?;
+        fs::write(&path, &parquet)
+            .with_context(|| format!("Failed to write Parquet table to {:?}", path))?;
+        manifest::record_output(&path, &parquet);
+    }
     Ok(())
 }
 
+#[cfg(not(feature = "parquet"))]
+fn write_parquet_tables(_args: &Args, _dir: &Path, _reports: &[Report]) -> anyhow::Result<()> {
+    anyhow::bail!(
+        "--parquet-dir needs a build with the parquet feature, e.g. cargo install --features parquet"
+    )
+}
+
+/// The contents to write to an output file: encrypted to the --encrypt-to recipients, if any.
+fn sealed(args: &Args, contents: &[u8]) -> anyhow::Result<Vec<u8>> {
+    if args.encrypt_to.is_empty() {
+        Ok(contents.to_vec())
+    } else {
+        encryption::encrypt(contents, &args.encrypt_to, false)
+    }
+}
+
+/// Generate the report in the specified format.
+fn render_report(
+    args: &Args,
+    output_format: &OutputFormat,
+    report: &Report,
+) -> anyhow::Result<String> {
+    let rendered = match output_format {
+        OutputFormat::Json | OutputFormat::Jsonl => {
+            render_reports(args, None, output_format, std::slice::from_ref(report))?
+        }
+        OutputFormat::Plain => PlainTextFormatter {
+            locale: args.locale,
+            calendar: args.calendar,
+            calendar_detail: args.calendar_detail,
+            tone: args.tone,
+        }
+        .format(report),
+        OutputFormat::Color => ColorTerminalFormatter {
+            locale: args.locale,
+            calendar: args.calendar,
+            color: args.color(),
+        }
+        .format(report),
+        OutputFormat::Markdown => MarkdownFormatter {
+            footnote_links: args.footnote_links,
+            locale: args.locale,
+            calendar: args.calendar,
+            calendar_detail: args.calendar_detail,
+            tone: args.tone,
+            group_by: args.group_by,
+            stable: args.stable_markdown,
+            sections: render_sections(args, report)?,
+        }
+        .format(report),
+        OutputFormat::Html => HtmlFormatter {
+            locale: args.locale,
+            calendar: args.calendar,
+            mix_chart: args.mix_chart,
+            group_by: args.group_by,
+        }
+        .format(report),
+        OutputFormat::Csv => CsvFormatter.format(report),
+        OutputFormat::Slack => SlackFormatter {
+            locale: args.locale,
+        }
+        .format(report),
+        OutputFormat::Ics => IcsFormatter.format(report),
+    };
+    Ok(rendered)
+}
+
+/// Render the --section-template snippets of the report, keyed by the section they replace.
+fn render_sections(
+    args: &Args,
+    report: &Report,
+) -> anyhow::Result<BTreeMap<MarkdownSection, String>> {
+    args.section_templates
+        .iter()
+        .map(|(section, path)| {
+            let rendered = TemplateFormatter::from_file(path)?
+                .render(report)
+                .with_context(|| format!("Failed to render section template {:?}", path))?;
+            Ok((*section, rendered))
+        })
+        .collect()
+}
+
 /// Format an error message for the user.
 fn format_error(error: &anyhow::Error) -> String {
+    // An organization's SAML SSO refused the token; the guidance matters more than the context.
+    if let Some(sso) = error.downcast_ref::<github::SsoAuthorizationRequired>() {
+        return sso.to_string();
+    }
     // Check if the error is a reqwest error and further, what kind it is.
     if let Some(reqwest_err) = error.downcast_ref::<reqwest::Error>() {
         if reqwest_err.is_connect() {
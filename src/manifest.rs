@@ -0,0 +1,256 @@
+//! Manifest module: records what a run did — its inputs, the reports it generated, how many
+//! API requests it sent, the warnings it logged, and the files it wrote with their checksums —
+//! and writes it as a JSON sidecar for `--manifest`, so pipelines can verify where a report
+//! came from.
+//!
+//! Facts are recorded into a process-wide log as the run goes, so the code that writes a file
+//! or logs a warning doesn't have to thread the manifest through.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use github_activity_rs::Report;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// The version of the manifest JSON layout.
+const MANIFEST_VERSION: u32 = 1;
+
+/// A report generated during the run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportInput {
+    /// The GitHub username the report covers.
+    pub username: String,
+    /// Start of the reported time range.
+    pub start_date: DateTime<Utc>,
+    /// End of the reported time range.
+    pub end_date: DateTime<Utc>,
+    /// Contributions counted by the report's contribution calendar.
+    pub contributions: i64,
+}
+
+/// A file written during the run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutputFile {
+    /// Where the file was written, as given.
+    pub path: PathBuf,
+    /// Size of the file in bytes.
+    pub bytes: usize,
+    /// Hex-encoded SHA-256 checksum of the file contents.
+    pub sha256: String,
+}
+
+/// Everything recorded so far.
+#[derive(Debug)]
+struct RunLog {
+    reports: Vec<ReportInput>,
+    api_requests: usize,
+    warnings: Vec<String>,
+    outputs: Vec<OutputFile>,
+}
+
+static RUN_LOG: Mutex<RunLog> = Mutex::new(RunLog {
+    reports: Vec::new(),
+    api_requests: 0,
+    warnings: Vec::new(),
+    outputs: Vec::new(),
+});
+
+fn run_log() -> MutexGuard<'static, RunLog> {
+    // A panic while holding the lock leaves the log usable; it only ever grows.
+    RUN_LOG
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Records a generated report.
+pub fn record_report(report: &Report) {
+    let contributions = report.activity.user.as_ref().map_or(0, |user| {
+        user.contributions_collection
+            .contribution_calendar
+            .total_contributions
+    });
+    run_log().reports.push(ReportInput {
+        username: report.username.clone(),
+        start_date: report.start_date,
+        end_date: report.end_date,
+        contributions,
+    });
+}
+
+/// Records `count` requests sent to the GitHub API.
+pub fn record_api_requests(count: usize) {
+    run_log().api_requests += count;
+}
+
+/// Records that `contents` were written to `path`.
+pub fn record_output(path: &Path, contents: &[u8]) {
+    run_log().outputs.push(OutputFile {
+        path: path.to_path_buf(),
+        bytes: contents.len(),
+        sha256: format!("{:x}", Sha256::digest(contents)),
+    });
+}
+
+/// A writer that checksums everything written through it, for files that are streamed to
+/// disk rather than rendered in memory first.
+pub struct ChecksumWriter<W> {
+    inner: W,
+    hasher: Sha256,
+    bytes: usize,
+}
+
+impl<W: Write> ChecksumWriter<W> {
+    /// Checksums everything written to `inner`.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+            bytes: 0,
+        }
+    }
+
+    /// Flushes the writer and records that its contents were written to `path`.
+    pub fn finish(mut self, path: &Path) -> io::Result<()> {
+        self.inner.flush()?;
+        run_log().outputs.push(OutputFile {
+            path: path.to_path_buf(),
+            bytes: self.bytes,
+            sha256: format!("{:x}", self.hasher.finalize()),
+        });
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.bytes += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// The manifest of a run.
+#[derive(Debug, Serialize)]
+pub struct Manifest {
+    /// Version of the manifest layout.
+    pub manifest_version: u32,
+    /// Name and version of the tool that produced the run.
+    pub generator: String,
+    /// When the run started.
+    pub started_at: DateTime<Utc>,
+    /// When the run finished.
+    pub finished_at: DateTime<Utc>,
+    /// The command-line arguments of the run, without the program name.
+    pub arguments: Vec<String>,
+    /// The reports generated, in the order they were fetched.
+    pub reports: Vec<ReportInput>,
+    /// How many requests were sent to the GitHub API.
+    pub api_requests: usize,
+    /// Warnings logged during the run.
+    pub warnings: Vec<String>,
+    /// The files written, in the order they were written.
+    pub outputs: Vec<OutputFile>,
+}
+
+impl Manifest {
+    /// The manifest of the run so far, started at `started_at` with `arguments`.
+    pub fn collect(started_at: DateTime<Utc>, arguments: Vec<String>) -> Self {
+        let log = run_log();
+        Self {
+            manifest_version: MANIFEST_VERSION,
+            generator: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            started_at,
+            finished_at: Utc::now(),
+            arguments,
+            reports: log.reports.clone(),
+            api_requests: log.api_requests,
+            warnings: log.warnings.clone(),
+            outputs: log.outputs.clone(),
+        }
+    }
+
+    /// Writes the manifest as pretty-printed JSON to `path`.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let json =
+            serde_json::to_string_pretty(self).context("Failed to serialize run manifest")?;
+        fs::write(path, json).with_context(|| format!("Failed to write manifest to {:?}", path))
+    }
+}
+
+/// A logger that records every warning for the manifest and hands records on to `inner`.
+pub struct WarningRecorder<L> {
+    inner: L,
+}
+
+impl<L: Log + 'static> WarningRecorder<L> {
+    /// Installs the recorder in front of `inner`, whose own filter still decides what is
+    /// printed; warnings are recorded even when `inner` hides them.
+    pub fn install(inner: L, inner_filter: LevelFilter) -> Result<()> {
+        log::set_boxed_logger(Box::new(Self { inner })).context("Failed to install logger")?;
+        log::set_max_level(inner_filter.max(LevelFilter::Warn));
+        Ok(())
+    }
+}
+
+impl<L: Log> Log for WarningRecorder<L> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Warn || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if record.level() == Level::Warn {
+            run_log().warnings.push(record.args().to_string());
+        }
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_includes_recorded_outputs() {
+        let path = Path::new("manifest-test/report.md");
+        record_output(path, b"hello");
+        let manifest = Manifest::collect(Utc::now(), vec!["--username".into(), "octocat".into()]);
+        assert!(manifest.outputs.contains(&OutputFile {
+            path: path.to_path_buf(),
+            bytes: 5,
+            sha256: "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824".into(),
+        }));
+        assert_eq!(manifest.arguments, ["--username", "octocat"]);
+    }
+
+    #[test]
+    fn test_checksum_writer_matches_record_output() {
+        let mut writer = ChecksumWriter::new(Vec::new());
+        writer.write_all(b"hel").unwrap();
+        writer.write_all(b"lo").unwrap();
+        let path = Path::new("manifest-test/streamed.json");
+        writer.finish(path).unwrap();
+        let manifest = Manifest::collect(Utc::now(), Vec::new());
+        let streamed = manifest.outputs.iter().find(|o| o.path == path).unwrap();
+        assert_eq!(streamed.bytes, 5);
+        assert_eq!(
+            streamed.sha256,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+    }
+}
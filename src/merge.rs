@@ -0,0 +1,285 @@
+//! Merge module: combines the activity of two consecutive time ranges into the activity of
+//! the whole range, so a stored report can be extended with only the newest contributions.
+
+use crate::github::user_activity;
+use std::collections::{BTreeMap, HashSet};
+
+type ContributionCalendar =
+    user_activity::UserActivityUserContributionsCollectionContributionCalendar;
+type CalendarWeek = user_activity::UserActivityUserContributionsCollectionContributionCalendarWeeks;
+type CalendarDay =
+    user_activity::UserActivityUserContributionsCollectionContributionCalendarWeeksContributionDays;
+
+/// Merges `newer` activity, fetched for the range right after the one `older` covers, into
+/// `older`.
+///
+/// Totals, calendar days, and per-repository commit counts are added up; contribution nodes
+/// are concatenated, with a node present in both ranges taken from `newer`.
+pub fn merge_activity(
+    older: user_activity::ResponseData,
+    newer: user_activity::ResponseData,
+) -> user_activity::ResponseData {
+    let (Some(mut user), Some(newer_user)) = (older.user, newer.user) else {
+        return user_activity::ResponseData {
+            user: None,
+            rate_limit: None,
+        };
+    };
+    let cc = &mut user.contributions_collection;
+    let new = newer_user.contributions_collection;
+
+    cc.total_commit_contributions += new.total_commit_contributions;
+    cc.total_issue_contributions += new.total_issue_contributions;
+    cc.total_pull_request_contributions += new.total_pull_request_contributions;
+    cc.total_pull_request_review_contributions += new.total_pull_request_review_contributions;
+    cc.contribution_calendar = merge_calendars(
+        std::mem::take(&mut cc.contribution_calendar),
+        new.contribution_calendar,
+    );
+
+    for repo in new.commit_contributions_by_repository {
+        match cc
+            .commit_contributions_by_repository
+            .iter_mut()
+            .find(|r| r.repository.name_with_owner == repo.repository.name_with_owner)
+        {
+            Some(existing) => {
+                existing.contributions.total_count += repo.contributions.total_count;
+                existing.repository.updated_at = repo.repository.updated_at;
+            }
+            None => cc.commit_contributions_by_repository.push(repo),
+        }
+    }
+
+    let issues = &mut cc.issue_contributions;
+    issues.total_count += new.issue_contributions.total_count;
+    issues.page_info = new.issue_contributions.page_info;
+    issues.nodes = merge_nodes(issues.nodes.take(), new.issue_contributions.nodes, |node| {
+        node.issue.url.clone()
+    });
+
+    let prs = &mut cc.pull_request_contributions;
+    prs.total_count += new.pull_request_contributions.total_count;
+    prs.page_info = new.pull_request_contributions.page_info;
+    prs.nodes = merge_nodes(
+        prs.nodes.take(),
+        new.pull_request_contributions.nodes,
+        |node| node.pull_request.url.clone(),
+    );
+
+    let reviews = &mut cc.pull_request_review_contributions;
+    reviews.total_count += new.pull_request_review_contributions.total_count;
+    reviews.page_info = new.pull_request_review_contributions.page_info;
+    reviews.nodes = merge_nodes(
+        reviews.nodes.take(),
+        new.pull_request_review_contributions.nodes,
+        |node| {
+            format!(
+                "{} {}",
+                node.pull_request_review.pull_request.url, node.occurred_at
+            )
+        },
+    );
+
+    user_activity::ResponseData {
+        user: Some(user),
+        rate_limit: None,
+    }
+}
+
+/// Appends `newer` to `older`, dropping the older copy of any node whose key is in both.
+fn merge_nodes<T>(
+    older: Option<Vec<T>>,
+    newer: Option<Vec<T>>,
+    key: impl Fn(&T) -> String,
+) -> Option<Vec<T>> {
+    let (older, newer) = match (older, newer) {
+        (None, None) => return None,
+        (older, newer) => (older.unwrap_or_default(), newer.unwrap_or_default()),
+    };
+    let newer_keys: HashSet<String> = newer.iter().map(&key).collect();
+    let mut merged: Vec<T> = older
+        .into_iter()
+        .filter(|node| !newer_keys.contains(&key(node)))
+        .collect();
+    merged.extend(newer);
+    Some(merged)
+}
+
+/// Adds up the two calendars day by day and regroups the days into Sunday-based weeks.
+fn merge_calendars(
+    older: ContributionCalendar,
+    newer: ContributionCalendar,
+) -> ContributionCalendar {
+    let mut days: BTreeMap<String, CalendarDay> = BTreeMap::new();
+    let all_days = older
+        .weeks
+        .into_iter()
+        .chain(newer.weeks)
+        .flat_map(|week| week.contribution_days);
+    for day in all_days {
+        match days.get_mut(&day.date) {
+            Some(existing) => existing.contribution_count += day.contribution_count,
+            None => {
+                days.insert(day.date.clone(), day);
+            }
+        }
+    }
+
+    let mut weeks: Vec<CalendarWeek> = Vec::new();
+    for day in days.into_values() {
+        match weeks.last_mut() {
+            Some(week) if day.weekday != 0 => week.contribution_days.push(day),
+            _ => weeks.push(CalendarWeek {
+                contribution_days: vec![day],
+            }),
+        }
+    }
+
+    ContributionCalendar {
+        total_contributions: older.total_contributions + newer.total_contributions,
+        weeks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn activity(
+        commits: i64,
+        days: serde_json::Value,
+        repos: serde_json::Value,
+        prs: serde_json::Value,
+    ) -> user_activity::ResponseData {
+        let pr_count = prs.as_array().map_or(0, Vec::len);
+        serde_json::from_value(json!({
+            "user": { "contributionsCollection": {
+                "totalCommitContributions": commits,
+                "totalIssueContributions": 0,
+                "totalPullRequestContributions": pr_count,
+                "totalPullRequestReviewContributions": 0,
+                "contributionCalendar": {
+                    "totalContributions": commits,
+                    "weeks": [{ "contributionDays": days }]
+                },
+                "commitContributionsByRepository": repos,
+                "issueContributions": {
+                    "totalCount": 0,
+                    "pageInfo": { "endCursor": null, "hasNextPage": false },
+                    "nodes": []
+                },
+                "pullRequestContributions": {
+                    "totalCount": pr_count,
+                    "pageInfo": { "endCursor": null, "hasNextPage": false },
+                    "nodes": prs
+                },
+                "pullRequestReviewContributions": {
+                    "totalCount": 0,
+                    "pageInfo": { "endCursor": null, "hasNextPage": false },
+                    "nodes": []
+                }
+            } },
+            "rateLimit": null
+        }))
+        .unwrap()
+    }
+
+    fn day(date: &str, weekday: i64, count: i64) -> serde_json::Value {
+        json!({ "date": date, "contributionCount": count, "weekday": weekday })
+    }
+
+    fn repo(name: &str, count: i64) -> serde_json::Value {
+        json!({
+            "repository": { "nameWithOwner": name, "updatedAt": "2025-03-01T00:00:00Z" },
+            "contributions": { "totalCount": count }
+        })
+    }
+
+    fn pr(number: i64, state: &str) -> serde_json::Value {
+        json!({ "pullRequest": {
+            "number": number,
+            "title": format!("PR {}", number),
+            "url": format!("https://github.com/o/r/pull/{}", number),
+            "createdAt": "2025-03-01T00:00:00Z",
+            "state": state,
+            "merged": false,
+            "mergedAt": null,
+            "mergeCommit": null,
+            "closedAt": null,
+            "additions": 1,
+            "deletions": 1,
+            "changedFiles": 1
+        } })
+    }
+
+    #[test]
+    fn test_merge_activity() {
+        let older = activity(
+            3,
+            json!([day("2025-03-07", 5, 1), day("2025-03-08", 6, 2)]),
+            json!([repo("o/a", 2), repo("o/b", 1)]),
+            json!([pr(1, "OPEN"), pr(2, "OPEN")]),
+        );
+        let newer = activity(
+            4,
+            json!([day("2025-03-08", 6, 1), day("2025-03-09", 0, 3)]),
+            json!([repo("o/b", 3), repo("o/c", 1)]),
+            json!([pr(2, "MERGED"), pr(3, "OPEN")]),
+        );
+
+        let merged = merge_activity(older, newer);
+        let cc = merged.user.unwrap().contributions_collection;
+        assert_eq!(cc.total_commit_contributions, 7);
+
+        let calendar = &cc.contribution_calendar;
+        assert_eq!(calendar.total_contributions, 7);
+        let weeks: Vec<Vec<(&str, i64)>> = calendar
+            .weeks
+            .iter()
+            .map(|week| {
+                week.contribution_days
+                    .iter()
+                    .map(|d| (d.date.as_str(), d.contribution_count))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            weeks,
+            [
+                vec![("2025-03-07", 1), ("2025-03-08", 3)],
+                vec![("2025-03-09", 3)]
+            ]
+        );
+
+        let repos: Vec<(&str, i64)> = cc
+            .commit_contributions_by_repository
+            .iter()
+            .map(|r| {
+                (
+                    r.repository.name_with_owner.as_str(),
+                    r.contributions.total_count,
+                )
+            })
+            .collect();
+        assert_eq!(repos, [("o/a", 2), ("o/b", 4), ("o/c", 1)]);
+
+        let prs: Vec<(i64, serde_json::Value)> = cc
+            .pull_request_contributions
+            .nodes
+            .unwrap()
+            .into_iter()
+            .map(|node| {
+                (
+                    node.pull_request.number,
+                    serde_json::to_value(node.pull_request.state).unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            prs,
+            [(1, json!("OPEN")), (2, json!("MERGED")), (3, json!("OPEN"))]
+        );
+    }
+}
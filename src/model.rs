@@ -0,0 +1,519 @@
+//! Model module: the activity of a report as written to JSON, independent of the GraphQL
+//! query it was fetched with.
+//!
+//! The generated `user_activity` types mirror the query, so their names and nesting would
+//! change whenever a field is added to it. Saved reports, templates, and JSON Lines use these
+//! flat, snake_case types instead; the `activity` of a report is converted on the way in and
+//! out. Pagination details (`pageInfo`, per-connection `totalCount`) are dropped, since a
+//! saved report always holds every fetched node.
+
+use crate::github::user_activity::{
+    self, LabelNames, LabelNamesNodes, UserActivityUser as User,
+    UserActivityUserContributionsCollection as ContributionsCollection,
+    UserActivityUserContributionsCollectionCommitContributionsByRepository as RepositoryNode,
+    UserActivityUserContributionsCollectionCommitContributionsByRepositoryContributions as RepositoryCount,
+    UserActivityUserContributionsCollectionCommitContributionsByRepositoryRepository as RepositoryName,
+    UserActivityUserContributionsCollectionContributionCalendar as ContributionCalendar,
+    UserActivityUserContributionsCollectionContributionCalendarWeeks as CalendarWeek,
+    UserActivityUserContributionsCollectionContributionCalendarWeeksContributionDays as CalendarDay,
+    UserActivityUserContributionsCollectionIssueContributions as IssueConnection,
+    UserActivityUserContributionsCollectionIssueContributionsNodes as IssueNode,
+    UserActivityUserContributionsCollectionIssueContributionsNodesIssue as IssueFields,
+    UserActivityUserContributionsCollectionIssueContributionsPageInfo as IssuePageInfo,
+    UserActivityUserContributionsCollectionPullRequestContributions as PullRequestConnection,
+    UserActivityUserContributionsCollectionPullRequestContributionsNodes as PullRequestNode,
+    UserActivityUserContributionsCollectionPullRequestContributionsNodesPullRequest as PullRequestFields,
+    UserActivityUserContributionsCollectionPullRequestContributionsNodesPullRequestMergeCommit as MergeCommit,
+    UserActivityUserContributionsCollectionPullRequestContributionsPageInfo as PullRequestPageInfo,
+    UserActivityUserContributionsCollectionPullRequestReviewContributions as ReviewConnection,
+    UserActivityUserContributionsCollectionPullRequestReviewContributionsNodes as ReviewNode,
+    UserActivityUserContributionsCollectionPullRequestReviewContributionsNodesPullRequestReview as ReviewFields,
+    UserActivityUserContributionsCollectionPullRequestReviewContributionsNodesPullRequestReviewPullRequest as ReviewedPullRequestFields,
+    UserActivityUserContributionsCollectionPullRequestReviewContributionsPageInfo as ReviewPageInfo,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The contributions of a user over the report's time range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Activity {
+    /// Contribution counts as GitHub totals them.
+    pub totals: Totals,
+    /// The contribution calendar, one entry per day.
+    pub calendar: Vec<Day>,
+    /// Repositories the user committed to.
+    pub repositories: Vec<RepositoryCommits>,
+    /// Issues the user opened.
+    pub issues: Vec<Issue>,
+    /// Pull requests the user opened.
+    pub pull_requests: Vec<PullRequest>,
+    /// Pull request reviews the user submitted.
+    pub reviews: Vec<Review>,
+}
+
+/// Contribution counts as GitHub totals them; they may exceed the listed items, e.g. commits
+/// to repositories the token can't see.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Totals {
+    /// Commits.
+    pub commits: i64,
+    /// Issues opened.
+    pub issues: i64,
+    /// Pull requests opened.
+    pub pull_requests: i64,
+    /// Pull request reviews submitted.
+    pub reviews: i64,
+    /// Contributions counted by the contribution calendar.
+    pub contributions: i64,
+}
+
+/// A day of the contribution calendar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Day {
+    /// The date, as GitHub returned it.
+    pub date: String,
+    /// Contributions made that day.
+    pub count: i64,
+    /// Day of the week, from 0 (Sunday) to 6 (Saturday).
+    pub weekday: i64,
+}
+
+/// The user's commits to a repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepositoryCommits {
+    /// The repository as `owner/name`.
+    pub repository: String,
+    /// When the repository was last updated.
+    pub updated_at: String,
+    /// Commits the user made to it.
+    pub commits: i64,
+}
+
+/// An issue the user opened.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Issue {
+    /// GraphQL node ID, used to fetch the issue's timeline.
+    pub id: String,
+    pub number: i64,
+    pub title: String,
+    pub url: String,
+    pub created_at: String,
+    pub state: String,
+    pub closed_at: Option<String>,
+    /// Label names, in the order GitHub returned them.
+    pub labels: Vec<String>,
+}
+
+/// A pull request the user opened.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PullRequest {
+    pub number: i64,
+    pub title: String,
+    pub url: String,
+    pub created_at: String,
+    pub state: String,
+    pub merged: bool,
+    pub merged_at: Option<String>,
+    /// SHA of the merge commit, once merged.
+    pub merge_commit: Option<String>,
+    pub closed_at: Option<String>,
+    pub additions: i64,
+    pub deletions: i64,
+    pub changed_files: i64,
+    /// Label names, in the order GitHub returned them.
+    pub labels: Vec<String>,
+}
+
+/// A pull request review the user submitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Review {
+    /// The reviewed pull request.
+    pub pull_request: ReviewedPullRequest,
+    /// The review state, e.g. `APPROVED`; empty for reports from before states were fetched.
+    pub state: String,
+    /// The review body, when fetched with `--review-bodies`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    pub occurred_at: String,
+}
+
+/// The pull request of a review.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewedPullRequest {
+    pub number: i64,
+    pub title: String,
+    pub url: String,
+    /// Label names, in the order GitHub returned them.
+    pub labels: Vec<String>,
+}
+
+impl Activity {
+    /// The activity of fetched response data; `None` when GitHub found no such user.
+    pub fn from_response(data: &user_activity::ResponseData) -> Option<Self> {
+        let cc = &data.user.as_ref()?.contributions_collection;
+        Some(Self {
+            totals: Totals {
+                commits: cc.total_commit_contributions,
+                issues: cc.total_issue_contributions,
+                pull_requests: cc.total_pull_request_contributions,
+                reviews: cc.total_pull_request_review_contributions,
+                contributions: cc.contribution_calendar.total_contributions,
+            },
+            calendar: cc
+                .contribution_calendar
+                .weeks
+                .iter()
+                .flat_map(|week| &week.contribution_days)
+                .map(|day| Day {
+                    date: day.date.clone(),
+                    count: day.contribution_count,
+                    weekday: day.weekday,
+                })
+                .collect(),
+            repositories: cc
+                .commit_contributions_by_repository
+                .iter()
+                .map(RepositoryCommits::from)
+                .collect(),
+            issues: cc
+                .issue_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(Issue::from)
+                .collect(),
+            pull_requests: cc
+                .pull_request_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(PullRequest::from)
+                .collect(),
+            reviews: cc
+                .pull_request_review_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(Review::from)
+                .collect(),
+        })
+    }
+
+    /// Converts the activity back into response data, as if every page had been fetched. The
+    /// calendar's days are grouped into weeks starting on Sunday.
+    pub fn into_response(self) -> user_activity::ResponseData {
+        let mut weeks: Vec<CalendarWeek> = Vec::new();
+        for day in self.calendar {
+            let starts_week = weeks
+                .last()
+                .and_then(|week| week.contribution_days.last())
+                .is_none_or(|last| day.weekday <= last.weekday);
+            if starts_week {
+                weeks.push(CalendarWeek {
+                    contribution_days: Vec::new(),
+                });
+            }
+            if let Some(week) = weeks.last_mut() {
+                week.contribution_days.push(CalendarDay {
+                    date: day.date,
+                    contribution_count: day.count,
+                    weekday: day.weekday,
+                });
+            }
+        }
+        let (issues, pull_requests, reviews) = (
+            self.issues.len() as i64,
+            self.pull_requests.len() as i64,
+            self.reviews.len() as i64,
+        );
+        user_activity::ResponseData {
+            user: Some(User {
+                contributions_collection: ContributionsCollection {
+                    total_commit_contributions: self.totals.commits,
+                    total_issue_contributions: self.totals.issues,
+                    total_pull_request_contributions: self.totals.pull_requests,
+                    total_pull_request_review_contributions: self.totals.reviews,
+                    contribution_calendar: ContributionCalendar {
+                        total_contributions: self.totals.contributions,
+                        weeks,
+                    },
+                    commit_contributions_by_repository: self
+                        .repositories
+                        .into_iter()
+                        .map(RepositoryNode::from)
+                        .collect(),
+                    issue_contributions: IssueConnection {
+                        total_count: issues,
+                        page_info: IssuePageInfo {
+                            end_cursor: None,
+                            has_next_page: false,
+                        },
+                        nodes: Some(self.issues.into_iter().map(IssueNode::from).collect()),
+                    },
+                    pull_request_contributions: PullRequestConnection {
+                        total_count: pull_requests,
+                        page_info: PullRequestPageInfo {
+                            end_cursor: None,
+                            has_next_page: false,
+                        },
+                        nodes: Some(
+                            self.pull_requests
+                                .into_iter()
+                                .map(PullRequestNode::from)
+                                .collect(),
+                        ),
+                    },
+                    pull_request_review_contributions: ReviewConnection {
+                        total_count: reviews,
+                        page_info: ReviewPageInfo {
+                            end_cursor: None,
+                            has_next_page: false,
+                        },
+                        nodes: Some(self.reviews.into_iter().map(ReviewNode::from).collect()),
+                    },
+                },
+            }),
+            rate_limit: None,
+        }
+    }
+}
+
+/// Serializes response data as an [`Activity`], or `null` when there is no user; for
+/// `#[serde(serialize_with)]`.
+pub fn serialize_activity<S: Serializer>(
+    data: &user_activity::ResponseData,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    Activity::from_response(data).serialize(serializer)
+}
+
+/// Deserializes response data from an [`Activity`], or `null` for no user; for
+/// `#[serde(deserialize_with)]`.
+pub fn deserialize_activity<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<user_activity::ResponseData, D::Error> {
+    Ok(Option::<Activity>::deserialize(deserializer)?
+        .map(Activity::into_response)
+        .unwrap_or_default())
+}
+
+/// The names of fetched labels.
+fn label_names(labels: &Option<LabelNames>) -> Vec<String> {
+    labels
+        .iter()
+        .flat_map(|labels| labels.nodes.iter().flatten().flatten())
+        .map(|label| label.name.clone())
+        .collect()
+}
+
+/// Labels as fetched, or none when there are no names.
+fn labels_of(names: Vec<String>) -> Option<LabelNames> {
+    (!names.is_empty()).then(|| LabelNames {
+        nodes: Some(
+            names
+                .into_iter()
+                .map(|name| Some(LabelNamesNodes { name }))
+                .collect(),
+        ),
+    })
+}
+
+impl From<&RepositoryNode> for RepositoryCommits {
+    fn from(node: &RepositoryNode) -> Self {
+        Self {
+            repository: node.repository.name_with_owner.clone(),
+            updated_at: node.repository.updated_at.clone(),
+            commits: node.contributions.total_count,
+        }
+    }
+}
+
+impl From<RepositoryCommits> for RepositoryNode {
+    fn from(repository: RepositoryCommits) -> Self {
+        Self {
+            repository: RepositoryName {
+                name_with_owner: repository.repository,
+                updated_at: repository.updated_at,
+            },
+            contributions: RepositoryCount {
+                total_count: repository.commits,
+            },
+        }
+    }
+}
+
+impl From<&IssueNode> for Issue {
+    fn from(node: &IssueNode) -> Self {
+        let issue = &node.issue;
+        Self {
+            id: issue.id.clone(),
+            number: issue.number,
+            title: issue.title.clone(),
+            url: issue.url.clone(),
+            created_at: issue.created_at.clone(),
+            state: issue.state.clone(),
+            closed_at: issue.closed_at.clone(),
+            labels: label_names(&issue.labels),
+        }
+    }
+}
+
+impl From<Issue> for IssueNode {
+    fn from(issue: Issue) -> Self {
+        Self {
+            issue: IssueFields {
+                id: issue.id,
+                number: issue.number,
+                title: issue.title,
+                url: issue.url,
+                created_at: issue.created_at,
+                state: issue.state,
+                closed_at: issue.closed_at,
+                labels: labels_of(issue.labels),
+            },
+        }
+    }
+}
+
+impl From<&PullRequestNode> for PullRequest {
+    fn from(node: &PullRequestNode) -> Self {
+        let pr = &node.pull_request;
+        Self {
+            number: pr.number,
+            title: pr.title.clone(),
+            url: pr.url.clone(),
+            created_at: pr.created_at.clone(),
+            state: pr.state.clone(),
+            merged: pr.merged,
+            merged_at: pr.merged_at.clone(),
+            merge_commit: pr.merge_commit.as_ref().map(|commit| commit.oid.clone()),
+            closed_at: pr.closed_at.clone(),
+            additions: pr.additions,
+            deletions: pr.deletions,
+            changed_files: pr.changed_files,
+            labels: label_names(&pr.labels),
+        }
+    }
+}
+
+impl From<PullRequest> for PullRequestNode {
+    fn from(pr: PullRequest) -> Self {
+        Self {
+            pull_request: PullRequestFields {
+                number: pr.number,
+                title: pr.title,
+                url: pr.url,
+                created_at: pr.created_at,
+                state: pr.state,
+                merged: pr.merged,
+                merged_at: pr.merged_at,
+                merge_commit: pr.merge_commit.map(|oid| MergeCommit { oid }),
+                closed_at: pr.closed_at,
+                additions: pr.additions,
+                deletions: pr.deletions,
+                changed_files: pr.changed_files,
+                labels: labels_of(pr.labels),
+            },
+        }
+    }
+}
+
+impl From<&ReviewNode> for Review {
+    fn from(node: &ReviewNode) -> Self {
+        let review = &node.pull_request_review;
+        Self {
+            pull_request: ReviewedPullRequest {
+                number: review.pull_request.number,
+                title: review.pull_request.title.clone(),
+                url: review.pull_request.url.clone(),
+                labels: label_names(&review.pull_request.labels),
+            },
+            state: review.state.clone(),
+            body: review.body.clone(),
+            occurred_at: node.occurred_at.clone(),
+        }
+    }
+}
+
+impl From<Review> for ReviewNode {
+    fn from(review: Review) -> Self {
+        Self {
+            pull_request_review: ReviewFields {
+                pull_request: ReviewedPullRequestFields {
+                    number: review.pull_request.number,
+                    title: review.pull_request.title,
+                    url: review.pull_request.url,
+                    labels: labels_of(review.pull_request.labels),
+                },
+                state: review.state,
+                body: review.body,
+            },
+            occurred_at: review.occurred_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_activity_round_trip() {
+        let page = |nodes: serde_json::Value| json!({ "totalCount": 1, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes });
+        let data: user_activity::ResponseData = serde_json::from_value(json!({ "user": { "contributionsCollection": {
+            "totalCommitContributions": 3,
+            "totalIssueContributions": 1,
+            "totalPullRequestContributions": 1,
+            "totalPullRequestReviewContributions": 1,
+            "contributionCalendar": { "totalContributions": 6, "weeks": [
+                { "contributionDays": [{ "date": "2025-03-07", "contributionCount": 2, "weekday": 5 }, { "date": "2025-03-08", "contributionCount": 0, "weekday": 6 }] },
+                { "contributionDays": [{ "date": "2025-03-09", "contributionCount": 4, "weekday": 0 }] }
+            ] },
+            "commitContributionsByRepository": [{
+                "repository": { "nameWithOwner": "o/b", "updatedAt": "2025-03-02T00:00:00Z" },
+                "contributions": { "totalCount": 3 }
+            }],
+            "issueContributions": page(json!([{ "issue": {
+                "id": "I_1", "number": 1, "title": "Bug", "url": "https://github.com/o/b/issues/1",
+                "createdAt": "2025-03-01T00:00:00Z", "state": "OPEN", "closedAt": null,
+                "labels": { "nodes": [{ "name": "bug" }] }
+            } }])),
+            "pullRequestContributions": page(json!([{ "pullRequest": {
+                "number": 2, "title": "Fix", "url": "https://github.com/o/b/pull/2",
+                "createdAt": "2025-03-02T10:00:00Z", "state": "MERGED", "merged": true,
+                "mergedAt": "2025-03-03T10:00:00Z", "mergeCommit": { "oid": "abc123" }, "closedAt": null,
+                "additions": 5, "deletions": 1, "changedFiles": 2
+            } }])),
+            "pullRequestReviewContributions": page(json!([{
+                "pullRequestReview": { "pullRequest": { "number": 3, "title": "Other", "url": "https://github.com/o/b/pull/3" }, "state": "APPROVED" },
+                "occurredAt": "2025-03-03T00:00:00Z"
+            }]))
+        } } }))
+        .unwrap();
+
+        let activity = Activity::from_response(&data).unwrap();
+        let value = serde_json::to_value(&activity).unwrap();
+        assert_eq!(
+            value["totals"],
+            json!({ "commits": 3, "issues": 1, "pull_requests": 1, "reviews": 1, "contributions": 6 })
+        );
+        assert_eq!(
+            value["calendar"][2],
+            json!({ "date": "2025-03-09", "count": 4, "weekday": 0 })
+        );
+        assert_eq!(
+            value["repositories"][0],
+            json!({ "repository": "o/b", "updated_at": "2025-03-02T00:00:00Z", "commits": 3 })
+        );
+        assert_eq!(value["issues"][0]["labels"], json!(["bug"]));
+        assert_eq!(value["pull_requests"][0]["merge_commit"], "abc123");
+        assert_eq!(value["reviews"][0]["pull_request"]["number"], 3);
+        assert!(value["reviews"][0].get("body").is_none());
+
+        assert_eq!(activity.clone().into_response(), data);
+        assert_eq!(
+            Activity::from_response(&user_activity::ResponseData::default()),
+            None
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! Narrative module: composes a few short sentences per report section from the fetched
+//! activity, so `--tone narrative` reports read as prose that can be pasted into a status
+//! email.
+
+use crate::format::lines_changed;
+use crate::locale::NumberLocale;
+use crate::report::Report;
+use crate::revert::repository_of;
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+/// How plain-text and Markdown reports phrase their sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tone {
+    /// Totals followed by one bullet or table row per contribution.
+    #[default]
+    Bullet,
+    /// A few generated sentences per section.
+    Narrative,
+}
+
+impl FromStr for Tone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bullet" => Ok(Self::Bullet),
+            "narrative" => Ok(Self::Narrative),
+            _ => Err(format!("Invalid tone: {}. Use bullet or narrative", s)),
+        }
+    }
+}
+
+/// One section of a narrative report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paragraph {
+    /// Section heading, e.g. `Pull Requests`.
+    pub heading: &'static str,
+    /// The sentences of the section.
+    pub text: String,
+}
+
+/// The narrative sections of `report`: a summary, then commits, pull requests, issues, and
+/// reviews. Sections without contributions are left out; a report without user data has none.
+pub fn paragraphs(report: &Report, locale: NumberLocale) -> Vec<Paragraph> {
+    let Some(user) = &report.activity.user else {
+        return Vec::new();
+    };
+    let cc = &user.contributions_collection;
+    let count = |n: i64, one: &str, many: &str| {
+        format!("{} {}", locale.format(n), if n == 1 { one } else { many })
+    };
+    let mut paragraphs = Vec::new();
+
+    let totals = [
+        (cc.total_commit_contributions, "commit", "commits"),
+        (cc.total_issue_contributions, "issue", "issues"),
+        (
+            cc.total_pull_request_contributions,
+            "pull request",
+            "pull requests",
+        ),
+        (
+            cc.total_pull_request_review_contributions,
+            "review",
+            "reviews",
+        ),
+    ];
+    let total: i64 = totals.iter().map(|(n, _, _)| n).sum();
+    let summary = if total == 0 {
+        "No contributions in this period.".to_string()
+    } else {
+        let parts: Vec<String> = totals
+            .iter()
+            .filter(|(n, _, _)| *n > 0)
+            .map(|&(n, one, many)| count(n, one, many))
+            .collect();
+        format!(
+            "Made {}: {}.",
+            count(total, "contribution", "contributions"),
+            and_list(&parts)
+        )
+    };
+    paragraphs.push(Paragraph {
+        heading: "Summary",
+        text: summary,
+    });
+
+    let repositories = &cc.commit_contributions_by_repository;
+    if cc.total_commit_contributions > 0 && !repositories.is_empty() {
+        let commits = count(cc.total_commit_contributions, "commit", "commits");
+        let text = match repositories
+            .iter()
+            .max_by_key(|r| r.contributions.total_count)
+        {
+            Some(top) if repositories.len() > 1 => format!(
+                "Pushed {} to {} repositories, most to {} ({}).",
+                commits,
+                locale.format(repositories.len() as i64),
+                top.repository.name_with_owner,
+                locale.format(top.contributions.total_count)
+            ),
+            _ => format!(
+                "Pushed {} to {}.",
+                commits, repositories[0].repository.name_with_owner
+            ),
+        };
+        paragraphs.push(Paragraph {
+            heading: "Commits",
+            text,
+        });
+    }
+
+    let prs: Vec<_> = cc
+        .pull_request_contributions
+        .nodes
+        .iter()
+        .flatten()
+        .map(|node| &node.pull_request)
+        .collect();
+    if !prs.is_empty() {
+        let merged = prs.iter().filter(|pr| pr.merged).count();
+        let (additions, deletions) = lines_changed(cc);
+        paragraphs.push(Paragraph {
+            heading: "Pull Requests",
+            text: format!(
+                "Opened {}{}{}, changing {} (+{} -{}).",
+                count(prs.len() as i64, "pull request", "pull requests"),
+                spread(prs.iter().map(|pr| pr.url.as_str()), locale),
+                of_which(merged, prs.len(), "merged", "merged", locale),
+                count(additions + deletions, "line", "lines"),
+                locale.format(additions),
+                locale.format(deletions)
+            ),
+        });
+    }
+
+    let issues: Vec<_> = cc
+        .issue_contributions
+        .nodes
+        .iter()
+        .flatten()
+        .map(|node| &node.issue)
+        .collect();
+    if !issues.is_empty() {
+        let closed = issues
+            .iter()
+            .filter(|issue| issue.state.eq_ignore_ascii_case("closed"))
+            .count();
+        paragraphs.push(Paragraph {
+            heading: "Issues",
+            text: format!(
+                "Opened {}{}{}.",
+                count(issues.len() as i64, "issue", "issues"),
+                spread(issues.iter().map(|issue| issue.url.as_str()), locale),
+                of_which(closed, issues.len(), "is closed", "are closed", locale)
+            ),
+        });
+    }
+
+    let reviews: Vec<_> = cc
+        .pull_request_review_contributions
+        .nodes
+        .iter()
+        .flatten()
+        .map(|node| &node.pull_request_review)
+        .collect();
+    if !reviews.is_empty() {
+        let outcomes: Vec<String> = [
+            ("APPROVED", "approving"),
+            ("CHANGES_REQUESTED", "requesting changes on"),
+            ("COMMENTED", "commenting on"),
+        ]
+        .iter()
+        .filter_map(|(state, verb)| {
+            let n = reviews
+                .iter()
+                .filter(|review| review.state == *state)
+                .count();
+            (n > 0).then(|| format!("{} {}", verb, locale.format(n as i64)))
+        })
+        .collect();
+        let outcomes = if outcomes.is_empty() {
+            String::new()
+        } else {
+            format!(", {}", and_list(&outcomes))
+        };
+        paragraphs.push(Paragraph {
+            heading: "Reviews",
+            text: format!(
+                "Submitted {}{}{}.",
+                count(reviews.len() as i64, "review", "reviews"),
+                spread(
+                    reviews
+                        .iter()
+                        .map(|review| review.pull_request.url.as_str()),
+                    locale
+                ),
+                outcomes
+            ),
+        });
+    }
+
+    paragraphs
+}
+
+/// Where contributions with the given URLs went, e.g. ` in octocat/hello-world` or
+/// ` across 3 repositories`; empty when no URL names a repository.
+fn spread<'a>(urls: impl Iterator<Item = &'a str>, locale: NumberLocale) -> String {
+    let repositories: BTreeSet<String> = urls.filter_map(repository_of).collect();
+    match repositories.len() {
+        0 => String::new(),
+        1 => format!(
+            " in {}",
+            repositories.into_iter().next().unwrap_or_default()
+        ),
+        n => format!(" across {} repositories", locale.format(n as i64)),
+    }
+}
+
+/// How many of `total` contributions reached an outcome, e.g. `, 3 of which merged`; empty when
+/// none did.
+fn of_which(n: usize, total: usize, one: &str, many: &str, locale: NumberLocale) -> String {
+    match (n, total) {
+        (0, _) => String::new(),
+        (1, 1) => format!(", which {}", one),
+        (n, total) if n == total => format!(", all of which {}", many),
+        (n, _) => format!(", {} of which {}", locale.format(n as i64), many),
+    }
+}
+
+/// Joins phrases into a list, e.g. `a, b and c`.
+fn and_list(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    fn report(cc: serde_json::Value) -> Report {
+        let mut collection = json!({
+            "totalCommitContributions": 0,
+            "totalIssueContributions": 0,
+            "totalPullRequestContributions": 0,
+            "totalPullRequestReviewContributions": 0,
+            "contributionCalendar": { "totalContributions": 0, "weeks": [] },
+            "commitContributionsByRepository": [],
+            "issueContributions": { "totalCount": 0, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": [] },
+            "pullRequestContributions": { "totalCount": 0, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": [] },
+            "pullRequestReviewContributions": { "totalCount": 0, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": [] }
+        });
+        for (key, value) in cc.as_object().unwrap() {
+            collection[key] = value.clone();
+        }
+        Report::new(
+            "octocat".into(),
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 8, 0, 0, 0).unwrap(),
+            serde_json::from_value(json!({ "user": { "contributionsCollection": collection } }))
+                .unwrap(),
+        )
+    }
+
+    fn pr(number: i64, repo: &str, merged: bool) -> serde_json::Value {
+        json!({ "pullRequest": {
+            "number": number, "title": "PR", "url": format!("https://github.com/{}/pull/{}", repo, number),
+            "createdAt": "2025-03-02T00:00:00Z", "state": if merged { "MERGED" } else { "OPEN" },
+            "merged": merged, "mergedAt": null, "closedAt": null,
+            "additions": 1000, "deletions": 10, "changedFiles": 3
+        } })
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let report = report(json!({
+            "totalCommitContributions": 30,
+            "totalPullRequestContributions": 4,
+            "totalPullRequestReviewContributions": 3,
+            "commitContributionsByRepository": [
+                { "repository": { "nameWithOwner": "octo/api", "updatedAt": "2025-03-02T00:00:00Z" }, "contributions": { "totalCount": 20 } },
+                { "repository": { "nameWithOwner": "octo/web", "updatedAt": "2025-03-02T00:00:00Z" }, "contributions": { "totalCount": 10 } }
+            ],
+            "pullRequestContributions": { "totalCount": 4, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": [
+                pr(1, "octo/api", true), pr(2, "octo/api", true), pr(3, "octo/web", true), pr(4, "octo/web", false)
+            ] },
+            "pullRequestReviewContributions": { "totalCount": 3, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": [
+                { "pullRequestReview": { "pullRequest": { "number": 5, "title": "A", "url": "https://github.com/octo/api/pull/5" }, "state": "APPROVED" }, "occurredAt": "2025-03-03T00:00:00Z" },
+                { "pullRequestReview": { "pullRequest": { "number": 6, "title": "B", "url": "https://github.com/octo/api/pull/6" }, "state": "APPROVED" }, "occurredAt": "2025-03-03T00:00:00Z" },
+                { "pullRequestReview": { "pullRequest": { "number": 7, "title": "C", "url": "https://github.com/octo/api/pull/7" }, "state": "CHANGES_REQUESTED" }, "occurredAt": "2025-03-03T00:00:00Z" }
+            ] }
+        }));
+
+        let text: Vec<(&str, String)> = paragraphs(&report, NumberLocale::default())
+            .into_iter()
+            .map(|p| (p.heading, p.text))
+            .collect();
+        assert_eq!(
+            text,
+            [
+                (
+                    "Summary",
+                    "Made 37 contributions: 30 commits, 4 pull requests and 3 reviews.".into()
+                ),
+                (
+                    "Commits",
+                    "Pushed 30 commits to 2 repositories, most to octo/api (20).".into()
+                ),
+                (
+                    "Pull Requests",
+                    "Opened 4 pull requests across 2 repositories, 3 of which merged, changing 4,040 lines (+4,000 -40).".into()
+                ),
+                (
+                    "Reviews",
+                    "Submitted 3 reviews in octo/api, approving 2 and requesting changes on 1."
+                        .into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paragraphs_without_contributions() {
+        let paragraphs = paragraphs(&report(json!({})), NumberLocale::default());
+        assert_eq!(
+            paragraphs,
+            [Paragraph {
+                heading: "Summary",
+                text: "No contributions in this period.".into(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! Newsletter module: combines the reports of a team over the same time range into one
+//! curated Markdown newsletter, with team-wide totals, the busiest repositories, and
+//! highlights per person, in a configurable order.
+
+use crate::locale::NumberLocale;
+use crate::narrative::paragraphs;
+use crate::report::Report;
+use crate::revert::repository_of;
+use crate::text;
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+/// How many merged pull requests are highlighted per person.
+const HIGHLIGHTED_PRS: usize = 3;
+
+/// A section of the newsletter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    /// Contribution totals summed over the whole team.
+    Totals,
+    /// The repositories the team contributed to most.
+    TopRepositories,
+    /// A summary and the largest merged pull requests of each person.
+    Highlights,
+}
+
+impl FromStr for Section {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "totals" => Ok(Self::Totals),
+            "top-repositories" => Ok(Self::TopRepositories),
+            "highlights" => Ok(Self::Highlights),
+            _ => Err(format!(
+                "Invalid newsletter section: {}. Use totals, top-repositories, or highlights",
+                s
+            )),
+        }
+    }
+}
+
+/// The sections of a newsletter when none are configured, in order.
+pub const DEFAULT_SECTIONS: [Section; 3] = [
+    Section::Totals,
+    Section::TopRepositories,
+    Section::Highlights,
+];
+
+/// A Markdown newsletter of a team's reports.
+#[derive(Debug, Clone)]
+pub struct Newsletter {
+    /// Title of the newsletter.
+    pub title: String,
+    /// The sections to include, in order.
+    pub sections: Vec<Section>,
+    /// How many repositories the top repositories section lists.
+    pub top_repositories: usize,
+    /// How totals are grouped, e.g. `12,345`.
+    pub locale: NumberLocale,
+}
+
+impl Default for Newsletter {
+    fn default() -> Self {
+        Self {
+            title: "Team Newsletter".into(),
+            sections: DEFAULT_SECTIONS.to_vec(),
+            top_repositories: 5,
+            locale: NumberLocale::default(),
+        }
+    }
+}
+
+/// Contributions of the team to one repository.
+#[derive(Default)]
+struct RepositoryActivity {
+    commits: i64,
+    pull_requests: i64,
+    contributors: BTreeSet<String>,
+}
+
+impl Newsletter {
+    /// Renders the newsletter of `reports`, which all cover the same time range.
+    pub fn render(&self, reports: &[Report]) -> String {
+        let mut output = format!("# {}\n\n", self.title);
+        if let Some(first) = reports.first() {
+            output.push_str(&format!(
+                "_{} to {} · {} {}_\n",
+                first.start_date.format("%Y-%m-%d"),
+                first.end_date.format("%Y-%m-%d"),
+                self.locale.format(reports.len() as i64),
+                if reports.len() == 1 {
+                    "person"
+                } else {
+                    "people"
+                }
+            ));
+        }
+        for section in &self.sections {
+            output.push('\n');
+            output.push_str(&match section {
+                Section::Totals => self.totals(reports),
+                Section::TopRepositories => self.top_repositories(reports),
+                Section::Highlights => self.highlights(reports),
+            });
+        }
+        output
+    }
+
+    fn totals(&self, reports: &[Report]) -> String {
+        let (mut commits, mut issues, mut prs, mut reviews) = (0, 0, 0, 0);
+        let (mut merged, mut additions, mut deletions) = (0, 0, 0);
+        for user in reports.iter().filter_map(|r| r.activity.user.as_ref()) {
+            let cc = &user.contributions_collection;
+            commits += cc.total_commit_contributions;
+            issues += cc.total_issue_contributions;
+            prs += cc.total_pull_request_contributions;
+            reviews += cc.total_pull_request_review_contributions;
+            for node in cc.pull_request_contributions.nodes.iter().flatten() {
+                merged += i64::from(node.pull_request.merged);
+                additions += node.pull_request.additions;
+                deletions += node.pull_request.deletions;
+            }
+        }
+        let n = |n| self.locale.format(n);
+        format!(
+            "## Team Totals\n\n- **Commits:** {}\n- **Pull Requests:** {} ({} merged)\n- **Issues:** {}\n- **Reviews:** {}\n- **Lines Changed:** +{} -{}\n",
+            n(commits),
+            n(prs),
+            n(merged),
+            n(issues),
+            n(reviews),
+            n(additions),
+            n(deletions)
+        )
+    }
+
+    fn top_repositories(&self, reports: &[Report]) -> String {
+        let mut repositories: BTreeMap<String, RepositoryActivity> = BTreeMap::new();
+        for report in reports {
+            let Some(user) = &report.activity.user else {
+                continue;
+            };
+            let cc = &user.contributions_collection;
+            for repo in &cc.commit_contributions_by_repository {
+                let activity = repositories
+                    .entry(repo.repository.name_with_owner.clone())
+                    .or_default();
+                activity.commits += repo.contributions.total_count;
+                activity.contributors.insert(report.username.clone());
+            }
+            for node in cc.pull_request_contributions.nodes.iter().flatten() {
+                if let Some(name) = repository_of(&node.pull_request.url) {
+                    let activity = repositories.entry(name).or_default();
+                    activity.pull_requests += 1;
+                    activity.contributors.insert(report.username.clone());
+                }
+            }
+        }
+        let mut ranked: Vec<(String, RepositoryActivity)> = repositories.into_iter().collect();
+        // Busiest first; the map already sorted ties by name, and the sort is stable.
+        ranked.sort_by_key(|(_, a)| std::cmp::Reverse(a.commits + a.pull_requests));
+
+        let mut output = String::from("## Top Repositories\n\n");
+        if ranked.is_empty() {
+            output.push_str("No repository activity.\n");
+            return output;
+        }
+        output.push_str("| Repository | Commits | Pull Requests | Contributors |\n");
+        output.push_str("|------------|---------|---------------|--------------|\n");
+        for (name, activity) in ranked.iter().take(self.top_repositories) {
+            output.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                name,
+                self.locale.format(activity.commits),
+                self.locale.format(activity.pull_requests),
+                activity
+                    .contributors
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        output
+    }
+
+    fn highlights(&self, reports: &[Report]) -> String {
+        let mut output = String::from("## Highlights\n");
+        for report in reports {
+            output.push_str(&format!("\n### {}\n\n", report.username));
+            let Some(user) = &report.activity.user else {
+                output.push_str("No user data available.\n");
+                continue;
+            };
+            if let Some(summary) = paragraphs(report, self.locale).first() {
+                output.push_str(&format!("{}\n", summary.text));
+            }
+            let mut merged: Vec<_> = user
+                .contributions_collection
+                .pull_request_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(|node| &node.pull_request)
+                .filter(|pr| pr.merged)
+                .collect();
+            merged.sort_by_key(|pr| std::cmp::Reverse(pr.additions + pr.deletions));
+            if !merged.is_empty() {
+                output.push('\n');
+            }
+            for pr in merged.iter().take(HIGHLIGHTED_PRS) {
+                output.push_str(&format!(
+                    "- Merged [#{} {}]({}) (+{} -{})\n",
+                    pr.number,
+                    text::title(&pr.title),
+                    pr.url,
+                    self.locale.format(pr.additions),
+                    self.locale.format(pr.deletions)
+                ));
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    fn report(username: &str, commits: i64, repo: &str, prs: &[(i64, bool, i64)]) -> Report {
+        let nodes: Vec<_> = prs
+            .iter()
+            .map(|&(number, merged, additions)| {
+                json!({ "pullRequest": {
+                    "number": number, "title": format!("Change {}", number),
+                    "url": format!("https://github.com/{}/pull/{}", repo, number),
+                    "createdAt": "2025-03-02T00:00:00Z", "state": if merged { "MERGED" } else { "OPEN" },
+                    "merged": merged, "mergedAt": null, "closedAt": null,
+                    "additions": additions, "deletions": 1, "changedFiles": 1
+                } })
+            })
+            .collect();
+        let empty = json!({ "totalCount": 0, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": [] });
+        let activity = json!({ "user": { "contributionsCollection": {
+            "totalCommitContributions": commits,
+            "totalIssueContributions": 0,
+            "totalPullRequestContributions": prs.len(),
+            "totalPullRequestReviewContributions": 0,
+            "contributionCalendar": { "totalContributions": 0, "weeks": [] },
+            "commitContributionsByRepository": [
+                { "repository": { "nameWithOwner": repo, "updatedAt": "2025-03-02T00:00:00Z" }, "contributions": { "totalCount": commits } }
+            ],
+            "issueContributions": empty,
+            "pullRequestContributions": { "totalCount": prs.len(), "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes },
+            "pullRequestReviewContributions": empty
+        } } });
+        Report::new(
+            username.into(),
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 8, 0, 0, 0).unwrap(),
+            serde_json::from_value(activity).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_render() {
+        let reports = [
+            report(
+                "octocat",
+                8,
+                "octo/api",
+                &[(1, true, 10), (2, true, 500), (3, false, 5)],
+            ),
+            report("hubot", 3, "octo/web", &[]),
+        ];
+        let newsletter = Newsletter {
+            title: "Friday Update".into(),
+            top_repositories: 1,
+            ..Default::default()
+        }
+        .render(&reports);
+
+        assert!(newsletter.starts_with(
+            "# Friday Update\n\n_2025-03-01 to 2025-03-08 · 2 people_\n\n## Team Totals\n"
+        ));
+        assert!(newsletter.contains("- **Pull Requests:** 3 (2 merged)\n"));
+        assert!(newsletter.contains("| octo/api | 8 | 3 | octocat |\n"));
+        assert!(!newsletter.contains("| octo/web"));
+        assert!(newsletter.contains(
+            "### octocat\n\nMade 11 contributions: 8 commits and 3 pull requests.\n\n- Merged [#2 Change 2](https://github.com/octo/api/pull/2) (+500 -1)\n- Merged [#1 Change 1]"
+        ));
+        assert!(newsletter.ends_with("### hubot\n\nMade 3 contributions: 3 commits.\n"));
+    }
+
+    #[test]
+    fn test_render_section_order() {
+        let reports = [report("octocat", 1, "octo/api", &[])];
+        let newsletter = Newsletter {
+            sections: vec![Section::Highlights, Section::Totals],
+            ..Default::default()
+        }
+        .render(&reports);
+        let highlights = newsletter.find("## Highlights").unwrap();
+        let totals = newsletter.find("## Team Totals").unwrap();
+        assert!(highlights < totals);
+        assert!(!newsletter.contains("## Top Repositories"));
+        assert_eq!("top_repositories".parse(), Ok(Section::TopRepositories));
+        assert!("weather".parse::<Section>().is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! Release module: annotates merged pull requests with the first release that shipped them,
+//! so a report answers "did this actually ship?".
+
+use crate::github::GithubClient;
+use crate::github::repo_releases::RepoReleasesRepositoryReleasesNodes as Release;
+use crate::github::user_activity::UserActivityUserContributionsCollectionPullRequestContributionsNodesPullRequest as PullRequest;
+use crate::revert::repository_of;
+use anyhow::{Context, Result};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Releases that shipped merged pull requests, keyed by pull request URL.
+pub type ShippedIn = BTreeMap<String, ShippedRelease>;
+
+/// How many releases published after a merge are checked before giving up on a pull request,
+/// e.g. because it landed on a branch that is never released.
+const MAX_CANDIDATE_RELEASES: usize = 5;
+
+/// The first release containing a pull request's merge commit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShippedRelease {
+    /// The release's tag name.
+    pub tag_name: String,
+    /// URL of the release page.
+    pub url: String,
+    /// When the release was published.
+    pub published_at: Option<String>,
+}
+
+/// Published releases that could contain a pull request merged at `merged_at`, oldest first.
+pub fn candidate_releases<'a>(merged_at: &str, releases: &'a [Release]) -> Vec<&'a Release> {
+    let mut candidates: Vec<&Release> = releases
+        .iter()
+        .filter(|r| !r.is_draft)
+        .filter(|r| r.published_at.as_deref().is_some_and(|p| p >= merged_at))
+        .collect();
+    candidates.sort_by(|a, b| a.published_at.cmp(&b.published_at));
+    candidates.truncate(MAX_CANDIDATE_RELEASES);
+    candidates
+}
+
+/// Finds the first release of its repository that contains each merged pull request.
+///
+/// Releases published after the merge are checked oldest first with the compare API; pull
+/// requests without a merge commit or without a containing release are left out.
+pub async fn find_releases<'a>(
+    client: &GithubClient,
+    pull_requests: impl IntoIterator<Item = &'a PullRequest>,
+) -> Result<ShippedIn> {
+    let mut releases_by_repo: HashMap<String, Vec<Release>> = HashMap::new();
+    let mut shipped = ShippedIn::new();
+
+    for pr in pull_requests {
+        let (Some(merged_at), Some(merge_commit), Some(repo)) = (
+            pr.merged_at.as_deref(),
+            pr.merge_commit.as_ref(),
+            repository_of(&pr.url),
+        ) else {
+            continue;
+        };
+        if !releases_by_repo.contains_key(&repo) {
+            info!("Fetching releases of {}", repo);
+            let releases = client
+                .fetch_releases(&repo)
+                .await
+                .with_context(|| format!("Failed to fetch releases of {}", repo))?;
+            releases_by_repo.insert(repo.clone(), releases);
+        }
+
+        for release in candidate_releases(merged_at, &releases_by_repo[&repo]) {
+            debug!(
+                "Checking whether {} contains PR #{}",
+                release.tag_name, pr.number
+            );
+            if client
+                .tag_contains(&repo, &release.tag_name, &merge_commit.oid)
+                .await?
+            {
+                shipped.insert(
+                    pr.url.clone(),
+                    ShippedRelease {
+                        tag_name: release.tag_name.clone(),
+                        url: release.url.clone(),
+                        published_at: release.published_at.clone(),
+                    },
+                );
+                break;
+            }
+        }
+    }
+
+    Ok(shipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_candidate_releases() {
+        let releases: Vec<Release> = serde_json::from_value(json!([
+            { "tagName": "v3", "url": "http://example.com/v3", "publishedAt": "2025-03-20T00:00:00Z", "isDraft": false },
+            { "tagName": "v4-draft", "url": "http://example.com/v4", "publishedAt": null, "isDraft": true },
+            { "tagName": "v2", "url": "http://example.com/v2", "publishedAt": "2025-03-10T00:00:00Z", "isDraft": false },
+            { "tagName": "v1", "url": "http://example.com/v1", "publishedAt": "2025-03-01T00:00:00Z", "isDraft": false }
+        ]))
+        .unwrap();
+
+        let tags: Vec<&str> = candidate_releases("2025-03-05T00:00:00Z", &releases)
+            .iter()
+            .map(|r| r.tag_name.as_str())
+            .collect();
+
+        assert_eq!(tags, ["v2", "v3"]);
+    }
+}
@@ -0,0 +1,626 @@
+//! Report module: the canonical, versioned JSON representation of a generated report.
+//!
+//! A `Report` bundles the fetched activity with the metadata every formatter needs
+//! (username and time range), so a saved JSON report can be re-rendered into any other
+//! format without access to the GitHub API.
+//!
+//! The activity is written in the shape of [`model::Activity`](crate::model::Activity), not
+//! the shape of the GraphQL response, so changing the query doesn't change the JSON.
+
+use crate::category::Categories;
+use crate::coverage::RepositoryCoverage;
+use crate::fork::Forks;
+use crate::github::user_activity;
+use crate::identity::UnattributedEmail;
+use crate::model::Activity;
+use crate::release::ShippedIn;
+use crate::revert::Reverts;
+use crate::statistics::Statistics;
+use crate::timeline::IssueHistories;
+use crate::triage::TriageSummary;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::ser::{SerializeSeq, Serializer as _};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// The current version of the report JSON schema.
+///
+/// Bump this whenever the serialized shape changes and add a migration step to
+/// `Report::from_value` for the previous version.
+pub const SCHEMA_VERSION: u32 = 5;
+
+/// The JSON Schema describing reports of the current schema version.
+pub const SCHEMA: &str = include_str!("report.schema.json");
+
+/// A generated report: activity data plus the inputs it was generated for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    /// Version of the report JSON schema this report was written with.
+    pub schema_version: u32,
+    /// The GitHub username the report covers.
+    pub username: String,
+    /// Start of the reported time range.
+    pub start_date: DateTime<Utc>,
+    /// End of the reported time range.
+    pub end_date: DateTime<Utc>,
+    /// The (filtered) activity data, serialized as a [`model::Activity`](crate::model::Activity)
+    /// or `null` when GitHub found no such user.
+    #[serde(
+        serialize_with = "crate::model::serialize_activity",
+        deserialize_with = "crate::model::deserialize_activity"
+    )]
+    pub activity: user_activity::ResponseData,
+    /// Triage activity in the filtered repository, when generated in triage mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub triage: Option<TriageSummary>,
+    /// Timeline history of the user's issues keyed by issue URL, when fetched with
+    /// `--include-timeline`.
+    #[serde(default, skip_serializing_if = "IssueHistories::is_empty")]
+    pub issue_histories: IssueHistories,
+    /// Merged pull requests that were later reverted, keyed by pull request URL, when
+    /// generated with `--detect-reverts`.
+    #[serde(default, skip_serializing_if = "Reverts::is_empty")]
+    pub reverted_prs: Reverts,
+    /// The first release that shipped each merged pull request, keyed by pull request URL,
+    /// when generated with `--include-releases`.
+    #[serde(default, skip_serializing_if = "ShippedIn::is_empty")]
+    pub shipped_in: ShippedIn,
+    /// Streaks, averages, and contribution ratios, when generated with `--stats`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statistics: Option<Statistics>,
+    /// The category of each issue and pull request, keyed by URL, when the config has
+    /// category rules.
+    #[serde(default, skip_serializing_if = "Categories::is_empty")]
+    pub categories: Categories,
+    /// Author emails of commits GitHub didn't attribute to the user, when generated with
+    /// `--check-commit-emails`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unattributed_commits: Vec<UnattributedEmail>,
+    /// How many of each repository's merged pull requests the user reviewed, when generated
+    /// with `--review-coverage`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub review_coverage: Vec<RepositoryCoverage>,
+    /// The user's forks among the reported repositories, with the repository each was forked
+    /// from, when generated with `--mark-forks`.
+    #[serde(default, skip_serializing_if = "Forks::is_empty")]
+    pub forks: Forks,
+    /// Data left out of the report because the token couldn't read it, e.g. when the token
+    /// lacks access to the `--org-scope` organization.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl Report {
+    /// Creates a report with the current schema version.
+    pub fn new(
+        username: String,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+        activity: user_activity::ResponseData,
+    ) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            username,
+            start_date,
+            end_date,
+            activity,
+            triage: None,
+            issue_histories: IssueHistories::new(),
+            reverted_prs: Reverts::new(),
+            shipped_in: ShippedIn::new(),
+            statistics: None,
+            categories: Categories::new(),
+            unattributed_commits: Vec::new(),
+            review_coverage: Vec::new(),
+            forks: Forks::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Serializes the report into pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize report to JSON")
+    }
+
+    /// Serializes the report as JSON straight into `writer`, pretty-printed unless `compact`.
+    pub fn write_json<W: Write>(&self, writer: W, compact: bool) -> Result<()> {
+        if compact {
+            serde_json::to_writer(writer, self)
+        } else {
+            serde_json::to_writer_pretty(writer, self)
+        }
+        .context("Failed to serialize report to JSON")
+    }
+
+    /// Parses a report from JSON, migrating older snapshots to the current schema.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(json).context("Report is not valid JSON")?;
+        Self::from_value(value)
+    }
+
+    /// Reads a saved report from disk.
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read report from {:?}", path))?;
+        Self::from_json(&json).with_context(|| format!("Failed to load report from {:?}", path))
+    }
+
+    /// Builds a report from a JSON value of any known schema version.
+    fn from_value(value: Value) -> Result<Self> {
+        match schema_version(&value)? {
+            0 => migrate_v0(value),
+            1 => migrate_v1(value),
+            2 => migrate_v2(value),
+            3 => migrate_v3(value),
+            4 => migrate_v4(value),
+            5 => serde_json::from_value(value).context("Failed to parse report"),
+            v => bail!(
+                "Report schema version {} is newer than the supported version {}; \
+                 please upgrade github-activity-rs",
+                v,
+                SCHEMA_VERSION
+            ),
+        }
+    }
+}
+
+/// Serializes reports as a JSON array straight into `writer`, one report at a time,
+/// pretty-printed unless `compact`.
+pub fn write_json_array<W: Write>(reports: &[Report], writer: W, compact: bool) -> Result<()> {
+    if compact {
+        write_seq(reports, &mut serde_json::Serializer::new(writer))
+    } else {
+        write_seq(reports, &mut serde_json::Serializer::pretty(writer))
+    }
+    .context("Failed to serialize reports to JSON")
+}
+
+fn write_seq<W: Write, F: serde_json::ser::Formatter>(
+    reports: &[Report],
+    serializer: &mut serde_json::Serializer<W, F>,
+) -> serde_json::Result<()> {
+    let mut seq = serializer.serialize_seq(Some(reports.len()))?;
+    for report in reports {
+        seq.serialize_element(report)?;
+    }
+    seq.end()
+}
+
+/// Returns the schema version of a serialized report.
+fn schema_version(value: &Value) -> Result<u64> {
+    match value.get("schema_version") {
+        Some(version) => version
+            .as_u64()
+            .context("schema_version must be a non-negative integer"),
+        // Reports written before versioning were the bare GraphQL response data.
+        None => Ok(0),
+    }
+}
+
+/// Checks a serialized report against the embedded JSON Schema.
+///
+/// Returns a list of human-readable problems; an empty list means the report is valid.
+/// Reports written with another schema version are not checked field by field; instead a
+/// single problem describing how to migrate (or upgrade) is returned.
+pub fn validate(value: &Value) -> Result<Vec<String>> {
+    let version = schema_version(value)?;
+    if version < SCHEMA_VERSION as u64 {
+        return Ok(vec![format!(
+            "report uses schema version {} but the current version is {}; migrate it with \
+             `github-activity-rs render <report> --format json --output <new-report>`",
+            version, SCHEMA_VERSION
+        )]);
+    }
+    if version > SCHEMA_VERSION as u64 {
+        return Ok(vec![format!(
+            "report uses schema version {} which is newer than the supported version {}; \
+             please upgrade github-activity-rs",
+            version, SCHEMA_VERSION
+        )]);
+    }
+
+    let schema: Value =
+        serde_json::from_str(SCHEMA).context("Embedded report schema is invalid")?;
+    let validator = jsonschema::validator_for(&schema)
+        .map_err(|e| anyhow::anyhow!("Embedded report schema is invalid: {}", e))?;
+    Ok(validator
+        .iter_errors(value)
+        .map(|error| {
+            let path = error.instance_path().to_string();
+            let path = if path.is_empty() {
+                "/".to_string()
+            } else {
+                path
+            };
+            format!("{}: {}", path, error)
+        })
+        .collect())
+}
+
+/// Migrates an unversioned snapshot (bare `ResponseData`) to the current schema.
+///
+/// Those snapshots did not record the username or time range, so the time range is
+/// recovered from the contribution calendar and the username is left empty.
+fn migrate_v0(mut value: Value) -> Result<Report> {
+    add_missing_issue_ids(&mut value);
+    add_missing_pr_sizes(&mut value);
+    add_missing_review_states(&mut value);
+    let activity: user_activity::ResponseData =
+        serde_json::from_value(value).context("Failed to parse unversioned report")?;
+
+    let days: Vec<DateTime<Utc>> = activity
+        .user
+        .iter()
+        .flat_map(|user| &user.contributions_collection.contribution_calendar.weeks)
+        .flat_map(|week| &week.contribution_days)
+        .filter_map(|day| parse_calendar_date(&day.date))
+        .collect();
+    let start_date = days.iter().min().copied().unwrap_or_default();
+    let end_date = days.iter().max().copied().unwrap_or_default();
+
+    Ok(Report::new(String::new(), start_date, end_date, activity))
+}
+
+/// Migrates a version 1 report, whose issue nodes did not record their node ID.
+fn migrate_v1(mut value: Value) -> Result<Report> {
+    if let Some(activity) = value.get_mut("activity") {
+        add_missing_issue_ids(activity);
+        add_missing_pr_sizes(activity);
+        add_missing_review_states(activity);
+    }
+    migrate_v4(value).context("Failed to parse version 1 report")
+}
+
+/// Migrates a version 2 report, whose pull request nodes did not record their size.
+fn migrate_v2(mut value: Value) -> Result<Report> {
+    if let Some(activity) = value.get_mut("activity") {
+        add_missing_pr_sizes(activity);
+        add_missing_review_states(activity);
+    }
+    migrate_v4(value).context("Failed to parse version 2 report")
+}
+
+/// Migrates a version 3 report, whose pull request review nodes did not record their state.
+fn migrate_v3(mut value: Value) -> Result<Report> {
+    if let Some(activity) = value.get_mut("activity") {
+        add_missing_review_states(activity);
+    }
+    migrate_v4(value).context("Failed to parse version 3 report")
+}
+
+/// Migrates a version 4 report, whose activity was the GraphQL response data as fetched.
+fn migrate_v4(mut value: Value) -> Result<Report> {
+    if let Some(activity) = value.get_mut("activity") {
+        let data: user_activity::ResponseData = serde_json::from_value(activity.take())
+            .context("Failed to parse version 4 activity")?;
+        *activity = serde_json::to_value(Activity::from_response(&data))
+            .context("Failed to convert version 4 activity")?;
+    }
+    value["schema_version"] = SCHEMA_VERSION.into();
+    serde_json::from_value(value).context("Failed to parse version 4 report")
+}
+
+/// Gives issue nodes without an `id` an empty one; the ID is only needed to fetch timelines,
+/// which older reports never had.
+fn add_missing_issue_ids(activity: &mut Value) {
+    let nodes = activity
+        .pointer_mut("/user/contributionsCollection/issueContributions/nodes")
+        .and_then(Value::as_array_mut);
+    for issue in nodes
+        .into_iter()
+        .flatten()
+        .filter_map(|node| node.get_mut("issue"))
+    {
+        if let Some(issue) = issue.as_object_mut() {
+            issue.entry("id").or_insert_with(|| "".into());
+        }
+    }
+}
+
+/// Gives pull request nodes without additions, deletions, or changed files a size of zero;
+/// older reports never fetched it.
+fn add_missing_pr_sizes(activity: &mut Value) {
+    let nodes = activity
+        .pointer_mut("/user/contributionsCollection/pullRequestContributions/nodes")
+        .and_then(Value::as_array_mut);
+    for pr in nodes
+        .into_iter()
+        .flatten()
+        .filter_map(|node| node.get_mut("pullRequest"))
+    {
+        if let Some(pr) = pr.as_object_mut() {
+            for field in ["additions", "deletions", "changedFiles"] {
+                pr.entry(field).or_insert_with(|| 0.into());
+            }
+        }
+    }
+}
+
+/// Gives pull request review nodes without a state an empty one; older reports never fetched
+/// it, and the empty state is left out of formatted reports.
+fn add_missing_review_states(activity: &mut Value) {
+    let nodes = activity
+        .pointer_mut("/user/contributionsCollection/pullRequestReviewContributions/nodes")
+        .and_then(Value::as_array_mut);
+    for review in nodes
+        .into_iter()
+        .flatten()
+        .filter_map(|node| node.get_mut("pullRequestReview"))
+    {
+        if let Some(review) = review.as_object_mut() {
+            review.entry("state").or_insert_with(|| "".into());
+        }
+    }
+}
+
+/// Parses a calendar date, which GitHub returns either as `YYYY-MM-DD` or as a full timestamp.
+fn parse_calendar_date(date: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(date) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn dummy_activity() -> user_activity::ResponseData {
+        serde_json::from_value(json!({
+            "user": {
+                "contributionsCollection": {
+                    "totalCommitContributions": 3,
+                    "totalIssueContributions": 0,
+                    "totalPullRequestContributions": 0,
+                    "totalPullRequestReviewContributions": 0,
+                    "contributionCalendar": {
+                        "totalContributions": 3,
+                        "weeks": [{
+                            "contributionDays": [
+                                { "date": "2025-03-02", "contributionCount": 1, "weekday": 0 },
+                                { "date": "2025-03-03", "contributionCount": 2, "weekday": 1 }
+                            ]
+                        }]
+                    },
+                    "commitContributionsByRepository": [],
+                    "issueContributions": {
+                        "totalCount": 0,
+                        "pageInfo": { "endCursor": null, "hasNextPage": false },
+                        "nodes": []
+                    },
+                    "pullRequestContributions": {
+                        "totalCount": 0,
+                        "pageInfo": { "endCursor": null, "hasNextPage": false },
+                        "nodes": []
+                    },
+                    "pullRequestReviewContributions": {
+                        "totalCount": 0,
+                        "pageInfo": { "endCursor": null, "hasNextPage": false },
+                        "nodes": []
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    /// A report as written before schema version 5, with the GraphQL response as activity.
+    fn legacy_report(version: u32) -> Value {
+        let mut value = serde_json::to_value(Report::new(
+            "dummy".into(),
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 8, 0, 0, 0).unwrap(),
+            dummy_activity(),
+        ))
+        .unwrap();
+        value["schema_version"] = json!(version);
+        value["activity"] = serde_json::to_value(dummy_activity()).unwrap();
+        value
+    }
+
+    #[test]
+    fn test_report_round_trip() {
+        let report = Report::new(
+            "dummy".into(),
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 8, 0, 0, 0).unwrap(),
+            dummy_activity(),
+        );
+        let json = report.to_json().unwrap();
+        let parsed = Report::from_json(&json).unwrap();
+        assert_eq!(parsed, report);
+    }
+
+    #[test]
+    fn test_report_migrates_unversioned_snapshot() {
+        let json = serde_json::to_string(&dummy_activity()).unwrap();
+        let report = Report::from_json(&json).unwrap();
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+        assert_eq!(report.activity, dummy_activity());
+        assert_eq!(
+            report.start_date,
+            Utc.with_ymd_and_hms(2025, 3, 2, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            report.end_date,
+            Utc.with_ymd_and_hms(2025, 3, 3, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_report_migrates_v1_issue_without_id() {
+        let mut value = legacy_report(1);
+        value["activity"]["user"]["contributionsCollection"]["issueContributions"]["nodes"] = json!([{
+            "issue": {
+                "number": 1,
+                "title": "Old issue",
+                "url": "http://example.com/issues/1",
+                "createdAt": "2025-03-02T00:00:00Z",
+                "state": "OPEN",
+                "closedAt": null
+            }
+        }]);
+
+        let report = Report::from_json(&value.to_string()).unwrap();
+
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+        let issues = report
+            .activity
+            .user
+            .unwrap()
+            .contributions_collection
+            .issue_contributions;
+        assert_eq!(issues.nodes.unwrap()[0].issue.id, "");
+    }
+
+    #[test]
+    fn test_report_migrates_v2_pr_without_size() {
+        let mut value = legacy_report(2);
+        value["activity"]["user"]["contributionsCollection"]["pullRequestContributions"]["nodes"] = json!([{
+            "pullRequest": {
+                "number": 1,
+                "title": "Old PR",
+                "url": "http://example.com/pull/1",
+                "createdAt": "2025-03-02T00:00:00Z",
+                "state": "OPEN",
+                "merged": false,
+                "mergedAt": null,
+                "closedAt": null
+            }
+        }]);
+
+        let report = Report::from_json(&value.to_string()).unwrap();
+
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+        let prs = report
+            .activity
+            .user
+            .unwrap()
+            .contributions_collection
+            .pull_request_contributions;
+        let pr = &prs.nodes.unwrap()[0].pull_request;
+        assert_eq!((pr.additions, pr.deletions, pr.changed_files), (0, 0, 0));
+    }
+
+    #[test]
+    fn test_report_migrates_v3_review_without_state() {
+        let mut value = legacy_report(3);
+        value["activity"]["user"]["contributionsCollection"]["pullRequestReviewContributions"]["nodes"] = json!([{
+            "pullRequestReview": {
+                "pullRequest": {
+                    "number": 1,
+                    "title": "Old PR",
+                    "url": "http://example.com/pull/1"
+                }
+            },
+            "occurredAt": "2025-03-02T00:00:00Z"
+        }]);
+
+        let report = Report::from_json(&value.to_string()).unwrap();
+
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+        let reviews = report
+            .activity
+            .user
+            .unwrap()
+            .contributions_collection
+            .pull_request_review_contributions;
+        let review = &reviews.nodes.unwrap()[0].pull_request_review;
+        assert_eq!(review.state, "");
+        assert_eq!(review.body, None);
+    }
+
+    #[test]
+    fn test_report_migrates_v4_graphql_activity() {
+        let report = Report::from_json(&legacy_report(4).to_string()).unwrap();
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+        assert_eq!(report.activity, dummy_activity());
+
+        let value: Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(value["activity"]["totals"]["commits"], 3);
+        assert_eq!(value["activity"]["calendar"][1]["count"], 2);
+        assert!(value["activity"].get("user").is_none());
+    }
+
+    #[test]
+    fn test_schema_matches_current_version() {
+        let schema: Value = serde_json::from_str(SCHEMA).unwrap();
+        assert_eq!(
+            schema["properties"]["schema_version"]["const"],
+            json!(SCHEMA_VERSION)
+        );
+    }
+
+    #[test]
+    fn test_validate_accepts_generated_report() {
+        let report = Report::new(
+            "dummy".into(),
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 8, 0, 0, 0).unwrap(),
+            dummy_activity(),
+        );
+        let value: Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(validate(&value).unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_validate_reports_schema_violations() {
+        let report = Report::new(
+            "dummy".into(),
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 8, 0, 0, 0).unwrap(),
+            dummy_activity(),
+        );
+        let mut value: Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        value["activity"]["totals"]["commits"] = json!("three");
+        let problems = validate(&value).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("/activity/totals/commits"));
+    }
+
+    #[test]
+    fn test_validate_hints_migration_for_unversioned_report() {
+        let value = serde_json::to_value(dummy_activity()).unwrap();
+        let problems = validate(&value).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("schema version 0"));
+        assert!(problems[0].contains("render"));
+    }
+
+    #[test]
+    fn test_report_rejects_newer_schema_version() {
+        let json = json!({ "schema_version": SCHEMA_VERSION + 1 }).to_string();
+        let err = Report::from_json(&json).unwrap_err();
+        assert!(format!("{}", err).contains("newer than the supported version"));
+    }
+
+    #[test]
+    fn test_write_json_array() {
+        let report = Report::new(
+            "dummy".into(),
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 8, 0, 0, 0).unwrap(),
+            dummy_activity(),
+        );
+        let reports = [report.clone(), report.clone()];
+
+        for compact in [false, true] {
+            let mut json = Vec::new();
+            write_json_array(&reports, &mut json, compact).unwrap();
+            assert_eq!(
+                serde_json::from_slice::<Value>(&json).unwrap(),
+                serde_json::to_value(&reports).unwrap()
+            );
+            assert_eq!(json.contains(&b'\n'), !compact);
+        }
+    }
+}
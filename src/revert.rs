@@ -0,0 +1,179 @@
+//! Revert module: flags merged pull requests that were later reverted, so a report doesn't
+//! celebrate work that never stuck.
+//!
+//! A revert is recognized by the conventions of GitHub's "Revert" button: the revert pull
+//! request is titled `Revert "<original title>"` and its body starts with
+//! `Reverts owner/repo#<number>`.
+
+use crate::github::revert_pull_requests::RevertPullRequestsSearchNodesOnPullRequest as Candidate;
+use crate::github::user_activity::UserActivityUserContributionsCollectionPullRequestContributionsNodesPullRequest as PullRequest;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Reverted pull requests keyed by the URL of the reverted pull request.
+pub type Reverts = BTreeMap<String, RevertedBy>;
+
+/// The pull request that reverted a merged pull request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevertedBy {
+    /// Number of the revert pull request.
+    pub number: i64,
+    /// Title of the revert pull request.
+    pub title: String,
+    /// URL of the revert pull request.
+    pub url: String,
+    /// When the revert was merged.
+    pub merged_at: Option<String>,
+}
+
+/// Extracts "owner/repo" from a pull request URL such as
+/// `https://github.com/owner/repo/pull/1`.
+pub fn repository_of(url: &str) -> Option<String> {
+    let path = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let mut parts = path.split('/').skip(1);
+    let owner = parts.next().filter(|s| !s.is_empty())?;
+    let name = parts.next().filter(|s| !s.is_empty())?;
+    Some(format!("{}/{}", owner, name))
+}
+
+/// Matches merged pull requests against revert candidates from the same repositories.
+///
+/// Only reverts merged after the original pull request count; when several candidates match,
+/// the earliest revert wins.
+pub fn find_reverts<'a>(
+    pull_requests: impl IntoIterator<Item = &'a PullRequest>,
+    candidates: &[Candidate],
+) -> Reverts {
+    let mut reverts = Reverts::new();
+    for pr in pull_requests.into_iter().filter(|pr| pr.merged) {
+        let Some(repo) = repository_of(&pr.url) else {
+            continue;
+        };
+        let revert = candidates
+            .iter()
+            .filter(|c| c.url != pr.url && repository_of(&c.url).as_deref() == Some(&repo))
+            .filter(|c| match (&c.merged_at, &pr.merged_at) {
+                (Some(reverted_at), Some(merged_at)) => reverted_at >= merged_at,
+                _ => true,
+            })
+            .filter(|c| {
+                reverts_title(&c.title, &pr.title) || reverts_body(&c.body, &repo, pr.number)
+            })
+            .min_by(|a, b| a.merged_at.cmp(&b.merged_at));
+        if let Some(revert) = revert {
+            reverts.insert(
+                pr.url.clone(),
+                RevertedBy {
+                    number: revert.number,
+                    title: revert.title.clone(),
+                    url: revert.url.clone(),
+                    merged_at: revert.merged_at.clone(),
+                },
+            );
+        }
+    }
+    reverts
+}
+
+/// Whether `title` is GitHub's revert title for a pull request titled `original`.
+fn reverts_title(title: &str, original: &str) -> bool {
+    title
+        .strip_prefix("Revert \"")
+        .and_then(|rest| rest.strip_suffix('"'))
+        .is_some_and(|reverted| reverted == original)
+}
+
+/// Whether `body` contains `Reverts owner/repo#number` (or `Reverts #number`).
+fn reverts_body(body: &str, repo: &str, number: i64) -> bool {
+    let full = format!("{}#{}", repo, number);
+    let short = format!("#{}", number);
+    let words: Vec<&str> = body.split_whitespace().collect();
+    words.windows(2).any(|pair| {
+        pair[0].eq_ignore_ascii_case("reverts")
+            && (pair[1].eq_ignore_ascii_case(&full) || pair[1] == short)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pull_request(number: i64, title: &str, merged_at: &str) -> PullRequest {
+        PullRequest {
+            number,
+            title: title.into(),
+            url: format!("https://github.com/owner/repo/pull/{}", number),
+            created_at: "2025-03-01T00:00:00Z".into(),
+            state: "MERGED".into(),
+            merged: true,
+            merged_at: Some(merged_at.into()),
+            merge_commit: None,
+            closed_at: Some(merged_at.into()),
+            additions: 0,
+            deletions: 0,
+            changed_files: 0,
+            labels: None,
+        }
+    }
+
+    fn candidates() -> Vec<Candidate> {
+        serde_json::from_value(json!([
+            {
+                "number": 20,
+                "title": "Revert \"Add caching\"",
+                "url": "https://github.com/owner/repo/pull/20",
+                "body": "Reverts owner/repo#10",
+                "mergedAt": "2025-03-05T00:00:00Z"
+            },
+            {
+                "number": 21,
+                "title": "Back out flaky retry logic",
+                "url": "https://github.com/owner/repo/pull/21",
+                "body": "Reverts #11 because it broke CI.",
+                "mergedAt": "2025-03-06T00:00:00Z"
+            },
+            {
+                // Same number in another repository.
+                "number": 22,
+                "title": "Revert retry logic",
+                "url": "https://github.com/other/repo/pull/22",
+                "body": "Reverts other/repo#12",
+                "mergedAt": "2025-03-06T00:00:00Z"
+            }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_repository_of() {
+        assert_eq!(
+            repository_of("https://github.com/owner/repo/pull/1").as_deref(),
+            Some("owner/repo")
+        );
+        assert_eq!(repository_of("https://github.com/"), None);
+    }
+
+    #[test]
+    fn test_find_reverts() {
+        let prs = [
+            pull_request(10, "Add caching", "2025-03-02T00:00:00Z"),
+            pull_request(11, "Retry flaky requests", "2025-03-03T00:00:00Z"),
+            pull_request(12, "Retry logic", "2025-03-03T00:00:00Z"),
+            // Number 1 must not match "#10" or "#11".
+            pull_request(1, "Unrelated", "2025-03-01T00:00:00Z"),
+        ];
+
+        let reverts = find_reverts(&prs, &candidates());
+
+        assert_eq!(reverts.len(), 2);
+        assert_eq!(reverts["https://github.com/owner/repo/pull/10"].number, 20);
+        assert_eq!(reverts["https://github.com/owner/repo/pull/11"].number, 21);
+    }
+
+    #[test]
+    fn test_find_reverts_ignores_reverts_merged_before_the_pull_request() {
+        let prs = [pull_request(10, "Add caching", "2025-03-09T00:00:00Z")];
+        assert!(find_reverts(&prs, &candidates()).is_empty());
+    }
+}
@@ -0,0 +1,433 @@
+//! Review module: a quarter- or year-scale summary of one person's work for performance
+//! reviews and brag documents, with highlights, totals compared against the previous period
+//! of the same length, and a per-month breakdown.
+
+use crate::calendar::{CalendarGranularity, Rollup, rollups};
+use crate::format::lines_changed;
+use crate::github::user_activity::UserActivityUserContributionsCollection as ContributionsCollection;
+use crate::locale::NumberLocale;
+use crate::narrative::paragraphs;
+use crate::report::Report;
+use crate::revert::repository_of;
+use crate::text;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// How many pull requests and repositories are highlighted.
+const HIGHLIGHTS: usize = 5;
+
+/// What kind of report a run produces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportMode {
+    /// The activity of the requested range in the selected format.
+    #[default]
+    Standard,
+    /// A performance review summary in Markdown (see [`ReviewSummary`]).
+    Review,
+}
+
+impl FromStr for ReportMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "standard" => Ok(Self::Standard),
+            "review" => Ok(Self::Review),
+            _ => Err(format!("Invalid mode: {}. Use standard or review", s)),
+        }
+    }
+}
+
+/// One total of the period next to the same total of the previous period.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Trend {
+    /// What is counted, e.g. `Pull requests`.
+    pub label: String,
+    /// The total of the reviewed period.
+    pub current: i64,
+    /// The total of the previous period.
+    pub previous: i64,
+}
+
+impl Trend {
+    /// The change from the previous period in whole percent; `None` when it had none.
+    pub fn change_percent(&self) -> Option<i64> {
+        (self.previous != 0).then(|| {
+            ((self.current - self.previous) as f64 * 100.0 / self.previous as f64).round() as i64
+        })
+    }
+}
+
+/// A merged pull request highlighted for its size.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PullRequestHighlight {
+    /// Repository in the format owner/name.
+    pub repository: String,
+    /// Pull request number.
+    pub number: i64,
+    /// Pull request title.
+    pub title: String,
+    /// Pull request URL.
+    pub url: String,
+    /// Lines added.
+    pub additions: i64,
+    /// Lines deleted.
+    pub deletions: i64,
+}
+
+/// A repository highlighted for how many of its pull requests were reviewed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RepositoryReviews {
+    /// Repository in the format owner/name.
+    pub repository: String,
+    /// Reviews submitted in the repository.
+    pub reviews: usize,
+}
+
+/// The performance review summary of one report against the report of the period before.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewSummary {
+    /// The reviewed user.
+    pub username: String,
+    /// Start of the reviewed period.
+    pub start_date: DateTime<Utc>,
+    /// End of the reviewed period.
+    pub end_date: DateTime<Utc>,
+    /// Start of the previous period.
+    pub previous_start_date: DateTime<Utc>,
+    /// The summary sentence of the reviewed period.
+    pub summary: String,
+    /// Totals of both periods.
+    pub totals: Vec<Trend>,
+    /// The largest merged pull requests, largest first.
+    pub largest_pull_requests: Vec<PullRequestHighlight>,
+    /// The repositories with the most reviews, most first.
+    pub most_reviewed_repositories: Vec<RepositoryReviews>,
+    /// Contributions per month of the reviewed period.
+    pub months: Vec<Rollup>,
+}
+
+impl ReviewSummary {
+    /// Summarizes `current`, comparing its totals with `previous`.
+    pub fn new(current: &Report, previous: &Report, locale: NumberLocale) -> Self {
+        let cc = current
+            .activity
+            .user
+            .as_ref()
+            .map(|user| &user.contributions_collection);
+        let previous_cc = previous
+            .activity
+            .user
+            .as_ref()
+            .map(|user| &user.contributions_collection);
+        let summary = paragraphs(current, locale)
+            .into_iter()
+            .next()
+            .map(|paragraph| paragraph.text)
+            .unwrap_or_else(|| "No user data available.".to_string());
+        Self {
+            username: current.username.clone(),
+            start_date: current.start_date,
+            end_date: current.end_date,
+            previous_start_date: previous.start_date,
+            summary,
+            totals: totals(cc)
+                .into_iter()
+                .zip(totals(previous_cc))
+                .map(|((label, current), (_, previous))| Trend {
+                    label: label.to_string(),
+                    current,
+                    previous,
+                })
+                .collect(),
+            largest_pull_requests: cc.map(largest_pull_requests).unwrap_or_default(),
+            most_reviewed_repositories: cc.map(most_reviewed_repositories).unwrap_or_default(),
+            months: cc
+                .map(|cc| rollups(cc, CalendarGranularity::Monthly))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The sentence describing the largest changes from the previous period, if any total
+    /// changed.
+    fn trend_sentence(&self) -> Option<String> {
+        let changes: Vec<String> = self
+            .totals
+            .iter()
+            .filter_map(|trend| {
+                let percent = trend.change_percent().filter(|&p| p != 0)?;
+                let direction = if percent > 0 { "up" } else { "down" };
+                Some(format!(
+                    "{} {} {}%",
+                    trend.label.to_lowercase(),
+                    direction,
+                    percent.abs()
+                ))
+            })
+            .collect();
+        (!changes.is_empty())
+            .then(|| format!("Compared with the previous period: {}.", changes.join(", ")))
+    }
+
+    /// Renders the summary as a Markdown document.
+    pub fn to_markdown(&self, locale: NumberLocale) -> String {
+        let mut output = format!(
+            "# Performance Review: {}\n\n**Period:** {} to {} (compared with {} to {})\n\n",
+            self.username,
+            self.start_date.format("%Y-%m-%d"),
+            self.end_date.format("%Y-%m-%d"),
+            self.previous_start_date.format("%Y-%m-%d"),
+            self.start_date.format("%Y-%m-%d")
+        );
+        output.push_str(&format!("## Summary\n\n{}", self.summary));
+        if let Some(trends) = self.trend_sentence() {
+            output.push_str(&format!(" {}", trends));
+        }
+        output.push_str("\n\n");
+
+        output.push_str("## Highlights\n\n");
+        if self.largest_pull_requests.is_empty() && self.most_reviewed_repositories.is_empty() {
+            output.push_str("No merged pull requests or reviews in this period.\n\n");
+        }
+        if !self.largest_pull_requests.is_empty() {
+            output.push_str("### Largest Pull Requests\n\n");
+            for pr in &self.largest_pull_requests {
+                output.push_str(&format!(
+                    "- [{}#{} {}]({}) (+{} -{})\n",
+                    pr.repository,
+                    pr.number,
+                    text::title(&pr.title),
+                    pr.url,
+                    locale.format(pr.additions),
+                    locale.format(pr.deletions)
+                ));
+            }
+            output.push('\n');
+        }
+        if !self.most_reviewed_repositories.is_empty() {
+            output.push_str("### Most-Reviewed Repositories\n\n");
+            for repo in &self.most_reviewed_repositories {
+                output.push_str(&format!(
+                    "- {}: {} {}\n",
+                    repo.repository,
+                    locale.format(repo.reviews as i64),
+                    if repo.reviews == 1 {
+                        "review"
+                    } else {
+                        "reviews"
+                    }
+                ));
+            }
+            output.push('\n');
+        }
+
+        output.push_str("## Totals\n\n");
+        output.push_str("| | This Period | Previous Period | Change |\n");
+        output.push_str("|-|-------------|-----------------|--------|\n");
+        for trend in &self.totals {
+            let change = match trend.change_percent() {
+                Some(percent) => format!("{:+}%", percent),
+                None if trend.current == 0 => "–".to_string(),
+                None => "new".to_string(),
+            };
+            output.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                trend.label,
+                locale.format(trend.current),
+                locale.format(trend.previous),
+                change
+            ));
+        }
+
+        if !self.months.is_empty() {
+            output.push_str("\n## Monthly Breakdown\n\n");
+            output.push_str("| Month | Contributions | Issues | Pull Requests | Reviews |\n");
+            output.push_str("|-------|---------------|--------|---------------|---------|\n");
+            for month in &self.months {
+                output.push_str(&format!(
+                    "| {} | {} | {} | {} | {} |\n",
+                    month.label,
+                    locale.format(month.contribution_count),
+                    locale.format(month.issues as i64),
+                    locale.format(month.pull_requests as i64),
+                    locale.format(month.reviews as i64)
+                ));
+            }
+        }
+        output
+    }
+}
+
+/// The totals compared between periods, labeled; zero without user data.
+fn totals(cc: Option<&ContributionsCollection>) -> Vec<(&'static str, i64)> {
+    let (additions, deletions) = cc.map(lines_changed).unwrap_or_default();
+    vec![
+        ("Commits", cc.map_or(0, |cc| cc.total_commit_contributions)),
+        ("Issues", cc.map_or(0, |cc| cc.total_issue_contributions)),
+        (
+            "Pull requests",
+            cc.map_or(0, |cc| cc.total_pull_request_contributions),
+        ),
+        (
+            "Reviews",
+            cc.map_or(0, |cc| cc.total_pull_request_review_contributions),
+        ),
+        ("Lines changed", additions + deletions),
+    ]
+}
+
+/// The largest merged pull requests by lines changed, largest first.
+fn largest_pull_requests(cc: &ContributionsCollection) -> Vec<PullRequestHighlight> {
+    let mut merged: Vec<_> = cc
+        .pull_request_contributions
+        .nodes
+        .iter()
+        .flatten()
+        .map(|node| &node.pull_request)
+        .filter(|pr| pr.merged)
+        .collect();
+    merged.sort_by_key(|pr| std::cmp::Reverse(pr.additions + pr.deletions));
+    merged
+        .into_iter()
+        .take(HIGHLIGHTS)
+        .map(|pr| PullRequestHighlight {
+            repository: repository_of(&pr.url).unwrap_or_default(),
+            number: pr.number,
+            title: pr.title.clone(),
+            url: pr.url.clone(),
+            additions: pr.additions,
+            deletions: pr.deletions,
+        })
+        .collect()
+}
+
+/// The repositories with the most reviews, most first and then by name.
+fn most_reviewed_repositories(cc: &ContributionsCollection) -> Vec<RepositoryReviews> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for node in cc.pull_request_review_contributions.nodes.iter().flatten() {
+        if let Some(repository) = repository_of(&node.pull_request_review.pull_request.url) {
+            *counts.entry(repository).or_default() += 1;
+        }
+    }
+    let mut repositories: Vec<RepositoryReviews> = counts
+        .into_iter()
+        .map(|(repository, reviews)| RepositoryReviews {
+            repository,
+            reviews,
+        })
+        .collect();
+    // The sort is stable, so ties stay in name order.
+    repositories.sort_by_key(|repo| std::cmp::Reverse(repo.reviews));
+    repositories.truncate(HIGHLIGHTS);
+    repositories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn report(start: &str, end: &str, prs: &[(&str, i64, bool)], reviews: &[&str]) -> Report {
+        let pr_nodes: Vec<_> = prs
+            .iter()
+            .enumerate()
+            .map(|(i, (repo, size, merged))| {
+                json!({ "pullRequest": {
+                    "number": i + 1, "title": format!("PR {}", i + 1),
+                    "url": format!("https://github.com/{}/pull/{}", repo, i + 1),
+                    "createdAt": "2025-02-03T00:00:00Z", "state": "MERGED", "merged": merged,
+                    "mergedAt": null, "mergeCommit": null, "closedAt": null,
+                    "additions": size, "deletions": 0, "changedFiles": 1
+                } })
+            })
+            .collect();
+        let review_nodes: Vec<_> = reviews
+            .iter()
+            .map(|repo| {
+                json!({ "occurredAt": "2025-03-04T00:00:00Z", "pullRequestReview": {
+                    "pullRequest": {
+                        "number": 9, "title": "Reviewed",
+                        "url": format!("https://github.com/{}/pull/9", repo)
+                    },
+                    "createdAt": "2025-03-04T00:00:00Z", "state": "APPROVED"
+                } })
+            })
+            .collect();
+        let page = |nodes: Vec<serde_json::Value>| json!({ "totalCount": nodes.len(), "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes });
+        let activity = serde_json::from_value(json!({ "user": { "contributionsCollection": {
+            "totalCommitContributions": 10,
+            "totalIssueContributions": 0,
+            "totalPullRequestContributions": prs.len(),
+            "totalPullRequestReviewContributions": reviews.len(),
+            "contributionCalendar": { "totalContributions": 5, "weeks": [
+                { "contributionDays": [
+                    { "date": "2025-02-03T00:00:00Z", "contributionCount": 2, "weekday": 1 },
+                    { "date": "2025-03-04T00:00:00Z", "contributionCount": 3, "weekday": 2 }
+                ] }
+            ] },
+            "commitContributionsByRepository": [],
+            "issueContributions": page(vec![]),
+            "pullRequestContributions": page(pr_nodes),
+            "pullRequestReviewContributions": page(review_nodes)
+        } }, "rateLimit": null }))
+        .unwrap();
+        Report::new(
+            "octocat".into(),
+            DateTime::parse_from_rfc3339(start).unwrap().into(),
+            DateTime::parse_from_rfc3339(end).unwrap().into(),
+            activity,
+        )
+    }
+
+    #[test]
+    fn test_review_summary() {
+        let current = report(
+            "2025-01-01T00:00:00Z",
+            "2025-04-01T00:00:00Z",
+            &[("o/a", 50, true), ("o/b", 500, true), ("o/a", 900, false)],
+            &["o/b", "o/a", "o/b"],
+        );
+        let previous = report(
+            "2024-10-01T00:00:00Z",
+            "2025-01-01T00:00:00Z",
+            &[("o/a", 10, true), ("o/a", 10, true)],
+            &[],
+        );
+        let summary = ReviewSummary::new(&current, &previous, NumberLocale::default());
+
+        // Only merged pull requests are highlighted, largest first.
+        let largest: Vec<_> = summary
+            .largest_pull_requests
+            .iter()
+            .map(|pr| (pr.repository.as_str(), pr.number))
+            .collect();
+        assert_eq!(largest, [("o/b", 2), ("o/a", 1)]);
+        let reviewed: Vec<_> = summary
+            .most_reviewed_repositories
+            .iter()
+            .map(|repo| (repo.repository.as_str(), repo.reviews))
+            .collect();
+        assert_eq!(reviewed, [("o/b", 2), ("o/a", 1)]);
+
+        let prs = &summary.totals[2];
+        assert_eq!(
+            (prs.current, prs.previous, prs.change_percent()),
+            (3, 2, Some(50))
+        );
+        assert_eq!(summary.totals[3].change_percent(), None);
+        let months: Vec<_> = summary.months.iter().map(|m| m.label.as_str()).collect();
+        assert_eq!(months, ["2025-02", "2025-03"]);
+
+        let markdown = summary.to_markdown(NumberLocale::default());
+        assert!(markdown.starts_with("# Performance Review: octocat\n\n"));
+        assert!(markdown.contains("(compared with 2024-10-01 to 2025-01-01)"));
+        assert!(markdown.contains("Compared with the previous period: pull requests up 50%,"));
+        assert!(markdown.contains("- [o/b#2 PR 2](https://github.com/o/b/pull/2) (+500 -0)\n"));
+        assert!(markdown.contains("- o/b: 2 reviews\n"));
+        assert!(markdown.contains("| Pull requests | 3 | 2 | +50% |\n"));
+        assert!(markdown.contains("| Reviews | 3 | 0 | new |\n"));
+        assert!(markdown.contains("| 2025-03 | 3 | 0 | 0 | 3 |\n"));
+    }
+}
@@ -0,0 +1,117 @@
+//! Scope module: narrows pull request activity to the files a user cares about, so engineers
+//! in a monorepo can report on their area instead of the whole repository.
+//!
+//! Paths are selected with `--path` globs and/or by CODEOWNERS ownership. Commit contributions
+//! are only available as per-repository totals and are not scoped.
+
+use crate::filter::retain_pull_requests;
+use crate::github::{GithubClient, user_activity};
+use crate::revert::repository_of;
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use log::{debug, info};
+use std::collections::HashSet;
+
+/// A set of path globs, e.g. `packages/frontend/**`.
+pub struct PathScope {
+    globs: GlobSet,
+}
+
+impl PathScope {
+    /// Compiles the given globs; a path is in scope when it matches any of them.
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(
+                Glob::new(pattern).with_context(|| format!("Invalid path glob: {}", pattern))?,
+            );
+        }
+        Ok(Self {
+            globs: builder.build().context("Failed to compile path globs")?,
+        })
+    }
+
+    /// Whether the path is in scope.
+    pub fn is_match(&self, path: &str) -> bool {
+        self.globs.is_match(path)
+    }
+}
+
+/// Splits a pull request URL such as `https://github.com/owner/repo/pull/1` into its
+/// repository and number.
+pub fn pull_request_ref(url: &str) -> Option<(String, i64)> {
+    let repo = repository_of(url)?;
+    let number = url.trim_end_matches('/').rsplit('/').next()?.parse().ok()?;
+    Some((repo, number))
+}
+
+/// Keeps only the pull requests and reviews of pull requests that change at least one file for
+/// which `in_scope` returns true, fetching each pull request's changed files once.
+pub async fn scope_activity(
+    client: &GithubClient,
+    activity: user_activity::ResponseData,
+    in_scope: impl Fn(&str) -> bool,
+) -> Result<user_activity::ResponseData> {
+    let urls: HashSet<String> = activity
+        .user
+        .iter()
+        .flat_map(|user| {
+            let cc = &user.contributions_collection;
+            let prs = cc
+                .pull_request_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(|node| node.pull_request.url.clone());
+            let reviews = cc
+                .pull_request_review_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(|node| node.pull_request_review.pull_request.url.clone());
+            prs.chain(reviews)
+        })
+        .collect();
+    info!("Fetching changed files of {} pull request(s)", urls.len());
+
+    let mut keep = HashSet::new();
+    for url in urls {
+        let Some((repo, number)) = pull_request_ref(&url) else {
+            debug!("Skipping pull request with unrecognized URL {}", url);
+            continue;
+        };
+        let files = client.fetch_pr_files(&repo, number).await?;
+        if files.iter().any(|file| in_scope(file)) {
+            keep.insert(url);
+        }
+    }
+    info!("{} pull request(s) touch paths in scope", keep.len());
+
+    Ok(retain_pull_requests(activity, &keep))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_scope_is_match() {
+        let scope = PathScope::new(&["packages/frontend/**".to_string()]).unwrap();
+        assert!(scope.is_match("packages/frontend/src/app.ts"));
+        assert!(!scope.is_match("packages/backend/src/main.rs"));
+    }
+
+    #[test]
+    fn test_path_scope_rejects_invalid_glob() {
+        assert!(PathScope::new(&["packages/[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_pull_request_ref() {
+        assert_eq!(
+            pull_request_ref("https://github.com/owner/repo/pull/42"),
+            Some(("owner/repo".to_string(), 42))
+        );
+        assert_eq!(pull_request_ref("https://github.com/owner/repo"), None);
+    }
+}
@@ -0,0 +1,181 @@
+//! Sparkline module: per-repository weekly trends for the repository tables.
+//!
+//! Commit contributions only come as a total per repository, so the trend of a repository is
+//! built from the timestamps of the items listed in the report: issues and pull requests by
+//! when they were opened, reviews by when they were submitted.
+
+use crate::report::Report;
+use crate::revert::repository_of;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// Bars of a unicode sparkline, from no activity to the busiest week.
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Width of a week's bar in an SVG sparkline, in pixels.
+const SVG_BAR_WIDTH: usize = 4;
+
+/// Height of an SVG sparkline, in pixels.
+const SVG_HEIGHT: i64 = 16;
+
+/// Weekly item counts of each repository of a report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sparklines {
+    weeks: usize,
+    counts: HashMap<String, Vec<i64>>,
+}
+
+impl Sparklines {
+    /// Counts the items of `report` per repository and week, the first week starting at the
+    /// report's start date. Items outside the reported range are left out.
+    pub fn new(report: &Report) -> Self {
+        let days = (report.end_date - report.start_date).num_days().max(1);
+        let weeks = ((days + 6) / 7) as usize;
+        let mut counts: HashMap<String, Vec<i64>> = HashMap::new();
+        let mut count = |url: &str, at: &str| {
+            let (Some(repo), Ok(at)) = (repository_of(url), DateTime::parse_from_rfc3339(at))
+            else {
+                return;
+            };
+            let offset = at.with_timezone(&Utc) - report.start_date;
+            if offset < chrono::Duration::zero() {
+                return;
+            }
+            let week = (offset.num_days() / 7) as usize;
+            if week < weeks {
+                counts.entry(repo).or_insert_with(|| vec![0; weeks])[week] += 1;
+            }
+        };
+        if let Some(user) = &report.activity.user {
+            let cc = &user.contributions_collection;
+            for node in cc.issue_contributions.nodes.iter().flatten() {
+                count(&node.issue.url, &node.issue.created_at);
+            }
+            for node in cc.pull_request_contributions.nodes.iter().flatten() {
+                count(&node.pull_request.url, &node.pull_request.created_at);
+            }
+            for node in cc.pull_request_review_contributions.nodes.iter().flatten() {
+                count(
+                    &node.pull_request_review.pull_request.url,
+                    &node.occurred_at,
+                );
+            }
+        }
+        Self { weeks, counts }
+    }
+
+    /// The weekly item counts of `repo`, all zero when it has no listed items.
+    pub fn counts(&self, repo: &str) -> Vec<i64> {
+        self.counts
+            .get(repo)
+            .cloned()
+            .unwrap_or_else(|| vec![0; self.weeks])
+    }
+
+    /// The trend of `repo` as unicode block characters, one per week, e.g. `▁▃█▁`.
+    pub fn unicode(&self, repo: &str) -> String {
+        let counts = self.counts(repo);
+        let max = counts.iter().copied().max().unwrap_or(0);
+        counts
+            .iter()
+            .map(|&count| BARS[bar_level(count, max, BARS.len() as i64 - 1) as usize])
+            .collect()
+    }
+
+    /// The trend of `repo` as an inline SVG bar chart, one bar per week.
+    pub fn svg(&self, repo: &str) -> String {
+        let counts = self.counts(repo);
+        let max = counts.iter().copied().max().unwrap_or(0);
+        let bars: String = counts
+            .iter()
+            .enumerate()
+            .map(|(week, &count)| {
+                let height = bar_level(count, max, SVG_HEIGHT - 1) + 1;
+                format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"><title>{}</title></rect>",
+                    week * SVG_BAR_WIDTH,
+                    SVG_HEIGHT - height,
+                    SVG_BAR_WIDTH - 1,
+                    height,
+                    count
+                )
+            })
+            .collect();
+        format!(
+            "<svg class=\"sparkline\" width=\"{}\" height=\"{}\" role=\"img\" aria-label=\"Weekly activity\">{}</svg>",
+            counts.len() * SVG_BAR_WIDTH,
+            SVG_HEIGHT,
+            bars
+        )
+    }
+}
+
+/// The level, from 0 to `top`, of a week with `count` items when the busiest has `max`; any
+/// activity is at least level 1 so it stands out from an empty week.
+fn bar_level(count: i64, max: i64, top: i64) -> i64 {
+    if count <= 0 || max <= 0 {
+        0
+    } else {
+        (count * top + max - 1) / max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[test]
+    fn test_sparklines() {
+        let page = |nodes: serde_json::Value| json!({ "totalCount": 0, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes });
+        let issue = |repo: &str, created_at: &str| {
+            json!({ "issue": {
+                "id": "", "number": 1, "title": "Issue", "url": format!("https://github.com/{}/issues/1", repo),
+                "createdAt": created_at, "state": "OPEN", "closedAt": null
+            } })
+        };
+        let activity = serde_json::from_value(json!({ "user": { "contributionsCollection": {
+            "totalCommitContributions": 0,
+            "totalIssueContributions": 4,
+            "totalPullRequestContributions": 0,
+            "totalPullRequestReviewContributions": 1,
+            "contributionCalendar": { "totalContributions": 0, "weeks": [] },
+            "commitContributionsByRepository": [],
+            "issueContributions": page(json!([
+                issue("o/a", "2025-03-01T10:00:00Z"),
+                issue("o/a", "2025-03-16T10:00:00Z"),
+                issue("o/a", "2025-03-17T10:00:00Z"),
+                issue("o/a", "2025-04-30T10:00:00Z")
+            ])),
+            "pullRequestContributions": page(json!([])),
+            "pullRequestReviewContributions": page(json!([{
+                "pullRequestReview": { "pullRequest": { "number": 3, "title": "Other", "url": "https://github.com/o/b/pull/3" }, "state": "APPROVED" },
+                "occurredAt": "2025-03-09T00:00:00Z"
+            }]))
+        } } }))
+        .unwrap();
+        let report = Report::new(
+            "octocat".into(),
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 22, 0, 0, 0).unwrap(),
+            activity,
+        );
+
+        let sparklines = Sparklines::new(&report);
+        assert_eq!(sparklines.counts("o/a"), [1, 0, 2]);
+        assert_eq!(sparklines.counts("o/b"), [0, 1, 0]);
+        assert_eq!(sparklines.counts("o/c"), [0, 0, 0]);
+        assert_eq!(sparklines.unicode("o/a"), "▅▁█");
+        assert_eq!(sparklines.unicode("o/c"), "▁▁▁");
+
+        let svg = sparklines.svg("o/a");
+        assert!(svg.starts_with("<svg class=\"sparkline\" width=\"12\" height=\"16\""));
+        assert!(
+            svg.contains("<rect x=\"8\" y=\"0\" width=\"3\" height=\"16\"><title>2</title></rect>")
+        );
+        assert!(
+            svg.contains("<rect x=\"4\" y=\"15\" width=\"3\" height=\"1\"><title>0</title></rect>")
+        );
+    }
+}
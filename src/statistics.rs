@@ -0,0 +1,389 @@
+//! Statistics module: derives streaks, the busiest day, averages, and contribution ratios
+//! from the contribution calendar and the fetched contributions, for the `--stats` section.
+
+use crate::format::contribution_mix;
+use crate::github::user_activity::UserActivityUserContributionsCollection as ContributionsCollection;
+use crate::report::Report;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// The calendar period a forecast projects the totals to the end of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForecastPeriod {
+    /// The week, starting on Sunday like GitHub's calendar.
+    Week,
+    /// The calendar month.
+    Month,
+    /// The calendar quarter.
+    Quarter,
+    /// The calendar year.
+    Year,
+}
+
+impl FromStr for ForecastPeriod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "week" => Ok(Self::Week),
+            "month" => Ok(Self::Month),
+            "quarter" => Ok(Self::Quarter),
+            "year" => Ok(Self::Year),
+            _ => Err(format!(
+                "Invalid forecast period: {}. Use week, month, quarter, or year",
+                s
+            )),
+        }
+    }
+}
+
+impl ForecastPeriod {
+    /// The name of the period, e.g. `quarter`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Quarter => "quarter",
+            Self::Year => "year",
+        }
+    }
+
+    /// The first day after the period that `date` falls in.
+    fn next_start(&self, date: NaiveDate) -> NaiveDate {
+        let first_of = |year: i32, month: u32| {
+            let (year, month) = if month > 12 {
+                (year + 1, month - 12)
+            } else {
+                (year, month)
+            };
+            NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(date)
+        };
+        match self {
+            Self::Week => {
+                date + Duration::days(7 - i64::from(date.weekday().num_days_from_sunday()))
+            }
+            Self::Month => first_of(date.year(), date.month() + 1),
+            Self::Quarter => first_of(date.year(), (date.month() - 1) / 3 * 3 + 4),
+            Self::Year => first_of(date.year() + 1, 1),
+        }
+    }
+}
+
+/// Totals projected to the end of a calendar period at the report's run rate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Forecast {
+    /// The period projected to, e.g. `quarter`.
+    pub period: String,
+    /// The last day of the period.
+    pub through: String,
+    /// Projected commit contributions.
+    pub commits: i64,
+    /// Projected issue contributions.
+    pub issues: i64,
+    /// Projected pull request contributions.
+    pub pull_requests: i64,
+    /// Projected pull request review contributions.
+    pub reviews: i64,
+}
+
+impl Forecast {
+    /// Projects the totals of `cc`, made from `start` to `end`, to the end of the `period`
+    /// that `end` falls in, assuming the same daily rate for the rest of it. The projection is
+    /// of the report's whole range, so it reads best when the range starts with the period.
+    pub fn project(
+        cc: &ContributionsCollection,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        period: ForecastPeriod,
+    ) -> Self {
+        let period_end = period
+            .next_start(end.date_naive())
+            .and_hms_opt(0, 0, 0)
+            .map_or(end, |midnight| midnight.and_utc());
+        let (elapsed, total) = (
+            (end - start).num_seconds(),
+            (period_end - start).num_seconds(),
+        );
+        let project = |n: i64| {
+            if elapsed <= 0 {
+                n
+            } else {
+                (n as f64 * total as f64 / elapsed as f64).round() as i64
+            }
+        };
+        Self {
+            period: period.as_str().to_string(),
+            through: (period_end - Duration::days(1)).date_naive().to_string(),
+            commits: project(cc.total_commit_contributions),
+            issues: project(cc.total_issue_contributions),
+            pull_requests: project(cc.total_pull_request_contributions),
+            reviews: project(cc.total_pull_request_review_contributions),
+        }
+    }
+}
+
+/// The day with the most contributions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BusiestDay {
+    /// The date, e.g. `2025-03-04`.
+    pub date: String,
+    /// Contributions made that day.
+    pub contribution_count: i64,
+}
+
+/// A contribution type's share of all contributions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Share {
+    /// The contribution type, e.g. `pull requests`.
+    pub kind: String,
+    /// Its share in whole percent.
+    pub percent: i64,
+}
+
+/// Statistics of one report's activity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    /// Most consecutive days with contributions.
+    pub longest_streak: i64,
+    /// Consecutive days with contributions up to the end of the range; a last day without
+    /// contributions (yet) doesn't break the streak of the days before.
+    pub current_streak: i64,
+    /// The day with the most contributions, if any day had one.
+    pub busiest_day: Option<BusiestDay>,
+    /// Days of the calendar with at least one contribution.
+    pub active_days: i64,
+    /// Days covered by the calendar.
+    pub days: i64,
+    /// Contributions per calendar day.
+    pub average_per_day: f64,
+    /// Contributions per seven calendar days.
+    pub average_per_week: f64,
+    /// Each contribution type's share, in the order commits, issues, pull requests, reviews;
+    /// empty without contributions.
+    pub mix: Vec<Share>,
+    /// Share of the listed pull requests that were merged, in whole percent.
+    pub merge_rate: Option<i64>,
+    /// Reviews submitted per pull request opened.
+    pub reviews_per_pull_request: Option<f64>,
+    /// Totals projected to the end of a period, when requested with `--forecast`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forecast: Option<Forecast>,
+}
+
+impl Statistics {
+    /// The statistics of `report`, with a forecast to the end of `forecast` if given; `None`
+    /// for a report without user data.
+    pub fn from_report(report: &Report, forecast: Option<ForecastPeriod>) -> Option<Self> {
+        let cc = &report.activity.user.as_ref()?.contributions_collection;
+        Some(Self {
+            forecast: forecast
+                .map(|period| Forecast::project(cc, report.start_date, report.end_date, period)),
+            ..Self::from_contributions(cc)
+        })
+    }
+
+    /// The statistics of a contributions collection.
+    pub fn from_contributions(cc: &ContributionsCollection) -> Self {
+        let mut days: Vec<(NaiveDate, i64)> = cc
+            .contribution_calendar
+            .weeks
+            .iter()
+            .flat_map(|week| &week.contribution_days)
+            .filter_map(|day| {
+                let date = NaiveDate::parse_from_str(day.date.get(..10)?, "%Y-%m-%d").ok()?;
+                Some((date, day.contribution_count))
+            })
+            .collect();
+        // Calendars merged from several fetches may repeat the days where they meet.
+        days.sort_by_key(|&(date, _)| date);
+        days.dedup_by_key(|&mut (date, _)| date);
+
+        let mut statistics = Self {
+            days: days.len() as i64,
+            ..Default::default()
+        };
+        let mut streak = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for &(date, count) in &days {
+            let consecutive = previous.is_some_and(|p| p.succ_opt() == Some(date));
+            streak = match (count > 0, consecutive) {
+                (false, _) => 0,
+                (true, true) => streak + 1,
+                (true, false) => 1,
+            };
+            statistics.longest_streak = statistics.longest_streak.max(streak);
+            statistics.active_days += i64::from(count > 0);
+            if count > 0
+                && statistics
+                    .busiest_day
+                    .as_ref()
+                    .is_none_or(|busiest| count > busiest.contribution_count)
+            {
+                statistics.busiest_day = Some(BusiestDay {
+                    date: date.to_string(),
+                    contribution_count: count,
+                });
+            }
+            previous = Some(date);
+        }
+        statistics.current_streak = match days.as_slice() {
+            [.., (_, 0)] => current_streak(&days[..days.len() - 1]),
+            _ => current_streak(&days),
+        };
+
+        let total: i64 = days.iter().map(|&(_, count)| count).sum();
+        if !days.is_empty() {
+            statistics.average_per_day = total as f64 / days.len() as f64;
+            statistics.average_per_week = statistics.average_per_day * 7.0;
+        }
+        statistics.mix = contribution_mix(cc)
+            .map(|mix| {
+                mix.iter()
+                    .map(|&(kind, percent)| Share {
+                        kind: kind.to_string(),
+                        percent,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let prs: Vec<_> = cc
+            .pull_request_contributions
+            .nodes
+            .iter()
+            .flatten()
+            .collect();
+        if !prs.is_empty() {
+            let merged = prs.iter().filter(|node| node.pull_request.merged).count();
+            statistics.merge_rate = Some((merged * 100 / prs.len()) as i64);
+        }
+        if cc.total_pull_request_contributions > 0 {
+            statistics.reviews_per_pull_request = Some(
+                cc.total_pull_request_review_contributions as f64
+                    / cc.total_pull_request_contributions as f64,
+            );
+        }
+        statistics
+    }
+}
+
+/// Consecutive days with contributions at the end of `days`.
+fn current_streak(days: &[(NaiveDate, i64)]) -> i64 {
+    let mut streak = 0;
+    let mut next: Option<NaiveDate> = None;
+    for &(date, count) in days.iter().rev() {
+        if count <= 0 || next.is_some_and(|n| date.succ_opt() != Some(n)) {
+            break;
+        }
+        streak += 1;
+        next = Some(date);
+    }
+    streak
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn contributions(counts: &[i64], prs: &[bool]) -> ContributionsCollection {
+        let days: Vec<_> = counts
+            .iter()
+            .enumerate()
+            .map(|(i, count)| json!({ "date": format!("2025-03-{:02}", i + 1), "contributionCount": count, "weekday": 0 }))
+            .collect();
+        let nodes: Vec<_> = prs
+            .iter()
+            .enumerate()
+            .map(|(i, merged)| {
+                json!({ "pullRequest": {
+                "number": i, "title": "PR", "url": "https://github.com/octo/api/pull/1",
+                "createdAt": "2025-03-02T00:00:00Z", "state": "OPEN",
+                "merged": merged, "mergedAt": null, "closedAt": null,
+                "additions": 1, "deletions": 1, "changedFiles": 1
+            } })
+            })
+            .collect();
+        let empty = json!({ "totalCount": 0, "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": [] });
+        let total: i64 = counts.iter().sum();
+        serde_json::from_value(json!({
+            "totalCommitContributions": total - prs.len() as i64 - 3,
+            "totalIssueContributions": 0,
+            "totalPullRequestContributions": prs.len(),
+            "totalPullRequestReviewContributions": 3,
+            "contributionCalendar": { "totalContributions": total, "weeks": [{ "contributionDays": days }] },
+            "commitContributionsByRepository": [],
+            "issueContributions": empty,
+            "pullRequestContributions": { "totalCount": prs.len(), "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes },
+            "pullRequestReviewContributions": empty
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_statistics() {
+        let statistics = Statistics::from_contributions(&contributions(
+            &[1, 2, 0, 5, 1, 1, 3, 0],
+            &[true, false],
+        ));
+        assert_eq!(statistics.longest_streak, 4);
+        assert_eq!(statistics.current_streak, 4);
+        assert_eq!(
+            statistics.busiest_day,
+            Some(BusiestDay {
+                date: "2025-03-04".into(),
+                contribution_count: 5
+            })
+        );
+        assert_eq!((statistics.active_days, statistics.days), (6, 8));
+        assert_eq!(statistics.average_per_day, 13.0 / 8.0);
+        assert_eq!(statistics.average_per_week, 13.0 * 7.0 / 8.0);
+        assert_eq!(
+            statistics.mix.iter().map(|s| s.percent).collect::<Vec<_>>(),
+            [62, 0, 15, 23]
+        );
+        assert_eq!(statistics.merge_rate, Some(50));
+        assert_eq!(statistics.reviews_per_pull_request, Some(1.5));
+    }
+
+    #[test]
+    fn test_forecast() {
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        let cc = contributions(&[10], &[true, true, false, true, false]);
+        // 30 days into a 90-day quarter.
+        let forecast = Forecast::project(
+            &cc,
+            at("2025-01-01T00:00:00Z"),
+            at("2025-01-31T00:00:00Z"),
+            ForecastPeriod::Quarter,
+        );
+        assert_eq!(forecast.through, "2025-03-31");
+        assert_eq!((forecast.pull_requests, forecast.commits), (15, 6));
+        assert_eq!(forecast.reviews, 9);
+
+        let next = |period: ForecastPeriod, date: &str| {
+            period.next_start(date.parse().unwrap()).to_string()
+        };
+        assert_eq!(next(ForecastPeriod::Week, "2025-03-05"), "2025-03-09");
+        assert_eq!(next(ForecastPeriod::Week, "2025-03-09"), "2025-03-16");
+        assert_eq!(next(ForecastPeriod::Month, "2025-12-15"), "2026-01-01");
+        assert_eq!(next(ForecastPeriod::Quarter, "2025-11-02"), "2026-01-01");
+        assert_eq!(next(ForecastPeriod::Year, "2025-06-01"), "2026-01-01");
+        assert_eq!("Quarter".parse(), Ok(ForecastPeriod::Quarter));
+        assert!("decade".parse::<ForecastPeriod>().is_err());
+    }
+
+    #[test]
+    fn test_current_streak_ends_at_the_last_active_day() {
+        let statistics = Statistics::from_contributions(&contributions(&[3, 0, 0], &[]));
+        assert_eq!(statistics.longest_streak, 1);
+        assert_eq!(statistics.current_streak, 0);
+        assert_eq!(statistics.merge_rate, None);
+        assert_eq!(statistics.reviews_per_pull_request, None);
+
+        let empty = Statistics::from_contributions(&contributions(&[], &[]));
+        assert_eq!(empty.busiest_day, None);
+        assert_eq!(empty.average_per_day, 0.0);
+    }
+}
@@ -0,0 +1,264 @@
+//! Storage module: where the activity cache, the reports of previous `--since-last-run` runs,
+//! and backfill progress are kept.
+//!
+//! Each of them is a namespace of JSON documents in a [`StorageBackend`]. By default that's a
+//! SQLite database in the user's state directory; builds with the `postgres` feature can use
+//! `--store postgres://…` instead, so several instances, e.g. `--watch` daemons on different
+//! hosts, share one central store.
+
+use crate::last_run::state_dir;
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A shared handle to a storage backend.
+pub type Storage = Arc<dyn StorageBackend>;
+
+/// How long a SQLite store waits for another process holding its lock.
+const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A key-value store of JSON documents, grouped in namespaces.
+pub trait StorageBackend: Send + Sync {
+    /// The document stored under `key` in `namespace`, if there is one.
+    fn get(&self, namespace: &str, key: &str) -> Result<Option<String>>;
+
+    /// Stores `value` under `key` in `namespace`, replacing any previous document.
+    fn put(&self, namespace: &str, key: &str, value: &str) -> Result<()>;
+
+    /// Deletes every document in `namespace`, returning how many were removed.
+    fn clear(&self, namespace: &str) -> Result<usize>;
+}
+
+/// Opens the store at `url`: a `postgres://` URL, a SQLite database path (optionally prefixed
+/// with `sqlite:`), or, without one, `store.sqlite3` in the user's state directory if there is
+/// one.
+pub fn open(url: Option<&str>) -> Result<Option<Storage>> {
+    let Some(url) = url else {
+        return match state_dir() {
+            Some(dir) => Ok(Some(Arc::new(SqliteBackend::open(
+                &dir.join("store.sqlite3"),
+            )?))),
+            None => Ok(None),
+        };
+    };
+    if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        #[cfg(feature = "postgres")]
+        return Ok(Some(Arc::new(PostgresBackend::connect(url)?)));
+        #[cfg(not(feature = "postgres"))]
+        anyhow::bail!(
+            "--store {} needs a build with the postgres feature: cargo install --path . --features postgres",
+            url
+        );
+    }
+    let path = url.strip_prefix("sqlite:").unwrap_or(url);
+    Ok(Some(Arc::new(SqliteBackend::open(Path::new(path))?)))
+}
+
+/// A store in a SQLite database file.
+pub struct SqliteBackend {
+    connection: Mutex<rusqlite::Connection>,
+}
+
+impl SqliteBackend {
+    /// Opens the database at `path`, creating it and its directory when missing.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create state directory {:?}", dir))?;
+        }
+        let connection = rusqlite::Connection::open(path)
+            .with_context(|| format!("Failed to open store {:?}", path))?;
+        Self::new(connection).with_context(|| format!("Failed to set up store {:?}", path))
+    }
+
+    /// A store that only lives as long as it's open.
+    #[cfg(test)]
+    pub fn in_memory() -> Result<Self> {
+        Self::new(rusqlite::Connection::open_in_memory()?)
+    }
+
+    fn new(connection: rusqlite::Connection) -> Result<Self> {
+        connection.busy_timeout(SQLITE_BUSY_TIMEOUT)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS github_activity_entries (
+                namespace TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                PRIMARY KEY (namespace, key)
+            )",
+        )?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> std::sync::MutexGuard<'_, rusqlite::Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl StorageBackend for SqliteBackend {
+    fn get(&self, namespace: &str, key: &str) -> Result<Option<String>> {
+        use rusqlite::OptionalExtension;
+        self.connection()
+            .query_row(
+                "SELECT value FROM github_activity_entries WHERE namespace = ?1 AND key = ?2",
+                (namespace, key),
+                |row| row.get(0),
+            )
+            .optional()
+            .with_context(|| format!("Failed to read {} {} from the store", namespace, key))
+    }
+
+    fn put(&self, namespace: &str, key: &str, value: &str) -> Result<()> {
+        self.connection()
+            .execute(
+                "INSERT INTO github_activity_entries (namespace, key, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (namespace, key) DO UPDATE SET value = excluded.value",
+                (namespace, key, value),
+            )
+            .with_context(|| format!("Failed to write {} {} to the store", namespace, key))?;
+        Ok(())
+    }
+
+    fn clear(&self, namespace: &str) -> Result<usize> {
+        self.connection()
+            .execute(
+                "DELETE FROM github_activity_entries WHERE namespace = ?1",
+                (namespace,),
+            )
+            .with_context(|| format!("Failed to clear {} in the store", namespace))
+    }
+}
+
+/// A store in a table of a PostgreSQL database, shared by every instance pointed at it.
+#[cfg(feature = "postgres")]
+pub struct PostgresBackend {
+    client: Mutex<postgres::Client>,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresBackend {
+    /// Connects to the database at `url`, creating the table when missing.
+    ///
+    /// TCP connections follow the URL's `sslmode`, verifying the server's certificate against
+    /// the system's roots: `prefer`, the default, encrypts when the server supports it,
+    /// `require` refuses a server that doesn't, and `disable` never encrypts.
+    pub fn connect(url: &str) -> Result<Self> {
+        let tls = native_tls::TlsConnector::new().context("Failed to set up TLS")?;
+        let tls = postgres_native_tls::MakeTlsConnector::new(tls);
+        let mut client = blocking(|| postgres::Client::connect(url, tls))
+            .context("Failed to connect to the PostgreSQL store")?;
+        blocking(|| {
+            client.batch_execute(
+                "CREATE TABLE IF NOT EXISTS github_activity_entries (
+                    namespace TEXT NOT NULL,
+                    key TEXT NOT NULL,
+                    value TEXT NOT NULL,
+                    PRIMARY KEY (namespace, key)
+                )",
+            )
+        })
+        .context("Failed to set up the PostgreSQL store")?;
+        Ok(Self {
+            client: Mutex::new(client),
+        })
+    }
+
+    fn client(&self) -> std::sync::MutexGuard<'_, postgres::Client> {
+        self.client
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl StorageBackend for PostgresBackend {
+    fn get(&self, namespace: &str, key: &str) -> Result<Option<String>> {
+        let row = blocking(|| {
+            self.client().query_opt(
+                "SELECT value FROM github_activity_entries WHERE namespace = $1 AND key = $2",
+                &[&namespace, &key],
+            )
+        })
+        .with_context(|| format!("Failed to read {} {} from the store", namespace, key))?;
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    fn put(&self, namespace: &str, key: &str, value: &str) -> Result<()> {
+        blocking(|| {
+            self.client().execute(
+                "INSERT INTO github_activity_entries (namespace, key, value) VALUES ($1, $2, $3)
+                 ON CONFLICT (namespace, key) DO UPDATE SET value = excluded.value",
+                &[&namespace, &key, &value],
+            )
+        })
+        .with_context(|| format!("Failed to write {} {} to the store", namespace, key))?;
+        Ok(())
+    }
+
+    fn clear(&self, namespace: &str) -> Result<usize> {
+        let removed = blocking(|| {
+            self.client().execute(
+                "DELETE FROM github_activity_entries WHERE namespace = $1",
+                &[&namespace],
+            )
+        })
+        .with_context(|| format!("Failed to clear {} in the store", namespace))?;
+        Ok(removed as usize)
+    }
+}
+
+/// Runs `f`, which blocks on its own runtime, without stalling the async runtime it's called
+/// from.
+#[cfg(feature = "postgres")]
+fn blocking<T>(f: impl FnOnce() -> T) -> T {
+    match tokio::runtime::Handle::try_current() {
+        Ok(_) => tokio::task::block_in_place(f),
+        Err(_) => f(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlite_namespaces() {
+        let store = SqliteBackend::in_memory().unwrap();
+        assert_eq!(store.get("activity", "a").unwrap(), None);
+        store.put("activity", "a", "1").unwrap();
+        store.put("activity", "a", "2").unwrap();
+        store.put("activity", "b", "3").unwrap();
+        store.put("last-run", "a", "4").unwrap();
+        assert_eq!(store.get("activity", "a").unwrap().as_deref(), Some("2"));
+        assert_eq!(store.get("last-run", "a").unwrap().as_deref(), Some("4"));
+
+        assert_eq!(store.clear("activity").unwrap(), 2);
+        assert_eq!(store.get("activity", "b").unwrap(), None);
+        assert_eq!(store.get("last-run", "a").unwrap().as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn test_open_sqlite_path() {
+        let path = std::env::temp_dir().join(format!(
+            "github-activity-store-{}/store.sqlite3",
+            std::process::id()
+        ));
+        let url = format!("sqlite:{}", path.display());
+        open(Some(&url))
+            .unwrap()
+            .unwrap()
+            .put("backfill", "a", "{}")
+            .unwrap();
+        let reopened = open(Some(path.to_str().unwrap())).unwrap().unwrap();
+        assert_eq!(
+            reopened.get("backfill", "a").unwrap().as_deref(),
+            Some("{}")
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,273 @@
+//! Team module: parses the TOML team spec of the `newsletter` and `aggregate-anon`
+//! subcommands.
+//!
+//! ```toml
+//! title = "Friday Update"
+//! team = "example/platform"          # or: members = ["octocat", "hubot"]
+//! period = "7d"
+//! org = "example"
+//! sections = ["highlights", "totals"]
+//! top-repositories = 10
+//! output = "newsletter.md"
+//! ```
+//!
+//! It also writes the index of the per-team reports of `--output-dir`.
+
+use crate::args::{GitHubUsername, parse_datetime, parse_period, resolve_date_range};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Duration, Utc};
+use github_activity_rs::newsletter::{DEFAULT_SECTIONS, Newsletter, Section};
+use github_activity_rs::report::Report;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A team spec as written in the spec file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TeamSpec {
+    /// Title of the newsletter.
+    pub title: Option<String>,
+    /// GitHub team whose members are included, as `org/team-slug`.
+    pub team: Option<String>,
+    /// GitHub usernames included, in addition to the team's members.
+    #[serde(default)]
+    pub members: Vec<String>,
+    /// Time period (e.g., 7d, 2w, 1m); mutually exclusive with `from`/`to`.
+    pub period: Option<String>,
+    /// Start date in ISO 8601 format.
+    pub from: Option<String>,
+    /// End date in ISO 8601 format.
+    pub to: Option<String>,
+    /// Optional organization filter of the contributions.
+    pub org: Option<String>,
+    /// Sections of the newsletter, in order.
+    pub sections: Option<Vec<String>>,
+    /// How many repositories the top repositories section lists.
+    pub top_repositories: Option<usize>,
+    /// Path the newsletter is written to; printed when omitted.
+    pub output: Option<PathBuf>,
+}
+
+/// A validated team spec, ready to be fetched.
+#[derive(Debug)]
+pub struct Team {
+    /// Title of the newsletter.
+    pub title: String,
+    /// GitHub team whose members are included, as `org/team-slug`.
+    pub team: Option<String>,
+    /// GitHub usernames included, in addition to the team's members.
+    pub members: Vec<GitHubUsername>,
+    /// Length of the reported time range, when given as a period.
+    pub period: Option<Duration>,
+    /// Start of the reported time range.
+    pub start_date: DateTime<Utc>,
+    /// End of the reported time range.
+    pub end_date: DateTime<Utc>,
+    /// Optional organization filter.
+    pub org: Option<String>,
+    /// Sections of the newsletter, in order.
+    pub sections: Vec<Section>,
+    /// How many repositories the top repositories section lists.
+    pub top_repositories: usize,
+    /// Path the newsletter is written to, if any.
+    pub output: Option<PathBuf>,
+}
+
+impl TeamSpec {
+    /// Validates the spec using the same rules as the command-line arguments.
+    pub fn resolve(&self) -> Result<Team> {
+        if self.team.is_none() && self.members.is_empty() {
+            bail!("Either specify team or members");
+        }
+        if let Some(team) = &self.team
+            && !team.contains('/')
+        {
+            bail!("Team must be in the format org/team-slug: {}", team);
+        }
+        let members = self
+            .members
+            .iter()
+            .map(|member| member.parse::<GitHubUsername>())
+            .collect::<Result<_, _>>()
+            .map_err(anyhow::Error::msg)?;
+        let period = self
+            .period
+            .as_deref()
+            .map(parse_period)
+            .transpose()
+            .map_err(anyhow::Error::msg)?;
+        let from = self
+            .from
+            .as_deref()
+            .map(parse_datetime)
+            .transpose()
+            .map_err(anyhow::Error::msg)?;
+        let to = self
+            .to
+            .as_deref()
+            .map(parse_datetime)
+            .transpose()
+            .map_err(anyhow::Error::msg)?;
+        let (start_date, end_date) = resolve_date_range(period, from, to)
+            .map_err(|_| anyhow::anyhow!("Either specify period or both from and to"))?;
+        let sections = match &self.sections {
+            Some(sections) => sections
+                .iter()
+                .map(|section| section.parse())
+                .collect::<Result<_, _>>()
+                .map_err(anyhow::Error::msg)?,
+            None => DEFAULT_SECTIONS.to_vec(),
+        };
+
+        Ok(Team {
+            title: self
+                .title
+                .clone()
+                .unwrap_or_else(|| Newsletter::default().title),
+            team: self.team.clone(),
+            members,
+            period,
+            start_date,
+            end_date,
+            org: self.org.clone(),
+            sections,
+            top_repositories: self
+                .top_repositories
+                .unwrap_or(Newsletter::default().top_repositories),
+            output: self.output.clone(),
+        })
+    }
+}
+
+/// Reads and validates a team spec file.
+pub fn load_spec(path: &Path) -> Result<Team> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read team spec from {:?}", path))?;
+    parse_spec(&contents).with_context(|| format!("Invalid team spec in {:?}", path))
+}
+
+/// Parses and validates the contents of a team spec file.
+fn parse_spec(contents: &str) -> Result<Team> {
+    let spec: TeamSpec = toml::from_str(contents).context("Failed to parse TOML")?;
+    spec.resolve()
+}
+
+/// The report file of one team written with `--output-dir`.
+pub struct TeamFile {
+    /// The team's name in the config.
+    pub name: String,
+    /// The file name, relative to the output directory.
+    pub file: String,
+    /// The reports of the team's members.
+    pub reports: Vec<Report>,
+}
+
+/// The Markdown index of the team reports: each team's members and total contributions,
+/// linking its file.
+pub fn team_index(teams: &[TeamFile]) -> String {
+    let mut output = String::from("# Activity by Team\n\n");
+    if let Some(report) = teams.iter().flat_map(|team| &team.reports).next() {
+        output.push_str(&format!(
+            "**Period:** {} to {}\n\n",
+            report.start_date.format("%Y-%m-%d"),
+            report.end_date.format("%Y-%m-%d")
+        ));
+    }
+    output.push_str("| Team | Members | Contributions | Report |\n");
+    output.push_str("|------|---------|---------------|--------|\n");
+    for team in teams {
+        let members: Vec<&str> = team
+            .reports
+            .iter()
+            .map(|report| report.username.as_str())
+            .collect();
+        let contributions: i64 = team
+            .reports
+            .iter()
+            .filter_map(|report| report.activity.user.as_ref())
+            .map(|user| {
+                user.contributions_collection
+                    .contribution_calendar
+                    .total_contributions
+            })
+            .sum();
+        output.push_str(&format!(
+            "| {} | {} | {} | [{}]({}) |\n",
+            team.name,
+            members.join(", "),
+            contributions,
+            team.file,
+            team.file
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec_valid() {
+        let team = parse_spec(
+            r#"
+            title = "Friday Update"
+            team = "example/platform"
+            members = ["octocat"]
+            period = "7d"
+            sections = ["highlights", "top-repositories"]
+            top-repositories = 3
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(team.title, "Friday Update");
+        assert_eq!(team.team.as_deref(), Some("example/platform"));
+        assert_eq!(team.members[0].0, "octocat");
+        assert_eq!((team.end_date - team.start_date).num_days(), 7);
+        assert_eq!(
+            team.sections,
+            [Section::Highlights, Section::TopRepositories]
+        );
+        assert_eq!(team.top_repositories, 3);
+        assert_eq!(team.output, None);
+    }
+
+    #[test]
+    fn test_parse_spec_defaults_and_errors() {
+        let team = parse_spec("members = [\"octocat\"]\nperiod = \"1w\"").unwrap();
+        assert_eq!(team.sections, DEFAULT_SECTIONS);
+        assert_eq!(team.title, "Team Newsletter");
+
+        assert!(parse_spec("period = \"7d\"").is_err());
+        assert!(parse_spec("members = [\"octocat\"]").is_err());
+        assert!(parse_spec("team = \"platform\"\nperiod = \"7d\"").is_err());
+        let err = parse_spec("members = [\"octocat\"]\nperiod = \"7d\"\nsections = [\"weather\"]")
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid newsletter section"));
+    }
+
+    #[test]
+    fn test_team_index() {
+        let start = "2025-03-01T00:00:00Z".parse().unwrap();
+        let end = "2025-03-08T00:00:00Z".parse().unwrap();
+        let report =
+            |username: &str| Report::new(username.to_string(), start, end, Default::default());
+        let index = team_index(&[
+            TeamFile {
+                name: "platform".into(),
+                file: "platform.md".into(),
+                reports: vec![report("octocat"), report("hubot")],
+            },
+            TeamFile {
+                name: "web".into(),
+                file: "web.md".into(),
+                reports: vec![report("monalisa")],
+            },
+        ]);
+        assert!(index.contains("**Period:** 2025-03-01 to 2025-03-08"));
+        assert!(index.contains("| platform | octocat, hubot | 0 | [platform.md](platform.md) |"));
+        assert!(index.contains("| web | monalisa | 0 | [web.md](web.md) |"));
+    }
+}
@@ -0,0 +1,303 @@
+//! Text module: normalizes user-written text, such as issue and pull request titles, before
+//! formatters lay it out.
+//!
+//! Titles can contain control characters, right-to-left scripts, and emoji. Control
+//! characters and stray bidi formatting characters are stripped, so they can't end a table
+//! row or flip the direction of the rest of a line; right-to-left text is wrapped in a bidi
+//! isolate; and long titles are cut at a grapheme boundary by display width, so emoji and
+//! accented letters aren't split and wide characters don't push columns out of line.
+//!
+//! Grapheme clusters and display widths are approximated from the character ranges that
+//! matter for titles (combining marks, emoji sequences and flags, East Asian wide
+//! characters) rather than the full Unicode tables.
+
+/// Display width titles are truncated to.
+pub const MAX_TITLE_WIDTH: usize = 100;
+
+/// Appended to truncated text.
+const ELLIPSIS: char = '…';
+
+/// First strong isolate: starts text whose direction is taken from its first strong
+/// character.
+const FSI: char = '\u{2068}';
+
+/// Pop directional isolate: ends an isolate started with [`FSI`].
+const PDI: char = '\u{2069}';
+
+/// Zero width joiner, which joins the characters around it into one emoji.
+const ZWJ: char = '\u{200D}';
+
+/// A title ready to be laid out: sanitized, truncated to [`MAX_TITLE_WIDTH`], and isolated
+/// when it contains right-to-left text.
+pub fn title(text: &str) -> String {
+    isolate(&truncate(&sanitize(text), MAX_TITLE_WIDTH))
+}
+
+/// Strips control characters and bidi formatting characters; line breaks and tabs become
+/// spaces.
+pub fn sanitize(text: &str) -> String {
+    let mut sanitized = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' | '\t' => sanitized.push(' '),
+            c if c.is_control() || is_bidi_control(c) || c == '\u{FEFF}' => {}
+            c => sanitized.push(c),
+        }
+    }
+    sanitized
+}
+
+/// Wraps `text` in a first strong isolate when it contains right-to-left characters, so it
+/// doesn't reorder the text around it.
+pub fn isolate(text: &str) -> String {
+    if text.chars().any(is_right_to_left) {
+        format!("{}{}{}", FSI, text, PDI)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Cuts `text` to at most `max_width` columns at a grapheme boundary, ending it with `…`
+/// when anything was cut.
+pub fn truncate(text: &str, max_width: usize) -> String {
+    if width(text) <= max_width {
+        return text.to_string();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for grapheme in graphemes(text) {
+        let grapheme_width = grapheme_width(grapheme);
+        if used + grapheme_width + 1 > max_width {
+            break;
+        }
+        truncated.push_str(grapheme);
+        used += grapheme_width;
+    }
+    truncated.truncate(truncated.trim_end().len());
+    truncated.push(ELLIPSIS);
+    truncated
+}
+
+/// Pads `text` with spaces to `width` columns.
+pub fn pad(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(self::width(text));
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+/// The display width of `text` in a monospace terminal.
+pub fn width(text: &str) -> usize {
+    graphemes(text).into_iter().map(grapheme_width).sum()
+}
+
+/// The grapheme clusters of `text`: a character with the combining marks, variation
+/// selectors, and emoji modifiers following it, emoji joined with zero width joiners, and
+/// flags made of two regional indicators.
+pub fn graphemes(text: &str) -> Vec<&str> {
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    let mut previous: Option<char> = None;
+    let mut regional_indicators = 0;
+    for (i, c) in text.char_indices() {
+        let extends = match previous {
+            None => false,
+            Some(ZWJ) => true,
+            Some(p) if is_regional_indicator(p) && is_regional_indicator(c) => {
+                regional_indicators % 2 == 1
+            }
+            Some(_) => is_extender(c) || c == ZWJ,
+        };
+        if !extends && i > 0 {
+            clusters.push(&text[start..i]);
+            start = i;
+        }
+        regional_indicators = if is_regional_indicator(c) {
+            regional_indicators + 1
+        } else {
+            0
+        };
+        previous = Some(c);
+    }
+    if start < text.len() {
+        clusters.push(&text[start..]);
+    }
+    clusters
+}
+
+/// The display width of a grapheme cluster: two columns for wide characters, emoji
+/// presentation, and flags; none for a lone combining mark.
+fn grapheme_width(grapheme: &str) -> usize {
+    let mut chars = grapheme.chars();
+    let Some(first) = chars.next() else {
+        return 0;
+    };
+    if is_extender(first) || first == ZWJ {
+        0
+    } else if is_wide(first)
+        || is_regional_indicator(first)
+        || grapheme.contains('\u{FE0F}')
+        || grapheme.contains(ZWJ)
+    {
+        2
+    } else {
+        1
+    }
+}
+
+/// Whether `c` attaches to the character before it: combining marks, variation selectors,
+/// emoji modifiers, and tag characters.
+fn is_extender(c: char) -> bool {
+    matches!(c as u32,
+        0x0300..=0x036F
+        | 0x0483..=0x0489
+        | 0x0591..=0x05BD
+        | 0x05BF
+        | 0x05C1..=0x05C2
+        | 0x05C4..=0x05C5
+        | 0x05C7
+        | 0x0610..=0x061A
+        | 0x064B..=0x065F
+        | 0x0670
+        | 0x06D6..=0x06DC
+        | 0x06DF..=0x06E4
+        | 0x06E7..=0x06E8
+        | 0x06EA..=0x06ED
+        | 0x0900..=0x0903
+        | 0x093A..=0x094F
+        | 0x0E31
+        | 0x0E34..=0x0E3A
+        | 0x0E47..=0x0E4E
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200C
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F
+        | 0x1F3FB..=0x1F3FF
+        | 0xE0020..=0xE007F
+        | 0xE0100..=0xE01EF
+    )
+}
+
+/// Whether `c` takes two columns: East Asian wide and fullwidth characters, and emoji shown
+/// as pictures by default.
+fn is_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115F
+        | 0x231A..=0x231B
+        | 0x2329..=0x232A
+        | 0x23E9..=0x23EC
+        | 0x23F0
+        | 0x23F3
+        | 0x25FD..=0x25FE
+        | 0x2614..=0x2615
+        | 0x2648..=0x2653
+        | 0x267F
+        | 0x2693
+        | 0x26A1
+        | 0x26AA..=0x26AB
+        | 0x26BD..=0x26BE
+        | 0x26C4..=0x26C5
+        | 0x26CE
+        | 0x26D4
+        | 0x26EA
+        | 0x26F2..=0x26F3
+        | 0x26F5
+        | 0x26FA
+        | 0x26FD
+        | 0x2705
+        | 0x270A..=0x270B
+        | 0x2728
+        | 0x274C
+        | 0x274E
+        | 0x2753..=0x2755
+        | 0x2757
+        | 0x2795..=0x2797
+        | 0x27B0
+        | 0x27BF
+        | 0x2B1B..=0x2B1C
+        | 0x2B50
+        | 0x2B55
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F004
+        | 0x1F0CF
+        | 0x1F18E
+        | 0x1F191..=0x1F19A
+        | 0x1F200..=0x1F251
+        | 0x1F300..=0x1F64F
+        | 0x1F680..=0x1F6FF
+        | 0x1F7E0..=0x1F7EB
+        | 0x1F90C..=0x1F9FF
+        | 0x1FA70..=0x1FAFF
+        | 0x20000..=0x3FFFD
+    )
+}
+
+/// Whether `c` is a regional indicator, two of which make a flag.
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+/// Whether `c` belongs to a right-to-left script, such as Hebrew or Arabic.
+fn is_right_to_left(c: char) -> bool {
+    matches!(c as u32,
+        0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF
+    )
+}
+
+/// Whether `c` is a bidi formatting character: marks, embeddings, overrides, and isolates.
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(sanitize("Fix\r\nbug\tnow\u{7}"), "Fix bug now");
+        assert_eq!(sanitize("abc\u{202E}fed\u{2066}"), "abcfed");
+    }
+
+    #[test]
+    fn test_graphemes_and_width() {
+        let family = "👨\u{200D}👩\u{200D}👧";
+        let text = format!("e\u{301}{}🇩🇪👍🏽日本", family);
+        assert_eq!(
+            graphemes(&text),
+            ["e\u{301}", family, "🇩🇪", "👍🏽", "日", "本"]
+        );
+        assert_eq!(width(&text), 1 + 2 + 2 + 2 + 2 + 2);
+        assert_eq!(graphemes("🇩🇪🇫🇷").len(), 2);
+        assert_eq!(pad("日本", 6), "日本  ");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("Fix the parser", 8), "Fix the…");
+        assert_eq!(truncate("ab👨\u{200D}👩\u{200D}👧cd", 4), "ab…");
+        assert_eq!(truncate("日本語のタイトル", 7), "日本語…");
+        assert_eq!(truncate("cafe\u{301} au lait", 5), "cafe\u{301}…");
+    }
+
+    #[test]
+    fn test_title() {
+        assert_eq!(title("Fix bug"), "Fix bug");
+        assert_eq!(title("תיקון באג\u{202C}"), "\u{2068}תיקון באג\u{2069}");
+        let long = title(&"very ".repeat(40));
+        assert_eq!(width(&long), MAX_TITLE_WIDTH);
+        assert!(long.ends_with('…'));
+    }
+}
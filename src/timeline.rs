@@ -0,0 +1,200 @@
+//! Timeline module: turns the timeline events of the user's issues into a short per-issue
+//! history, so detailed reports show what happened to an issue and not only that it was opened.
+
+use crate::github::issue_timelines::{
+    IssueTimelinesNodesOnIssue as Issue,
+    IssueTimelinesNodesOnIssueTimelineItemsNodes as TimelineItem,
+    IssueTimelinesNodesOnIssueTimelineItemsNodesOnAssignedEventAssignee as Assignee,
+    IssueTimelinesNodesOnIssueTimelineItemsNodesOnCrossReferencedEventSource as Source,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Issue histories keyed by issue URL.
+pub type IssueHistories = BTreeMap<String, Vec<TimelineEvent>>;
+
+/// The kind of an issue timeline event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEventKind {
+    /// A label was applied.
+    Labeled,
+    /// Someone was assigned.
+    Assigned,
+    /// The issue was closed.
+    Closed,
+    /// The issue was reopened.
+    Reopened,
+    /// The issue was referenced from another issue or pull request.
+    CrossReferenced,
+}
+
+/// A single event in an issue's history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    /// What happened.
+    pub kind: TimelineEventKind,
+    /// Login of the user who caused the event, if known.
+    pub actor: Option<String>,
+    /// When the event happened.
+    pub created_at: String,
+    /// Extra detail: the label, the assignee, or the referencing URL.
+    pub detail: Option<String>,
+}
+
+impl TimelineEvent {
+    /// Short human-readable description, e.g. "labeled bug by octocat on 2025-03-01".
+    pub fn describe(&self) -> String {
+        let mut description = match self.kind {
+            TimelineEventKind::Labeled => "labeled",
+            TimelineEventKind::Assigned => "assigned",
+            TimelineEventKind::Closed => "closed",
+            TimelineEventKind::Reopened => "reopened",
+            TimelineEventKind::CrossReferenced => "referenced in",
+        }
+        .to_string();
+        if let Some(detail) = &self.detail {
+            description.push(' ');
+            description.push_str(detail);
+        }
+        if let Some(actor) = &self.actor {
+            description.push_str(" by ");
+            description.push_str(actor);
+        }
+        let date = self.created_at.get(..10).unwrap_or(&self.created_at);
+        format!("{} on {}", description, date)
+    }
+}
+
+/// Joins the events of one issue into a single history line.
+pub fn history_line(events: &[TimelineEvent]) -> String {
+    events
+        .iter()
+        .map(TimelineEvent::describe)
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+/// Builds the history of each issue from its fetched timeline, in chronological order.
+/// Issues without any of the selected events are left out.
+pub fn histories_from_issues(issues: &[Issue]) -> IssueHistories {
+    let mut histories = IssueHistories::new();
+    for issue in issues {
+        let mut events: Vec<TimelineEvent> = issue
+            .timeline_items
+            .nodes
+            .iter()
+            .flatten()
+            .flatten()
+            .filter_map(event_from_item)
+            .collect();
+        if events.is_empty() {
+            continue;
+        }
+        events.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        histories.insert(issue.url.clone(), events);
+    }
+    histories
+}
+
+fn event_from_item(item: &TimelineItem) -> Option<TimelineEvent> {
+    let event = |kind, actor: Option<&str>, created_at: &str, detail: Option<String>| {
+        Some(TimelineEvent {
+            kind,
+            actor: actor.map(str::to_string),
+            created_at: created_at.to_string(),
+            detail,
+        })
+    };
+    match item {
+        TimelineItem::LabeledEvent(e) => event(
+            TimelineEventKind::Labeled,
+            e.actor.as_ref().map(|a| a.login.as_str()),
+            &e.created_at,
+            Some(e.label.name.clone()),
+        ),
+        TimelineItem::AssignedEvent(e) => event(
+            TimelineEventKind::Assigned,
+            e.actor.as_ref().map(|a| a.login.as_str()),
+            &e.created_at,
+            e.assignee.as_ref().map(|assignee| match assignee {
+                Assignee::User(user) => user.login.clone(),
+                Assignee::Bot(bot) => bot.login.clone(),
+            }),
+        ),
+        TimelineItem::ClosedEvent(e) => event(
+            TimelineEventKind::Closed,
+            e.actor.as_ref().map(|a| a.login.as_str()),
+            &e.created_at,
+            None,
+        ),
+        TimelineItem::ReopenedEvent(e) => event(
+            TimelineEventKind::Reopened,
+            e.actor.as_ref().map(|a| a.login.as_str()),
+            &e.created_at,
+            None,
+        ),
+        TimelineItem::CrossReferencedEvent(e) => event(
+            TimelineEventKind::CrossReferenced,
+            e.actor.as_ref().map(|a| a.login.as_str()),
+            &e.created_at,
+            Some(match &e.source {
+                Source::Issue(source) => source.url.clone(),
+                Source::PullRequest(source) => source.url.clone(),
+            }),
+        ),
+        // Event types not requested by the timeline query.
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn actor(login: &str) -> serde_json::Value {
+        json!({ "__typename": "User", "login": login })
+    }
+
+    #[test]
+    fn test_histories_from_issues() {
+        let issues: Vec<Issue> = serde_json::from_value(json!([
+            {
+                "url": "http://example.com/issues/1",
+                "timelineItems": { "nodes": [
+                    { "__typename": "ClosedEvent", "actor": actor("maintainer"), "createdAt": "2025-03-05T00:00:00Z" },
+                    { "__typename": "LabeledEvent", "actor": actor("maintainer"), "createdAt": "2025-03-02T00:00:00Z", "label": { "name": "bug" } },
+                    { "__typename": "AssignedEvent", "actor": actor("maintainer"), "createdAt": "2025-03-03T00:00:00Z", "assignee": { "__typename": "User", "login": "dev" } },
+                    { "__typename": "CrossReferencedEvent", "actor": actor("dev"), "createdAt": "2025-03-04T00:00:00Z", "source": { "__typename": "PullRequest", "url": "http://example.com/pull/2" } }
+                ] }
+            },
+            {
+                "url": "http://example.com/issues/3",
+                "timelineItems": { "nodes": [] }
+            }
+        ]))
+        .unwrap();
+
+        let histories = histories_from_issues(&issues);
+
+        assert_eq!(histories.len(), 1);
+        let events = &histories["http://example.com/issues/1"];
+        let kinds: Vec<_> = events.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                TimelineEventKind::Labeled,
+                TimelineEventKind::Assigned,
+                TimelineEventKind::CrossReferenced,
+                TimelineEventKind::Closed,
+            ]
+        );
+        assert_eq!(
+            history_line(events),
+            "labeled bug by maintainer on 2025-03-02 → assigned dev by maintainer on 2025-03-03 \
+             → referenced in http://example.com/pull/2 by dev on 2025-03-04 \
+             → closed by maintainer on 2025-03-05"
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Triage module: summarizes a maintainer's triage work in a repository from issue timeline
+//! events, since labeling, closing, and answering issues barely shows up in contribution counts.
+
+use crate::github::repo_triage::{
+    IssueStateReason, RepoTriageRepositoryIssuesNodes as Issue,
+    RepoTriageRepositoryIssuesNodesTimelineItemsNodes as TimelineItem,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A single triage action the user performed on an issue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriageAction {
+    /// The issue number.
+    pub number: i64,
+    /// The issue title.
+    pub title: String,
+    /// The issue URL.
+    pub url: String,
+    /// When the action happened.
+    pub occurred_at: String,
+    /// Extra detail, e.g. the label that was applied.
+    pub detail: Option<String>,
+}
+
+/// Triage activity of one user in one repository within the report's time range.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TriageSummary {
+    /// The repository ("owner/repo") that was triaged.
+    pub repository: String,
+    /// Labels the user applied to issues.
+    pub labeled: Vec<TriageAction>,
+    /// Issues the user closed or marked as a duplicate.
+    pub closed_as_duplicate: Vec<TriageAction>,
+    /// Issues the user closed as not planned (stale, won't fix).
+    pub closed_as_not_planned: Vec<TriageAction>,
+    /// Issues where the user gave the first response to the author.
+    pub first_responses: Vec<TriageAction>,
+}
+
+impl TriageSummary {
+    /// Summarizes the triage actions `username` performed between `start_date` and `end_date`.
+    pub fn from_issues(
+        repository: &str,
+        username: &str,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+        issues: &[Issue],
+    ) -> Self {
+        let in_range = |timestamp: &str| {
+            DateTime::parse_from_rfc3339(timestamp)
+                .map(|t| t >= start_date && t <= end_date)
+                .unwrap_or(false)
+        };
+        let is_user = |login: Option<&str>| login.is_some_and(|l| l.eq_ignore_ascii_case(username));
+
+        let mut summary = TriageSummary {
+            repository: repository.to_string(),
+            ..Default::default()
+        };
+
+        for issue in issues {
+            let action = |occurred_at: &str, detail: Option<String>| TriageAction {
+                number: issue.number,
+                title: issue.title.clone(),
+                url: issue.url.clone(),
+                occurred_at: occurred_at.to_string(),
+                detail,
+            };
+            let issue_author = issue.author.as_ref().map(|a| a.login.as_str());
+            let items = issue.timeline_items.nodes.iter().flatten().flatten();
+
+            let mut answered = false;
+            let mut marked_duplicate = false;
+            for item in items {
+                match item {
+                    TimelineItem::LabeledEvent(event)
+                        if is_user(event.actor.as_ref().map(|a| a.login.as_str()))
+                            && in_range(&event.created_at) =>
+                    {
+                        summary
+                            .labeled
+                            .push(action(&event.created_at, Some(event.label.name.clone())));
+                    }
+                    TimelineItem::ClosedEvent(event) => {
+                        if !is_user(event.actor.as_ref().map(|a| a.login.as_str()))
+                            || !in_range(&event.created_at)
+                        {
+                            continue;
+                        }
+                        match event.state_reason {
+                            Some(IssueStateReason::DUPLICATE) if !marked_duplicate => {
+                                marked_duplicate = true;
+                                summary
+                                    .closed_as_duplicate
+                                    .push(action(&event.created_at, None));
+                            }
+                            Some(IssueStateReason::NOT_PLANNED) => summary
+                                .closed_as_not_planned
+                                .push(action(&event.created_at, None)),
+                            _ => {}
+                        }
+                    }
+                    TimelineItem::MarkedAsDuplicateEvent(event)
+                        if !marked_duplicate
+                            && is_user(event.actor.as_ref().map(|a| a.login.as_str()))
+                            && in_range(&event.created_at) =>
+                    {
+                        marked_duplicate = true;
+                        summary
+                            .closed_as_duplicate
+                            .push(action(&event.created_at, None));
+                    }
+                    TimelineItem::IssueComment(comment) => {
+                        // Only the first comment by someone other than the issue author counts
+                        // as the first response.
+                        let comment_author = comment.author.as_ref().map(|a| a.login.as_str());
+                        if answered || comment_author.is_none() || comment_author == issue_author {
+                            continue;
+                        }
+                        answered = true;
+                        if is_user(comment_author) && in_range(&comment.created_at) {
+                            summary
+                                .first_responses
+                                .push(action(&comment.created_at, None));
+                        }
+                    }
+                    // Events by other users or outside the time range, and event types not
+                    // requested by the triage query.
+                    _ => {}
+                }
+            }
+        }
+
+        summary
+    }
+
+    /// Total number of triage actions in the summary.
+    pub fn total(&self) -> usize {
+        self.labeled.len()
+            + self.closed_as_duplicate.len()
+            + self.closed_as_not_planned.len()
+            + self.first_responses.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn actor(login: &str) -> serde_json::Value {
+        json!({ "__typename": "User", "login": login })
+    }
+
+    fn dummy_issues() -> Vec<Issue> {
+        serde_json::from_value(json!([
+            {
+                "number": 1,
+                "title": "Crash on startup",
+                "url": "http://example.com/issues/1",
+                "createdAt": "2025-03-01T00:00:00Z",
+                "author": actor("reporter"),
+                "timelineItems": { "nodes": [
+                    // The author's own comment is not a response.
+                    { "__typename": "IssueComment", "author": actor("reporter"), "createdAt": "2025-03-02T00:00:00Z" },
+                    { "__typename": "IssueComment", "author": actor("maintainer"), "createdAt": "2025-03-03T00:00:00Z" },
+                    { "__typename": "LabeledEvent", "actor": actor("Maintainer"), "createdAt": "2025-03-03T00:00:00Z", "label": { "name": "bug" } },
+                    { "__typename": "MarkedAsDuplicateEvent", "actor": actor("maintainer"), "createdAt": "2025-03-04T00:00:00Z" },
+                    { "__typename": "ClosedEvent", "actor": actor("maintainer"), "createdAt": "2025-03-04T00:00:00Z", "stateReason": "DUPLICATE" }
+                ] }
+            },
+            {
+                "number": 2,
+                "title": "Old feature request",
+                "url": "http://example.com/issues/2",
+                "createdAt": "2024-01-01T00:00:00Z",
+                "author": actor("reporter"),
+                "timelineItems": { "nodes": [
+                    // Someone else answered first.
+                    { "__typename": "IssueComment", "author": actor("other"), "createdAt": "2024-01-02T00:00:00Z" },
+                    { "__typename": "IssueComment", "author": actor("maintainer"), "createdAt": "2025-03-05T00:00:00Z" },
+                    // Outside the time range.
+                    { "__typename": "LabeledEvent", "actor": actor("maintainer"), "createdAt": "2024-01-03T00:00:00Z", "label": { "name": "enhancement" } },
+                    { "__typename": "ClosedEvent", "actor": actor("maintainer"), "createdAt": "2025-03-06T00:00:00Z", "stateReason": "NOT_PLANNED" }
+                ] }
+            }
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_triage_summary_from_issues() {
+        let summary = TriageSummary::from_issues(
+            "owner/repo",
+            "maintainer",
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 31, 0, 0, 0).unwrap(),
+            &dummy_issues(),
+        );
+
+        assert_eq!(summary.repository, "owner/repo");
+        assert_eq!(summary.labeled.len(), 1);
+        assert_eq!(summary.labeled[0].detail.as_deref(), Some("bug"));
+        // Marking as duplicate and closing as duplicate count once.
+        assert_eq!(summary.closed_as_duplicate.len(), 1);
+        assert_eq!(summary.closed_as_duplicate[0].number, 1);
+        assert_eq!(summary.closed_as_not_planned.len(), 1);
+        assert_eq!(summary.closed_as_not_planned[0].number, 2);
+        assert_eq!(summary.first_responses.len(), 1);
+        assert_eq!(summary.first_responses[0].number, 1);
+        assert_eq!(summary.total(), 4);
+    }
+
+    #[test]
+    fn test_triage_summary_ignores_other_users() {
+        let summary = TriageSummary::from_issues(
+            "owner/repo",
+            "someone-else",
+            Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 3, 31, 0, 0, 0).unwrap(),
+            &dummy_issues(),
+        );
+        assert_eq!(summary.total(), 0);
+    }
+}
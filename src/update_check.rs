@@ -0,0 +1,173 @@
+//! Update check module: at most once a day, looks up the latest release of this tool and
+//! tells the user when a newer version is available, along with its headline change.
+//!
+//! The check never fails a run: any error is logged at debug level and ignored.
+
+use crate::cache::cache_dir;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use log::debug;
+use reqwest::header::USER_AGENT;
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// The repository whose releases are checked.
+const RELEASES_REPO: &str = "isSerge/github-activity-rs";
+
+/// Environment variable that disables the check, like `--no-update-check`.
+pub const OPT_OUT_VAR: &str = "GITHUB_ACTIVITY_NO_UPDATE_CHECK";
+
+/// How long the release lookup may take before it is abandoned.
+const TIMEOUT_SECS: u64 = 3;
+
+/// The parts of a GitHub release the notice needs.
+#[derive(Debug, Deserialize)]
+struct LatestRelease {
+    tag_name: String,
+    body: Option<String>,
+}
+
+/// Checks for a newer release if the last check was more than a day ago, returning the notice
+/// to print, if any.
+pub async fn check() -> Option<String> {
+    match try_check().await {
+        Ok(notice) => notice,
+        Err(err) => {
+            debug!("Update check failed: {:#}", err);
+            None
+        }
+    }
+}
+
+async fn try_check() -> Result<Option<String>> {
+    let stamp = stamp_path().context("No cache directory to record update checks in")?;
+    let last_checked = fs::read_to_string(&stamp)
+        .ok()
+        .and_then(|s| DateTime::parse_from_rfc3339(s.trim()).ok())
+        .map(|t| t.with_timezone(&Utc));
+    let now = Utc::now();
+    if !is_due(last_checked, now) {
+        debug!("Skipping update check; last checked at {:?}", last_checked);
+        return Ok(None);
+    }
+
+    // Record the attempt up front so an unreachable API isn't retried on every run.
+    if let Some(dir) = stamp.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    }
+    fs::write(&stamp, now.to_rfc3339()).with_context(|| format!("Failed to write {:?}", stamp))?;
+
+    let release: LatestRelease = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(TIMEOUT_SECS))
+        .build()
+        .context("Failed to build HTTP client")?
+        .get(format!(
+            "https://api.github.com/repos/{}/releases/latest",
+            RELEASES_REPO
+        ))
+        .header(USER_AGENT, "github-activity-rs")
+        .send()
+        .await
+        .context("Failed to send latest release request")?
+        .error_for_status()
+        .context("Failed to fetch latest release")?
+        .json()
+        .await
+        .context("Failed to parse latest release response")?;
+
+    Ok(notice(env!("CARGO_PKG_VERSION"), &release))
+}
+
+/// Whether a check last run at `last_checked` should run again at `now`.
+fn is_due(last_checked: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    last_checked.is_none_or(|last| now - last >= Duration::days(1))
+}
+
+/// Where the time of the last check is recorded.
+fn stamp_path() -> Option<PathBuf> {
+    Some(cache_dir()?.join("last-update-check"))
+}
+
+/// The one-line notice for `release`, if it is newer than `current`.
+fn notice(current: &str, release: &LatestRelease) -> Option<String> {
+    let latest = parse_version(&release.tag_name)?;
+    if latest <= parse_version(current)? {
+        return None;
+    }
+    let mut line = format!(
+        "A new version of github-activity-rs is available: {} (you have {})",
+        release.tag_name, current
+    );
+    if let Some(headline) = release.body.as_deref().and_then(headline) {
+        line.push_str(&format!(" — {}", headline));
+    }
+    Some(line)
+}
+
+/// Parses `1.2.3` or `v1.2.3`, ignoring any pre-release or build suffix.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    Some((
+        parts.next()??,
+        parts.next()??,
+        parts.next().unwrap_or(Some(0))?,
+    ))
+}
+
+/// The first entry of the release notes, skipping headings and list markers.
+fn headline(notes: &str) -> Option<String> {
+    notes
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| line.trim_start_matches(['-', '*', ' ']))
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn release(tag: &str, body: &str) -> LatestRelease {
+        LatestRelease {
+            tag_name: tag.into(),
+            body: Some(body.into()),
+        }
+    }
+
+    #[test]
+    fn test_is_due_once_a_day() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 2, 12, 0, 0).unwrap();
+        assert!(is_due(None, now));
+        assert!(!is_due(Some(now - Duration::hours(23)), now));
+        assert!(is_due(Some(now - Duration::hours(24)), now));
+    }
+
+    #[test]
+    fn test_notice_includes_headline_of_newer_release() {
+        let notes = "## Highlights\n\n- HTML reports with a contribution calendar\n- Bug fixes";
+        assert_eq!(
+            notice("0.1.0", &release("v0.2.0", notes)).as_deref(),
+            Some(
+                "A new version of github-activity-rs is available: v0.2.0 (you have 0.1.0) — HTML reports with a contribution calendar"
+            )
+        );
+        assert_eq!(notice("0.2.0", &release("v0.2.0", notes)), None);
+        assert_eq!(notice("0.10.0", &release("v0.9.1", notes)), None);
+        assert_eq!(notice("0.1.0", &release("nightly", notes)), None);
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("v1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2"), Some((1, 2, 0)));
+        assert_eq!(parse_version("1.2.3-rc.1"), Some((1, 2, 3)));
+        assert_eq!(parse_version("latest"), None);
+    }
+}
@@ -0,0 +1,116 @@
+//! Watch module: the schedule the `watch` subcommand regenerates reports on, either a fixed
+//! interval (`--every 24h`) or a cron expression (`--cron "0 8 * * MON"`).
+
+use chrono::{DateTime, Duration, Utc};
+use std::str::FromStr;
+
+/// When scheduled reports are generated.
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    /// Right away, then every interval after the previous run started; a run that's overdue,
+    /// e.g. after the host slept, happens once right away rather than once per missed interval.
+    Every(Duration),
+    /// At the times matching a cron expression, in UTC.
+    Cron(Box<cron::Schedule>),
+}
+
+impl Schedule {
+    /// When the next run is due, given when the previous one started (`None` before the first
+    /// run); `None` when the cron expression matches no later time.
+    pub fn next_run(
+        &self,
+        previous: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Every(interval) => {
+                Some(previous.map_or(now, |previous| (previous + *interval).max(now)))
+            }
+            Self::Cron(schedule) => schedule.after(&now).next(),
+        }
+    }
+}
+
+/// Parses an interval like `30min`, `6h`, `1d`, or `1w`.
+pub fn parse_interval(s: &str) -> Result<Duration, String> {
+    let (amount, unit) = s.split_at(
+        s.find(|c: char| !c.is_ascii_digit())
+            .ok_or("Invalid interval. Use e.g. 30min, 6h, 1d, or 1w")?,
+    );
+    let amount: i64 = amount.parse().map_err(|_| "Invalid number in interval")?;
+    let interval = match unit {
+        "min" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        "w" => Duration::weeks(amount),
+        _ => {
+            return Err(format!(
+                "Invalid interval unit: {}. Use min (minutes), h (hours), d (days), or w (weeks)",
+                unit
+            ));
+        }
+    };
+    if interval < Duration::minutes(1) {
+        return Err("The interval must be at least a minute".into());
+    }
+    Ok(interval)
+}
+
+/// Parses a cron expression: the usual five fields (minute, hour, day of month, month, day of
+/// week), or six with seconds first.
+pub fn parse_cron(s: &str) -> Result<Box<cron::Schedule>, String> {
+    let expression = if s.split_whitespace().count() == 5 {
+        format!("0 {}", s)
+    } else {
+        s.to_string()
+    };
+    cron::Schedule::from_str(&expression)
+        .map(Box::new)
+        .map_err(|err| format!("Invalid cron expression {:?}: {}", s, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_next_run() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 5, 10, 30, 0).unwrap();
+        let every = Schedule::Every(parse_interval("6h").unwrap());
+        assert_eq!(every.next_run(None, now), Some(now));
+        assert_eq!(
+            every.next_run(Some(now), now),
+            Some(Utc.with_ymd_and_hms(2025, 3, 5, 16, 30, 0).unwrap())
+        );
+
+        // Three days asleep lead to a single run right away, not one per missed interval.
+        let hourly = Schedule::Every(parse_interval("1h").unwrap());
+        assert_eq!(
+            hourly.next_run(Some(now - Duration::days(3)), now),
+            Some(now)
+        );
+
+        // 2025-03-05 is a Wednesday; the next Monday 08:00 is the 10th.
+        let weekly = Schedule::Cron(parse_cron("0 8 * * Mon").unwrap());
+        assert_eq!(
+            weekly.next_run(Some(now), now),
+            Some(Utc.with_ymd_and_hms(2025, 3, 10, 8, 0, 0).unwrap())
+        );
+        let daily = Schedule::Cron(parse_cron("0 0 9 * * *").unwrap());
+        assert_eq!(
+            daily.next_run(None, now),
+            Some(Utc.with_ymd_and_hms(2025, 3, 6, 9, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_interval_and_cron_errors() {
+        assert_eq!(parse_interval("30min"), Ok(Duration::minutes(30)));
+        assert_eq!(parse_interval("1w"), Ok(Duration::weeks(1)));
+        assert!(parse_interval("24").is_err());
+        assert!(parse_interval("2m").is_err());
+        assert!(parse_interval("0h").is_err());
+        assert!(parse_cron("every day").is_err());
+    }
+}
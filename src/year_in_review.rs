@@ -0,0 +1,383 @@
+//! Year-in-review module: a light-hearted annual summary of one person's contributions, with
+//! the year's total, longest streak, top repositories, busiest month, and first and last
+//! contribution, in Markdown or as a standalone HTML page.
+
+use crate::calendar::{CalendarGranularity, rollups};
+use crate::format::{HTML_STYLE, escape_html};
+use crate::github::user_activity::UserActivityUserContributionsCollection as ContributionsCollection;
+use crate::locale::NumberLocale;
+use crate::report::Report;
+use crate::revert::repository_of;
+use crate::statistics::Statistics;
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// How many repositories are listed.
+const TOP_REPOSITORIES: usize = 5;
+
+/// A repository ranked by the contributions made to it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RepositoryContributions {
+    /// Repository in the format owner/name.
+    pub repository: String,
+    /// Commits, issues, pull requests, and reviews in the repository.
+    pub contributions: i64,
+}
+
+/// The month with the most contributions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BusiestMonth {
+    /// The month, e.g. `2025-03`.
+    pub month: String,
+    /// Contributions made that month.
+    pub contribution_count: i64,
+}
+
+/// The year-in-review summary of one report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct YearInReview {
+    /// The reviewed user.
+    pub username: String,
+    /// The reviewed year.
+    pub year: i32,
+    /// Contributions of the year, from the contribution calendar.
+    pub total_contributions: i64,
+    /// Most consecutive days with contributions.
+    pub longest_streak: i64,
+    /// The repositories with the most contributions, most first.
+    pub top_repositories: Vec<RepositoryContributions>,
+    /// The month with the most contributions, if any had one.
+    pub busiest_month: Option<BusiestMonth>,
+    /// The first day with a contribution, e.g. `2025-01-03`.
+    pub first_contribution: Option<String>,
+    /// The last day with a contribution.
+    pub last_contribution: Option<String>,
+}
+
+impl YearInReview {
+    /// Summarizes `report` as the review of `year`.
+    pub fn new(report: &Report, year: i32) -> Self {
+        let cc = report
+            .activity
+            .user
+            .as_ref()
+            .map(|user| &user.contributions_collection);
+        let active_days: Vec<&str> = cc
+            .into_iter()
+            .flat_map(|cc| &cc.contribution_calendar.weeks)
+            .flat_map(|week| &week.contribution_days)
+            .filter(|day| day.contribution_count > 0)
+            .filter_map(|day| day.date.get(..10))
+            .collect();
+        Self {
+            username: report.username.clone(),
+            year,
+            total_contributions: cc.map_or(0, |cc| cc.contribution_calendar.total_contributions),
+            longest_streak: cc.map_or(0, |cc| Statistics::from_contributions(cc).longest_streak),
+            top_repositories: cc.map(top_repositories).unwrap_or_default(),
+            busiest_month: cc.and_then(busiest_month),
+            first_contribution: active_days.iter().min().map(|day| day.to_string()),
+            last_contribution: active_days.iter().max().map(|day| day.to_string()),
+        }
+    }
+
+    /// The highlights of the year as label and value pairs, in display order.
+    fn highlights(&self, locale: NumberLocale) -> Vec<(&'static str, String)> {
+        let mut highlights = vec![
+            (
+                "Total contributions",
+                locale.format(self.total_contributions),
+            ),
+            (
+                "Longest streak",
+                counted(locale, self.longest_streak, "day"),
+            ),
+        ];
+        if let Some(month) = &self.busiest_month {
+            highlights.push((
+                "Busiest month",
+                format!(
+                    "{} ({})",
+                    month_name(locale, &month.month),
+                    counted(locale, month.contribution_count, "contribution")
+                ),
+            ));
+        }
+        if let Some(first) = &self.first_contribution {
+            highlights.push(("First contribution", first.clone()));
+        }
+        if let Some(last) = &self.last_contribution {
+            highlights.push(("Last contribution", last.clone()));
+        }
+        highlights
+    }
+
+    /// Renders the summary as a Markdown document.
+    pub fn to_markdown(&self, locale: NumberLocale) -> String {
+        let mut output = format!("# {} Year in Review: {}\n\n", self.year, self.username);
+        if self.total_contributions == 0 {
+            output.push_str("No contributions this year.\n");
+            return output;
+        }
+        for (label, value) in self.highlights(locale) {
+            output.push_str(&format!("- **{}:** {}\n", label, value));
+        }
+        if !self.top_repositories.is_empty() {
+            output.push_str("\n## Top Repositories\n\n");
+            for (i, repo) in self.top_repositories.iter().enumerate() {
+                output.push_str(&format!(
+                    "{}. {} ({})\n",
+                    i + 1,
+                    repo.repository,
+                    counted(locale, repo.contributions, "contribution")
+                ));
+            }
+        }
+        output
+    }
+
+    /// Renders the summary as a standalone HTML page.
+    pub fn to_html(&self, locale: NumberLocale) -> String {
+        let title = format!(
+            "{} Year in Review: {}",
+            self.year,
+            escape_html(&self.username)
+        );
+        let mut output =
+            String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+        output.push_str(&format!(
+            "<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+            title, HTML_STYLE, title
+        ));
+        if self.total_contributions == 0 {
+            output.push_str("<p>No contributions this year.</p>\n");
+        } else {
+            output.push_str("<table>\n");
+            for (label, value) in self.highlights(locale) {
+                output.push_str(&format!(
+                    "<tr><th>{}</th><td>{}</td></tr>\n",
+                    label,
+                    escape_html(&value)
+                ));
+            }
+            output.push_str("</table>\n");
+            if !self.top_repositories.is_empty() {
+                output.push_str("<h2>Top Repositories</h2>\n<ol>\n");
+                for repo in &self.top_repositories {
+                    output.push_str(&format!(
+                        "<li>{} ({})</li>\n",
+                        escape_html(&repo.repository),
+                        counted(locale, repo.contributions, "contribution")
+                    ));
+                }
+                output.push_str("</ol>\n");
+            }
+        }
+        output.push_str("</body>\n</html>\n");
+        output
+    }
+}
+
+/// The repositories with the most commits, issues, pull requests, and reviews, most first and
+/// then by name.
+fn top_repositories(cc: &ContributionsCollection) -> Vec<RepositoryContributions> {
+    let mut counts: BTreeMap<String, i64> = BTreeMap::new();
+    for repo in &cc.commit_contributions_by_repository {
+        *counts
+            .entry(repo.repository.name_with_owner.clone())
+            .or_default() += repo.contributions.total_count;
+    }
+    let urls = cc
+        .issue_contributions
+        .nodes
+        .iter()
+        .flatten()
+        .map(|node| &node.issue.url)
+        .chain(
+            cc.pull_request_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(|node| &node.pull_request.url),
+        )
+        .chain(
+            cc.pull_request_review_contributions
+                .nodes
+                .iter()
+                .flatten()
+                .map(|node| &node.pull_request_review.pull_request.url),
+        );
+    for url in urls {
+        if let Some(repository) = repository_of(url) {
+            *counts.entry(repository).or_default() += 1;
+        }
+    }
+    let mut repositories: Vec<RepositoryContributions> = counts
+        .into_iter()
+        .map(|(repository, contributions)| RepositoryContributions {
+            repository,
+            contributions,
+        })
+        .collect();
+    // The sort is stable, so ties stay in name order.
+    repositories.sort_by_key(|repo| std::cmp::Reverse(repo.contributions));
+    repositories.truncate(TOP_REPOSITORIES);
+    repositories
+}
+
+/// The month with the most contributions; the earliest one on ties.
+fn busiest_month(cc: &ContributionsCollection) -> Option<BusiestMonth> {
+    rollups(cc, CalendarGranularity::Monthly)
+        .into_iter()
+        .filter(|month| month.contribution_count > 0)
+        .fold(None, |busiest: Option<BusiestMonth>, month| {
+            if busiest
+                .as_ref()
+                .is_some_and(|b| b.contribution_count >= month.contribution_count)
+            {
+                busiest
+            } else {
+                Some(BusiestMonth {
+                    month: month.label,
+                    contribution_count: month.contribution_count,
+                })
+            }
+        })
+}
+
+/// `n` followed by `noun`, pluralized unless `n` is one, e.g. `1,024 contributions`.
+fn counted(locale: NumberLocale, n: i64, noun: &str) -> String {
+    format!(
+        "{} {}{}",
+        locale.format(n),
+        noun,
+        if n == 1 { "" } else { "s" }
+    )
+}
+
+/// The name of a `YYYY-MM` month in the language of `locale`, e.g. `March`; the label itself
+/// if it isn't one.
+fn month_name(locale: NumberLocale, month: &str) -> String {
+    NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+        .map(|date| locale.month_name(date.month()).to_string())
+        .unwrap_or_else(|_| month.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use serde_json::json;
+
+    fn report(days: &[(&str, i64)], commits: &[(&str, i64)], prs: &[&str]) -> Report {
+        let days: Vec<_> = days
+            .iter()
+            .map(|(date, count)| json!({ "date": date, "contributionCount": count, "weekday": 0 }))
+            .collect();
+        let total: i64 = days
+            .iter()
+            .map(|day| day["contributionCount"].as_i64().unwrap())
+            .sum();
+        let repos: Vec<_> = commits
+            .iter()
+            .map(|(repo, count)| {
+                json!({ "repository": { "nameWithOwner": repo, "updatedAt": "2025-03-02T00:00:00Z" }, "contributions": { "totalCount": count } })
+            })
+            .collect();
+        let pr_nodes: Vec<_> = prs
+            .iter()
+            .enumerate()
+            .map(|(i, repo)| {
+                json!({ "pullRequest": {
+                    "number": i + 1, "title": "PR",
+                    "url": format!("https://github.com/{}/pull/{}", repo, i + 1),
+                    "createdAt": "2025-02-03T00:00:00Z", "state": "OPEN", "merged": false,
+                    "mergedAt": null, "mergeCommit": null, "closedAt": null,
+                    "additions": 1, "deletions": 0, "changedFiles": 1
+                } })
+            })
+            .collect();
+        let page = |nodes: Vec<serde_json::Value>| json!({ "totalCount": nodes.len(), "pageInfo": { "endCursor": null, "hasNextPage": false }, "nodes": nodes });
+        let activity = serde_json::from_value(json!({ "user": { "contributionsCollection": {
+            "totalCommitContributions": 0,
+            "totalIssueContributions": 0,
+            "totalPullRequestContributions": prs.len(),
+            "totalPullRequestReviewContributions": 0,
+            "contributionCalendar": { "totalContributions": total, "weeks": [{ "contributionDays": days }] },
+            "commitContributionsByRepository": repos,
+            "issueContributions": page(vec![]),
+            "pullRequestContributions": page(pr_nodes),
+            "pullRequestReviewContributions": page(vec![])
+        } }, "rateLimit": null }))
+        .unwrap();
+        Report::new(
+            "octocat".into(),
+            DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+                .unwrap()
+                .into(),
+            DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+                .unwrap()
+                .into(),
+            activity,
+        )
+    }
+
+    #[test]
+    fn test_year_in_review() {
+        let report = report(
+            &[
+                ("2025-01-02", 0),
+                ("2025-01-03", 2),
+                ("2025-03-10", 4),
+                ("2025-03-11", 1),
+                ("2025-03-12", 1),
+                ("2025-11-30", 3),
+                ("2025-12-01", 0),
+            ],
+            &[("o/a", 3), ("o/b", 3), ("o/c", 1)],
+            &["o/c", "o/c", "o/d"],
+        );
+        let review = YearInReview::new(&report, 2025);
+        assert_eq!(review.total_contributions, 11);
+        assert_eq!(review.longest_streak, 3);
+        assert_eq!(
+            review.busiest_month,
+            Some(BusiestMonth {
+                month: "2025-03".into(),
+                contribution_count: 6
+            })
+        );
+        assert_eq!(review.first_contribution.as_deref(), Some("2025-01-03"));
+        assert_eq!(review.last_contribution.as_deref(), Some("2025-11-30"));
+        let top: Vec<_> = review
+            .top_repositories
+            .iter()
+            .map(|repo| (repo.repository.as_str(), repo.contributions))
+            .collect();
+        assert_eq!(top, [("o/a", 3), ("o/b", 3), ("o/c", 3), ("o/d", 1)]);
+
+        let markdown = review.to_markdown(NumberLocale::default());
+        assert!(markdown.starts_with("# 2025 Year in Review: octocat\n\n"));
+        assert!(markdown.contains("- **Longest streak:** 3 days\n"));
+        assert!(markdown.contains("- **Busiest month:** March (6 contributions)\n"));
+        assert!(markdown.contains("1. o/a (3 contributions)\n"));
+
+        let html = review.to_html(NumberLocale::default());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<tr><th>First contribution</th><td>2025-01-03</td></tr>\n"));
+        assert!(html.contains("<li>o/d (1 contribution)</li>\n"));
+    }
+
+    #[test]
+    fn test_year_without_contributions() {
+        let review = YearInReview::new(&report(&[("2025-01-02", 0)], &[], &[]), 2025);
+        assert_eq!(review.busiest_month, None);
+        assert_eq!(review.first_contribution, None);
+        assert!(
+            review
+                .to_markdown(NumberLocale::default())
+                .ends_with("No contributions this year.\n")
+        );
+    }
+}